@@ -352,6 +352,16 @@ bool ASTJsonConverter::visit(FunctionDefinition const& _node)
 	};
 //	if (_node.isPartOfExternalInterface())
 //		attributes.emplace_back("functionSelector", _node.externalIdentifierHex());
+	if (_node.functionID())
+		attributes.emplace_back("functionID", *_node.functionID());
+	if (_node.isInline())
+		attributes.emplace_back("inline", true);
+	if (_node.isResponsible())
+		attributes.emplace_back("responsible", true);
+	if (_node.externalMsg())
+		attributes.emplace_back("externalMsg", true);
+	if (_node.internalMsg())
+		attributes.emplace_back("internalMsg", true);
 	if (!_node.annotation().baseFunctions.empty())
 		attributes.emplace_back(make_pair("baseFunctions", getContainerIds(_node.annotation().baseFunctions, true)));
 	if (m_legacy)
@@ -377,6 +387,8 @@ bool ASTJsonConverter::visit(VariableDeclaration const& _node)
 		attributes.emplace_back("functionSelector", _node.externalIdentifierHex());
 	if (m_inEvent)
 		attributes.emplace_back("indexed", _node.isIndexed());
+	if (_node.isStatic())
+		attributes.emplace_back("static", true);
 	if (!_node.annotation().baseFunctions.empty())
 		attributes.emplace_back(make_pair("baseFunctions", getContainerIds(_node.annotation().baseFunctions, true)));
 	setJsonNode(_node, "VariableDeclaration", std::move(attributes));
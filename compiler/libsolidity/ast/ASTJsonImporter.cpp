@@ -144,6 +144,10 @@ ASTPointer<ASTNode> ASTJsonImporter::convertJsonToASTNode(Json::Value const& _js
 		return createMapping(_json);
 	if (nodeType == "ArrayTypeName")
 		return createArrayTypeName(_json);
+	if (nodeType == "Optional")
+		return createOptional(_json);
+	if (nodeType == "vector")
+		return createTvmVector(_json);
 	if (nodeType == "InlineAssembly")
 		return createInlineAssembly(_json);
 	if (nodeType == "Block")
@@ -164,6 +168,8 @@ ASTPointer<ASTNode> ASTJsonImporter::convertJsonToASTNode(Json::Value const& _js
 		return createWhileStatement(_json, WhileStatement::LoopType::REPEAT);
 	if (nodeType == "ForStatement")
 		return createForStatement(_json);
+	if (nodeType == "ForEachStatement")
+		return createForEachStatement(_json);
 	if (nodeType == "Continue")
 		return createContinue(_json);
 	if (nodeType == "Break")
@@ -192,6 +198,10 @@ ASTPointer<ASTNode> ASTJsonImporter::convertJsonToASTNode(Json::Value const& _js
 		return createFunctionCall(_json);
 	if (nodeType == "FunctionCallOptions")
 		return createFunctionCallOptions(_json);
+	if (nodeType == "InitializerList")
+		return createInitializerList(_json);
+	if (nodeType == "CallList")
+		return createCallList(_json);
 	if (nodeType == "NewExpression")
 		return createNewExpression(_json);
 	if (nodeType == "MemberAccess")
@@ -204,6 +214,10 @@ ASTPointer<ASTNode> ASTJsonImporter::convertJsonToASTNode(Json::Value const& _js
 		return createIdentifier(_json);
 	if (nodeType == "ElementaryTypeNameExpression")
 		return createElementaryTypeNameExpression(_json);
+	if (nodeType == "MappingNameExpression")
+		return createMappingNameExpression(_json);
+	if (nodeType == "OptionalNameExpression")
+		return createOptionalNameExpression(_json);
 	if (nodeType == "Literal")
 		return createLiteral(_json);
 	if (nodeType == "StructuredDocumentation")
@@ -383,8 +397,19 @@ ASTPointer<FunctionDefinition> ASTJsonImporter::createFunctionDefinition(Json::V
 		kind = Token::Fallback;
 	else if (kindStr == "receive")
 		kind = Token::Receive;
+	else if (kindStr == "onBounce")
+		kind = Token::onBounce;
+	else if (kindStr == "onTickTock")
+		kind = Token::onTickTock;
 	else
-		astAssert(false, "Expected 'kind' to be one of [constructor, function, fallback, receive]");
+		astAssert(false, "Expected 'kind' to be one of [constructor, function, fallback, receive, onBounce, onTickTock]");
+
+	std::optional<uint32_t> functionID;
+	if (_node.isMember("functionID"))
+	{
+		astAssert(_node["functionID"].isUInt(), "Expected 'functionID' to be an unsigned integer!");
+		functionID = _node["functionID"].asUInt();
+	}
 
 	std::vector<ASTPointer<ModifierInvocation>> modifiers;
 	for (auto& mod: member(_node, "modifiers"))
@@ -401,7 +426,12 @@ ASTPointer<FunctionDefinition> ASTJsonImporter::createFunctionDefinition(Json::V
 		createParameterList(member(_node, "parameters")),
 		modifiers,
 		createParameterList(member(_node, "returnParameters")),
-		memberAsBool(_node, "implemented") ? createBlock(member(_node, "body")) : nullptr
+		memberAsBool(_node, "implemented") ? createBlock(member(_node, "body")) : nullptr,
+		functionID,
+		_node.isMember("inline") ? memberAsBool(_node, "inline") : false,
+		_node.isMember("responsible") ? memberAsBool(_node, "responsible") : false,
+		_node.isMember("externalMsg") ? memberAsBool(_node, "externalMsg") : false,
+		_node.isMember("internalMsg") ? memberAsBool(_node, "internalMsg") : false
 	);
 }
 
@@ -418,7 +448,9 @@ ASTPointer<VariableDeclaration> ASTJsonImporter::createVariableDeclaration(Json:
 		memberAsBool(_node, "stateVariable"),
 		_node.isMember("indexed") ? memberAsBool(_node, "indexed") : false,
 		memberAsBool(_node, "constant"),
-		_node["overrides"].isNull() ? nullptr : createOverrideSpecifier(member(_node, "overrides"))
+		_node["overrides"].isNull() ? nullptr : createOverrideSpecifier(member(_node, "overrides")),
+		nullptr,
+		_node.isMember("static") ? memberAsBool(_node, "static") : false
 	);
 }
 
@@ -522,6 +554,22 @@ ASTPointer<ArrayTypeName> ASTJsonImporter::createArrayTypeName(Json::Value const
 	);
 }
 
+ASTPointer<Optional> ASTJsonImporter::createOptional(Json::Value const&  _node)
+{
+	std::vector<ASTPointer<TypeName>> types;
+	for (auto& type: member(_node, "maybeTypes"))
+		types.push_back(convertJsonToASTNode<TypeName>(type));
+	return createASTNode<Optional>(_node, types);
+}
+
+ASTPointer<TvmVector> ASTJsonImporter::createTvmVector(Json::Value const&  _node)
+{
+	return createASTNode<TvmVector>(
+		_node,
+		convertJsonToASTNode<TypeName>(member(_node, "type"))
+	);
+}
+
 ASTPointer<InlineAssembly> ASTJsonImporter::createInlineAssembly(Json::Value const& _node)
 {
 	astAssert(_node["evmVersion"].isString(), "Expected evmVersion to be a string!");
@@ -617,6 +665,17 @@ ASTPointer<ForStatement> ASTJsonImporter::createForStatement(Json::Value const&
 	);
 }
 
+ASTPointer<ForEachStatement> ASTJsonImporter::createForEachStatement(Json::Value const&  _node)
+{
+	return createASTNode<ForEachStatement>(
+		_node,
+		nullOrASTString(_node, "documentation"),
+		nullOrCast<Statement>(member(_node, "rangeDeclaration")),
+		nullOrCast<Expression>(member(_node, "rangeExpression")),
+		convertJsonToASTNode<Statement>(member(_node, "body"))
+	);
+}
+
 ASTPointer<Continue> ASTJsonImporter::createContinue(Json::Value const&  _node)
 {
 	return createASTNode<Continue>(
@@ -787,6 +846,31 @@ ASTPointer<FunctionCallOptions> ASTJsonImporter::createFunctionCallOptions(Json:
 	);
 }
 
+ASTPointer<InitializerList> ASTJsonImporter::createInitializerList(Json::Value const&  _node)
+{
+	std::vector<ASTPointer<Expression>> options;
+	for (auto& option: member(_node, "options"))
+		options.push_back(convertJsonToASTNode<Expression>(option));
+	std::vector<ASTPointer<ASTString>> names;
+	for (auto& name: member(_node, "names"))
+	{
+		astAssert(name.isString(), "Expected 'names' members to be strings!");
+		names.push_back(make_shared<ASTString>(name.asString()));
+	}
+
+	return createASTNode<InitializerList>(_node, options, names);
+}
+
+ASTPointer<CallList> ASTJsonImporter::createCallList(Json::Value const&  _node)
+{
+	ASTPointer<Expression> function = convertJsonToASTNode<Expression>(member(_node, "expression"));
+	std::vector<ASTPointer<Expression>> arguments;
+	for (auto& argument: member(_node, "arguments"))
+		arguments.push_back(convertJsonToASTNode<Expression>(argument));
+
+	return createASTNode<CallList>(_node, function, arguments);
+}
+
 ASTPointer<NewExpression> ASTJsonImporter::createNewExpression(Json::Value const&  _node)
 {
 	return createASTNode<NewExpression>(
@@ -836,6 +920,22 @@ ASTPointer<ElementaryTypeNameExpression> ASTJsonImporter::createElementaryTypeNa
 	);
 }
 
+ASTPointer<MappingNameExpression> ASTJsonImporter::createMappingNameExpression(Json::Value const&  _node)
+{
+	return createASTNode<MappingNameExpression>(
+		_node,
+		createMapping(member(_node, "typeName"))
+	);
+}
+
+ASTPointer<OptionalNameExpression> ASTJsonImporter::createOptionalNameExpression(Json::Value const&  _node)
+{
+	return createASTNode<OptionalNameExpression>(
+		_node,
+		createOptional(member(_node, "typeName"))
+	);
+}
+
 ASTPointer<ASTNode> ASTJsonImporter::createLiteral(Json::Value const&  _node)
 {
 	static string const valStr = "value";
@@ -90,6 +90,8 @@ private:
 	ASTPointer<FunctionTypeName> createFunctionTypeName(Json::Value const& _node);
 	ASTPointer<Mapping> createMapping(Json::Value const& _node);
 	ASTPointer<ArrayTypeName> createArrayTypeName(Json::Value const& _node);
+	ASTPointer<Optional> createOptional(Json::Value const& _node);
+	ASTPointer<TvmVector> createTvmVector(Json::Value const& _node);
 	ASTPointer<InlineAssembly> createInlineAssembly(Json::Value const& _node);
 	ASTPointer<Block> createBlock(Json::Value const& _node);
 	ASTPointer<PlaceholderStatement> createPlaceholderStatement(Json::Value const& _node);
@@ -98,6 +100,7 @@ private:
 	ASTPointer<TryStatement> createTryStatement(Json::Value const& _node);
 	ASTPointer<WhileStatement> createWhileStatement(Json::Value const& _node, WhileStatement::LoopType loopType);
 	ASTPointer<ForStatement> createForStatement(Json::Value const& _node);
+	ASTPointer<ForEachStatement> createForEachStatement(Json::Value const& _node);
 	ASTPointer<Continue> createContinue(Json::Value const& _node);
 	ASTPointer<Break> createBreak(Json::Value const& _node);
 	ASTPointer<Return> createReturn(Json::Value const& _node);
@@ -112,12 +115,16 @@ private:
 	ASTPointer<BinaryOperation> createBinaryOperation(Json::Value const& _node);
 	ASTPointer<FunctionCall> createFunctionCall(Json::Value const& _node);
 	ASTPointer<FunctionCallOptions> createFunctionCallOptions(Json::Value const& _node);
+	ASTPointer<InitializerList> createInitializerList(Json::Value const& _node);
+	ASTPointer<CallList> createCallList(Json::Value const& _node);
 	ASTPointer<NewExpression> createNewExpression(Json::Value const& _node);
 	ASTPointer<MemberAccess> createMemberAccess(Json::Value const& _node);
 	ASTPointer<IndexAccess> createIndexAccess(Json::Value const& _node);
 	ASTPointer<IndexRangeAccess> createIndexRangeAccess(Json::Value const& _node);
 	ASTPointer<Identifier> createIdentifier(Json::Value const& _node);
 	ASTPointer<ElementaryTypeNameExpression> createElementaryTypeNameExpression(Json::Value const& _node);
+	ASTPointer<MappingNameExpression> createMappingNameExpression(Json::Value const& _node);
+	ASTPointer<OptionalNameExpression> createOptionalNameExpression(Json::Value const& _node);
 	ASTPointer<ASTNode> createLiteral(Json::Value const& _node);
 	ASTPointer<StructuredDocumentation> createDocumentation(Json::Value const& _node);
 	///@}
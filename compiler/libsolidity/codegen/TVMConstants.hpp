@@ -17,9 +17,12 @@
  */
 
 #pragma once
+#include <cstddef>
 #include <string>
 
 namespace TvmConst {
+	// optimizer runs from which the size optimizer is skipped
+	const size_t SizeOptimizerRunsLimit = 1000;
 	namespace C4 {
 		// length of key in dict c4
 		const int KeyLength = 64;
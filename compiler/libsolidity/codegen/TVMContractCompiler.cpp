@@ -222,12 +222,14 @@ void TVMContractCompiler::generateCode(
 Pointer<Contract>
 TVMContractCompiler::generateContractCode(
 	ContractDefinition const *contract,
-	PragmaDirectiveHelper const &pragmaHelper
+	PragmaDirectiveHelper const &pragmaHelper,
+	DispatchSettings const &dispatchSettings,
+	OptimiserSettings const &optimiserSettings
 ) {
 	std::vector<std::string> pragmas;
 	std::vector<Pointer<Function>> functions;
 
-	TVMCompilerContext ctx{contract, pragmaHelper};
+	TVMCompilerContext ctx{contract, pragmaHelper, dispatchSettings};
 
 	if (!ctx.isStdlib()) {
 		pragmas.emplace_back(std::string{} + ".version sol " + solidity::frontend::VersionNumber);
@@ -399,12 +401,16 @@ TVMContractCompiler::generateContractCode(
 	LocSquasher sq;
 	c->accept(sq);
 
-	optimizeCode(c);
+	optimizeCode(c, optimiserSettings);
 
 	return c;
 }
 
-void TVMContractCompiler::optimizeCode(Pointer<Contract>& c) {
+void TVMContractCompiler::optimizeCode(Pointer<Contract>& c, OptimiserSettings const& optimiserSettings) {
+	// Only OptimiserSettings::standard(), which `"enabled": true` selects, runs the CSE
+	bool const optimize = optimiserSettings.runCSE;
+	bool const peephole = optimiserSettings.runPeephole;
+
 	DeleterCallX dc;
 	c->accept(dc);
 
@@ -414,26 +420,45 @@ void TVMContractCompiler::optimizeCode(Pointer<Contract>& c) {
 	StackOptimizer opt;
 	c->accept(opt);
 
-	PeepholeOptimizer peepHole{false, false};
-	c->accept(peepHole);
+	if (peephole) {
+		PeepholeOptimizer peepHole{false, false};
+		c->accept(peepHole);
+	}
 
 	opt = StackOptimizer{};
 	c->accept(opt);
 
-	peepHole = PeepholeOptimizer{false, false};
-	c->accept(peepHole);
+	if (peephole) {
+		PeepholeOptimizer peepHole{false, false};
+		c->accept(peepHole);
 
-	peepHole = PeepholeOptimizer{true, false};
-	c->accept(peepHole);
+		peepHole = PeepholeOptimizer{true, false};
+		c->accept(peepHole);
+
+		peepHole = PeepholeOptimizer{true, true};
+		c->accept(peepHole);
+	}
 
-	peepHole = PeepholeOptimizer{true, true};
-	c->accept(peepHole);
+	// The optimizer runs the stack and peephole optimizers once more
+	if (optimize) {
+		opt = StackOptimizer{};
+		c->accept(opt);
+
+		if (peephole) {
+			PeepholeOptimizer peepHole{true, true};
+			c->accept(peepHole);
+		}
+	}
 
 	LocSquasher sq = LocSquasher{};
 	c->accept(sq);
 
-	SizeOptimizer so{};
-	so.optimize(c);
+	// Code shared by the size optimizer costs a call each time it runs, which contracts
+	// expected to run often are better off without
+	if (!optimize || optimiserSettings.expectedExecutionsPerDeployment < TvmConst::SizeOptimizerRunsLimit) {
+		SizeOptimizer so{};
+		so.optimize(c);
+	}
 }
 
 void TVMContractCompiler::fillInlineFunctions(TVMCompilerContext &ctx, ContractDefinition const *contract) {
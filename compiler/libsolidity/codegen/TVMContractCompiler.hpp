@@ -19,6 +19,7 @@
 #pragma once
 
 #include "TVM.h"
+#include <libsolidity/interface/OptimiserSettings.h>
 #include "TVMPusher.hpp"
 #include "TvmAst.hpp"
 
@@ -54,8 +55,13 @@ public:
 		ContractDefinition const& contract,
 		PragmaDirectiveHelper const &pragmaHelper
 	);
-	static Pointer<Contract> generateContractCode(ContractDefinition const* contract, PragmaDirectiveHelper const& pragmaHelper);
-	static void optimizeCode(Pointer<Contract>& c);
+	static Pointer<Contract> generateContractCode(
+		ContractDefinition const* contract,
+		PragmaDirectiveHelper const& pragmaHelper,
+		DispatchSettings const& dispatchSettings = {},
+		OptimiserSettings const& optimiserSettings = OptimiserSettings::minimal()
+	);
+	static void optimizeCode(Pointer<Contract>& c, OptimiserSettings const& optimiserSettings = OptimiserSettings::minimal());
 private:
 	static void fillInlineFunctions(TVMCompilerContext& ctx, ContractDefinition const* contract);
 };
@@ -481,9 +481,43 @@ TVMFunctionCompiler::generateGetter(StackPusher &pusher, VariableDeclaration con
 Pointer<Function>
 TVMFunctionCompiler::generatePublicFunctionSelector(TVMCompilerContext& ctx, ContractDefinition const *contract) {
 	StackPusher pusher{&ctx};
-	const std::vector<std::pair<uint32_t, std::string>>& functions = pusher.ctx().getPublicFunctions();
+	const DispatchSettings& settings = pusher.ctx().dispatchSettings();
 	TVMFunctionCompiler compiler{pusher, contract};
-	compiler.buildPublicFunctionSelector(functions, 0, functions.size());
+
+	// stack: functionID
+	// prioritized functions are compared one by one before the search over the others
+	std::vector<std::pair<uint32_t, std::string>> functions;
+	for (const auto& [functionId, name] : pusher.ctx().getPublicFunctions()) {
+		if (std::find(settings.priority.begin(), settings.priority.end(), name) == settings.priority.end()) {
+			functions.emplace_back(functionId, name);
+		}
+	}
+	for (const std::string& priority : settings.priority) {
+		bool found = false;
+		for (const auto& [functionId, name] : pusher.ctx().getPublicFunctions()) {
+			if (name == priority) {
+				compiler.pushPublicFunctionCall(functionId, name);
+				found = true;
+			}
+		}
+		if (!found) {
+			cast_error(*contract, "Dispatch priority names \"" + priority + "\", which is not a public function of contract \"" + contract->name() + "\".");
+		}
+	}
+
+	switch (settings.strategy) {
+	case DispatchStrategy::Tree:
+		compiler.buildPublicFunctionSelector(functions, 0, functions.size(), 4);
+		break;
+	case DispatchStrategy::Binary:
+		compiler.buildPublicFunctionSelector(functions, 0, functions.size(), 2);
+		break;
+	case DispatchStrategy::Linear:
+		for (const auto& [functionId, name] : functions) {
+			compiler.pushPublicFunctionCall(functionId, name);
+		}
+		break;
+	}
 	return createNode<Function>(1, 1, "public_function_selector", Function::FunctionType::Macro, pusher.getBlock());
 }
 
@@ -1936,47 +1970,48 @@ void TVMFunctionCompiler::pushReceiveOrFallback() {
 	}
 }
 
+void TVMFunctionCompiler::pushPublicFunctionCall(uint32_t functionId, const std::string& name) {
+	m_pusher.pushS(0);
+	m_pusher.pushInt(functionId);
+	m_pusher.push(-2 + 1, "EQUAL");
+	m_pusher.push(-1, ""); // fix stack
+	m_pusher.startContinuation();
+	m_pusher.pushCall(0, 0, name);
+	m_pusher.endContinuationFromRef();
+	m_pusher.ifJmp();
+}
+
 void TVMFunctionCompiler::buildPublicFunctionSelector(
 	const std::vector<std::pair<uint32_t, std::string>>& functions,
 	int left,
-	int right
+	int right,
+	int fanout
 ) {
 	int qty = right - left;
 	int blockSize = 1;
-	while (4 * blockSize < qty) {
-		blockSize *= 4;
+	while (fanout * blockSize < qty) {
+		blockSize *= fanout;
 	}
-	solAssert(4 * blockSize >= qty, "");
-
-	auto pushOne = [&](uint32_t functionId, const std::string& name) {
-		m_pusher.pushS(0);
-		m_pusher.pushInt(functionId);
-		m_pusher.push(-2 + 1, "EQUAL");
-		m_pusher.push(-1, ""); // fix stack
-		m_pusher.startContinuation();
-		m_pusher.pushCall(0, 0, name);
-		m_pusher.endContinuationFromRef();
-		m_pusher.ifJmp();
-	};
+	solAssert(fanout * blockSize >= qty, "");
 
 	// stack: functionID
-	if (right - left <= 4) {
+	if (right - left <= fanout) {
 		for (int i = left; i < right; ++i) {
 			const auto& [functionId, name] = functions.at(i);
-			pushOne(functionId, name);
+			pushPublicFunctionCall(functionId, name);
 		}
 	} else {
 		for (int i = left; i < right; i += blockSize) {
 			int j = std::min(i + blockSize, right);
 			const auto& [functionId, name] = functions.at(j - 1);
 			if (j - i == 1) {
-				pushOne(functionId, name);
+				pushPublicFunctionCall(functionId, name);
 			} else {
 				m_pusher.pushS(0);
 				m_pusher.pushInt(functionId);
 				m_pusher.push(-2 + 1, "LEQ");
 				m_pusher.startContinuation();
-				buildPublicFunctionSelector(functions, i, j);
+				buildPublicFunctionSelector(functions, i, j, fanout);
 				m_pusher.endContinuationFromRef();
 				m_pusher.ifJmp();
 			}
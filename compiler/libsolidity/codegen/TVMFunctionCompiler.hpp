@@ -128,7 +128,8 @@ private:
 	void updC4IfItNeeds();
 	void pushReceiveOrFallback();
 
-	void buildPublicFunctionSelector(const std::vector<std::pair<uint32_t, std::string>>& functions, int left, int right);
+	void pushPublicFunctionCall(uint32_t functionId, const std::string& name);
+	void buildPublicFunctionSelector(const std::vector<std::pair<uint32_t, std::string>>& functions, int left, int right, int fanout);
     void pushLocation(const ASTNode& node, bool reset = false);
 
 private:
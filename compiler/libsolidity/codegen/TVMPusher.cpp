@@ -2544,8 +2544,13 @@ void TVMCompilerContext::initMembers(ContractDefinition const *contract) {
 	}
 }
 
-TVMCompilerContext::TVMCompilerContext(ContractDefinition const *contract, PragmaDirectiveHelper const &pragmaHelper) :
+TVMCompilerContext::TVMCompilerContext(
+	ContractDefinition const *contract,
+	PragmaDirectiveHelper const &pragmaHelper,
+	DispatchSettings dispatchSettings
+) :
 	m_pragmaHelper{pragmaHelper},
+	m_dispatchSettings{std::move(dispatchSettings)},
 	m_usage{*contract}
 {
 	initMembers(contract);
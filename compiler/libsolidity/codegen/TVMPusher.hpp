@@ -24,6 +24,7 @@
 #include <liblangutil/SourceReferenceFormatter.h>
 #include <libsolidity/ast/AST.h>
 #include <libsolidity/ast/ASTVisitor.h>
+#include <libsolidity/interface/DispatchSettings.h>
 
 #include "TVMCommons.hpp"
 #include "TvmAst.hpp"
@@ -52,7 +53,11 @@ private:
 
 class TVMCompilerContext {
 public:
-	TVMCompilerContext(ContractDefinition const* contract, PragmaDirectiveHelper const& pragmaHelper);
+	TVMCompilerContext(
+		ContractDefinition const* contract,
+		PragmaDirectiveHelper const& pragmaHelper,
+		DispatchSettings dispatchSettings = {}
+	);
 	void initMembers(ContractDefinition const* contract);
 	int getStateVarIndex(VariableDeclaration const *variable) const;
 	std::vector<VariableDeclaration const *> notConstantStateVariables() const;
@@ -78,6 +83,7 @@ public:
 	Pointer<CodeBlock> getInlinedFunction(const std::string& name);
 	void addPublicFunction(uint32_t functionId, const std::string& functionName);
 	const std::vector<std::pair<uint32_t, std::string>>& getPublicFunctions();
+	DispatchSettings const& dispatchSettings() const { return m_dispatchSettings; }
 
 	bool addAndDoesHaveLoop(FunctionDefinition const* _v, FunctionDefinition const* _to);
 	bool dfs(FunctionDefinition const* v);
@@ -106,6 +112,7 @@ private:
 	ContractDefinition const* m_contract{};
 	bool ignoreIntOverflow{};
 	PragmaDirectiveHelper const& m_pragmaHelper;
+	DispatchSettings m_dispatchSettings;
 	std::map<VariableDeclaration const*, int> m_stateVarIndex;
 	std::set<FunctionDefinition const*> m_libFunctions;
 	FunctionDefinition const* m_currentFunction{};
@@ -477,7 +477,7 @@ std::pair<bool, bool> CompilerStack::compile(bool json)
 {
 	bool didCompileSomething{};
 	if (m_stackState < AnalysisPerformed)
-		if (!parseAndAnalyze())
+		if (!(m_importedSources ? analyze() : parseAndAnalyze()))
 			return {false, didCompileSomething};
 
 	if (m_hasError)
@@ -579,7 +579,7 @@ std::pair<bool, bool> CompilerStack::compile(bool json)
 					}
 					if (m_generateCode) {
 						Pointer<solidity::frontend::Contract> codeContract =
-							TVMContractCompiler::generateContractCode(targetContract, pragmaHelper);
+							TVMContractCompiler::generateContractCode(targetContract, pragmaHelper, m_dispatchSettings, m_optimiserSettings);
 						ostringstream out;
 						Printer p{out};
 						codeContract->accept(p);
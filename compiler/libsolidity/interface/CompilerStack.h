@@ -27,6 +27,7 @@
 #include <libsolidity/interface/OptimiserSettings.h>
 #include <libsolidity/interface/Version.h>
 #include <libsolidity/interface/DebugSettings.h>
+#include <libsolidity/interface/DispatchSettings.h>
 
 #include <liblangutil/ErrorReporter.h>
 #include <liblangutil/EVMVersion.h>
@@ -175,6 +176,10 @@ public:
 		m_mainContract = mainContract;
 	}
 
+	void setDispatchSettings(DispatchSettings _dispatchSettings) {
+		m_dispatchSettings = std::move(_dispatchSettings);
+	}
+
 	void generateAbi() {
 		m_generateAbi = true;
 	}
@@ -450,7 +455,7 @@ private:
 
 
 	ReadCallback::Callback m_readFile;
-	OptimiserSettings m_optimiserSettings;
+	OptimiserSettings m_optimiserSettings = OptimiserSettings::minimal();
 	RevertStrings m_revertStrings = RevertStrings::Default;
 	langutil::EVMVersion m_evmVersion;
 	// smt::SMTSolverChoice m_enabledSMTSolvers;
@@ -484,6 +489,7 @@ private:
 	bool m_release = VersionIsRelease;
 	bool m_structWarning = false;
 	std::string m_mainContract;
+	DispatchSettings m_dispatchSettings;
 	bool m_generateAbi{};
 	bool m_generateCode{};
 	bool m_withOptimizations{};
@@ -0,0 +1,60 @@
+/*
+ * Copyright 2018-2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the  terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License.
+ *
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the  GNU General Public License for more details at: https://www.gnu.org/licenses/gpl-3.0.html
+ */
+/**
+ * Settings of the public function selector.
+ */
+
+#pragma once
+
+#include <optional>
+#include <string>
+#include <vector>
+
+namespace solidity::frontend
+{
+
+enum class DispatchStrategy
+{
+	Tree, // search tree of fanout 4 by function ID
+	Binary, // binary search by function ID
+	Linear // one comparison per function in ID order
+};
+
+inline std::string dispatchStrategyToString(DispatchStrategy _strategy)
+{
+	switch (_strategy)
+	{
+	case DispatchStrategy::Tree: return "tree";
+	case DispatchStrategy::Binary: return "binary";
+	case DispatchStrategy::Linear: return "linear";
+	}
+	// Cannot reach this.
+	return "INVALID";
+}
+
+inline std::optional<DispatchStrategy> dispatchStrategyFromString(std::string const& _str)
+{
+	for (auto i: {DispatchStrategy::Tree, DispatchStrategy::Binary, DispatchStrategy::Linear})
+		if (dispatchStrategyToString(i) == _str)
+			return i;
+	return {};
+}
+
+struct DispatchSettings
+{
+	DispatchStrategy strategy = DispatchStrategy::Tree;
+	/// Functions compared first, in this order, before the search over the others
+	std::vector<std::string> priority;
+};
+
+}
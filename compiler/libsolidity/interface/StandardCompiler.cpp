@@ -294,7 +294,7 @@ std::optional<Json::Value> checkRootKeys(Json::Value const& _input)
 
 std::optional<Json::Value> checkSourceKeys(Json::Value const& _input, string const& _name)
 {
-	static set<string> keys{"content", "keccak256", "urls"};
+	static set<string> keys{"ast", "content", "keccak256", "urls"};
 	return checkKeys(_input, keys, "sources." + _name);
 }
 
@@ -307,10 +307,16 @@ std::optional<Json::Value> checkAuxiliaryInputKeys(Json::Value const& _input)
 std::optional<Json::Value> checkSettingsKeys(Json::Value const& _input)
 {
 	static set<string> keys{"parserErrorRecovery", "debug", "evmVersion", "libraries", "metadata", "optimizer", "outputSelection", "remappings",
-		"includePaths", "structWarning", "forceRemoteUpdate", "mainContract"};
+		"includePaths", "structWarning", "forceRemoteUpdate", "mainContract", "dispatch"};
 	return checkKeys(_input, keys, "settings");
 }
 
+std::optional<Json::Value> checkDispatchKeys(Json::Value const& _input)
+{
+	static set<string> keys{"strategy", "priority"};
+	return checkKeys(_input, keys, "settings.dispatch");
+}
+
 std::optional<Json::Value> checkOptimizerKeys(Json::Value const& _input)
 {
 	static set<string> keys{"details", "enabled", "runs"};
@@ -452,6 +458,20 @@ boost::variant<OptimiserSettings, Json::Value> parseOptimizerSettings(Json::Valu
 	return { std::move(settings) };
 }
 
+/// Source units of the "SolidityAST" input by source name
+map<string, Json::Value> parseAstFromInput(StringMap const& _sources)
+{
+	map<string, Json::Value> sourceJsons;
+	for (auto const& [sourceName, sourceCode]: _sources)
+	{
+		Json::Value ast;
+		astAssert(util::jsonParseStrict(sourceCode, ast), "Input file could not be parsed to JSON");
+		astAssert(ast["nodeType"].asString() == "SourceUnit", "Top-level node should be a 'SourceUnit'");
+		sourceJsons.emplace(sourceName, std::move(ast));
+	}
+	return sourceJsons;
+}
+
 }
 
 boost::variant<StandardCompiler::InputsAndSettings, Json::Value> StandardCompiler::parseInput(Json::Value const& _input)
@@ -486,7 +506,13 @@ boost::variant<StandardCompiler::InputsAndSettings, Json::Value> StandardCompile
 		if (sources[sourceName]["keccak256"].isString())
 			hash = sources[sourceName]["keccak256"].asString();
 
-		if (sources[sourceName]["content"].isString())
+		if (ret.language == "SolidityAST")
+		{
+			if (!sources[sourceName]["ast"].isObject())
+				return formatFatalError("JSONError", "\"sources." + sourceName + ".ast\" must be an object.");
+			ret.sources[sourceName] = util::jsonCompactPrint(sources[sourceName]["ast"]);
+		}
+		else if (sources[sourceName]["content"].isString())
 		{
 			string content = sources[sourceName]["content"].asString();
 			if (!hash.empty() && !hashMatchesContent(hash, content))
@@ -619,6 +645,33 @@ boost::variant<StandardCompiler::InputsAndSettings, Json::Value> StandardCompile
 		ret.mainContract = settings["mainContract"].asString();
 	}
 
+	if (settings.isMember("dispatch"))
+	{
+		Json::Value const& dispatch = settings["dispatch"];
+		if (auto result = checkDispatchKeys(dispatch))
+			return *result;
+		if (dispatch.isMember("strategy"))
+		{
+			if (!dispatch["strategy"].isString())
+				return formatFatalError("JSONError", "\"settings.dispatch.strategy\" must be a String.");
+			std::optional<DispatchStrategy> strategy = dispatchStrategyFromString(dispatch["strategy"].asString());
+			if (!strategy)
+				return formatFatalError("JSONError", "Invalid dispatch strategy requested, expected \"tree\", \"binary\" or \"linear\".");
+			ret.dispatchSettings.strategy = *strategy;
+		}
+		if (dispatch.isMember("priority"))
+		{
+			if (!dispatch["priority"].isArray())
+				return formatFatalError("JSONError", "\"settings.dispatch.priority\" must be an array of function names.");
+			for (auto const& name: dispatch["priority"])
+			{
+				if (!name.isString())
+					return formatFatalError("JSONError", "\"settings.dispatch.priority\" must be an array of function names.");
+				ret.dispatchSettings.priority.push_back(name.asString());
+			}
+		}
+	}
+
 	if (settings.isMember("parserErrorRecovery"))
 	{
 		if (!settings["parserErrorRecovery"].isBool())
@@ -752,7 +805,9 @@ Json::Value StandardCompiler::compileSolidity(StandardCompiler::InputsAndSetting
 	CompilerStack compilerStack(m_readFile);
 
 	StringMap sourceList = std::move(_inputsAndSettings.sources);
-	compilerStack.setSources(sourceList);
+	bool const importAst = _inputsAndSettings.language == "SolidityAST";
+	if (!importAst)
+		compilerStack.setSources(sourceList);
 	// TODO: do we need EVMVersion and other stuff?
 	compilerStack.setEVMVersion(_inputsAndSettings.evmVersion);
 	compilerStack.setParserErrorRecovery(_inputsAndSettings.parserErrorRecovery);
@@ -768,12 +823,27 @@ Json::Value StandardCompiler::compileSolidity(StandardCompiler::InputsAndSetting
 
 	compilerStack.enableEwasmGeneration(isEwasmRequested(_inputsAndSettings.outputSelection));
 
-	if (sourceList.size() != 1) {
-		formatFatalError("JSONError", "Only one source is allowed.");
+	if (importAst)
+	{
+		// The input and everything it imports come as ASTs, the input is the one outputs are selected for
+		string inputFile;
+		for (auto const& name: _inputsAndSettings.outputSelection.getMemberNames())
+			if (sourceList.count(name))
+				inputFile = name;
+		if (inputFile.empty())
+			return formatFatalError("JSONError", "\"outputSelection\" must name one of the imported sources.");
+		compilerStack.setInputFile(inputFile);
+	}
+	else
+	{
+		if (sourceList.size() != 1) {
+			formatFatalError("JSONError", "Only one source is allowed.");
+		}
+		compilerStack.setInputFile(sourceList.begin()->first);
 	}
-	compilerStack.setInputFile(sourceList.begin()->first);
 
 	compilerStack.setMainContract(_inputsAndSettings.mainContract);
+	compilerStack.setDispatchSettings(_inputsAndSettings.dispatchSettings);
 
 	for (auto const& path: _inputsAndSettings.includePaths)
 	{
@@ -788,11 +858,17 @@ Json::Value StandardCompiler::compileSolidity(StandardCompiler::InputsAndSetting
 
 	try
 	{
+		if (importAst)
+			compilerStack.importASTs(parseAstFromInput(sourceList));
 		compilerStack.generateAbi();
 		if (binariesRequested)
 		{
 			compilerStack.generateCode();
 		}
+		else if (importAst)
+		{
+			compilerStack.analyze();
+		}
 		else
 		{
 			compilerStack.parseAndAnalyze();
@@ -863,6 +939,16 @@ Json::Value StandardCompiler::compileSolidity(StandardCompiler::InputsAndSetting
 			"Unimplemented feature (" + _exception.lineInfo() + ")"
 		));
 	}
+	catch (InvalidAstError const& _exception)
+	{
+		errors.append(formatErrorWithException(
+			_exception,
+			false,
+			"InvalidAstError",
+			"general",
+			"Invalid AST"
+		));
+	}
 	catch (util::Exception const& _exception)
 	{
 		errors.append(formatError(
@@ -1011,10 +1097,12 @@ Json::Value StandardCompiler::compile(Json::Value const& _input) noexcept
 		InputsAndSettings settings = boost::get<InputsAndSettings>(std::move(parsed));
 		if (settings.language == "Solidity")
 			return compileSolidity(std::move(settings));
+		else if (settings.language == "SolidityAST")
+			return compileSolidity(std::move(settings));
 		else if (settings.language == "Yul")
 			return compileYul(std::move(settings));
 		else
-			return formatFatalError("JSONError", "Only \"Solidity\" or \"Yul\" is supported as a language.");
+			return formatFatalError("JSONError", "Only \"Solidity\", \"SolidityAST\" or \"Yul\" is supported as a language.");
 	}
 	catch (Json::LogicError const& _exception)
 	{
@@ -62,6 +62,7 @@ private:
 		bool forceRemoteUpdate = false;
 		bool parserErrorRecovery = false;
 		std::string mainContract;
+		DispatchSettings dispatchSettings;
 		std::map<std::string, std::string> sources;
 		std::map<util::h256, std::string> smtLib2Responses;
 		langutil::EVMVersion evmVersion;
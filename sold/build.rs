@@ -87,6 +87,21 @@ fn main() {
     println!("cargo:rustc-env=BUILD_GIT_COMMIT={}", git_commit);
     println!("cargo:rustc-env=BUILD_GIT_DATE={}", commit_date);
     println!("cargo:rustc-env=BUILD_TIME={}", build_time);
+    println!("cargo:rustc-env=BUILD_LINKER_VERSION={}", get_linker_version().unwrap_or_else(|| "Unknown".to_string()));
+}
+
+/// Version of tvm_linker the lock file pins, with the commit of its git source
+fn get_linker_version() -> Option<String> {
+    let lock = std::fs::read_to_string("Cargo.lock").ok()?;
+    let package = lock.split("[[package]]").find(|package| package.contains("name = \"tvm_linker\""))?;
+    let value = |key: &str| package.lines()
+        .find_map(|line| line.strip_prefix(key))
+        .map(|value| value.trim().trim_matches('"').to_string());
+    let version = value("version = ")?;
+    match value("source = ").and_then(|source| source.rsplit_once('#').map(|(_, commit)| commit.to_string())) {
+        Some(commit) => Some(format!("{}+{}", version, commit)),
+        None => Some(version),
+    }
 }
 
 fn get_cpp_stdlib() -> Option<String> {
@@ -0,0 +1,435 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Human-readable ABI. One declaration per line, in the order of the JSON ABI:
+//
+//     abi 2.2
+//     header pubkey, time, expire
+//     function transfer(address dest, uint128 amount) external returns (bool value0)
+//     function getOwner() external returns (tuple(address addr, uint32 since) owner) id 0x1a2b3c4d
+//     event Transferred(address dest, uint128 amount)
+//     data 1 uint256 _owner
+//     field uint256 _pubkey
+//
+// Tuple components are written inline in the type, `//` starts a comment. Both formats
+// convert into each other without loss, so either can be given wherever an ABI is read.
+
+use std::io::Write;
+
+use serde_json::{json, Map, Value};
+
+use crate::error::{invalid_json, Result, SoldError, Status};
+use crate::messages;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct ShowArgs {
+    /// ABI file, in JSON or human-readable format
+    #[clap(value_parser)]
+    pub input: String,
+    /// Print the ABI as JSON instead
+    #[clap(long, value_parser)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct EventIdArgs {
+    /// ABI file, in JSON or human-readable format
+    #[clap(long, value_parser)]
+    pub abi: String,
+    /// Only the event with this name
+    #[clap(long, value_parser)]
+    pub event: Option<String>,
+    /// Print a JSON array instead of a table
+    #[clap(long, value_parser)]
+    pub json: bool,
+}
+
+/// Reads an ABI file in either format.
+pub fn load(path: &str) -> Result<Value> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|_| SoldError::SourceNotFound(path.to_owned()))?;
+    if text.trim_start().starts_with('{') {
+        serde_json::from_str(&text).map_err(|e| invalid_json(path, e))
+    } else {
+        parse(&text).map_err(|e| SoldError::ArgumentError(format!("{}:{}", path, e)))
+    }
+}
+
+pub fn show(args: ShowArgs) -> Status {
+    let abi = load(&args.input)?;
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    if args.json {
+        crate::printer::print_abi_json_canonically(&mut out, &abi)
+    } else {
+        print(&mut out, &abi)
+    }
+}
+
+/// Prints the ID of each event, the first 32 bits of the hash of its signature with the
+/// highest bit cleared, as computed by ton_abi for the external messages carrying it.
+pub fn event_id(args: EventIdArgs) -> Status {
+    let abi = load(&args.abi)?;
+    let contract = ton_abi::Contract::load(abi.to_string().as_bytes())
+        .map_err(|e| SoldError::InvalidOutput(format!("Invalid ABI: {}", e)))?;
+    let mut events: Vec<_> = contract.events().values()
+        .filter(|event| args.event.as_ref().map_or(true, |name| &event.name == name))
+        .collect();
+    if events.is_empty() {
+        return Err(SoldError::ArgumentError(match args.event {
+            Some(name) => format!("No event named \"{}\" in the ABI", name),
+            None => "The ABI has no events".to_owned(),
+        }))
+    }
+    events.sort_by(|a, b| a.name.cmp(&b.name));
+    if args.json {
+        let list: Vec<Value> = events.iter()
+            .map(|event| json!({
+                "name": event.name,
+                "id": format!("0x{:08x}", event.get_function_id()),
+                "signature": event.get_function_signature(),
+            }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&list)?);
+    } else {
+        let width = events.iter().map(|event| event.name.len()).max().unwrap_or_default();
+        for event in events {
+            println!("{:width$}  0x{:08x}  {}", event.name, event.get_function_id(), event.get_function_signature(), width = width);
+        }
+    }
+    Ok(())
+}
+
+fn abi_error() -> SoldError {
+    SoldError::InvalidOutput("ABI parsing failed".to_owned())
+}
+
+/// Type of a parameter with tuple components spelled out
+fn param_type(param: &Value) -> Result<String> {
+    let ty = param["type"].as_str().ok_or_else(abi_error)?;
+    match param["components"].as_array() {
+        Some(components) if ty.contains("tuple") => {
+            let inner = params(components)?;
+            Ok(ty.replacen("tuple", &format!("tuple({})", inner), 1))
+        }
+        _ => Ok(ty.to_owned()),
+    }
+}
+
+pub(crate) fn params(list: &[Value]) -> Result<String> {
+    let mut res = vec![];
+    for param in list {
+        let name = param["name"].as_str().ok_or_else(abi_error)?;
+        res.push(format!("{} {}", param_type(param)?, name));
+    }
+    Ok(res.join(", "))
+}
+
+pub(crate) fn array<'a>(abi: &'a Value, key: &str) -> &'a [Value] {
+    abi[key].as_array().map_or(&[], Vec::as_slice)
+}
+
+pub fn print(out: &mut impl Write, abi: &Value) -> Status {
+    match abi["version"].as_str() {
+        Some(version) => writeln!(out, "abi {}", version)?,
+        None => writeln!(out, "abi {}", abi["ABI version"])?,
+    }
+    let header: Vec<&str> = array(abi, "header").iter().filter_map(Value::as_str).collect();
+    if !header.is_empty() {
+        writeln!(out, "header {}", header.join(", "))?;
+    }
+    for function in array(abi, "functions") {
+        let name = function["name"].as_str().ok_or_else(abi_error)?;
+        write!(out, "function {}({}) external", name, params(array(function, "inputs"))?)?;
+        let outputs = array(function, "outputs");
+        if !outputs.is_empty() {
+            write!(out, " returns ({})", params(outputs)?)?;
+        }
+        if let Some(id) = function["id"].as_str() {
+            write!(out, " id {}", id)?;
+        }
+        writeln!(out)?;
+    }
+    for event in array(abi, "events") {
+        let name = event["name"].as_str().ok_or_else(abi_error)?;
+        write!(out, "event {}({})", name, params(array(event, "inputs"))?)?;
+        if let Some(id) = event["id"].as_str() {
+            write!(out, " id {}", id)?;
+        }
+        writeln!(out)?;
+    }
+    for data in array(abi, "data") {
+        let name = data["name"].as_str().ok_or_else(abi_error)?;
+        writeln!(out, "data {} {} {}", data["key"], param_type(data)?, name)?;
+    }
+    for field in array(abi, "fields") {
+        let name = field["name"].as_str().ok_or_else(abi_error)?;
+        write!(out, "field {} {}", param_type(field)?, name)?;
+        if field["init"] == true {
+            write!(out, " init")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Splits at commas outside parentheses.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut res = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                res.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    res.push(text[start..].trim());
+    res.retain(|part| !part.is_empty());
+    res
+}
+
+/// Index of the parenthesis closing the one at `open`
+fn closing(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i)
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses a type, moving inline tuple components into `components`.
+fn parse_type(ty: &str, param: &mut Map<String, Value>) -> std::result::Result<(), String> {
+    match ty.find("tuple(") {
+        Some(start) => {
+            let open = start + "tuple".len();
+            let close = closing(ty, open).ok_or_else(|| format!("unbalanced parentheses in \"{}\"", ty))?;
+            let components = parse_params(&ty[open + 1..close])?;
+            param.insert("type".to_owned(), json!(format!("{}tuple{}", &ty[..start], &ty[close + 1..])));
+            param.insert("components".to_owned(), Value::Array(components));
+        }
+        None => {
+            param.insert("type".to_owned(), json!(ty));
+        }
+    }
+    Ok(())
+}
+
+/// Parses `type name`, the type possibly containing spaces within parentheses.
+fn parse_param(text: &str) -> std::result::Result<Map<String, Value>, String> {
+    let mut depth = 0;
+    let mut split = None;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => split = Some(i),
+            _ => {}
+        }
+    }
+    let split = split.ok_or_else(|| format!("expected a type and a name, found \"{}\"", text))?;
+    let mut param = Map::new();
+    param.insert("name".to_owned(), json!(text[split..].trim()));
+    parse_type(text[..split].trim(), &mut param)?;
+    Ok(param)
+}
+
+fn parse_params(text: &str) -> std::result::Result<Vec<Value>, String> {
+    split_top_level(text).into_iter()
+        .map(|param| parse_param(param).map(Value::Object))
+        .collect()
+}
+
+/// Splits `name(params) rest` into its parts.
+fn parse_signature(text: &str) -> std::result::Result<(&str, &str, &str), String> {
+    let open = text.find('(').ok_or_else(|| format!("expected parameters in \"{}\"", text))?;
+    let close = closing(text, open).ok_or_else(|| format!("unbalanced parentheses in \"{}\"", text))?;
+    Ok((text[..open].trim(), &text[open + 1..close], text[close + 1..].trim()))
+}
+
+/// Parses ` id 0x...` at the end of a declaration, if any.
+fn parse_id(rest: &str, item: &mut Map<String, Value>) -> std::result::Result<(), String> {
+    if rest.is_empty() {
+        return Ok(())
+    }
+    match rest.strip_prefix("id") {
+        Some(id) if id.starts_with(char::is_whitespace) => {
+            item.insert("id".to_owned(), json!(id.trim()));
+            Ok(())
+        }
+        _ => Err(format!("unexpected \"{}\"", rest)),
+    }
+}
+
+fn parse_function(text: &str) -> std::result::Result<Value, String> {
+    let (name, inputs, rest) = parse_signature(text)?;
+    let rest = rest.strip_prefix("external").map_or(rest, str::trim_start);
+    let (outputs, rest) = match rest.strip_prefix("returns") {
+        Some(returns) => {
+            let returns = returns.trim_start();
+            if !returns.starts_with('(') {
+                return Err(format!("expected parameters after returns in \"{}\"", text))
+            }
+            let close = closing(returns, 0).ok_or_else(|| format!("unbalanced parentheses in \"{}\"", text))?;
+            (parse_params(&returns[1..close])?, returns[close + 1..].trim())
+        }
+        None => (vec![], rest),
+    };
+    let mut function = Map::new();
+    function.insert("name".to_owned(), json!(name));
+    parse_id(rest, &mut function)?;
+    function.insert("inputs".to_owned(), Value::Array(parse_params(inputs)?));
+    function.insert("outputs".to_owned(), Value::Array(outputs));
+    Ok(Value::Object(function))
+}
+
+fn parse_event(text: &str) -> std::result::Result<Value, String> {
+    let (name, inputs, rest) = parse_signature(text)?;
+    let mut event = Map::new();
+    event.insert("name".to_owned(), json!(name));
+    parse_id(rest, &mut event)?;
+    event.insert("inputs".to_owned(), Value::Array(parse_params(inputs)?));
+    Ok(Value::Object(event))
+}
+
+fn parse_line(line: &str, abi: &mut Map<String, Value>) -> std::result::Result<(), String> {
+    let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let push = |abi: &mut Map<String, Value>, key: &str, item: Value| {
+        if let Some(Value::Array(items)) = abi.get_mut(key) {
+            items.push(item);
+        }
+    };
+    match keyword {
+        "abi" => {
+            let major = rest.split('.').next().and_then(|major| major.parse::<u8>().ok())
+                .ok_or_else(|| format!("invalid ABI version \"{}\"", rest))?;
+            abi.insert("ABI version".to_owned(), json!(major));
+            if rest.contains('.') {
+                abi.insert("version".to_owned(), json!(rest));
+            }
+        }
+        "header" => {
+            let header: Vec<&str> = split_top_level(rest);
+            abi.insert("header".to_owned(), json!(header));
+        }
+        "function" => push(abi, "functions", parse_function(rest)?),
+        "event" => push(abi, "events", parse_event(rest)?),
+        "data" => {
+            let (key, param) = rest.split_once(char::is_whitespace)
+                .ok_or_else(|| format!("expected a key, a type and a name, found \"{}\"", rest))?;
+            let key = key.parse::<u64>().map_err(|_| format!("invalid data key \"{}\"", key))?;
+            let mut data = Map::new();
+            data.insert("key".to_owned(), json!(key));
+            data.extend(parse_param(param.trim())?);
+            push(abi, "data", Value::Object(data));
+        }
+        "field" => {
+            let (param, init) = match rest.strip_suffix(" init") {
+                Some(param) => (param, true),
+                None => (rest, false),
+            };
+            let mut field = parse_param(param.trim())?;
+            if init {
+                field.insert("init".to_owned(), json!(true));
+            }
+            if !abi.contains_key("fields") {
+                abi.insert("fields".to_owned(), json!([]));
+            }
+            push(abi, "fields", Value::Object(field));
+        }
+        _ => return Err(format!("unknown declaration \"{}\"", keyword)),
+    }
+    Ok(())
+}
+
+/// Parses the human-readable format into a JSON ABI. Errors are prefixed with the line number.
+pub fn parse(text: &str) -> std::result::Result<Value, String> {
+    let mut abi = Map::new();
+    abi.insert("ABI version".to_owned(), json!(2));
+    abi.insert("functions".to_owned(), json!([]));
+    abi.insert("data".to_owned(), json!([]));
+    abi.insert("events".to_owned(), json!([]));
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue
+        }
+        parse_line(line, &mut abi).map_err(|e| format!("{}: {}", i + 1, e))?;
+    }
+    Ok(Value::Object(abi))
+}
+
+/// Matches a name against a pattern where `*` stands for any characters and `?` for one.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Positions to resume from after the last `*`
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// ABI to publish: the functions whose names match any of `include` (all if it is empty)
+/// and none of `exclude`. The compiled code is not affected.
+pub fn published(abi: &Value, include: &[String], exclude: &[String]) -> Value {
+    if include.is_empty() && exclude.is_empty() {
+        return abi.clone()
+    }
+    let names: Vec<&str> = array(abi, "functions").iter()
+        .filter_map(|function| function["name"].as_str())
+        .collect();
+    for pattern in include.iter().chain(exclude) {
+        if !names.iter().any(|name| matches(pattern, name)) {
+            messages::warning(&format!("Warning: ABI pattern \"{}\" does not match any function", pattern));
+        }
+    }
+    let mut res = abi.clone();
+    if let Some(functions) = res["functions"].as_array_mut() {
+        functions.retain(|function| {
+            let name = function["name"].as_str().unwrap_or_default();
+            (include.is_empty() || include.iter().any(|pattern| matches(pattern, name)))
+                && !exclude.iter().any(|pattern| matches(pattern, name))
+        });
+    }
+    res
+}
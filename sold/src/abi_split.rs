@@ -0,0 +1,69 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The on-chain ABI leaves out the getters: view and pure functions and the accessors of
+// public state variables, which SDKs run locally on the account state rather than call
+// with messages. The ABI JSON doesn't tell them apart, so they are found in the AST of
+// the contract and its bases.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde_json::Value;
+
+use crate::ast;
+
+/// Names of the getters of `contract`
+pub fn getters(contract: &str, sources: &BTreeMap<&str, &Value>) -> BTreeSet<String> {
+    let mut contracts = HashMap::new();
+    for ast in sources.values() {
+        ast::walk(ast, &mut |node| {
+            if node["nodeType"] == "ContractDefinition" {
+                if let Some(id) = node["id"].as_u64() {
+                    contracts.insert(id, node);
+                }
+            }
+        });
+    }
+    let bases = contracts.values()
+        .find(|node| node["name"] == contract)
+        .and_then(|node| node["linearizedBaseContracts"].as_array())
+        .map_or(&[][..], Vec::as_slice);
+
+    let mut res = BTreeSet::new();
+    for base in bases.iter().filter_map(|id| contracts.get(&id.as_u64()?)) {
+        for member in base["nodes"].as_array().map_or(&[][..], Vec::as_slice) {
+            let public = matches!(member["visibility"].as_str(), Some("public" | "external"));
+            let getter = match member["nodeType"].as_str() {
+                Some("FunctionDefinition") => member["kind"] == "function"
+                    && matches!(member["stateMutability"].as_str(), Some("view" | "pure")),
+                Some("VariableDeclaration") => member["stateVariable"] == true && member["constant"] != true,
+                _ => false,
+            };
+            if public && getter {
+                if let Some(name) = member["name"].as_str() {
+                    res.insert(name.to_owned());
+                }
+            }
+        }
+    }
+    res
+}
+
+/// `abi` without the functions named in `getters`
+pub fn onchain(abi: &Value, getters: &BTreeSet<String>) -> Value {
+    let mut res = abi.clone();
+    if let Some(functions) = res["functions"].as_array_mut() {
+        functions.retain(|function| !function["name"].as_str().map_or(false, |name| getters.contains(name)));
+    }
+    res
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Build archive. Two builds of the same sources give byte-identical archives: entries
+// are sorted, owners and permissions are fixed, and all timestamps, the gzip one
+// included, are SOURCE_DATE_EPOCH or zero.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use flate2::{Compression, GzBuilder};
+
+use crate::error::Status;
+
+fn timestamp() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH").ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Packs the artifacts, named relative to the output directory, into a .tar.gz file.
+pub fn write(path: &Path, artifacts: &[PathBuf], output_dir: &Path) -> Status {
+    let mtime = timestamp();
+    let mut entries: Vec<(PathBuf, &PathBuf)> = artifacts.iter()
+        .map(|artifact| {
+            let name = artifact.strip_prefix(output_dir).ok()
+                .or_else(|| artifact.file_name().map(Path::new))
+                .unwrap_or(artifact);
+            (name.to_path_buf(), artifact)
+        })
+        .collect();
+    entries.sort();
+    entries.dedup_by(|a, b| a.0 == b.0);
+
+    let gz = GzBuilder::new()
+        .mtime(mtime as u32)
+        .write(File::create(path)?, Compression::best());
+    let mut tar = tar::Builder::new(gz);
+    for (name, artifact) in entries {
+        let content = std::fs::read(artifact)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_mtime(mtime);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+        tar.append_data(&mut header, name, content.as_slice())?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
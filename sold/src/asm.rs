@@ -0,0 +1,261 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Reader for the assembly printed by the compiler (see Printer in TvmAstVisitor.cpp):
+// a few top-level pragmas followed by functions, each introduced by `.globl`/`.type`,
+// `.macro` or `.internal-alias`/`.internal` directives and terminated by a blank line.
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FunctionKind {
+    Private,
+    Macro,
+    Internal,
+}
+
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub name: String,
+    pub kind: FunctionKind,
+    /// Directives introducing the function
+    pub header: Vec<String>,
+    /// Body lines as printed, without the trailing blank line
+    pub body: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Assembly {
+    /// Lines preceding the first function, e.g. `.version sol 0.61.2`
+    pub prologue: Vec<String>,
+    pub functions: Vec<Function>,
+}
+
+impl Assembly {
+    pub fn parse(text: &str) -> Assembly {
+        let mut res = Assembly::default();
+        let mut lines = text.lines().peekable();
+        while let Some(line) = lines.next() {
+            let function = if let Some(name) = line.strip_prefix(".globl") {
+                let mut header = vec![line.to_owned()];
+                if let Some(next) = lines.next_if(|next| next.starts_with(".type")) {
+                    header.push(next.to_owned());
+                }
+                Some((name.trim(), FunctionKind::Private, header))
+            } else if let Some(name) = line.strip_prefix(".macro ") {
+                Some((name.trim(), FunctionKind::Macro, vec![line.to_owned()]))
+            } else if let Some(alias) = line.strip_prefix(".internal-alias :") {
+                let mut header = vec![line.to_owned()];
+                if let Some(next) = lines.next_if(|next| next.starts_with(".internal ")) {
+                    header.push(next.to_owned());
+                }
+                let name = alias.split(',').next().unwrap_or_default();
+                Some((name.trim(), FunctionKind::Internal, header))
+            } else if let Some(name) = line.strip_prefix(".internal :") {
+                Some((name.trim(), FunctionKind::Internal, vec![line.to_owned()]))
+            } else {
+                None
+            };
+            match function {
+                Some((name, kind, header)) => res.functions.push(Function {
+                    name: name.to_owned(),
+                    kind,
+                    header,
+                    body: vec![],
+                }),
+                None => match res.functions.last_mut() {
+                    Some(function) => function.body.push(line.to_owned()),
+                    None => res.prologue.push(line.to_owned()),
+                },
+            }
+        }
+        for function in &mut res.functions {
+            while function.body.last().map_or(false, |line| line.trim().is_empty()) {
+                function.body.pop();
+            }
+        }
+        res
+    }
+
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    /// Prints the assembly in a form suitable for diffing: the compiler version and
+    /// comments are dropped, headers are reprinted uniformly and functions are sorted
+    /// by kind and name instead of following declaration order.
+    pub fn to_canonical_string(&self) -> String {
+        let mut res = String::new();
+        for line in &self.prologue {
+            let line = strip_comment(line).trim_end();
+            if !line.is_empty() && !line.starts_with(".version") {
+                res.push_str(line);
+                res.push_str("\n\n");
+            }
+        }
+        let mut functions: Vec<&Function> = self.functions.iter().collect();
+        functions.sort_by_key(|f| (f.kind.order(), &f.name));
+        for function in functions {
+            match function.kind {
+                FunctionKind::Private => {
+                    res.push_str(&format!(".globl\t{}\n.type\t{}, @function\n", function.name, function.name));
+                }
+                FunctionKind::Macro => res.push_str(&format!(".macro {}\n", function.name)),
+                FunctionKind::Internal => {
+                    for line in &function.header {
+                        res.push_str(line.trim_end());
+                        res.push('\n');
+                    }
+                }
+            }
+            for line in &function.body {
+                let line = strip_comment(line).trim_end();
+                if !line.is_empty() {
+                    res.push_str(line);
+                    res.push('\n');
+                }
+            }
+            res.push('\n');
+        }
+        res
+    }
+}
+
+impl FunctionKind {
+    fn order(self) -> u8 {
+        match self {
+            FunctionKind::Internal => 0,
+            FunctionKind::Macro => 1,
+            FunctionKind::Private => 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Loc {
+    pub file: String,
+    pub line: usize,
+}
+
+impl fmt::Display for Loc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// A single instruction or directive, e.g. `PUSHINT 1` or `.blob x4_`
+    Op { op: String, args: String, loc: Option<Loc> },
+    /// An instruction with an inline body, e.g. `PUSHCONT { ... }`
+    Block { op: String, body: Vec<Node>, loc: Option<Loc> },
+}
+
+/// Builds the instruction tree of a function body. `.loc` directives are not kept
+/// as nodes, instead every node remembers the last location seen before it.
+pub fn parse_body(lines: &[String]) -> Vec<Node> {
+    let mut pos = 0;
+    let mut loc = None;
+    parse_block(lines, &mut pos, &mut loc)
+}
+
+fn parse_block(lines: &[String], pos: &mut usize, loc: &mut Option<Loc>) -> Vec<Node> {
+    let mut nodes = vec![];
+    while *pos < lines.len() {
+        let line = strip_comment(&lines[*pos]).trim();
+        *pos += 1;
+        if line.is_empty() {
+            continue
+        }
+        if line == "}" {
+            break
+        }
+        if let Some(rest) = line.strip_prefix(".loc ") {
+            *loc = parse_loc(rest);
+            continue
+        }
+        if let Some(op) = line.strip_suffix('{') {
+            let here = loc.clone();
+            let body = parse_block(lines, pos, loc);
+            nodes.push(Node::Block { op: op.trim().to_owned(), body, loc: here });
+            continue
+        }
+        let (op, args) = match line.split_once(char::is_whitespace) {
+            Some((op, args)) => (op, args.trim()),
+            None => (line, ""),
+        };
+        nodes.push(Node::Op { op: op.to_owned(), args: args.to_owned(), loc: loc.clone() });
+    }
+    nodes
+}
+
+pub fn strip_comment(line: &str) -> &str {
+    match line.find(';') {
+        Some(pos) => &line[..pos],
+        None => line,
+    }
+}
+
+/// Parses the arguments of `.loc file, line`.
+pub fn parse_loc(args: &str) -> Option<Loc> {
+    let (file, line) = args.rsplit_once(',')?;
+    Some(Loc {
+        file: file.trim().to_owned(),
+        line: line.trim().parse().ok()?,
+    })
+}
+
+/// Whether `strip` drops `line`: a `.loc` directive or a line holding only a comment
+pub fn is_stripped(line: &str) -> bool {
+    let code = strip_comment(line).trim_end();
+    code.trim_start().starts_with(".loc ") || (code.trim().is_empty() && !line.trim().is_empty())
+}
+
+/// Removes `.loc` directives and comments, keeping only what ends up in the code.
+pub fn strip(text: &str) -> String {
+    let mut res = String::new();
+    for line in text.lines().filter(|line| !is_stripped(line)) {
+        res.push_str(strip_comment(line).trim_end());
+        res.push('\n');
+    }
+    res
+}
+
+/// Appends the referenced source line to every `.loc` directive.
+pub fn annotate(text: &str) -> String {
+    let mut sources: HashMap<String, Option<Vec<String>>> = HashMap::new();
+    let mut res = String::new();
+    for line in text.lines() {
+        res.push_str(line);
+        if let Some(loc) = line.trim_start().strip_prefix(".loc ").and_then(parse_loc) {
+            let source = sources.entry(loc.file.clone()).or_insert_with(|| {
+                std::fs::read_to_string(&loc.file).ok()
+                    .map(|text| text.lines().map(|l| l.trim().to_owned()).collect())
+            });
+            if let Some(text) = source.as_ref().and_then(|lines| lines.get(loc.line.wrapping_sub(1))) {
+                if !text.is_empty() {
+                    res.push_str(" ; ");
+                    res.push_str(text);
+                }
+            }
+        }
+        res.push('\n');
+    }
+    res
+}
+
+/// Extracts the callee of `CALL $name$`.
+pub fn call_target(args: &str) -> Option<&str> {
+    args.trim().strip_prefix('$')?.strip_suffix('$')
+}
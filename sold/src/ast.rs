@@ -0,0 +1,131 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Access to the compact JSON AST of a set of sources for the tools working on the
+// project as a whole. Declarations in this AST carry the range of the whole declaration
+// only, so the range of the name itself is recovered from the source text.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+use crate::error::Result;
+use crate::lexer::{self, TokenKind};
+
+/// Source range of a node, as given by its `src` attribute
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Src {
+    pub start: usize,
+    pub end: usize,
+    /// Index of the source unit
+    pub file: usize,
+}
+
+impl Src {
+    pub fn of(node: &Value) -> Option<Src> {
+        let mut parts = node["src"].as_str()?.split(':').map(|x| x.parse::<usize>().ok());
+        let start = parts.next()??;
+        let length = parts.next()??;
+        let file = parts.next()??;
+        Some(Src { start, end: start + length, file })
+    }
+}
+
+/// The analyzed sources, keyed by the path they were read from
+pub struct Project {
+    pub units: BTreeMap<String, Value>,
+}
+
+impl Project {
+    /// Runs the frontend on all files at once and keeps their ASTs.
+    pub fn load(files: &[PathBuf], include_paths: &[String]) -> Result<Project> {
+        let mut sources = serde_json::Map::new();
+        for file in files {
+            let path = file.to_string_lossy();
+            sources.insert(path.to_string(), json!({ "urls": [ path ] }));
+        }
+        let request = json!({
+            "language": "Solidity",
+            "settings": {
+                "includePaths": include_paths,
+                "outputSelection": { "*": { "": [ "ast" ] } },
+            },
+            "sources": sources,
+        });
+        let res = crate::run_compiler(&request.to_string())?;
+        crate::diagnostics::check_errors(&res.errors, crate::diagnostics::ErrorFormat::Human)?;
+        let units = res.sources.into_iter()
+            .filter_map(|(path, source)| Some((path, source.ast?)))
+            .collect();
+        Ok(Project { units })
+    }
+
+    pub fn node_by_id(&self, id: u64) -> Option<&Value> {
+        let mut res = None;
+        for unit in self.units.values() {
+            walk(unit, &mut |node| {
+                if res.is_none() && node["id"].as_u64() == Some(id) {
+                    res = Some(node);
+                }
+            });
+        }
+        res
+    }
+}
+
+/// Calls `f` for every AST node under `node`, parents first.
+pub fn walk<'a>(node: &'a Value, f: &mut impl FnMut(&'a Value)) {
+    match node {
+        Value::Object(map) => {
+            if map.contains_key("nodeType") {
+                f(node);
+            }
+            for value in map.values() {
+                walk(value, f);
+            }
+        }
+        Value::Array(items) => {
+            for value in items {
+                walk(value, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Range of the name of a declaration in `text`. The name is the first identifier equal to
+/// it, after the type for variables.
+pub fn declaration_name(text: &str, node: &Value) -> Option<Range<usize>> {
+    let name = node["name"].as_str().filter(|name| !name.is_empty())?;
+    let src = Src::of(node)?;
+    let start = Src::of(&node["typeName"]).map_or(src.start, |type_src| type_src.end);
+    let token = lexer::tokenize(text.get(start..src.end)?).into_iter()
+        .find(|t| t.kind == TokenKind::Identifier && t.text == name)?;
+    Some(start + token.start..start + token.end())
+}
+
+/// Range of the last name of a reference (`name`, `a.name`, `A.B.name`) in `text`.
+pub fn reference_name(text: &str, node: &Value, name: &str) -> Option<Range<usize>> {
+    let src = Src::of(node)?;
+    let range = src.end.checked_sub(name.len())?..src.end;
+    if range.start < src.start || text.get(range.clone())? != name {
+        return None
+    }
+    let before = text[..range.start].chars().next_back();
+    if before.map_or(false, |c| c.is_alphanumeric() || c == '_' || c == '$') {
+        return None
+    }
+    Some(range)
+}
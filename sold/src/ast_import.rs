@@ -0,0 +1,96 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Input given as an AST exported with --ast-json or --ast-compact-json, possibly rewritten
+// by other tools since. Its source units are passed to the frontend with the "SolidityAST"
+// language, which skips parsing, and the contract is built as if from the source the AST
+// was exported from: the one none of the others imports.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use crate::error::{invalid_json, Result, SoldError};
+
+/// Tells whether `input` names an exported AST rather than a source file
+pub fn is_ast(input: &str) -> bool {
+    input.ends_with(".json")
+}
+
+fn load(input: &str) -> Result<Vec<Value>> {
+    let text = std::fs::read_to_string(input)
+        .map_err(|_| SoldError::SourceNotFound(input.to_owned()))?;
+    let units = match serde_json::from_str(&text).map_err(|e| invalid_json(input, e))? {
+        Value::Array(units) => units,
+        unit => vec![unit],
+    };
+    for unit in &units {
+        if unit["nodeType"] != "SourceUnit" || !unit["absolutePath"].is_string() {
+            return Err(SoldError::ArgumentError(format!(
+                "\"{}\" is not an AST exported with --ast-json: every element must be a SourceUnit", input
+            )))
+        }
+    }
+    Ok(units)
+}
+
+fn root(input: &str, units: &[Value]) -> Result<String> {
+    let mut imported = BTreeSet::new();
+    for unit in units {
+        crate::ast::walk(unit, &mut |node| {
+            if node["nodeType"] == "ImportDirective" {
+                if let Some(path) = node["absolutePath"].as_str() {
+                    imported.insert(path.to_owned());
+                }
+            }
+        });
+    }
+    let roots: Vec<&str> = units.iter()
+        .filter_map(|unit| unit["absolutePath"].as_str())
+        .filter(|path| !imported.contains(*path))
+        .collect();
+    match roots.as_slice() {
+        [root] => Ok(root.to_string()),
+        [] => Err(SoldError::ArgumentError(format!("\"{}\" has no source that isn't imported by another", input))),
+        _ => Err(SoldError::ArgumentError(format!(
+            "\"{}\" has several sources that aren't imported by another: {}", input, roots.join(", ")
+        ))),
+    }
+}
+
+/// Path of the source the AST in `input` was exported from
+pub fn main_source(input: &str) -> Result<String> {
+    root(input, &load(input)?)
+}
+
+/// Standard JSON input importing `units`, source units by path, with the settings of
+/// `args` and the outputs of `main` selected
+pub fn import_input(args: &crate::Args, main: &str, units: BTreeMap<String, Value>) -> Result<Value> {
+    let mut json: Value = serde_json::from_str(&crate::standard_json_input(args, main))?;
+    json["language"] = Value::from("SolidityAST");
+    json["sources"] = units.into_iter()
+        .map(|(path, unit)| (path, serde_json::json!({ "ast": unit })))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    Ok(json)
+}
+
+/// Standard JSON input building the AST in `input` with the settings of `args`
+pub fn standard_json_input(args: &crate::Args, input: &str) -> Result<String> {
+    let units = load(input)?;
+    let main = root(input, &units)?;
+    let units = units.into_iter()
+        .map(|unit| (unit["absolutePath"].as_str().unwrap_or_default().to_owned(), unit))
+        .collect();
+    Ok(import_input(args, &main, units)?.to_string())
+}
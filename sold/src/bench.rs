@@ -0,0 +1,203 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Runs a getter of a built contract on the TVM over and over, the way the SDKs run them
+// off-chain: an external inbound message calling the function is given to the contract
+// whose data is that of the TVC. Gas and wall time of each run are collected, which is
+// the measure to compare alternative implementations by.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use ton_block::{ExternalInboundMessageHeader, Message, MsgAddressInt, Serializable};
+use ton_types::{BuilderData, Cell, SliceData};
+use ton_vm::executor::gas::gas_state::Gas;
+use ton_vm::executor::Engine;
+use ton_vm::stack::integer::IntegerData;
+use ton_vm::stack::savelist::SaveList;
+use ton_vm::stack::{Stack, StackItem};
+use ton_vm::SmartContractInfo;
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct BenchArgs {
+    /// Output prefix of the contract, whose <prefix>.abi.json and <prefix>.tvc are run
+    #[clap(value_parser)]
+    pub prefix: String,
+    /// Directory of the artifacts (by default, current directory is used)
+    #[clap(short('O'), long, value_parser)]
+    pub output_dir: Option<String>,
+    /// Function to run
+    #[clap(long, value_parser)]
+    pub getter: String,
+    /// Parameters of the function, inline JSON or @file
+    #[clap(long, value_parser)]
+    pub params: Option<String>,
+    /// Number of runs
+    #[clap(long, value_parser, default_value_t = 100)]
+    pub iters: usize,
+    /// Print the statistics in JSON format
+    #[clap(long, value_parser)]
+    pub json: bool,
+}
+
+/// Balance of the account while running, in nanoevers
+const BALANCE: u64 = 1_000_000_000;
+const GAS_LIMIT: i64 = 1_000_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    function: String,
+    iterations: usize,
+    exit_code: i32,
+    gas_min: i64,
+    gas_max: i64,
+    gas_mean: f64,
+    /// Wall times in microseconds
+    time_min: f64,
+    time_median: f64,
+    time_mean: f64,
+    time_p95: f64,
+    time_max: f64,
+}
+
+fn invalid(e: impl std::fmt::Display) -> SoldError {
+    SoldError::InvalidOutput(e.to_string())
+}
+
+/// The contract at the address given by the hash of its StateInit
+struct Account {
+    address: MsgAddressInt,
+    code: Cell,
+    data: Cell,
+}
+
+impl Account {
+    fn load(tvc_path: &str) -> Result<Account> {
+        let state = ton_utils::program::load_from_file(tvc_path)
+            .map_err(|_| SoldError::SourceNotFound(tvc_path.to_owned()))?;
+        let hash = state.hash().map_err(invalid)?;
+        Ok(Account {
+            address: MsgAddressInt::with_standart(None, 0, hash.into()).map_err(invalid)?,
+            code: state.code.clone().ok_or_else(|| SoldError::InvalidOutput("The TVC has no code".to_owned()))?,
+            data: state.data.clone().unwrap_or_default(),
+        })
+    }
+
+    /// External inbound message with `body` and the body itself
+    fn message(&self, body: Cell) -> Result<(Cell, SliceData)> {
+        let header = ExternalInboundMessageHeader { dst: self.address.clone(), ..Default::default() };
+        let message = Message::with_ext_in_header_and_body(header, body.clone().into());
+        Ok((message.serialize().map_err(invalid)?, body.into()))
+    }
+
+    /// Exit code and gas used by one run of the external message handler
+    fn run(&self, message: &Cell, body: &SliceData) -> (i32, i64) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as u32;
+        let mut info = SmartContractInfo::with_myself(self.address.serialize().unwrap_or_default().into());
+        *info.unix_time_mut() = now;
+        *info.balance_remaining_grams_mut() = BALANCE as u128;
+        let mut ctrls = SaveList::new();
+        ctrls.put(4, &mut StackItem::Cell(self.data.clone())).ok();
+        ctrls.put(7, &mut info.into_temp_data_item()).ok();
+
+        let mut stack = Stack::new();
+        stack
+            .push(StackItem::int(BALANCE))
+            .push(StackItem::int(0))
+            .push(StackItem::Cell(message.clone()))
+            .push(StackItem::Slice(body.clone()))
+            .push(StackItem::Integer(std::sync::Arc::new(IntegerData::minus_one())));
+
+        let mut engine = Engine::with_capabilities(0).setup_with_libraries(
+            SliceData::from(self.code.clone()),
+            Some(ctrls),
+            Some(stack),
+            Some(Gas::test_with_limit(GAS_LIMIT)),
+            vec![],
+        );
+        let exit_code = match engine.execute() {
+            Ok(code) => code,
+            Err(e) => ton_vm::error::tvm_exception_code(&e).map_or(-1, |code| code as i32),
+        };
+        (exit_code, engine.gas_used())
+    }
+}
+
+fn micros(duration: &Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+pub fn bench(args: BenchArgs) -> Status {
+    if args.iters == 0 {
+        return Err(SoldError::ArgumentError("Option --iters must be positive".to_owned()))
+    }
+    let output_dir = std::path::Path::new(args.output_dir.as_deref().unwrap_or("."));
+    let abi = crate::abi::load(&output_dir.join(format!("{}.abi.json", args.prefix)).to_string_lossy())?;
+    let account = Account::load(&output_dir.join(format!("{}.tvc", args.prefix)).to_string_lossy())?;
+    let params = args.params.as_deref().map(|value| crate::json_argument("--params", value)).transpose()?;
+
+    let body = ton_abi::json_abi::encode_function_call(
+        &abi.to_string(),
+        &args.getter,
+        None,
+        params.as_deref().unwrap_or("{}"),
+        false,
+        None,
+        Some(&account.address.to_string()),
+    ).map_err(|e| SoldError::ArgumentError(format!("Failed to encode the call of {}: {}", args.getter, e)))?;
+    let body = body.into_cell().map_err(invalid)?;
+    let (message, body) = account.message(body)?;
+
+    let mut gas = vec![];
+    let mut times = vec![];
+    let mut exit_code = 0;
+    for _ in 0..args.iters {
+        let started = Instant::now();
+        let (code, used) = account.run(&message, &body);
+        times.push(started.elapsed());
+        gas.push(used);
+        exit_code = code;
+        if code != 0 && code != 1 {
+            return Err(SoldError::ArgumentError(format!("{} failed with exit code {}", args.getter, code)))
+        }
+    }
+    times.sort();
+    let percentile = |p: usize| micros(&times[(times.len() - 1) * p / 100]);
+    let stats = Stats {
+        function: args.getter.clone(),
+        iterations: args.iters,
+        exit_code,
+        gas_min: *gas.iter().min().unwrap_or(&0),
+        gas_max: *gas.iter().max().unwrap_or(&0),
+        gas_mean: gas.iter().sum::<i64>() as f64 / gas.len() as f64,
+        time_min: percentile(0),
+        time_median: percentile(50),
+        time_mean: times.iter().map(micros).sum::<f64>() / times.len() as f64,
+        time_p95: percentile(95),
+        time_max: percentile(100),
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(())
+    }
+    println!("{}: {} runs", stats.function, stats.iterations);
+    println!("    gas   min {}, mean {:.1}, max {}", stats.gas_min, stats.gas_mean, stats.gas_max);
+    println!(
+        "    time  min {:.1} us, median {:.1} us, mean {:.1} us, p95 {:.1} us, max {:.1} us",
+        stats.time_min, stats.time_median, stats.time_mean, stats.time_p95, stats.time_max,
+    );
+    Ok(())
+}
@@ -0,0 +1,97 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+use ton_block::StateInit;
+
+use crate::error::{SoldError, Status};
+use crate::printer;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BindgenTarget {
+    /// TypeScript definitions for everscale-inpage-provider
+    InpageProvider,
+}
+
+impl BindgenTarget {
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            BindgenTarget::InpageProvider => "abi.ts",
+        }
+    }
+}
+
+pub fn generate(
+    target: BindgenTarget,
+    out: &mut impl Write,
+    name: &str,
+    abi: &serde_json::Value,
+    tvc: &[u8],
+    state: &StateInit,
+) -> Status {
+    match target {
+        BindgenTarget::InpageProvider => inpage_provider(out, name, abi, tvc, state),
+    }
+}
+
+/// Converts an output prefix such as `my-wallet` into `MyWallet`.
+fn type_name(name: &str) -> String {
+    let mut res = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            res.push(first.to_ascii_uppercase());
+            res.extend(chars);
+        }
+    }
+    if res.is_empty() || res.starts_with(|c: char| c.is_ascii_digit()) {
+        res.insert(0, '_');
+    }
+    res
+}
+
+fn inpage_provider(out: &mut impl Write, name: &str, abi: &serde_json::Value, tvc: &[u8], state: &StateInit) -> Status {
+    let code = state.code.as_ref()
+        .ok_or_else(|| SoldError::InvalidOutput("Contract state has no code".to_owned()))?;
+    let mut abi_text = vec![];
+    printer::print_abi_json_canonically(&mut abi_text, abi)?;
+    let abi_text = String::from_utf8_lossy(&abi_text);
+
+    let ty = type_name(name);
+    writeln!(out, "// Generated by sold {}. Do not edit.", env!("CARGO_PKG_VERSION"))?;
+    writeln!(out, "import {{ Address, GetExpectedAddressParams, ProviderRpcClient }} from 'everscale-inpage-provider';")?;
+    writeln!(out)?;
+    write!(out, "export const {}Abi = ", ty)?;
+    let lines: Vec<&str> = abi_text.trim_end().lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        let suffix = if i + 1 == lines.len() { " as const;" } else { "" };
+        writeln!(out, "{}{}", line, suffix)?;
+    }
+    writeln!(out)?;
+    writeln!(out, "export const {}Tvc = '{}';", ty, base64::encode(tvc))?;
+    writeln!(out, "export const {}CodeHash = '{}';", ty, code.repr_hash().to_hex_string())?;
+    writeln!(out)?;
+    writeln!(out, "export type {}DeployParams = Omit<GetExpectedAddressParams<typeof {}Abi>, 'tvc'>;", ty, ty)?;
+    writeln!(out)?;
+    writeln!(out, "export function {}Contract(provider: ProviderRpcClient, address: Address | string) {{", ty)?;
+    writeln!(out, "\treturn new provider.Contract({}Abi, typeof address === 'string' ? new Address(address) : address);", ty)?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "export async function get{}StateInit(provider: ProviderRpcClient, params: {}DeployParams) {{", ty, ty)?;
+    writeln!(out, "\treturn provider.getStateInit({}Abi, {{ ...params, tvc: {}Tvc }});", ty, ty)?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
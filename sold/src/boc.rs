@@ -0,0 +1,136 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Inspection of any bag of cells: a TVC, a message body, a state dump. Cells are selected
+// by path, the index of the root followed by the indices of the references to follow, so
+// `0.1.0` is the first reference of the second reference of the first root.
+
+use std::collections::{BTreeMap, HashSet};
+
+use ton_types::Cell;
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct InspectArgs {
+    /// BOC file, binary or base64 encoded
+    #[clap(value_parser)]
+    pub input: String,
+    /// Dump the data of the cell at the given path, e.g. 0.1 for the second reference of the first root
+    #[clap(long, value_parser)]
+    pub cell: Vec<String>,
+    /// Dump the data starting from the given bit
+    #[clap(long, value_parser, default_value_t = 0)]
+    pub offset: usize,
+    /// Dump at most the given number of bits
+    #[clap(long, value_parser)]
+    pub length: Option<usize>,
+}
+
+fn invalid(e: impl std::fmt::Display) -> SoldError {
+    SoldError::InvalidOutput(format!("Invalid BOC: {}", e))
+}
+
+fn load(input: &str) -> Result<Vec<Cell>> {
+    let bytes = std::fs::read(input).map_err(|_| SoldError::SourceNotFound(input.to_owned()))?;
+    // Text files are taken for base64
+    let bytes = match std::str::from_utf8(&bytes) {
+        Ok(text) => base64::decode(text.trim()).map_err(invalid)?,
+        Err(_) => bytes,
+    };
+    ton_types::deserialize_cells_tree(&mut std::io::Cursor::new(bytes)).map_err(invalid)
+}
+
+/// Hex of the bits `start..end` of `data`, with the completion tag `_` when they don't
+/// fill the last hex digit
+fn bits_hex(data: &[u8], start: usize, end: usize) -> String {
+    let bit = |i: usize| (data[i / 8] >> (7 - i % 8)) & 1;
+    let mut bits: Vec<u8> = (start..end).map(bit).collect();
+    let tagged = bits.len() % 4 != 0;
+    if tagged {
+        bits.push(1);
+        while bits.len() % 4 != 0 {
+            bits.push(0);
+        }
+    }
+    let mut res: String = bits.chunks(4)
+        .map(|nibble| format!("{:x}", nibble.iter().fold(0, |acc, bit| (acc << 1) | bit)))
+        .collect();
+    if tagged {
+        res.push('_');
+    }
+    res
+}
+
+fn cell_at(roots: &[Cell], path: &str) -> Result<Cell> {
+    let bad_path = || SoldError::ArgumentError(format!(
+        "Invalid cell path \"{}\", expected the root index followed by reference indices, e.g. 0.1", path
+    ));
+    let mut indices = path.split('.').map(|index| index.parse::<usize>().map_err(|_| bad_path()));
+    let root = indices.next().ok_or_else(bad_path)??;
+    let mut cell = roots.get(root)
+        .ok_or_else(|| SoldError::ArgumentError(format!("There is no root {} in the BOC", root)))?
+        .clone();
+    for index in indices {
+        let index = index?;
+        cell = cell.reference(index)
+            .map_err(|_| SoldError::ArgumentError(format!("Cell {} has no reference {}", path, index)))?;
+    }
+    Ok(cell)
+}
+
+fn print_hashes(cell: &Cell) {
+    for level in 0..=cell.level() as usize {
+        println!("  level {}: hash {} depth {}", level, cell.hash(level).to_hex_string(), cell.depth(level));
+    }
+}
+
+pub fn inspect(args: InspectArgs) -> Status {
+    let roots = load(&args.input)?;
+    println!("Roots: {}", roots.len());
+
+    let mut visited = HashSet::new();
+    let mut stack: Vec<Cell> = roots.clone();
+    let (mut bits, mut refs) = (0, 0);
+    let mut types: BTreeMap<String, usize> = BTreeMap::new();
+    while let Some(cell) = stack.pop() {
+        if !visited.insert(cell.repr_hash()) {
+            continue
+        }
+        bits += cell.bit_length();
+        refs += cell.references_count();
+        *types.entry(format!("{:?}", cell.cell_type())).or_default() += 1;
+        for i in 0..cell.references_count() {
+            stack.push(cell.reference(i).map_err(invalid)?);
+        }
+    }
+    println!("Cells: {} distinct, {} bits, {} references", visited.len(), bits, refs);
+    for (cell_type, count) in types {
+        println!("  {}: {}", cell_type, count);
+    }
+
+    for (index, root) in roots.iter().enumerate() {
+        println!("Root {}: {} bits, {} refs, level {}", index, root.bit_length(), root.references_count(), root.level());
+        print_hashes(root);
+    }
+
+    for path in &args.cell {
+        let cell = cell_at(&roots, path)?;
+        let start = args.offset.min(cell.bit_length());
+        let end = args.length.map_or(cell.bit_length(), |length| (start + length).min(cell.bit_length()));
+        println!("Cell {}: {} bits, {} refs, {:?}", path, cell.bit_length(), cell.references_count(), cell.cell_type());
+        print_hashes(&cell);
+        println!("  bits {}..{}: x{{{}}}", start, end, bits_hex(cell.data(), start, end));
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Complete build configurations as JSON or TOML: the options of the command line, keyed by
+// their long names in snake case, e.g. `{"input": ["Wallet.sol"], "output_dir": "build"}`.
+// Options left out take their defaults. The build info written by --build-info holds the
+// options the build was given under `options`, before the project config is merged in,
+// next to the versions it was built with, so that --from-config replays it exactly.
+
+use serde_json::{json, Value};
+
+use crate::error::{Result, SoldError};
+use crate::{messages, Args};
+
+/// Reads the configuration in `path`, TOML if its extension is .toml, JSON otherwise;
+/// a build info gives its `options`.
+pub fn load(path: &str) -> Result<Args> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|_| SoldError::SourceNotFound(path.to_owned()))?;
+    let invalid = |e: &dyn std::fmt::Display| SoldError::ConfigError(format!("{}: {}", path, e));
+    if path.ends_with(".toml") {
+        return toml::from_str(&text).map_err(|e| invalid(&e))
+    }
+    let mut json: Value = serde_json::from_str(&text).map_err(|e| invalid(&e))?;
+    if json.get("options").is_some() {
+        let built_with = json["compiler"].as_str().unwrap_or_default();
+        let compiler = crate::solidity_version();
+        if !built_with.is_empty() && built_with != compiler {
+            messages::warning(&format!(
+                "Warning: {} was built with compiler {}, replaying with {} may produce different artifacts",
+                path, built_with, compiler,
+            ));
+        }
+        return serde_json::from_value(json["options"].take()).map_err(|e| invalid(&e))
+    }
+    serde_json::from_value(json).map_err(|e| invalid(&e))
+}
+
+/// Build info of a build with `args`
+pub fn build_info(args: &Args) -> Result<Value> {
+    Ok(json!({
+        "compiler": crate::solidity_version(),
+        "sold": env!("CARGO_PKG_VERSION"),
+        "options": serde_json::to_value(args)?,
+    }))
+}
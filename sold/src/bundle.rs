@@ -0,0 +1,55 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The contract bundle keeps everything needed to deploy and call a contract in one file:
+// canonical ABI, base64 encoded TVC and code, code hash and the compiler that produced them.
+
+use std::io::Write;
+
+use ton_block::StateInit;
+use ton_types::BagOfCells;
+
+use crate::error::{SoldError, Status};
+use crate::printer;
+
+pub fn write(out: &mut impl Write, name: &str, abi: &serde_json::Value, tvc: &[u8], state: &StateInit) -> Status {
+    let code = state.code.as_ref()
+        .ok_or_else(|| SoldError::InvalidOutput("Contract state has no code".to_owned()))?;
+    let mut code_boc = vec![];
+    BagOfCells::with_root(code).write_to(&mut code_boc, false)
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+
+    let mut abi_text = vec![];
+    printer::print_abi_json_canonically(&mut abi_text, abi)?;
+    let abi_text = String::from_utf8_lossy(&abi_text);
+
+    writeln!(out, "{{")?;
+    writeln!(out, "\t\"name\": {},", serde_json::to_string(name)?)?;
+    write!(out, "\t\"abi\": ")?;
+    let lines: Vec<&str> = abi_text.trim_end().lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        let indent = if i == 0 { "" } else { "\t" };
+        let separator = if i + 1 == lines.len() { "," } else { "" };
+        writeln!(out, "{}{}{}", indent, line, separator)?;
+    }
+    writeln!(out, "\t\"tvc\": \"{}\",", base64::encode(tvc))?;
+    writeln!(out, "\t\"code\": \"{}\",", base64::encode(&code_boc))?;
+    writeln!(out, "\t\"codeHash\": \"{}\",", code.repr_hash().to_hex_string())?;
+    writeln!(out, "\t\"compiler\": {{")?;
+    writeln!(out, "\t\t\"name\": \"sold\",")?;
+    writeln!(out, "\t\t\"version\": {},", serde_json::to_string(&crate::solidity_version())?)?;
+    writeln!(out, "\t\t\"driver\": \"{}\"", env!("CARGO_PKG_VERSION"))?;
+    writeln!(out, "\t}}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Invocation database, the analog of compile_commands.json: for each compiled source, the
+// command line, the standard JSON settings passed to the compiler, the constants overridden
+// with -D or --network, and the sources it imports, so that tools can replay the exact
+// compilation. Builds update the entries of their inputs and keep the others.
+
+use std::io::Write;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::error::Status;
+
+pub const FILE_NAME: &str = "sold_commands.json";
+
+pub fn record(output_dir: &Path, input: &str, standard_json: &str, sources: &[&str]) -> Status {
+    let path = output_dir.join(FILE_NAME);
+    let mut entries: Vec<Value> = match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    entries.retain(|entry| entry["file"] != input);
+
+    let request: Value = serde_json::from_str(standard_json)?;
+    let imports: Vec<String> = sources.iter()
+        .filter(|source| **source != input)
+        .map(|source| std::fs::canonicalize(source).map_or_else(|_| source.to_string(), |path| path.display().to_string()))
+        .collect();
+    entries.push(json!({
+        "file": input,
+        "directory": std::env::current_dir()?,
+        "arguments": std::env::args().collect::<Vec<_>>(),
+        "settings": request["settings"],
+        "defines": crate::defines::current(),
+        "imports": imports,
+    }));
+    entries.sort_by(|a, b| a["file"].as_str().cmp(&b["file"].as_str()));
+
+    let mut file = std::fs::File::create(&path)?;
+    serde_json::to_writer_pretty(&mut file, &entries)?;
+    writeln!(file)?;
+    Ok(())
+}
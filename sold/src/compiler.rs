@@ -0,0 +1,230 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// A Compiler owns the state of its builds: the line tables of the sources the frontend
+// read, the defines, the selected frontend, the message handler and the async worker.
+// Builds of different compilers may run concurrently on different threads. The code of a
+// build finds its compiler through the thread it runs on, entered by Compiler::build; the
+// read callback of the frontend gets it as its context. Outside any compiler, as in the
+// command line tool, builds use a process-wide default one.
+//
+// Shared by all compilers: the source cache, the loaded frontend libraries and the color
+// choice. A frontend keeps global state during a compilation, so calls into the same
+// library are serialized; --isolate runs the frontend in child processes instead.
+//
+// Sources can be given in memory too, with Compiler::add_source, for services compiling
+// code they never write to disk. They are served through the source cache as the source
+// read from stdin is, but belong to the compiler, so those of concurrent compilers don't mix.
+//
+// Compiler::compile returns the artifacts in memory. The linker only writes files, so the
+// build runs in a temporary directory that is removed afterwards.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use serde_json::Value;
+
+use crate::defines::Defines;
+use crate::diagnostics::ColorChoice;
+use crate::error::{Result, SoldError};
+use crate::frontend::Frontend;
+use crate::messages::MessageHandler;
+use crate::report::BuildOutput;
+use crate::worker::Worker;
+use crate::Args;
+
+#[derive(Default)]
+pub(crate) struct State {
+    /// Byte offsets of the line ends of the sources the frontend read, by path
+    pub lines: Mutex<HashMap<String, Vec<usize>>>,
+    pub defines: Defines,
+    /// Sources given with `Compiler::add_source`, read instead of files
+    pub sources: Mutex<HashMap<PathBuf, Arc<Vec<u8>>>>,
+    /// Directories sources may be read from, any if None, see sandbox.rs
+    pub sandbox: Mutex<Option<Vec<PathBuf>>>,
+    /// Whether diagnostics are colored, see diagnostics.rs
+    pub color: Mutex<ColorChoice>,
+    /// The frontend in use, the embedded one if None
+    pub frontend: Mutex<Option<&'static Frontend>>,
+    pub handler: Option<Arc<dyn MessageHandler>>,
+    pub worker: Worker,
+}
+
+/// Artifacts of a contract built by `Compiler::compile`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompilationOutput {
+    pub contract: String,
+    /// ABI as published, without the functions left out by --abi-include and --abi-exclude
+    pub abi: Value,
+    pub assembly: String,
+    pub tvc: Vec<u8>,
+    /// Source positions of the code cells, as in <prefix>.debug.json
+    pub debug_map: Value,
+    pub warnings: usize,
+}
+
+/// Prefix of the artifacts of in-memory builds
+const PREFIX: &str = "contract";
+
+/// Fresh temporary directory of an in-memory build
+pub(crate) fn temp_dir() -> Result<PathBuf> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "sold-compile-{}-{}", std::process::id(), COUNT.fetch_add(1, Ordering::Relaxed),
+    ));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn read_artifacts(dir: &Path, output: BuildOutput) -> Result<CompilationOutput> {
+    let read = |extension: &str| -> Result<Vec<u8>> {
+        let path = dir.join(format!("{}.{}", PREFIX, extension));
+        std::fs::read(&path).map_err(|_| SoldError::InvalidOutput(format!("The build wrote no {}", extension)))
+    };
+    Ok(CompilationOutput {
+        contract: output.contract,
+        abi: serde_json::from_slice(&read("abi.json")?)?,
+        assembly: String::from_utf8_lossy(&read("code")?).into_owned(),
+        tvc: read("tvc")?,
+        debug_map: serde_json::from_slice(&read("debug.json")?)?,
+        warnings: output.warnings,
+    })
+}
+
+/// Handle of a compiler; clones share the state.
+#[derive(Clone, Default)]
+pub struct Compiler {
+    state: Arc<State>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Compiler>> = RefCell::new(None);
+}
+
+lazy_static! {
+    static ref DEFAULT: Compiler = Compiler::new();
+}
+
+/// The compiler of the build running on this thread, the default one if there is none
+pub(crate) fn current() -> Compiler {
+    CURRENT.with(|current| current.borrow().clone()).unwrap_or_else(|| DEFAULT.clone())
+}
+
+/// Restores the compiler entered before, also when the build panics
+struct Leave(Option<Compiler>);
+
+impl Drop for Leave {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+impl Compiler {
+    pub fn new() -> Compiler {
+        Compiler::default()
+    }
+
+    /// Compiler sending the messages of its builds to `handler`, see messages.rs
+    pub fn with_message_handler(handler: Arc<dyn MessageHandler>) -> Compiler {
+        Compiler { state: Arc::new(State { handler: Some(handler), ..Default::default() }) }
+    }
+
+    /// Compiler with the frontend, the message handler and the in-memory sources of `parent`
+    /// and state of its own
+    pub(crate) fn fork(parent: &Compiler) -> Compiler {
+        Compiler { state: Arc::new(State {
+            frontend: Mutex::new(*parent.state.frontend.lock().unwrap()),
+            handler: parent.state.handler.clone(),
+            sources: Mutex::new(parent.state.sources.lock().unwrap().clone()),
+            ..Default::default()
+        }) }
+    }
+
+    /// Same as `sold_lib::build()`, with the state of this compiler
+    pub fn build(&self, args: Args) -> Result<BuildOutput> {
+        self.enter(|| crate::build(args))
+    }
+
+    /// Builds the contract `args` select and returns its artifacts, leaving no files behind.
+    /// The options choosing where and which artifacts are written are ignored.
+    pub fn compile(&self, mut args: Args) -> Result<CompilationOutput> {
+        let dir = temp_dir()?;
+        args.output_dir = Some(dir.to_string_lossy().into_owned());
+        args.output_prefix = Some(PREFIX.to_owned());
+        args.abi_out = None;
+        args.tvc_out = None;
+        args.code_out = None;
+        args.debug_out = None;
+        args.emit = vec![];
+        args.asm_only = false;
+        args.no_abi = false;
+        args.no_tvc = false;
+        args.abi_json = false;
+        args.hashed_names = false;
+        args.archive = None;
+        let res = self.build(args).and_then(|output| read_artifacts(&dir, output));
+        std::fs::remove_dir_all(&dir).ok();
+        res
+    }
+
+    /// Builds the only deployable contract of the source file at `path` with the default
+    /// options, see `compile`
+    pub fn compile_file(&self, path: &str) -> Result<CompilationOutput> {
+        self.compile(Args { input: vec![path.to_owned()], ..Default::default() })
+    }
+
+    /// Serves `text` as the source file at `path` to the builds of this compiler, which read
+    /// it instead of the file system. Inputs and imports may name it; other imports are
+    /// still read from files.
+    pub fn add_source(&self, path: &str, text: &str) {
+        self.state.sources.lock().unwrap().insert(PathBuf::from(path), Arc::new(text.as_bytes().to_vec()));
+    }
+
+    /// Forgets the sources given with `add_source`
+    pub fn clear_sources(&self) {
+        self.state.sources.lock().unwrap().clear();
+    }
+
+    /// Adds `sources`, pairs of path and text, and builds the only deployable contract of
+    /// the one at `main` with the default options, see `compile`
+    pub fn compile_sources<'a>(&self, sources: impl IntoIterator<Item = (&'a str, &'a str)>, main: &str) -> Result<CompilationOutput> {
+        for (path, text) in sources {
+            self.add_source(path, text);
+        }
+        self.compile(Args { input: vec![main.to_owned()], ..Default::default() })
+    }
+
+    /// Same as `sold_lib::compile_async()`, with the state and the worker of this compiler
+    pub async fn compile_async(&self, args: Args) -> Result<BuildOutput> {
+        crate::worker::compile(self.clone(), args).await
+    }
+
+    /// Runs `f` with this compiler as the current one of the thread
+    pub(crate) fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.borrow_mut().replace(self.clone()));
+        let _leave = Leave(previous);
+        f()
+    }
+
+    pub(crate) fn state(&self) -> &Arc<State> {
+        &self.state
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{Result, SoldError};
+use crate::limits::StateLimits;
+use crate::plugins::PluginConfig;
+use crate::remappings::Remapping;
+use crate::storage::StoragePrices;
+use crate::Args;
+
+pub const CONFIG_FILE_NAME: &str = "sold.toml";
+/// Same settings in JSON, for projects generating them
+pub const CONFIG_JSON_FILE_NAME: &str = "sold.json";
+/// Entries marking the root of a repository, where the lookup of the config file stops
+const VCS_MARKERS: [&str; 3] = [".git", ".hg", ".svn"];
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Named compile-time constants per network, e.g. `[networks.mainnet]`
+    #[serde(default)]
+    pub networks: BTreeMap<String, BTreeMap<String, toml::Value>>,
+    /// Output generator plugins run after a successful build
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+    /// Prices used by --storage-fees, those of --network-preset if not set
+    #[serde(default)]
+    pub storage: Option<StoragePrices>,
+    /// Limits the linked contract is checked against, those of --network-preset if not set
+    #[serde(default)]
+    pub limits: Option<StateLimits>,
+    /// Shared libraries of other frontend versions by version, relative to the config file
+    #[serde(default)]
+    pub frontends: BTreeMap<String, PathBuf>,
+    /// URL of the index --auto-install downloads frontends from
+    #[serde(default)]
+    pub frontend_index: Option<String>,
+    /// Defaults of the build options, those given on the command line take precedence
+    #[serde(default)]
+    pub compiler: CompilerOptions,
+}
+
+/// The `[compiler]` section, with paths relative to the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompilerOptions {
+    pub include_path: Vec<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub contract: Option<String>,
+    pub optimize: bool,
+    pub optimize_runs: Option<u32>,
+    pub remappings: Vec<Remapping>,
+}
+
+impl CompilerOptions {
+    /// Fills the options of `args` not given on the command line. Include paths are searched
+    /// after those of the command line, and remappings of the command line replace those of
+    /// the same prefix.
+    pub fn apply(&self, dir: &Path, args: &mut Args) {
+        let path = |path: &Path| dir.join(path).to_string_lossy().into_owned();
+        args.include_path.extend(self.include_path.iter().map(|dir| path(dir)));
+        if args.output_dir.is_none() {
+            args.output_dir = self.output_dir.as_deref().map(path);
+        }
+        if args.contract.is_none() {
+            args.contract = self.contract.clone();
+        }
+        args.optimize |= self.optimize;
+        if args.optimize_runs.is_none() {
+            args.optimize_runs = self.optimize_runs;
+        }
+        let overridden = |remapping: &Remapping| args.remap.iter()
+            .any(|other| other.context == remapping.context && other.prefix == remapping.prefix);
+        let mut remappings: Vec<Remapping> = self.remappings.iter()
+            .filter(|remapping| !overridden(remapping))
+            .map(|remapping| Remapping {
+                target: if remapping.target.is_empty() { String::new() } else { path(Path::new(&remapping.target)) },
+                ..remapping.clone()
+            })
+            .collect();
+        remappings.append(&mut args.remap);
+        args.remap = remappings;
+    }
+}
+
+impl Config {
+    /// Looks for the config file, sold.toml or sold.json, in the directory of `input` and
+    /// its ancestors up to the root of the repository, or the home directory outside one.
+    pub fn discover(input: &Path) -> Result<Option<(PathBuf, Config)>> {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let mut dir = input.parent();
+        while let Some(current) = dir {
+            let candidates: Vec<PathBuf> = [CONFIG_FILE_NAME, CONFIG_JSON_FILE_NAME].iter()
+                .map(|name| current.join(name))
+                .filter(|candidate| candidate.is_file())
+                .collect();
+            match candidates.as_slice() {
+                [] => {}
+                [candidate] => return Ok(Some((candidate.clone(), Self::load(candidate)?))),
+                _ => return Err(SoldError::ConfigError(format!(
+                    "Both {} and {} found in {}, keep only one of them",
+                    CONFIG_FILE_NAME, CONFIG_JSON_FILE_NAME, current.display()
+                ))),
+            }
+            if VCS_MARKERS.iter().any(|marker| current.join(marker).exists()) || home.as_deref() == Some(current) {
+                break
+            }
+            dir = current.parent();
+        }
+        Ok(None)
+    }
+
+    pub fn load(path: &Path) -> Result<Config> {
+        let text = std::fs::read_to_string(path)?;
+        let parse_error = |e: &dyn std::fmt::Display| SoldError::ConfigError(format!("Failed to parse {}: {}", path.display(), e));
+        if path.extension().map_or(false, |ext| ext == "json") {
+            serde_json::from_str(&text).map_err(|e| parse_error(&e))
+        } else {
+            toml::from_str(&text).map_err(|e| parse_error(&e))
+        }
+    }
+
+    /// Returns the constants of the given network rendered as Solidity expressions.
+    pub fn network_constants(&self, network: &str) -> Result<BTreeMap<String, String>> {
+        let constants = self.networks.get(network)
+            .ok_or_else(|| SoldError::ConfigError(format!(
+                "Network \"{}\" is not defined in {}", network, CONFIG_FILE_NAME
+            )))?;
+        let mut res = BTreeMap::new();
+        for (name, value) in constants {
+            let expr = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(i) => i.to_string(),
+                toml::Value::Boolean(b) => b.to_string(),
+                _ => return Err(SoldError::ConfigError(format!(
+                    "Constant \"{}\" of network \"{}\" must be a string, an integer or a boolean", name, network
+                ))),
+            };
+            res.insert(name.clone(), expr);
+        }
+        Ok(res)
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Constants embedded in the code more than once. Slices are either pushed inline,
+// `PUSHSLICE x...`, which puts their bits into the instruction stream at every site, or
+// from a referenced cell, `PUSHREF { .blob x... }` as strings are, whose identical cells
+// the bag of cells stores once. So referenced constants are merged, and every inline
+// site but one wastes the bits of the constant. Pooling turns repeated inline slices into
+// references to a single shared cell.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::asm::{self, Node};
+use crate::error::Status;
+
+/// Inline slices shorter than this are cheaper than a reference and are not pooled
+const MIN_POOLED_BITS: u64 = 64;
+/// Data bits of a cell, which a pooled constant has to fit in
+const MAX_CELL_BITS: u64 = 1023;
+
+#[derive(Default)]
+struct Sites {
+    bits: u64,
+    inline: Vec<String>,
+    referenced: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Duplicate {
+    content: String,
+    bits: u64,
+    /// `function` or `function (file:line)` of every site pushing the constant inline
+    inline_sites: Vec<String>,
+    /// Sites pushing the constant from a referenced cell
+    ref_sites: Vec<String>,
+    /// Whether the code holds the constant once
+    merged: bool,
+    wasted_bits: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    duplicates: Vec<Duplicate>,
+    wasted_bits: u64,
+}
+
+/// Bits of a slice literal, `x` hex digits or `b` binary digits, where a trailing `_`
+/// marks the last set bit as the end of the data
+fn literal_bits(literal: &str) -> u64 {
+    let (digits, completed) = match literal.strip_suffix('_') {
+        Some(digits) => (digits, true),
+        None => (literal, false),
+    };
+    let (bits, last) = if let Some(hex) = digits.strip_prefix('x') {
+        (hex.len() as u64 * 4, hex.chars().last().and_then(|c| c.to_digit(16)).map(|d| d.trailing_zeros() + 1))
+    } else if let Some(binary) = digits.strip_prefix('b') {
+        (binary.len() as u64, binary.ends_with('1').then(|| 1))
+    } else {
+        return 0
+    };
+    match (completed, last) {
+        (true, Some(tag)) => bits.saturating_sub(tag as u64),
+        _ => bits,
+    }
+}
+
+/// Content and bits of a referenced cell holding only data, e.g. a string
+fn cell_content(body: &[Node]) -> Option<(String, u64)> {
+    let mut content = String::new();
+    let mut bits = 0;
+    for node in body {
+        match node {
+            Node::Op { op, args, .. } if op == ".blob" => {
+                content.push_str(args);
+                bits += literal_bits(args);
+            }
+            Node::Block { op, body, .. } if op == ".cell" => {
+                let (nested, nested_bits) = cell_content(body)?;
+                content.push_str(&format!(" {{ {} }}", nested));
+                bits += nested_bits;
+            }
+            _ => return None,
+        }
+    }
+    (!content.is_empty()).then(|| (content, bits))
+}
+
+fn collect(nodes: &[Node], function: &str, constants: &mut BTreeMap<String, Sites>) {
+    for node in nodes {
+        let site = |loc: &Option<asm::Loc>| match loc {
+            Some(loc) => format!("{} ({})", function, loc),
+            None => function.to_owned(),
+        };
+        match node {
+            Node::Op { op, args, loc } if op == "PUSHSLICE" && literal_bits(args) > 0 => {
+                let sites = constants.entry(args.clone()).or_default();
+                sites.bits = literal_bits(args);
+                sites.inline.push(site(loc));
+            }
+            Node::Block { op, body, loc } if op == "PUSHREF" || op == "PUSHREFSLICE" => {
+                match cell_content(body) {
+                    Some((content, bits)) => {
+                        let sites = constants.entry(content).or_default();
+                        sites.bits = bits;
+                        sites.referenced.push(site(loc));
+                    }
+                    None => collect(body, function, constants),
+                }
+            }
+            Node::Block { body, .. } => collect(body, function, constants),
+            Node::Op { .. } => {}
+        }
+    }
+}
+
+fn constants(assembly: &str) -> BTreeMap<String, Sites> {
+    let mut res = BTreeMap::new();
+    for function in asm::Assembly::parse(assembly).functions {
+        collect(&asm::parse_body(&function.body), &function.name, &mut res);
+    }
+    res
+}
+
+/// `assembly` with the inline slices of at least 64 bits used at several sites pushed
+/// from a single referenced cell instead.
+pub fn pool(assembly: &str) -> String {
+    let pooled: Vec<String> = constants(assembly).into_iter()
+        .filter(|(_, sites)| sites.inline.len() + sites.referenced.len() > 1)
+        .filter(|(_, sites)| (MIN_POOLED_BITS..=MAX_CELL_BITS).contains(&sites.bits))
+        .map(|(content, _)| content)
+        .collect();
+    let mut res = String::new();
+    for line in assembly.lines() {
+        let code = asm::strip_comment(line);
+        let indent = &code[..code.len() - code.trim_start().len()];
+        match code.trim().strip_prefix("PUSHSLICE").map(str::trim) {
+            Some(literal) if pooled.iter().any(|content| content == literal) => {
+                res.push_str(&format!("{}PUSHREFSLICE {{\n{}\t.blob {}\n{}}}\n", indent, indent, literal, indent));
+            }
+            _ => {
+                res.push_str(line);
+                res.push('\n');
+            }
+        }
+    }
+    res
+}
+
+/// Writes the constants `assembly` embeds at several sites, with the bits the copies take.
+pub fn write(out: &mut impl Write, assembly: &str) -> Status {
+    let mut duplicates: Vec<Duplicate> = constants(assembly).into_iter()
+        .filter(|(_, sites)| sites.inline.len() + sites.referenced.len() > 1)
+        .map(|(content, sites)| {
+            // The referenced cell, or else the first inline site, is the one copy needed
+            let copies = sites.inline.len() as u64 - if sites.referenced.is_empty() { 1 } else { 0 };
+            Duplicate {
+                content,
+                bits: sites.bits,
+                merged: copies == 0,
+                wasted_bits: copies * sites.bits,
+                inline_sites: sites.inline,
+                ref_sites: sites.referenced,
+            }
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.wasted_bits.cmp(&a.wasted_bits).then_with(|| a.content.cmp(&b.content)));
+    let report = Report {
+        wasted_bits: duplicates.iter().map(|duplicate| duplicate.wasted_bits).sum(),
+        duplicates,
+    };
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)?;
+    Ok(())
+}
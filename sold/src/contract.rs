@@ -0,0 +1,62 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The output of the frontend for a contract, as in `contracts.<source>.<name>` of the
+// standard JSON output. Outputs not selected in the input are absent; fields the frontend
+// may add later are ignored.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractArtifacts {
+    /// ABI as the frontend generates it, before the linker completes it
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub abi: Value,
+    /// TVM assembly, for deployable contracts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assembly: Option<String>,
+    /// IDs of the public functions and getters, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub function_ids: BTreeMap<String, FunctionId>,
+}
+
+/// Function ID, written as the frontend shows it: a 0x-prefixed hex string of 8 digits
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FunctionId(pub u32);
+
+impl fmt::Display for FunctionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:08x}", self.0)
+    }
+}
+
+impl Serialize for FunctionId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FunctionId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.strip_prefix("0x")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .map(FunctionId)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid function ID \"{}\"", text)))
+    }
+}
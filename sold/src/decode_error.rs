@@ -0,0 +1,114 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Explains the exit code of a failed transaction with the exception table written by
+// --errors. Given the body of the inbound message, the called function is decoded with the
+// ABI and the require and revert calls of that function are listed first. Codes thrown by
+// TVM itself or by the code the compiler generates are described as well.
+
+use serde_json::Value;
+
+use crate::error::{invalid_json, Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct DecodeErrorArgs {
+    /// Exception table written by --errors
+    #[clap(long, value_parser)]
+    pub errors: String,
+    /// Exit code of the compute phase
+    #[clap(long, value_parser, allow_hyphen_values = true)]
+    pub exit_code: i64,
+    /// ABI of the contract, to decode --body
+    #[clap(long, value_parser)]
+    pub abi: Option<String>,
+    /// Body of the inbound message as a base64 encoded BOC
+    #[clap(long, value_parser, requires = "abi")]
+    pub body: Option<String>,
+}
+
+fn invalid(e: impl std::fmt::Display) -> SoldError {
+    SoldError::ArgumentError(format!("Failed to decode the message body: {}", e))
+}
+
+/// Name and JSON parameters of the function the message body calls
+fn decode_body(abi_path: &str, body: &str) -> Result<(String, String)> {
+    let abi = crate::abi::load(abi_path)?;
+    let contract = ton_abi::Contract::load(abi.to_string().as_bytes())
+        .map_err(|e| SoldError::InvalidOutput(format!("Invalid ABI: {}", e)))?;
+    let bytes = base64::decode(body.trim()).map_err(invalid)?;
+    let cell = ton_types::deserialize_tree_of_cells(&mut std::io::Cursor::new(bytes)).map_err(invalid)?;
+    // The body does not tell whether the message was internal, which changes its layout
+    let decoded = contract.decode_input(cell.clone().into(), true)
+        .or_else(|_| contract.decode_input(cell.into(), false))
+        .map_err(invalid)?;
+    let params = ton_abi::token::Detokenizer::detokenize(&decoded.tokens).map_err(invalid)?;
+    Ok((decoded.function_name, params))
+}
+
+fn describe(exception: &Value) -> String {
+    let mut res = exception["kind"].as_str().unwrap_or_default().to_owned();
+    if let Some(message) = exception["message"].as_str() {
+        res.push_str(&format!(" {:?}", message));
+    }
+    res.push_str(&format!(
+        " in {} at {}:{}",
+        exception["function"].as_str().unwrap_or_default(),
+        exception["source"].as_str().unwrap_or_default(),
+        exception["line"],
+    ));
+    if let Some(constant) = exception["constant"].as_str() {
+        res.push_str(&format!(" ({})", constant));
+    }
+    res
+}
+
+pub fn decode_error(args: DecodeErrorArgs) -> Status {
+    let text = std::fs::read_to_string(&args.errors)
+        .map_err(|_| SoldError::SourceNotFound(args.errors.clone()))?;
+    let table: Value = serde_json::from_str(&text).map_err(|e| invalid_json(&args.errors, e))?;
+    let called = match (&args.abi, &args.body) {
+        (Some(abi), Some(body)) => Some(decode_body(abi, body)?),
+        _ => None,
+    };
+
+    println!("Exit code {}", args.exit_code);
+    if let Some((function, params)) = &called {
+        println!("Called {}.{} {}", table["contract"].as_str().unwrap_or_default(), function, params);
+    }
+    let exceptions: Vec<&Value> = table["exceptions"].as_array().map_or(vec![], |list| {
+        list.iter().filter(|exception| exception["code"] == args.exit_code).collect()
+    });
+    let (in_called, others): (Vec<&Value>, Vec<&Value>) = exceptions.iter().copied().partition(|exception| {
+        called.as_ref().map_or(false, |(function, _)| {
+            exception["function"].as_str().map_or(false, |name| name.rsplit('.').next() == Some(function.as_str()))
+        })
+    });
+    for exception in &in_called {
+        println!("  {}", describe(exception));
+    }
+    if !others.is_empty() {
+        if !in_called.is_empty() {
+            println!("Thrown with the same code elsewhere:");
+        }
+        for exception in &others {
+            println!("  {}", describe(exception));
+        }
+    }
+    if let Some((_, description)) = crate::exceptions::RUNTIME_CODES.iter().find(|(code, _)| *code == args.exit_code) {
+        let prefix = if exceptions.is_empty() { "" } else { "Or, thrown by the runtime: " };
+        println!("  {}{}", prefix, description);
+    } else if exceptions.is_empty() {
+        println!("  No require or revert of the contract throws this code");
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Defines override the initializer of `constant` state variables with the same name,
+// e.g. `-D FEE=2e9` turns `uint128 constant FEE = 1e9;` into `uint128 constant FEE = 2e9;`.
+// Substitution happens on read, so diagnostics point at the text libsolc actually saw.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use regex::Regex;
+
+use crate::error::{Result, SoldError, Status};
+
+struct Define {
+    pattern: Regex,
+    value: String,
+}
+
+/// Defines of a compiler, see compiler.rs
+#[derive(Default)]
+pub(crate) struct Defines {
+    defines: Mutex<BTreeMap<String, Define>>,
+    applied: Mutex<BTreeSet<String>>,
+}
+
+pub fn parse(text: &str) -> Result<(String, String)> {
+    let (name, value) = text.split_once('=')
+        .ok_or_else(|| SoldError::ArgumentError(format!("Invalid define \"{}\", expected NAME=VALUE", text)))?;
+    Ok((name.trim().to_owned(), value.trim().to_owned()))
+}
+
+impl Defines {
+    fn set(&self, defines: BTreeMap<String, String>) -> Status {
+        let mut map = self.defines.lock().unwrap();
+        map.clear();
+        for (name, value) in defines {
+            let pattern = Regex::new(&format!(
+                r"(\bconstant\s+(?:(?:public|internal|private)\s+)?{}\s*=)[^;]*;",
+                regex::escape(&name)
+            ))
+                .map_err(|e| SoldError::ArgumentError(format!("Invalid define name \"{}\": {}", name, e)))?;
+            map.insert(name, Define { pattern, value });
+        }
+        self.applied.lock().unwrap().clear();
+        Ok(())
+    }
+
+    pub fn apply(&self, buf: Vec<u8>) -> Vec<u8> {
+        let map = self.defines.lock().unwrap();
+        if map.is_empty() {
+            return buf
+        }
+        let mut text = match String::from_utf8(buf) {
+            Ok(text) => text,
+            Err(e) => return e.into_bytes(),
+        };
+        for (name, define) in map.iter() {
+            if define.pattern.is_match(&text) {
+                text = define.pattern.replace_all(&text, |caps: &regex::Captures| {
+                    format!("{} {};", &caps[1], define.value)
+                }).into_owned();
+                self.applied.lock().unwrap().insert(name.clone());
+            }
+        }
+        text.into_bytes()
+    }
+}
+
+fn with<T>(f: impl FnOnce(&Defines) -> T) -> T {
+    f(&crate::compiler::current().state().defines)
+}
+
+pub fn set(defines: BTreeMap<String, String>) -> Status {
+    with(|this| this.set(defines))
+}
+
+pub fn current() -> BTreeMap<String, String> {
+    with(|this| this.defines.lock().unwrap().iter()
+        .map(|(name, define)| (name.clone(), define.value.clone()))
+        .collect())
+}
+
+pub fn applied() -> Vec<String> {
+    with(|this| this.applied.lock().unwrap().iter().cloned().collect())
+}
+
+pub fn mark_applied(names: Vec<String>) {
+    with(|this| this.applied.lock().unwrap().extend(names))
+}
+
+/// Names of defines that did not match any constant declaration.
+pub fn unused() -> Vec<String> {
+    with(|this| {
+        let applied = this.applied.lock().unwrap();
+        this.defines.lock().unwrap().keys()
+            .filter(|name| !applied.contains(*name))
+            .cloned()
+            .collect()
+    })
+}
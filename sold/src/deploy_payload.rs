@@ -0,0 +1,60 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Payload for deploying the contract from a multisig wallet: the parameters of
+// `submitTransaction(dest, value, bounce, allBalance, payload, stateInit)`, with the
+// constructor call encoded as an internal message body and the value left for the
+// custodians to fill in.
+
+use std::io::Write;
+
+use serde_json::json;
+use ton_block::{Serializable, StateInit};
+use ton_types::{BagOfCells, Cell};
+
+use crate::error::{Result, SoldError, Status};
+
+fn boc(cell: &Cell) -> Result<String> {
+    let mut bytes = vec![];
+    BagOfCells::with_root(cell).write_to(&mut bytes, false)
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+    Ok(base64::encode(&bytes))
+}
+
+/// Writes the payload deploying `state` to workchain 0 with the given constructor parameters.
+pub fn write(out: &mut impl Write, abi: &serde_json::Value, state: &StateInit, params: &str) -> Status {
+    let state_init = state.serialize()
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+    let address = format!("0:{}", state_init.repr_hash().to_hex_string());
+    let body = ton_abi::json_abi::encode_function_call(
+        &serde_json::to_string(abi)?,
+        "constructor",
+        None,
+        params,
+        true,
+        None,
+        Some(&address),
+    ).and_then(|builder| builder.into_cell())
+        .map_err(|e| SoldError::ArgumentError(format!("Failed to encode the constructor call: {}", e)))?;
+    let payload = json!({
+        "dest": address,
+        "value": null,
+        "bounce": false,
+        "allBalance": false,
+        "payload": boc(&body)?,
+        "stateInit": boc(&state_init)?,
+    });
+    serde_json::to_writer_pretty(&mut *out, &payload)?;
+    writeln!(out)?;
+    Ok(())
+}
@@ -0,0 +1,396 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Compiler diagnostics are parsed from the `errors` section of the standard JSON output
+// into Diagnostic values first, and then handed to the renderer selected by --error-format.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{Result, SoldError, Status};
+use crate::messages::{self, Level};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorFormat {
+    /// Colored messages with source excerpts
+    #[default]
+    Human,
+    /// GitHub Actions workflow commands
+    Github,
+    /// A JSON array of all diagnostics on stdout
+    Json,
+    /// A SARIF 2.1.0 log on stdout, for code scanning
+    Sarif,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorChoice {
+    /// Color when stderr is a terminal, following NO_COLOR, CLICOLOR_FORCE and TERM=dumb
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Selects whether the diagnostics of the builds of the current compiler are colored.
+pub fn set_color(choice: ColorChoice) {
+    *crate::compiler::current().state().color.lock().unwrap() = choice;
+}
+
+fn use_color() -> bool {
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    let choice = *crate::compiler::current().state().color.lock().unwrap();
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        // See no-color.org and bixense.com/clicolors
+        _ if var("NO_COLOR").is_some() => false,
+        _ if var("CLICOLOR_FORCE").map_or(false, |value| value != "0") => true,
+        _ if std::env::var("TERM").map_or(false, |term| term == "dumb") => false,
+        // Embedders get the text as is
+        _ if !messages::to_console() => false,
+        _ => atty::is(atty::Stream::Stderr),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn level(self) -> Level {
+        match self {
+            Severity::Error => Level::Error,
+            Severity::Warning => Level::Warning,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    /// Byte offsets of the range in the source text
+    pub start: usize,
+    pub end: usize,
+}
+
+/// An entry of the `errors` section of the standard JSON output
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Error code of the frontend, e.g. "2072"
+    #[serde(rename = "errorCode", default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    /// Message with the source excerpt, as formatted by the compiler
+    #[serde(rename = "formattedMessage")]
+    pub formatted: String,
+    #[serde(rename = "sourceLocation", default, deserialize_with = "lenient_location", skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceLocation>,
+}
+
+// Locations the frontend can't tell come with negative offsets, the diagnostic is still valid
+fn lenient_location<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<SourceLocation>, D::Error> {
+    Ok(Option::<Value>::deserialize(deserializer)?.and_then(|location| SourceLocation::deserialize(location).ok()))
+}
+
+impl Diagnostic {
+    pub fn parse(entry: &Value) -> Result<Diagnostic> {
+        Diagnostic::deserialize(entry)
+            .map_err(|e| SoldError::InvalidOutput(format!("Invalid diagnostic: {}", e)))
+    }
+
+    /// One-based line and column of the start and the end of the location
+    pub fn span(&self) -> Option<((usize, usize), (usize, usize))> {
+        let location = self.location.as_ref()?;
+        let start = crate::get_line_column(&location.file, location.start)?;
+        let end = crate::get_line_column(&location.file, location.end).unwrap_or(start);
+        Some((start, end))
+    }
+}
+
+/// A diagnostic as printed by --error-format json, with one-based lines and columns
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonDiagnostic<'a> {
+    severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_column: Option<usize>,
+    message: &'a str,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    fn new(diagnostic: &'a Diagnostic) -> Self {
+        let span = diagnostic.span();
+        JsonDiagnostic {
+            severity: diagnostic.severity,
+            code: diagnostic.code.as_deref(),
+            file: diagnostic.location.as_ref().map(|location| location.file.as_str()),
+            line: span.map(|((line, _), _)| line),
+            column: span.map(|((_, column), _)| column),
+            end_line: span.map(|(_, (line, _))| line),
+            end_column: span.map(|(_, (_, column))| column),
+            message: &diagnostic.message,
+        }
+    }
+}
+
+/// Renders all diagnostics and fails if any of them is an error.
+pub fn check_errors(entries: &[Value], format: ErrorFormat) -> Status {
+    let diagnostics = entries.iter().map(Diagnostic::parse).collect::<Result<Vec<_>>>()?;
+    let mut errors = vec![];
+    for diagnostic in &diagnostics {
+        match format {
+            ErrorFormat::Human => render_human(diagnostic),
+            ErrorFormat::Github => render_github(diagnostic),
+            ErrorFormat::Json | ErrorFormat::Sarif => {}
+        }
+        if diagnostic.severity == Severity::Error {
+            errors.push(diagnostic.message.clone());
+        }
+    }
+    if format == ErrorFormat::Json {
+        let array: Vec<JsonDiagnostic> = diagnostics.iter().map(JsonDiagnostic::new).collect();
+        messages::output(&serde_json::to_string(&array)?);
+    }
+    if format == ErrorFormat::Sarif {
+        messages::output(&sarif(diagnostics.iter().map(sarif_diagnostic).collect()).to_string());
+    }
+    if !errors.is_empty() {
+        return Err(SoldError::CompilationFailed { diagnostics: errors })
+    }
+    Ok(())
+}
+
+/// Renders a diagnostic of sold itself, rather than of the compiler, pointing at the
+/// given places: file, line and column.
+pub fn report(severity: Severity, message: &str, locations: &[(String, usize, usize)], format: ErrorFormat) {
+    match format {
+        ErrorFormat::Human => {
+            let prefix = match severity {
+                Severity::Warning => colorize("Warning", ansi_term::Color::Yellow.bold()),
+                Severity::Error => colorize("Error", ansi_term::Color::Red.bold()),
+            };
+            let mut text = format!("{}: {}\n", prefix, colorize(message, ansi_term::Color::White.bold()));
+            for (file, line, column) in locations {
+                writeln!(text, "  {}{}:{}:{}", colorize("--> ", ansi_term::Color::Blue.bold()), file, line, column).unwrap();
+            }
+            messages::message(severity.level(), &text);
+        }
+        ErrorFormat::Github => {
+            let command = match severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            for (file, line, column) in locations {
+                messages::output(&format!(
+                    "::{} file={},line={},col={}::{}", command, escape_property(&relative_path(file)), line, column,
+                    escape_data(message),
+                ));
+            }
+        }
+        ErrorFormat::Json => {
+            let array: Vec<JsonDiagnostic> = locations.iter()
+                .map(|(file, line, column)| JsonDiagnostic {
+                    severity,
+                    code: None,
+                    file: Some(file.as_str()),
+                    line: Some(*line),
+                    column: Some(*column),
+                    end_line: None,
+                    end_column: None,
+                    message,
+                })
+                .collect();
+            messages::output(&serde_json::to_string(&array).unwrap_or_default());
+        }
+        ErrorFormat::Sarif => {
+            let results = locations.iter()
+                .map(|(file, line, column)| sarif_result(
+                    severity, None, message, Some((file.as_str(), json!({ "startLine": line, "startColumn": column }))),
+                ))
+                .collect();
+            messages::output(&sarif(results).to_string());
+        }
+    }
+}
+
+/// Rule of the diagnostics without a frontend error code, i.e. those of sold itself
+const SARIF_DEFAULT_RULE: &str = "sold";
+
+/// Result of a SARIF log, located in `file` at `region` if given
+fn sarif_result(severity: Severity, code: Option<&str>, message: &str, location: Option<(&str, Value)>) -> Value {
+    let mut result = json!({
+        "ruleId": code.unwrap_or(SARIF_DEFAULT_RULE),
+        "level": match severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        "message": { "text": message },
+    });
+    if let Some((file, region)) = location {
+        result["locations"] = json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": relative_path(file) },
+                "region": region,
+            },
+        }]);
+    }
+    result
+}
+
+fn sarif_diagnostic(diagnostic: &Diagnostic) -> Value {
+    let location = diagnostic.location.as_ref().map(|location| {
+        let mut region = json!({
+            "charOffset": location.start,
+            "charLength": location.end.saturating_sub(location.start),
+        });
+        if let Some(((line, column), (end_line, end_column))) = diagnostic.span() {
+            region["startLine"] = json!(line);
+            region["startColumn"] = json!(column);
+            region["endLine"] = json!(end_line);
+            region["endColumn"] = json!(end_column);
+        }
+        (location.file.as_str(), region)
+    });
+    sarif_result(diagnostic.severity, diagnostic.code.as_deref(), &diagnostic.message, location)
+}
+
+// See https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html. The rules are the
+// error codes of the frontend, which has no descriptions of them to include.
+fn sarif(results: Vec<Value>) -> Value {
+    let rules: std::collections::BTreeSet<&str> = results.iter()
+        .filter_map(|result| result["ruleId"].as_str())
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "sold",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn colorize(input: &str, style: ansi_term::Style) -> ansi_term::ANSIGenericString<str> {
+    if use_color() {
+        style.paint(input)
+    } else {
+        input.into()
+    }
+}
+
+fn render_human(diagnostic: &Diagnostic) {
+    let red = ansi_term::Color::Red.bold();
+    let yellow = ansi_term::Color::Yellow.bold();
+    let white = ansi_term::Color::White.bold();
+    let prefix = match diagnostic.severity {
+        Severity::Warning => colorize("Warning", yellow),
+        Severity::Error => colorize("Error", red),
+    };
+    let mut text = format!("{}: {}\n", prefix, colorize(&diagnostic.message, white));
+    format_message(&mut text, &diagnostic.formatted, diagnostic.span().map(|((line, _), _)| line));
+    messages::message(diagnostic.severity.level(), &text);
+}
+
+fn format_message(text: &mut String, message: &str, line: Option<usize>) {
+    if let Some(line) = line {
+        let message_lines = message.lines();
+        let line_number_size = ((line as f64).log10() as usize) + 1;
+        let leftpad = std::iter::repeat(" ").take(line_number_size).collect::<String>();
+        let blue = ansi_term::Color::Blue.bold();
+        let yellow = ansi_term::Color::Yellow.normal();
+        for (index, message_line) in message_lines.enumerate() {
+            if index == 0 {
+                writeln!(text, "{}{}{}", leftpad, colorize("--> ", blue), message_line).unwrap();
+                writeln!(text, "{} {}", leftpad, colorize("|", blue)).unwrap();
+            } else if index == 1 {
+                let line_hint = format!("{: >w$} |", line, w = line_number_size);
+                writeln!(text, "{} {}",
+                    colorize(&line_hint, blue),
+                    colorize(message_line, yellow)
+                ).unwrap();
+            } else {
+                writeln!(text, "{} {} {}", leftpad,
+                    colorize("|", blue),
+                    colorize(message_line, yellow)
+                ).unwrap();
+            }
+        }
+    } else {
+        text.push_str(message);
+    }
+}
+
+// See https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+fn render_github(diagnostic: &Diagnostic) {
+    let command = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let mut properties = vec![];
+    if let Some(location) = &diagnostic.location {
+        properties.push(format!("file={}", escape_property(&relative_path(&location.file))));
+        if let Some(((line, col), (end_line, end_col))) = diagnostic.span() {
+            properties.push(format!("line={}", line));
+            properties.push(format!("col={}", col));
+            properties.push(format!("endLine={}", end_line));
+            properties.push(format!("endColumn={}", end_col));
+        }
+    }
+    if properties.is_empty() {
+        messages::output(&format!("::{}::{}", command, escape_data(&diagnostic.message)));
+    } else {
+        messages::output(&format!("::{} {}::{}", command, properties.join(","), escape_data(&diagnostic.message)));
+    }
+}
+
+/// Paths in annotations are resolved against the repository root, which is
+/// where workflow steps run by default.
+fn relative_path(file: &str) -> String {
+    std::env::current_dir().ok()
+        .and_then(|dir| Path::new(file).strip_prefix(dir).ok().map(|p| p.to_string_lossy().replace('\\', "/")))
+        .unwrap_or_else(|| file.to_owned())
+}
+
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
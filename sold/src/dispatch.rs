@@ -0,0 +1,130 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Layout of the public function selector, the code comparing the function ID of an inbound
+// message with those of the public functions. The functions given with --dispatch-priority
+// are compared first, one by one, then the others are searched by ID as --dispatch says.
+// The report mirrors the layout the frontend generates to count the comparisons each
+// function takes.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::contract::FunctionId;
+use crate::error::Status;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Search tree of fanout 4 by function ID (default)
+    Tree,
+    /// Binary search by function ID
+    Binary,
+    /// One comparison per function in ID order
+    Linear,
+}
+
+impl Strategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            Strategy::Tree => "tree",
+            Strategy::Binary => "binary",
+            Strategy::Linear => "linear",
+        }
+    }
+}
+
+/// The `dispatch` settings of the standard JSON input, if any differ from the defaults
+pub fn settings(strategy: Option<Strategy>, priority: &[String]) -> Option<Value> {
+    if strategy.is_none() && priority.is_empty() {
+        return None
+    }
+    Some(serde_json::json!({
+        "strategy": strategy.unwrap_or(Strategy::Tree).as_str(),
+        "priority": priority,
+    }))
+}
+
+#[derive(Serialize)]
+struct Entry {
+    name: String,
+    id: String,
+    comparisons: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    strategy: &'static str,
+    /// Functions in the order they are compared
+    functions: Vec<Entry>,
+    max_comparisons: usize,
+}
+
+/// Comparisons reaching each of `count` functions sorted by ID in a search tree of
+/// `fanout`, as built by TVMFunctionCompiler::buildPublicFunctionSelector
+fn tree_comparisons(count: usize, fanout: usize) -> Vec<usize> {
+    if count <= fanout {
+        return (1..=count).collect()
+    }
+    let mut block_size = 1;
+    while fanout * block_size < count {
+        block_size *= fanout;
+    }
+    let mut res = vec![];
+    for (index, start) in (0..count).step_by(block_size).enumerate() {
+        let size = block_size.min(count - start);
+        if size == 1 {
+            res.push(index + 1);
+        } else {
+            res.extend(tree_comparisons(size, fanout).into_iter().map(|inner| index + 1 + inner));
+        }
+    }
+    res
+}
+
+/// Writes the selector layout of the functions in `function_ids`, ID by name.
+pub fn write(out: &mut impl Write, strategy: Option<Strategy>, priority: &[String], function_ids: &BTreeMap<String, FunctionId>) -> Status {
+    let strategy = strategy.unwrap_or(Strategy::Tree);
+    let mut functions: Vec<(FunctionId, &str)> = function_ids.iter()
+        .map(|(name, id)| (*id, name.as_str()))
+        .collect();
+    functions.sort_unstable();
+
+    let mut entries = vec![];
+    // The frontend refuses names of no public function
+    for name in priority {
+        if let Some((id, _)) = functions.iter().find(|(_, function)| function == name) {
+            entries.push(Entry { name: name.clone(), id: id.to_string(), comparisons: entries.len() + 1 });
+        }
+    }
+    let skipped = entries.len();
+    functions.retain(|(_, name)| !priority.iter().any(|p| p == name));
+    let comparisons = match strategy {
+        Strategy::Tree => tree_comparisons(functions.len(), 4),
+        Strategy::Binary => tree_comparisons(functions.len(), 2),
+        Strategy::Linear => (1..=functions.len()).collect(),
+    };
+    for ((id, name), count) in functions.into_iter().zip(comparisons) {
+        entries.push(Entry { name: name.to_owned(), id: id.to_string(), comparisons: skipped + count });
+    }
+
+    let max_comparisons = entries.iter().map(|entry| entry.comparisons).max().unwrap_or_default();
+    let report = Report { strategy: strategy.as_str(), functions: entries, max_comparisons };
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Contracts of the same name defined in different inputs of a multi-input build. Their
+// artifacts land in the same output directory, and the build report, bindings and tools
+// going by contract name can't tell them apart, so such builds are reported before
+// anything is compiled. Only deployable contracts count: abstract contracts, interfaces
+// and libraries produce no artifacts.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::diagnostics::{self, ErrorFormat, Severity};
+use crate::error::{SoldError, Status};
+use crate::lexer::{self, TokenKind};
+
+/// Names of the deployable contracts `text` defines, with their line and column
+pub fn contracts(text: &str) -> Vec<(String, usize, usize)> {
+    let tokens: Vec<_> = lexer::tokenize(text).into_iter().filter(|t| !t.is_trivia()).collect();
+    let mut res = vec![];
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match (token.kind, token.text) {
+            (TokenKind::Punct, "{") => depth += 1,
+            (TokenKind::Punct, "}") => depth -= 1,
+            (TokenKind::Identifier, "contract") if depth == 0 => {
+                let is_abstract = i > 0 && tokens[i - 1].text == "abstract";
+                if let Some(name) = tokens.get(i + 1).filter(|name| name.kind == TokenKind::Identifier && !is_abstract) {
+                    let (line, column) = lexer::line_column(text, name.start);
+                    res.push((name.text.to_owned(), line, column));
+                }
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
+/// Reports the contract names defined in more than one of `inputs`, as errors if `deny`.
+pub fn check(inputs: &[String], format: ErrorFormat, deny: bool) -> Status {
+    let mut definitions: BTreeMap<String, Vec<(String, usize, usize)>> = BTreeMap::new();
+    let mut seen: Vec<PathBuf> = vec![];
+    for input in inputs {
+        // The same file given twice defines nothing twice
+        let path = std::fs::canonicalize(input).unwrap_or_else(|_| PathBuf::from(input));
+        if seen.contains(&path) {
+            continue
+        }
+        seen.push(path);
+        let text = match std::fs::read_to_string(input) {
+            Ok(text) => text,
+            // Missing inputs fail when built
+            Err(_) => continue,
+        };
+        for (name, line, column) in contracts(&text) {
+            definitions.entry(name).or_default().push((input.clone(), line, column));
+        }
+    }
+
+    let severity = if deny { Severity::Error } else { Severity::Warning };
+    let mut duplicates = vec![];
+    for (name, locations) in definitions {
+        let files: Vec<&String> = locations.iter().map(|(file, _, _)| file).collect();
+        if files.iter().all(|file| *file == files[0]) {
+            continue
+        }
+        let message = format!("Contract \"{}\" is defined in several inputs", name);
+        diagnostics::report(severity, &message, &locations, format);
+        duplicates.push(name);
+    }
+    if deny && !duplicates.is_empty() {
+        return Err(SoldError::DuplicateContracts(duplicates))
+    }
+    Ok(())
+}
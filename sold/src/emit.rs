@@ -0,0 +1,66 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Which artifacts a build writes, and whether it links at all: the TVC and the debug map
+// come from the linker, and so does everything computed from the linked code. A build
+// writing only the ABI and the assembly stops before linking.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SoldError};
+use crate::Args;
+
+/// Artifacts written by a build, selected with --emit
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Artifact {
+    /// <prefix>.abi.json
+    Abi,
+    /// <prefix>.code, and <prefix>.unstripped.code with --strip
+    Code,
+    /// <prefix>.tvc
+    Tvc,
+    /// <prefix>.debug.json
+    Debug,
+}
+
+const ALL: [Artifact; 4] = [Artifact::Abi, Artifact::Code, Artifact::Tvc, Artifact::Debug];
+
+/// The artifacts selected with --emit, --asm-only, --no-abi and --no-tvc, all of them by default
+pub fn selection(args: &Args) -> Result<Vec<Artifact>> {
+    let mut res = if args.asm_only {
+        vec![Artifact::Code]
+    } else if args.emit.is_empty() {
+        ALL.to_vec()
+    } else {
+        args.emit.clone()
+    };
+    res.retain(|artifact| !((args.no_abi && *artifact == Artifact::Abi) || (args.no_tvc && *artifact == Artifact::Tvc)));
+    if res.is_empty() {
+        return Err(SoldError::ArgumentError("No artifacts are selected to be written".to_owned()))
+    }
+    Ok(res)
+}
+
+/// Whether the artifact is to be written, all of them are by default
+pub fn enabled(emit: &[Artifact], artifact: Artifact) -> bool {
+    emit.is_empty() || emit.contains(&artifact)
+}
+
+/// Whether a build writing `emit` has to link the contract
+pub fn links(args: &Args, emit: &[Artifact]) -> bool {
+    enabled(emit, Artifact::Tvc) || enabled(emit, Artifact::Debug)
+        || args.gen_key.is_some() || args.emit_deploy_payload || args.bundle || args.stdlib_report
+        || args.bindgen.is_some() || args.storage_fees || args.hashed_names || args.manifest
+        || args.archive.is_some() || args.hash_json || args.metadata || args.plugins
+}
@@ -0,0 +1,200 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::fmt;
+use std::time::Duration;
+
+pub type Result<T> = std::result::Result<T, SoldError>;
+pub type Status = Result<()>;
+
+/// Compilation errors, or failed inputs of a --keep-going build
+pub const EXIT_COMPILATION_FAILED: i32 = 1;
+pub const EXIT_LINK_FAILED: i32 = 2;
+/// Invalid arguments or configuration, missing or unreadable files
+pub const EXIT_IO_OR_CONFIG: i32 = 3;
+/// The frontend crashed or produced output sold doesn't understand
+pub const EXIT_INTERNAL_ERROR: i32 = 4;
+/// A check such as `sold fmt --check`, `sold repro-check` or `sold verify` found differences
+pub const EXIT_CHECK_FAILED: i32 = 5;
+// Same as timeout(1), so wrappers can tell a hung build from a failed one.
+pub const EXIT_TIMEOUT: i32 = 124;
+pub const EXIT_MEMORY_LIMIT: i32 = 125;
+
+#[derive(Debug)]
+pub enum SoldError {
+    /// Invalid or inconsistent command line arguments
+    ArgumentError(String),
+    /// Malformed or incomplete sold.toml
+    ConfigError(String),
+    /// Input source or library file doesn't exist
+    SourceNotFound(String),
+    /// The compiler reported errors; warnings are not included
+    CompilationFailed { diagnostics: Vec<String> },
+    /// The compiler produced output sold doesn't understand
+    InvalidOutput(String),
+    /// Assembling or linking of the generated code failed
+    LinkError(String),
+    /// The linked contract doesn't fit into the limits of the network
+    LimitExceeded(String),
+    /// Keypair generation, loading or storing failed
+    KeyError(String),
+    /// Initialization of static fields failed
+    InitError(String),
+    /// An output generator plugin failed
+    PluginError(String),
+    /// The verification service rejected or failed to process a submission
+    VerificationError(String),
+    /// Downloading a frontend with --auto-install failed
+    InstallError(String),
+    /// The build didn't finish within the time limit
+    Timeout(Duration),
+    /// The build used more memory than allowed, both values are in bytes
+    MemoryLimitExceeded { limit: u64, used: u64 },
+    /// The isolated frontend process died instead of producing a result
+    FrontendCrash { status: String, stderr: String },
+    /// The worker process of a build under resource limits failed, with the error it reported
+    BuildFailed { message: String, exit_code: i32 },
+    /// `sold fmt --check` found files that are not formatted
+    Unformatted(Vec<String>),
+    /// `sold gas-diff --fail-on-regression` found functions costing more gas
+    GasRegression(Vec<String>),
+    /// `sold repro-check` found artifacts differing between two builds
+    NotReproducible(Vec<String>),
+    /// --via-ast found outputs differing between direct compilation and compilation via the AST
+    AstMismatch(Vec<String>),
+    /// `sold verify` rebuilt code differing from the deployed one, with the paths of the
+    /// differing cells if the deployed code was given
+    CodeMismatch(Vec<String>),
+    /// Contracts of the same name are defined in several inputs, with --deny-duplicate-contracts
+    DuplicateContracts(Vec<String>),
+    /// Some of the inputs of a --keep-going build failed
+    InputsFailed { failed: Vec<String>, total: usize },
+    IoError(std::io::Error),
+    JsonError(serde_json::Error),
+}
+
+impl fmt::Display for SoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SoldError::ArgumentError(msg) => write!(f, "{}", msg),
+            SoldError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
+            SoldError::SourceNotFound(path) => write!(f, "File \"{}\" not found", path),
+            SoldError::CompilationFailed { .. } => write!(f, "Compilation failed"),
+            SoldError::InvalidOutput(msg) => write!(f, "{}", msg),
+            SoldError::LinkError(msg) => write!(f, "Linking failed: {}", msg),
+            SoldError::LimitExceeded(msg) => write!(f, "{}", msg),
+            SoldError::KeyError(msg) => write!(f, "{}", msg),
+            SoldError::InitError(msg) => write!(f, "Failed to initialize static fields: {}", msg),
+            SoldError::PluginError(msg) => write!(f, "{}", msg),
+            SoldError::VerificationError(msg) => write!(f, "{}", msg),
+            SoldError::InstallError(msg) => write!(f, "Failed to install a frontend: {}", msg),
+            SoldError::Timeout(limit) => write!(f, "Build timed out after {} s", limit.as_secs()),
+            SoldError::MemoryLimitExceeded { limit, used } => write!(f,
+                "Build exceeded the memory limit of {} MB (used {} MB)", limit >> 20, used >> 20
+            ),
+            SoldError::FrontendCrash { status, stderr } => {
+                write!(f, "Compiler frontend crashed ({})", status)?;
+                if !stderr.is_empty() {
+                    write!(f, ":\n{}", stderr)?;
+                }
+                Ok(())
+            }
+            SoldError::BuildFailed { message, .. } => write!(f, "{}", message),
+            SoldError::Unformatted(files) => write!(f,
+                "{} files are not formatted: {}", files.len(), files.join(", ")
+            ),
+            SoldError::GasRegression(functions) => write!(f,
+                "{} functions cost more gas: {}", functions.len(), functions.join(", ")
+            ),
+            SoldError::NotReproducible(artifacts) => write!(f,
+                "{} artifacts differ between builds: {}", artifacts.len(), artifacts.join(", ")
+            ),
+            SoldError::AstMismatch(outputs) => write!(f,
+                "{} outputs differ when compiled via the AST: {}", outputs.len(), outputs.join(", ")
+            ),
+            SoldError::CodeMismatch(cells) if cells.is_empty() => write!(f, "Code doesn't match the sources"),
+            SoldError::CodeMismatch(cells) => write!(f,
+                "Code doesn't match the sources, {} cells differ: {}", cells.len(), cells.join(", ")
+            ),
+            SoldError::DuplicateContracts(names) => write!(f,
+                "{} contract names are defined in several inputs: {}", names.len(), names.join(", ")
+            ),
+            SoldError::InputsFailed { failed, total } => write!(f,
+                "{} of {} inputs failed: {}", failed.len(), total, failed.join(", ")
+            ),
+            SoldError::IoError(e) => write!(f, "{}", e),
+            SoldError::JsonError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl SoldError {
+    /// Exit code of the command line tool failing with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SoldError::CompilationFailed { .. }
+            | SoldError::DuplicateContracts(_)
+            | SoldError::InputsFailed { .. } => EXIT_COMPILATION_FAILED,
+            SoldError::LinkError(_)
+            | SoldError::LimitExceeded(_)
+            | SoldError::InitError(_) => EXIT_LINK_FAILED,
+            SoldError::ArgumentError(_)
+            | SoldError::ConfigError(_)
+            | SoldError::SourceNotFound(_)
+            | SoldError::KeyError(_)
+            | SoldError::PluginError(_)
+            | SoldError::VerificationError(_)
+            | SoldError::InstallError(_)
+            | SoldError::IoError(_) => EXIT_IO_OR_CONFIG,
+            SoldError::InvalidOutput(_)
+            | SoldError::FrontendCrash { .. }
+            | SoldError::JsonError(_) => EXIT_INTERNAL_ERROR,
+            SoldError::Unformatted(_)
+            | SoldError::GasRegression(_)
+            | SoldError::NotReproducible(_)
+            | SoldError::AstMismatch(_)
+            | SoldError::CodeMismatch(_) => EXIT_CHECK_FAILED,
+            SoldError::Timeout(_) => EXIT_TIMEOUT,
+            SoldError::MemoryLimitExceeded { .. } => EXIT_MEMORY_LIMIT,
+            SoldError::BuildFailed { exit_code, .. } => *exit_code,
+        }
+    }
+}
+
+/// Error of a JSON file given by the user that doesn't parse, which is an input error
+/// rather than the internal one of output sold doesn't understand
+pub fn invalid_json(path: impl fmt::Display, e: serde_json::Error) -> SoldError {
+    SoldError::ArgumentError(format!("Invalid JSON in \"{}\": {}", path, e))
+}
+
+impl std::error::Error for SoldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SoldError::IoError(e) => Some(e),
+            SoldError::JsonError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SoldError {
+    fn from(e: std::io::Error) -> Self {
+        SoldError::IoError(e)
+    }
+}
+
+impl From<serde_json::Error> for SoldError {
+    fn from(e: serde_json::Error) -> Self {
+        SoldError::JsonError(e)
+    }
+}
@@ -0,0 +1,299 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Table of the exceptions a contract can throw, for turning exit codes of failed
+// transactions into readable errors. It lists the `require` and `revert` calls of the
+// contract, its bases, libraries and free functions with their exception code, when it is
+// a literal or a constant, and message, and the error definitions with their selectors.
+// The message table is the part of it explorers need: the string literal messages with
+// their codes and locations.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ast::{self, Src};
+use crate::error::Status;
+
+#[derive(Serialize)]
+struct Exception {
+    /// None when the code is not a compile-time constant
+    code: Option<i64>,
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    constant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    /// The message is a string literal rather than an expression
+    #[serde(skip)]
+    literal: bool,
+    function: String,
+    source: String,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    code: i64,
+    message: &'a str,
+    source: &'a str,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Serialize)]
+struct ErrorDefinition {
+    name: String,
+    signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selector: Option<String>,
+    source: String,
+    line: usize,
+}
+
+#[derive(Serialize)]
+struct Table {
+    contract: String,
+    exceptions: Vec<Exception>,
+    errors: Vec<ErrorDefinition>,
+}
+
+pub struct Unit<'a> {
+    pub path: &'a str,
+    pub ast: &'a Value,
+    text: String,
+}
+
+impl Unit<'_> {
+    pub fn line_column(&self, node: &Value) -> (usize, usize) {
+        Src::of(node).map_or((0, 0), |src| crate::lexer::line_column(&self.text, src.start))
+    }
+
+    fn line(&self, node: &Value) -> usize {
+        self.line_column(node).0
+    }
+
+    fn snippet(&self, node: &Value) -> Option<String> {
+        let src = Src::of(node)?;
+        self.text.get(src.start..src.end).map(str::to_owned)
+    }
+}
+
+/// Value of a decimal or hexadecimal literal number
+fn literal_number(node: &Value) -> Option<i64> {
+    if node["nodeType"] != "Literal" || node["kind"] != "number" {
+        return None
+    }
+    let value = node["value"].as_str()?.replace('_', "");
+    match value.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+struct Collector<'a> {
+    /// Constants by declaration id
+    constants: HashMap<u64, &'a Value>,
+    exceptions: Vec<Exception>,
+}
+
+impl<'a> Collector<'a> {
+    /// Exception code and the name of the constant it comes from
+    fn code(&self, node: &Value) -> (Option<i64>, Option<String>) {
+        if let Some(value) = literal_number(node) {
+            return (Some(value), None)
+        }
+        match node["referencedDeclaration"].as_u64().and_then(|id| self.constants.get(&id)) {
+            Some(constant) => (
+                literal_number(&constant["value"]),
+                constant["name"].as_str().map(str::to_owned),
+            ),
+            None => (None, None),
+        }
+    }
+
+    fn message(&self, unit: &Unit, node: &Value) -> Option<String> {
+        if node["nodeType"] == "Literal" && node["kind"] == "string" {
+            node["value"].as_str().map(str::to_owned)
+        } else {
+            unit.snippet(node)
+        }
+    }
+
+    fn collect(&mut self, unit: &Unit, body: &Value, function: &str) {
+        let mut calls = vec![];
+        ast::walk(body, &mut |node| {
+            if node["nodeType"] != "FunctionCall" || node["expression"]["nodeType"] != "Identifier" {
+                return
+            }
+            // Builtins are referenced by negative ids
+            if node["expression"]["referencedDeclaration"].as_i64().map_or(true, |id| id >= 0) {
+                return
+            }
+            if let Some(kind @ ("require" | "revert")) = node["expression"]["name"].as_str() {
+                calls.push((kind, node));
+            }
+        });
+        for (kind, call) in calls {
+            let args = call["arguments"].as_array().map_or(&[][..], Vec::as_slice);
+            // require(condition, code, message) and revert(code, message)
+            let args = if kind == "require" { args.get(1..).unwrap_or_default() } else { args };
+            let (code, constant) = match args.first() {
+                Some(arg) => self.code(arg),
+                None => continue,
+            };
+            let (line, column) = unit.line_column(call);
+            self.exceptions.push(Exception {
+                code,
+                kind: kind.to_owned(),
+                constant,
+                message: args.get(1).and_then(|arg| self.message(unit, arg)),
+                literal: args.get(1).map_or(false, |arg| arg["nodeType"] == "Literal" && arg["kind"] == "string"),
+                function: function.to_owned(),
+                source: unit.path.to_owned(),
+                line,
+                column,
+            });
+        }
+    }
+}
+
+fn error_definition(unit: &Unit, node: &Value) -> ErrorDefinition {
+    let name = node["name"].as_str().unwrap_or_default().to_owned();
+    let types: Vec<&str> = node["parameters"]["parameters"].as_array().map_or(vec![], |params| {
+        params.iter().filter_map(|param| param["typeDescriptions"]["typeString"].as_str()).collect()
+    });
+    ErrorDefinition {
+        signature: format!("{}({})", name, types.join(",")),
+        name,
+        selector: node["errorSelector"].as_str().map(str::to_owned),
+        source: unit.path.to_owned(),
+        line: unit.line(node),
+    }
+}
+
+/// Source units of `sources`, the ASTs of all compiled sources, with their text
+pub fn units<'a>(sources: &BTreeMap<&'a str, &'a Value>) -> Vec<Unit<'a>> {
+    sources.iter()
+        .map(|(path, ast)| Unit { path: *path, ast: *ast, text: std::fs::read_to_string(path).unwrap_or_default() })
+        .collect()
+}
+
+/// Calls `f` with every function, modifier and error definition that can take part in
+/// `contract`: those of the contract, its bases and libraries, and the free ones. Members
+/// are named `Contract.function`, unnamed functions by their kind.
+pub fn visit<'a>(contract: &str, units: &'a [Unit<'a>], f: &mut impl FnMut(&'a Unit<'a>, &'a Value, String)) {
+    let mut contracts = HashMap::new();
+    for unit in units {
+        ast::walk(unit.ast, &mut |node| {
+            if node["nodeType"] == "ContractDefinition" {
+                if let Some(id) = node["id"].as_u64() {
+                    contracts.insert(id, node);
+                }
+            }
+        });
+    }
+    let bases: Vec<u64> = contracts.values()
+        .find(|node| node["name"] == contract)
+        .and_then(|node| node["linearizedBaseContracts"].as_array())
+        .map_or(vec![], |ids| ids.iter().filter_map(Value::as_u64).collect());
+
+    for unit in units {
+        for node in unit.ast["nodes"].as_array().map_or(&[][..], Vec::as_slice) {
+            match node["nodeType"].as_str() {
+                Some("FunctionDefinition") | Some("ErrorDefinition") => {
+                    f(unit, node, node["name"].as_str().unwrap_or_default().to_owned());
+                }
+                Some("ContractDefinition") => {
+                    let included = node["contractKind"] == "library"
+                        || node["id"].as_u64().map_or(false, |id| bases.contains(&id));
+                    if !included {
+                        continue
+                    }
+                    let name = node["name"].as_str().unwrap_or_default();
+                    for member in node["nodes"].as_array().map_or(&[][..], Vec::as_slice) {
+                        if matches!(member["nodeType"].as_str(), Some("FunctionDefinition" | "ModifierDefinition" | "ErrorDefinition")) {
+                            let function = match member["name"].as_str() {
+                                Some("") | None => member["kind"].as_str().unwrap_or_default(),
+                                Some(function) => function,
+                            };
+                            f(unit, member, format!("{}.{}", name, function));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Exception table of `contract` given the ASTs of all compiled sources
+fn table(contract: &str, sources: &BTreeMap<&str, &Value>) -> Table {
+    let units = units(sources);
+
+    let mut constants = HashMap::new();
+    for unit in &units {
+        ast::walk(unit.ast, &mut |node| {
+            if node["nodeType"] == "VariableDeclaration" && node["constant"] == true {
+                if let Some(id) = node["id"].as_u64() {
+                    constants.insert(id, node);
+                }
+            }
+        });
+    }
+
+    let mut collector = Collector { constants, exceptions: vec![] };
+    let mut errors = vec![];
+    visit(contract, &units, &mut |unit, node, name| {
+        if node["nodeType"] == "ErrorDefinition" {
+            errors.push(error_definition(unit, node));
+        } else {
+            collector.collect(unit, node, &name);
+        }
+    });
+
+    let mut exceptions = collector.exceptions;
+    // Known codes first, in ascending order
+    exceptions.sort_by(|a, b| {
+        (a.code.is_none(), a.code, &a.source, a.line).cmp(&(b.code.is_none(), b.code, &b.source, b.line))
+    });
+    Table { contract: contract.to_owned(), exceptions, errors }
+}
+
+/// Writes the exception table of `contract`.
+pub fn write(out: &mut impl Write, contract: &str, sources: &BTreeMap<&str, &Value>) -> Status {
+    serde_json::to_writer_pretty(&mut *out, &table(contract, sources))?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Writes the string literal messages of `contract` with their exception codes.
+pub fn write_messages(out: &mut impl Write, contract: &str, sources: &BTreeMap<&str, &Value>) -> Status {
+    let table = table(contract, sources);
+    let messages: Vec<Message> = table.exceptions.iter()
+        .filter(|exception| exception.literal)
+        .filter_map(|exception| Some(Message {
+            code: exception.code?,
+            message: exception.message.as_deref()?,
+            source: &exception.source,
+            line: exception.line,
+            column: exception.column,
+        }))
+        .collect();
+    serde_json::to_writer_pretty(&mut *out, &messages)?;
+    writeln!(out)?;
+    Ok(())
+}
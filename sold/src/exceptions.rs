@@ -0,0 +1,189 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The exit codes thrown by TVM and by the code the compiler generates, as opposed to the
+// require and revert calls listed by --errors. --exceptions writes the whole table with the
+// constructs of the contract that can raise each code; codes without constructs are raised
+// by the instructions of TVM or by the code shared by all functions, e.g. the selector and
+// the replay protection. Constructs are recognized on the AST, so a code is listed for any
+// construct that may raise it, e.g. for every integer addition even if it can't overflow.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Status;
+
+/// Exit codes of TVM and of the code generated by the compiler
+pub const RUNTIME_CODES: &[(i64, &str)] = &[
+    (2, "Stack underflow"),
+    (3, "Stack overflow"),
+    (4, "Integer overflow"),
+    (5, "Integer out of expected range"),
+    (6, "Invalid opcode"),
+    (7, "Type check error"),
+    (8, "Cell overflow"),
+    (9, "Cell underflow"),
+    (10, "Dictionary error"),
+    (11, "Unknown error"),
+    (12, "Fatal error"),
+    (13, "Out of gas"),
+    (-14, "Out of gas"),
+    (40, "External inbound message has an invalid signature"),
+    (50, "Array index or index of <mapping>.at() is out of range"),
+    (51, "Contract's constructor has already been called"),
+    (52, "Replay protection exception"),
+    (54, "<array>.pop() called for an empty array"),
+    (55, "Bad arguments of tvm.insertPubkey()"),
+    (57, "External inbound message is expired"),
+    (58, "External inbound message has no signature but has a public key"),
+    (60, "Inbound message has a wrong function id"),
+    (61, "Deployed StateInit has no public key in its data"),
+    (63, "<optional(T)>.get() called for an empty optional"),
+    (67, "Bad arguments of gasToValue or valueToGas"),
+    (68, "There is no config parameter 20 or 21"),
+    (69, "Zero to the power of zero"),
+    (70, "substr is longer than the whole string"),
+    (71, "Function marked with externalMsg was called by an internal message"),
+    (72, "Function marked with internalMsg was called by an external message"),
+    (73, "The value can't be converted to the enum type"),
+    (74, "Await answer message has a wrong source address"),
+    (75, "Await answer message has a wrong function id"),
+    (76, "Public function was called before the constructor"),
+    (77, "Variant type can't be converted to the target type"),
+    (78, "There is no private function with the function id"),
+    (79, "Contract uses pragma upgrade func but doesn't define onCodeUpgrade()"),
+];
+
+#[derive(Serialize)]
+struct Site {
+    construct: &'static str,
+    function: String,
+    source: String,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Serialize)]
+struct Exception {
+    code: i64,
+    meaning: &'static str,
+    /// "tvm" for the codes of TVM instructions, "compiler" for those of generated code
+    origin: &'static str,
+    sites: Vec<Site>,
+}
+
+#[derive(Serialize)]
+struct Table {
+    contract: String,
+    exceptions: Vec<Exception>,
+}
+
+const ARITHMETIC: &[&str] = &["+", "-", "*", "/", "%", "**", "<<", "++", "--", "+=", "-=", "*=", "/=", "%=", "<<="];
+
+fn type_string(node: &Value) -> &str {
+    node["typeDescriptions"]["typeString"].as_str().unwrap_or_default()
+}
+
+fn is_integer(type_string: &str) -> bool {
+    type_string.starts_with("uint") || type_string.starts_with("int") || type_string.starts_with("varUint")
+        || type_string.starts_with("varInt")
+}
+
+/// Codes `node` can raise, with the name of its construct
+fn constructs(node: &Value) -> Vec<(i64, &'static str)> {
+    let mut res = vec![];
+    let member = node["memberName"].as_str().unwrap_or_default();
+    let object = type_string(&node["expression"]);
+    match node["nodeType"].as_str().unwrap_or_default() {
+        "BinaryOperation" | "UnaryOperation" | "Assignment" => {
+            let operator = node["operator"].as_str().unwrap_or_default();
+            if ARITHMETIC.contains(&operator) && is_integer(type_string(node)) {
+                res.push((4, "integer arithmetic"));
+                if operator == "**" {
+                    res.push((69, "exponentiation"));
+                }
+            }
+        }
+        "IndexAccess" if type_string(&node["baseExpression"]).contains("[]") => {
+            res.push((50, "array index"));
+        }
+        "MemberAccess" => match member {
+            "at" if object.starts_with("mapping(") => res.push((50, "mapping at()")),
+            "pop" if object.contains("[]") => res.push((54, "array pop()")),
+            "get" if object.starts_with("optional(") => res.push((63, "optional get()")),
+            "substr" if object.starts_with("string") => res.push((70, "string substr()")),
+            "insertPubkey" if node["expression"]["name"] == "tvm" => res.push((55, "tvm.insertPubkey()")),
+            "toUint" | "toAddress" if object.starts_with("variant") => res.push((77, "variant conversion")),
+            _ => {}
+        },
+        "Identifier" if matches!(node["name"].as_str(), Some("gasToValue" | "valueToGas")) => {
+            res.push((67, "gas conversion"));
+            res.push((68, "gas conversion"));
+        }
+        "FunctionCall" if node["kind"] == "typeConversion" && type_string(node).starts_with("enum ") => {
+            res.push((73, "conversion to enum"));
+        }
+        "FunctionDefinition" => {
+            if node["kind"] == "constructor" {
+                res.push((51, "constructor"));
+            }
+            if node["externalMsg"] == true {
+                res.push((71, "externalMsg function"));
+            }
+            if node["internalMsg"] == true {
+                res.push((72, "internalMsg function"));
+            }
+        }
+        _ => {}
+    }
+    res
+}
+
+/// Writes the exception codes `contract` can raise given the ASTs of all compiled sources.
+pub fn write(out: &mut impl Write, contract: &str, sources: &BTreeMap<&str, &Value>) -> Status {
+    let units = crate::errors::units(sources);
+    let mut sites: BTreeMap<i64, Vec<Site>> = BTreeMap::new();
+    crate::errors::visit(contract, &units, &mut |unit, function, name| {
+        if function["nodeType"] == "ErrorDefinition" {
+            return
+        }
+        crate::ast::walk(function, &mut |node| {
+            for (code, construct) in constructs(node) {
+                let (line, column) = unit.line_column(node);
+                sites.entry(code).or_default().push(Site {
+                    construct,
+                    function: name.clone(),
+                    source: unit.path.to_owned(),
+                    line,
+                    column,
+                });
+            }
+        });
+    });
+
+    let exceptions = RUNTIME_CODES.iter()
+        .map(|(code, meaning)| Exception {
+            code: *code,
+            meaning: *meaning,
+            origin: if (0..32).contains(code) || *code == -14 { "tvm" } else { "compiler" },
+            sites: sites.remove(code).unwrap_or_default(),
+        })
+        .collect();
+    let table = Table { contract: contract.to_owned(), exceptions };
+    serde_json::to_writer_pretty(&mut *out, &table)?;
+    writeln!(out)?;
+    Ok(())
+}
@@ -0,0 +1,291 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Source formatter. It works on tokens rather than on the AST, so TVM extensions
+// (`responsible`, `optional(T)`, `repeat`, call options, `tvm.*`) need no special support,
+// and it keeps the line breaks of the author. Within a line the spacing is normalized:
+// one space around binary operators, after commas and before blocks, none inside
+// parentheses and brackets, and a single space wherever the author put any otherwise.
+// Lines are indented by four spaces per open bracket, brackets opened on one line
+// counting once. Pragma directives are kept as written.
+
+use std::path::Path;
+
+use crate::error::{Result, SoldError, Status};
+use crate::lexer::{self, Token, TokenKind};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct FmtArgs {
+    /// Source files or directories
+    #[clap(value_parser, default_value = ".")]
+    pub inputs: Vec<String>,
+    /// Don't write the files, fail if any of them is not formatted
+    #[clap(long, value_parser)]
+    pub check: bool,
+}
+
+const INDENT: &str = "    ";
+
+/// Keywords separated from the following parenthesis, unlike function names
+const SPACED_KEYWORDS: &[&str] = &[
+    "if", "for", "while", "repeat", "catch", "returns", "return", "emit", "else", "do",
+];
+
+const BINARY: &[&str] = &[
+    "=", "==", "!=", "<", ">", "<=", ">=", "&&", "||", "+=", "-=", "*=", "/=", "%=", "|=", "&=",
+    "^=", "<<=", ">>=", ">>>=", "=>", "**", "*", "/", "%", "|", "&", "^", "<<", ">>", ">>>", "?",
+];
+
+struct Item<'a> {
+    token: Token<'a>,
+    /// Whether the author separated the token from the previous one
+    space: bool,
+}
+
+#[derive(Default)]
+struct Line<'a> {
+    items: Vec<Item<'a>>,
+    blank_before: bool,
+}
+
+fn split_lines<'a>(tokens: &[Token<'a>]) -> Vec<Line<'a>> {
+    let mut lines = vec![];
+    let mut current = Line::default();
+    let mut space = false;
+    let mut newlines = 0;
+    for token in tokens {
+        if token.kind == TokenKind::Whitespace {
+            match token.text.matches('\n').count() {
+                0 => space = true,
+                n => newlines += n,
+            }
+            continue
+        }
+        if newlines > 0 {
+            if !current.items.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            current.blank_before = newlines > 1 && !lines.is_empty();
+            newlines = 0;
+        }
+        current.items.push(Item { token: *token, space });
+        space = false;
+    }
+    if !current.items.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+fn is_opener(token: &Token) -> bool {
+    token.kind == TokenKind::Punct && matches!(token.text, "(" | "[" | "{")
+}
+
+fn is_closer(token: &Token) -> bool {
+    token.kind == TokenKind::Punct && matches!(token.text, ")" | "]" | "}")
+}
+
+/// Whether a `+` or `-` after `prev` is unary, None at the start of a line
+fn is_unary(prev: Option<&Token>) -> Option<bool> {
+    let prev = prev?;
+    Some(match prev.kind {
+        TokenKind::Punct => !matches!(prev.text, ")" | "]" | "++" | "--"),
+        TokenKind::Identifier => prev.text == "return",
+        _ => false,
+    })
+}
+
+struct Context {
+    prev_unary: Option<bool>,
+    cur_unary: Option<bool>,
+    /// `cur` is a `{` ending the line, i.e. opens a block
+    opens_block: bool,
+    /// The author separated the tokens
+    space: bool,
+}
+
+fn spaced(prev: &Token, cur: &Token, cx: &Context) -> bool {
+    use TokenKind::*;
+    if cur.kind == LineComment {
+        return true
+    }
+    if prev.kind == BlockComment || cur.kind == BlockComment {
+        return cx.space
+    }
+    let (p, c) = (prev.text, cur.text);
+    // Never glue two operators into a different one, like `-` and `-` into `--`
+    if prev.kind == Punct && cur.kind == Punct && lexer::tokenize(&format!("{}{}", p, c)).len() != 2 {
+        return true
+    }
+    if matches!(c, "," | ";" | ")" | "]" | ".") || matches!(p, "(" | "[" | ".") {
+        return false
+    }
+    if matches!(p, "++" | "--" | "!" | "~") || matches!(c, "++" | "--") {
+        return false
+    }
+    if BINARY.contains(&p) || BINARY.contains(&c) {
+        return true
+    }
+    if matches!(p, "+" | "-") {
+        return match cx.prev_unary {
+            Some(unary) => !unary,
+            None => cx.space,
+        }
+    }
+    if matches!(c, "+" | "-") {
+        return cx.cur_unary == Some(false) || cx.space
+    }
+    if p == "," || (p == ";" && c != "}") {
+        return true
+    }
+    if matches!(p, ")" | "]") && (cur.kind == Identifier || c == "{") {
+        return true
+    }
+    if matches!(c, "(" | "[") {
+        if prev.kind == Identifier && SPACED_KEYWORDS.contains(&p) {
+            return true
+        }
+        if prev.kind == Identifier || matches!(p, ")" | "]") {
+            return false
+        }
+        return cx.space
+    }
+    if c == "{" {
+        return cx.opens_block || cx.space
+    }
+    if p == "}" && cur.kind == Identifier {
+        return true
+    }
+    cx.space
+}
+
+fn push_token(out: &mut String, token: &Token, indent: usize) {
+    match token.kind {
+        TokenKind::BlockComment => {
+            for (i, line) in token.text.split('\n').enumerate() {
+                if i == 0 {
+                    out.push_str(line.trim_end());
+                    continue
+                }
+                out.push('\n');
+                // Continuation lines of doc comments line up under the opening `/**`
+                let trimmed = line.trim();
+                if trimmed.starts_with('*') {
+                    out.push_str(&INDENT.repeat(indent));
+                    out.push(' ');
+                    out.push_str(trimmed);
+                } else {
+                    out.push_str(line.trim_end());
+                }
+            }
+        }
+        _ => out.push_str(token.text.trim_end()),
+    }
+}
+
+pub fn format_source(src: &str) -> String {
+    let tokens = lexer::tokenize(src);
+    let mut out = String::with_capacity(src.len());
+    // Indentation of the lines that opened the brackets still open
+    let mut stack: Vec<usize> = vec![];
+    for line in split_lines(&tokens) {
+        let first = &line.items[0].token;
+        let indent = if is_closer(first) {
+            stack.last().copied().unwrap_or(0)
+        } else {
+            stack.last().map_or(0, |indent| indent + 1)
+        };
+        if line.blank_before {
+            out.push('\n');
+        }
+        out.push_str(&INDENT.repeat(indent));
+
+        let last = &line.items[line.items.len() - 1].token;
+        if first.kind == TokenKind::Identifier && first.text == "pragma" {
+            out.push_str(src[first.start..last.end()].trim_end());
+        } else {
+            let code: Vec<&Token> = line.items.iter()
+                .map(|item| &item.token)
+                .filter(|token| !token.is_trivia())
+                .collect();
+            let mut prev_unary = None;
+            for (i, item) in line.items.iter().enumerate() {
+                let token = &item.token;
+                let position = code.iter().position(|t| t.start == token.start);
+                let cur_unary = position.and_then(|pos| is_unary(pos.checked_sub(1).map(|p| code[p])));
+                if i > 0 {
+                    let cx = Context {
+                        prev_unary,
+                        cur_unary,
+                        opens_block: token.text == "{" && position == Some(code.len() - 1),
+                        space: item.space,
+                    };
+                    if spaced(&line.items[i - 1].token, token, &cx) {
+                        out.push(' ');
+                    }
+                }
+                push_token(&mut out, token, indent);
+                prev_unary = cur_unary;
+            }
+        }
+        out.push('\n');
+
+        for item in &line.items {
+            if is_opener(&item.token) {
+                stack.push(indent);
+            } else if is_closer(&item.token) {
+                stack.pop();
+            }
+        }
+    }
+    out
+}
+
+/// Code tokens of the source, which formatting must not change
+fn code_tokens(src: &str) -> Vec<&str> {
+    lexer::tokenize(src).into_iter()
+        .filter(|t| !t.is_trivia())
+        .map(|t| t.text)
+        .collect()
+}
+
+fn format_file(path: &Path) -> Result<Option<String>> {
+    let text = std::fs::read_to_string(path)?;
+    let formatted = format_source(&text);
+    if code_tokens(&formatted) != code_tokens(&text) {
+        return Err(SoldError::InvalidOutput(format!(
+            "Formatting would change the code of {}, please report this", path.display()
+        )))
+    }
+    Ok(if formatted == text { None } else { Some(formatted) })
+}
+
+pub fn fmt(args: FmtArgs) -> Status {
+    let files = crate::sources::expand(&args.inputs)?;
+    let mut unformatted = vec![];
+    for file in &files {
+        if let Some(formatted) = format_file(file)? {
+            if args.check {
+                println!("Not formatted: {}", file.display());
+            } else {
+                std::fs::write(file, formatted)?;
+                println!("Formatted {}", file.display());
+            }
+            unformatted.push(file.display().to_string());
+        }
+    }
+    if args.check && !unformatted.is_empty() {
+        return Err(SoldError::Unformatted(unformatted))
+    }
+    Ok(())
+}
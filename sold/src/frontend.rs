@@ -0,0 +1,162 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Entry points of the frontend: those of the statically linked libsolc, or of a shared
+// library loaded at runtime with --solc-lib, which lets frontend patches be tried without
+// rebuilding sold. A library that fails to load leaves the embedded frontend in place.
+// Frontends of other versions can be listed in the [frontends] table of sold.toml; each
+// source is then built by the newest of them, embedded one included, that meets its
+// version pragma; when none does, --auto-install downloads one (see install.rs).
+// The frontend in use is selected per compiler, see compiler.rs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::os::raw::{c_char, c_void};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use lazy_static::lazy_static;
+
+use crate::compiler::{self, State};
+use crate::{libsolc, messages, pragma};
+
+type Alloc = unsafe extern "C" fn(u64) -> *mut c_char;
+type Compile = unsafe extern "C" fn(*const c_char, libsolc::CStyleReadFileCallback, *mut c_void) -> *mut c_char;
+type Free = unsafe extern "C" fn(*mut c_char);
+type Version = unsafe extern "C" fn() -> *const c_char;
+
+pub struct Frontend {
+    pub alloc: Alloc,
+    pub compile: Compile,
+    pub free: Free,
+    pub version: Version,
+    /// Path of the shared library, None for the embedded frontend
+    pub path: Option<String>,
+    /// Held during a compilation: the frontend allows only one CompilerStack at a time
+    calls: Mutex<()>,
+}
+
+impl Frontend {
+    /// Waits for the compilations of other compilers with this frontend to complete
+    pub fn exclusive(&self) -> MutexGuard<'_, ()> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+lazy_static! {
+    static ref EMBEDDED: Frontend = Frontend {
+        alloc: libsolc::solidity_alloc,
+        compile: libsolc::solidity_compile,
+        free: libsolc::solidity_free,
+        version: libsolc::solidity_version,
+        path: None,
+        calls: Mutex::new(()),
+    };
+    /// Loaded libraries by path, they stay loaded until the process exits
+    static ref LOADED: Mutex<HashMap<String, &'static Frontend>> = Mutex::new(HashMap::new());
+}
+
+fn load_library(path: &str) -> Result<Frontend, libloading::Error> {
+    unsafe {
+        let library = libloading::Library::new(path)?;
+        let frontend = Frontend {
+            alloc: *library.get::<Alloc>(b"solidity_alloc\0")?,
+            compile: *library.get::<Compile>(b"solidity_compile\0")?,
+            free: *library.get::<Free>(b"solidity_free\0")?,
+            version: *library.get::<Version>(b"solidity_version\0")?,
+            path: Some(path.to_owned()),
+            calls: Mutex::new(()),
+        };
+        std::mem::forget(library);
+        Ok(frontend)
+    }
+}
+
+/// Makes the shared library at `path` the frontend of the current compiler until another
+/// one is selected.
+pub fn load(path: &str) {
+    let mut loaded = LOADED.lock().unwrap();
+    let frontend = match loaded.get(path) {
+        Some(frontend) => *frontend,
+        None => match load_library(path) {
+            Ok(frontend) => {
+                tracing::info!(path, "loaded frontend");
+                let frontend: &'static Frontend = Box::leak(Box::new(frontend));
+                loaded.insert(path.to_owned(), frontend);
+                frontend
+            }
+            Err(e) => {
+                messages::warning(&format!("Warning: failed to load frontend \"{}\", using the embedded one: {}", path, e));
+                return
+            }
+        },
+    };
+    *compiler::current().state().frontend.lock().unwrap() = Some(frontend);
+}
+
+fn use_embedded() {
+    *compiler::current().state().frontend.lock().unwrap() = None;
+}
+
+/// The frontend selected in `compiler`
+pub(crate) fn selected(compiler: &State) -> &'static Frontend {
+    compiler.frontend.lock().unwrap().unwrap_or(&*EMBEDDED)
+}
+
+/// The frontend of the current compiler
+pub fn get() -> &'static Frontend {
+    selected(compiler::current().state())
+}
+
+/// Version of the frontend sold is built with
+pub fn embedded_version() -> String {
+    unsafe { std::ffi::CStr::from_ptr((EMBEDDED.version)()) }.to_string_lossy().into_owned()
+}
+
+/// Selects the newest frontend meeting the version pragma of `source`, among the embedded
+/// one and `frontends`, shared libraries by version. If none does, the embedded frontend
+/// reports the mismatch and the unmet requirement is returned.
+pub fn select(source: &str, frontends: &BTreeMap<String, PathBuf>) -> Option<String> {
+    let embedded = embedded_version();
+    let requirement = match pragma::requirement(source) {
+        Some(requirement) => requirement,
+        None => {
+            use_embedded();
+            return None
+        }
+    };
+    let mut candidates: Vec<((u64, u64, u64), Option<&PathBuf>)> = frontends.iter()
+        .filter_map(|(version, path)| Some((pragma::parse_version(version)?, Some(path))))
+        .collect();
+    if let Some(version) = pragma::parse_version(&embedded) {
+        candidates.push((version, None));
+    }
+    let newest = candidates.into_iter()
+        .filter(|(version, _)| pragma::satisfies(&requirement, *version))
+        // The embedded frontend wins over a library of the same version
+        .max_by_key(|(version, path)| (*version, path.is_none()));
+    match newest {
+        Some((version, Some(path))) => {
+            tracing::info!(requirement = requirement.as_str(), ?version, "selected frontend");
+            load(&path.to_string_lossy());
+            None
+        }
+        Some((_, None)) => {
+            use_embedded();
+            None
+        }
+        None => {
+            use_embedded();
+            Some(requirement)
+        }
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Function IDs with what indexers building selector databases need besides the name: the
+// canonical signature the ID is computed from, the parameters and the kind of function.
+// Functions of the ABI are external, public state variables among them getters, as are
+// the get-methods the ABI lists apart; functions with an ID but absent from the ABI can
+// only be called by internal messages.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{Result, SoldError, Status};
+use crate::FunctionId;
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    External,
+    Internal,
+    Getter,
+}
+
+#[derive(Serialize)]
+struct Entry {
+    id: FunctionId,
+    kind: Kind,
+    /// Signature the ID is computed from, e.g. `transfer(address,uint128)(bool)v2`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    inputs: Value,
+    outputs: Value,
+}
+
+fn named<'a>(abi: &'a Value, key: &str, name: &str) -> Option<&'a Value> {
+    abi[key].as_array()?.iter().find(|item| item["name"] == name)
+}
+
+/// Whether `function` is the getter of a public state variable
+fn is_state_variable(abi: &Value, name: &str, function: &Value) -> bool {
+    named(abi, "fields", name).is_some() && function["inputs"].as_array().map_or(true, Vec::is_empty)
+}
+
+fn describe(abi: &Value, function_ids: &BTreeMap<String, FunctionId>) -> Result<BTreeMap<String, Entry>> {
+    let contract = ton_abi::Contract::load(abi.to_string().as_bytes())
+        .map_err(|e| SoldError::InvalidOutput(format!("Invalid ABI: {}", e)))?;
+    let mut res = BTreeMap::new();
+    for (name, id) in function_ids {
+        let function = named(abi, "functions", name);
+        let getter = named(abi, "getters", name);
+        let kind = match (function, getter) {
+            (_, Some(_)) => Kind::Getter,
+            (Some(function), None) if is_state_variable(abi, name, function) => Kind::Getter,
+            (Some(_), None) => Kind::External,
+            (None, None) => Kind::Internal,
+        };
+        let entry = function.or(getter);
+        res.insert(name.clone(), Entry {
+            id: *id,
+            kind,
+            signature: contract.function(name).ok().map(|function| function.get_function_signature()),
+            inputs: entry.map_or_else(|| Value::Array(vec![]), |entry| entry["inputs"].clone()),
+            outputs: entry.map_or_else(|| Value::Array(vec![]), |entry| entry["outputs"].clone()),
+        });
+    }
+    Ok(res)
+}
+
+/// Writes the functions of `function_ids` by name, with their IDs, kinds, signatures and
+/// parameters as `abi` declares them.
+pub fn write(out: &mut impl Write, abi: &Value, function_ids: &BTreeMap<String, FunctionId>) -> Status {
+    serde_json::to_writer_pretty(&mut *out, &describe(abi, function_ids)?)?;
+    writeln!(out)?;
+    Ok(())
+}
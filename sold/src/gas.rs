@@ -0,0 +1,506 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Static gas estimation over the produced assembly. Every public function is explored
+// path by path: conditional continuations (IF, IFELSE, IFJMP, IFRET, THROWIF...) fork
+// the path, calls are expanded with the callee's own paths, and loops are charged for a
+// fixed number of iterations. Instruction prices follow the TVM basic rule (10 + opcode
+// bits) with surcharges for cell creation, cell loading and exceptions, so the numbers
+// are estimates rather than exact emulator results. Branches are labelled with the source
+// line of the `.loc` before them where the assembly has one; with --debug the functions
+// are attributed to their declarations through the debug map, as size-diff attributes
+// cells, which also works for code built with --strip.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::asm::{self, Assembly, FunctionKind, Loc, Node};
+use crate::error::{invalid_json, SoldError, Status};
+use crate::messages;
+use crate::size_diff::Attribution;
+
+// The prices are estimates: opcodes are taken as 8 bits long, and the surcharges are
+// those of the TVM price table without the cheaper reloading of a cell already loaded or
+// the gas of the stack depth beyond 32 entries.
+
+/// Price of an ordinary instruction: 10 plus an 8-bit opcode
+const BASIC: u64 = 18;
+const CELL_CREATE: u64 = 500;
+const CELL_LOAD: u64 = 100;
+const EXCEPTION: u64 = 50;
+/// Implicit jump and return of a call
+const CALL_OVERHEAD: u64 = 10;
+/// Paths kept per continuation, the most expensive ones win
+const MAX_PATHS: usize = 256;
+const SHOWN_PATHS: usize = 3;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct GasArgs {
+    /// Assembly file produced by the build (<prefix>.code)
+    #[clap(value_parser)]
+    pub input: String,
+    /// Analyze only the given function
+    #[clap(long, value_parser)]
+    pub function: Option<String>,
+    /// Number of iterations assumed for every loop
+    #[clap(long, value_parser, default_value_t = 1)]
+    pub loop_iterations: u64,
+    /// Library the code is linked with, instead of default
+    #[clap(short('L'), long, value_parser)]
+    pub lib: Option<String>,
+    /// Debug map of the build (<prefix>.debug.json), to attribute the functions to their
+    /// declarations
+    #[clap(long, value_parser)]
+    pub debug: Option<String>,
+    /// Print the report in JSON format
+    #[clap(long, value_parser)]
+    pub json: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Exit {
+    Fallthrough,
+    Return,
+    ReturnAlt,
+    Throw,
+}
+
+#[derive(Clone, Debug)]
+struct Path {
+    gas: u64,
+    exit: Exit,
+    /// Branch decisions taken along the path
+    trace: Vec<String>,
+}
+
+impl Path {
+    fn new(gas: u64, exit: Exit) -> Path {
+        Path { gas, exit, trace: vec![] }
+    }
+
+    fn labelled(mut self, label: String) -> Path {
+        self.trace.insert(0, label);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Flags {
+    truncated: bool,
+    loops: bool,
+    recursion: bool,
+}
+
+impl Flags {
+    fn merge(&mut self, other: &Flags) {
+        self.truncated |= other.truncated;
+        self.loops |= other.loops;
+        self.recursion |= other.recursion;
+    }
+}
+
+enum Step {
+    Gas(u64),
+    Paths(Vec<Path>),
+}
+
+#[derive(Serialize)]
+struct FunctionReport {
+    name: String,
+    /// Source declaration the debug map attributes the function to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    declaration: Option<String>,
+    worst: u64,
+    typical: u64,
+    paths: usize,
+    throwing_paths: usize,
+    truncated: bool,
+    loops: bool,
+    recursion: bool,
+    dominating: Vec<PathReport>,
+}
+
+#[derive(Serialize)]
+struct PathReport {
+    gas: u64,
+    throws: bool,
+    trace: Vec<String>,
+}
+
+struct Analyzer<'a> {
+    functions: &'a HashMap<String, Vec<Node>>,
+    loop_iterations: u64,
+    summaries: HashMap<String, (Vec<Path>, Flags)>,
+    active: HashSet<String>,
+    flags: Flags,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(functions: &'a HashMap<String, Vec<Node>>, loop_iterations: u64) -> Self {
+        Analyzer {
+            functions,
+            loop_iterations,
+            summaries: HashMap::new(),
+            active: HashSet::new(),
+            flags: Flags::default(),
+        }
+    }
+
+    fn report(&mut self, name: &str) -> FunctionReport {
+        self.flags = Flags::default();
+        let mut paths = self.call(name);
+        paths.sort_by(|a, b| b.gas.cmp(&a.gas));
+        let throwing_paths = paths.iter().filter(|p| p.exit == Exit::Throw).count();
+        let mut typical: Vec<u64> = paths.iter()
+            .filter(|p| p.exit != Exit::Throw)
+            .map(|p| p.gas)
+            .collect();
+        if typical.is_empty() {
+            typical = paths.iter().map(|p| p.gas).collect();
+        }
+        FunctionReport {
+            name: name.to_owned(),
+            declaration: None,
+            worst: paths.first().map_or(0, |p| p.gas),
+            typical: typical[typical.len() / 2],
+            paths: paths.len(),
+            throwing_paths,
+            truncated: self.flags.truncated,
+            loops: self.flags.loops,
+            recursion: self.flags.recursion,
+            dominating: paths.into_iter().take(SHOWN_PATHS).map(|p| PathReport {
+                gas: p.gas,
+                throws: p.exit == Exit::Throw,
+                trace: p.trace,
+            }).collect(),
+        }
+    }
+
+    /// Paths of a whole function; returns from it are turned into fallthrough.
+    fn call(&mut self, name: &str) -> Vec<Path> {
+        if let Some((paths, flags)) = self.summaries.get(name) {
+            self.flags.merge(flags);
+            return paths.clone()
+        }
+        let functions = self.functions;
+        let body = match functions.get(name) {
+            Some(body) => body,
+            None => return vec![Path::new(0, Exit::Fallthrough)],
+        };
+        if !self.active.insert(name.to_owned()) {
+            self.flags.recursion = true;
+            return vec![Path::new(0, Exit::Fallthrough)]
+        }
+        let outer = std::mem::take(&mut self.flags);
+        let mut paths = self.explore(body);
+        for path in &mut paths {
+            if path.exit != Exit::Throw {
+                path.exit = Exit::Fallthrough;
+            }
+        }
+        self.active.remove(name);
+        let flags = std::mem::replace(&mut self.flags, outer);
+        self.flags.merge(&flags);
+        self.summaries.insert(name.to_owned(), (paths.clone(), flags));
+        paths
+    }
+
+    fn explore(&mut self, nodes: &'a [Node]) -> Vec<Path> {
+        let mut paths = vec![Path::new(0, Exit::Fallthrough)];
+        let mut conts: Vec<&'a [Node]> = vec![];
+        // IFREFELSEREF is followed by its two bodies printed as bare blocks
+        let mut pending: Option<Option<&'a Loc>> = None;
+        for node in nodes {
+            if paths.iter().all(|p| p.exit != Exit::Fallthrough) {
+                break
+            }
+            let step = match node {
+                Node::Op { op, loc, .. } if op == "IFREFELSEREF" => {
+                    pending = Some(loc.as_ref());
+                    Step::Gas(0)
+                }
+                Node::Block { op, body, loc } => match op.as_str() {
+                    "PUSHCONT" | "PUSHREFCONT" => {
+                        conts.push(body);
+                        Step::Gas(BASIC)
+                    }
+                    "" => {
+                        conts.push(body);
+                        match pending {
+                            Some(loc) if conts.len() >= 2 => {
+                                pending = None;
+                                charge_step(self.step("IFELSE", "", loc, &mut conts), 2 * CELL_LOAD)
+                            }
+                            _ => Step::Gas(0),
+                        }
+                    }
+                    "IFREF" | "IFNOTREF" | "IFJMPREF" | "IFNOTJMPREF" | "IFREFELSE" | "IFELSEREF" => {
+                        conts.push(body);
+                        if op == "IFREFELSE" && conts.len() >= 2 {
+                            // the body is the true branch, the false one was pushed before
+                            let len = conts.len();
+                            conts.swap(len - 1, len - 2);
+                        }
+                        let op = match op.as_str() {
+                            "IFREFELSE" | "IFELSEREF" => "IFELSE",
+                            op => op.strip_suffix("REF").unwrap_or(op),
+                        };
+                        charge_step(self.step(op, "", loc.as_ref(), &mut conts), CELL_LOAD)
+                    }
+                    "CALLREF" => Step::Paths(charge(self.branch(body), BASIC + CELL_LOAD)),
+                    "JMPREF" => Step::Paths(charge(self.jump(body), BASIC + CELL_LOAD)),
+                    _ => Step::Gas(BASIC),
+                },
+                Node::Op { op, args, loc } => self.step(op, args, loc.as_ref(), &mut conts),
+            };
+            match step {
+                Step::Gas(gas) => {
+                    for path in paths.iter_mut().filter(|p| p.exit == Exit::Fallthrough) {
+                        path.gas += gas;
+                    }
+                }
+                Step::Paths(step) => paths = self.join(paths, &step),
+            }
+        }
+        paths
+    }
+
+    fn step(&mut self, op: &str, args: &str, loc: Option<&Loc>, conts: &mut Vec<&'a [Node]>) -> Step {
+        let at = |what: &str| match loc {
+            Some(loc) => format!("{}: {} {}", loc, op, what),
+            None => format!("{} {}", op, what),
+        };
+        let skip = || Path::new(0, Exit::Fallthrough).labelled(at("not taken"));
+        let paths = match op {
+            "IFELSE" => match (conts.pop(), conts.pop()) {
+                (Some(otherwise), Some(then)) => {
+                    let mut res = labelled(self.branch(then), at("taken"));
+                    res.extend(labelled(self.branch(otherwise), at("not taken")));
+                    res
+                }
+                _ => return Step::Gas(BASIC),
+            },
+            "IF" | "IFNOT" => match conts.pop() {
+                Some(then) => {
+                    let mut res = labelled(self.branch(then), at("taken"));
+                    res.push(skip());
+                    res
+                }
+                None => return Step::Gas(BASIC),
+            },
+            "IFJMP" | "IFNOTJMP" => match conts.pop() {
+                Some(then) => {
+                    let mut res = labelled(self.jump(then), at("taken"));
+                    res.push(skip());
+                    res
+                }
+                None => return Step::Gas(BASIC),
+            },
+            "IFRET" | "IFNOTRET" => vec![Path::new(0, Exit::Return).labelled(at("taken")), skip()],
+            "IFRETALT" | "IFNOTRETALT" => vec![Path::new(0, Exit::ReturnAlt).labelled(at("taken")), skip()],
+            "RET" => vec![Path::new(0, Exit::Return)],
+            "RETALT" => vec![Path::new(0, Exit::ReturnAlt)],
+            "REPEAT" | "REPEATBRK" | "UNTIL" | "UNTILBRK" | "AGAIN" | "AGAINBRK" => {
+                let body = conts.pop().map_or(0, |body| self.worst(body));
+                return Step::Gas(BASIC + self.loop_gas(body))
+            }
+            "WHILE" | "WHILEBRK" => {
+                let body = conts.pop().map_or(0, |body| self.worst(body));
+                let cond = conts.pop().map_or(0, |cond| self.worst(cond));
+                return Step::Gas(BASIC + self.loop_gas(body + cond))
+            }
+            "CALL" => match asm::call_target(args) {
+                Some(name) => charge(self.call(name), BASIC + CALL_OVERHEAD),
+                None => return Step::Gas(BASIC),
+            },
+            ".inline" => self.call(args.trim().trim_start_matches(':')),
+            _ if op.starts_with("THROW") && op.contains("IF") => vec![
+                Path::new(EXCEPTION, Exit::Throw).labelled(at("throws")),
+                Path::new(0, Exit::Fallthrough),
+            ],
+            _ if op.starts_with("THROW") => vec![Path::new(EXCEPTION, Exit::Throw).labelled(at("throws"))],
+            _ if op.starts_with('.') => return Step::Gas(0),
+            _ => return Step::Gas(op_gas(op)),
+        };
+        Step::Paths(charge(paths, BASIC))
+    }
+
+    /// Continuation executed as a call: returning from it continues the current one.
+    fn branch(&mut self, body: &'a [Node]) -> Vec<Path> {
+        let mut paths = self.explore(body);
+        for path in &mut paths {
+            if path.exit == Exit::Return {
+                path.exit = Exit::Fallthrough;
+            }
+        }
+        paths
+    }
+
+    /// Continuation executed as a jump: the current one never resumes.
+    fn jump(&mut self, body: &'a [Node]) -> Vec<Path> {
+        let mut paths = self.explore(body);
+        for path in &mut paths {
+            if path.exit == Exit::Fallthrough {
+                path.exit = Exit::Return;
+            }
+        }
+        paths
+    }
+
+    fn worst(&mut self, body: &'a [Node]) -> u64 {
+        self.explore(body).iter().map(|p| p.gas).max().unwrap_or(0)
+    }
+
+    fn loop_gas(&mut self, iteration: u64) -> u64 {
+        self.flags.loops = true;
+        iteration.saturating_mul(self.loop_iterations)
+    }
+
+    fn join(&mut self, prefix: Vec<Path>, step: &[Path]) -> Vec<Path> {
+        let mut res = Vec::new();
+        for path in prefix {
+            if path.exit != Exit::Fallthrough {
+                res.push(path);
+                continue
+            }
+            for next in step {
+                let mut trace = path.trace.clone();
+                trace.extend(next.trace.iter().cloned());
+                res.push(Path { gas: path.gas + next.gas, exit: next.exit, trace });
+            }
+        }
+        if res.len() > MAX_PATHS {
+            self.flags.truncated = true;
+            res.sort_by(|a, b| b.gas.cmp(&a.gas));
+            let cheapest = res.pop().unwrap();
+            res.truncate(MAX_PATHS - 1);
+            res.push(cheapest);
+        }
+        res
+    }
+}
+
+fn charge(mut paths: Vec<Path>, gas: u64) -> Vec<Path> {
+    for path in &mut paths {
+        path.gas += gas;
+    }
+    paths
+}
+
+fn charge_step(step: Step, gas: u64) -> Step {
+    match step {
+        Step::Gas(base) => Step::Gas(base + gas),
+        Step::Paths(paths) => Step::Paths(charge(paths, gas)),
+    }
+}
+
+fn labelled(paths: Vec<Path>, label: String) -> Vec<Path> {
+    paths.into_iter().map(|p| p.labelled(label.clone())).collect()
+}
+
+fn op_gas(op: &str) -> u64 {
+    match op {
+        "ENDC" | "ENDCST" | "ENDXC" | "STBREFR" | "STREFR" | "NEWDICT" => BASIC + CELL_CREATE,
+        "CTOS" | "XCTOS" | "LDREFRTOS" | "PLDREFIDX" | "PLDREF" => BASIC + CELL_LOAD,
+        "STREF" | "STBREF" | "STSLICE" | "STSLICER" => BASIC + 8,
+        _ if op.starts_with("DICT") => BASIC + CELL_LOAD + CELL_CREATE,
+        _ if op.starts_with("HASH") || op.starts_with("CHKSIGN") => BASIC + 8,
+        _ if op.starts_with("PUSHINT") || op.starts_with("PUSHSLICE") => BASIC + 8,
+        _ => BASIC,
+    }
+}
+
+/// Reports of the public functions of the assembly `text`, or of the function `only`
+fn reports(text: &str, lib_text: &str, only: Option<&str>, loop_iterations: u64) -> Vec<FunctionReport> {
+    let code = Assembly::parse(text);
+    let lib = Assembly::parse(lib_text);
+
+    // Functions of the contract shadow library functions of the same name
+    let functions: HashMap<String, Vec<Node>> = lib.functions.iter()
+        .chain(code.functions.iter())
+        .map(|f| (f.name.clone(), asm::parse_body(&f.body)))
+        .collect();
+
+    let entries: Vec<&str> = code.functions.iter()
+        .filter(|f| match only {
+            Some(name) => name == f.name,
+            None => f.kind != FunctionKind::Private,
+        })
+        .map(|f| f.name.as_str())
+        .collect();
+
+    let mut analyzer = Analyzer::new(&functions, loop_iterations);
+    entries.iter().map(|name| analyzer.report(name)).collect()
+}
+
+/// Worst and typical gas of each public function of the assembly `text` linked with the
+/// default library, with loops counted as one iteration
+pub fn estimate(text: &str) -> Vec<(String, u64, u64)> {
+    reports(text, &String::from_utf8_lossy(crate::STDLIB), None, 1).into_iter()
+        .map(|report| (report.name, report.worst, report.typical))
+        .collect()
+}
+
+pub fn analyze(args: GasArgs) -> Status {
+    let text = std::fs::read_to_string(&args.input)
+        .map_err(|_| SoldError::SourceNotFound(args.input.clone()))?;
+    let lib_text = match &args.lib {
+        Some(lib) => std::fs::read_to_string(lib)
+            .map_err(|_| SoldError::SourceNotFound(lib.clone()))?,
+        None => String::from_utf8_lossy(crate::STDLIB).into_owned(),
+    };
+    let mut reports = reports(&text, &lib_text, args.function.as_deref(), args.loop_iterations);
+    if reports.is_empty() {
+        return Err(SoldError::ArgumentError(match &args.function {
+            Some(name) => format!("Function \"{}\" is not defined in {}", name, args.input),
+            None => format!("{} contains no public functions", args.input),
+        }))
+    }
+
+    if let Some(debug) = &args.debug {
+        let text = std::fs::read_to_string(debug)
+            .map_err(|_| SoldError::SourceNotFound(debug.clone()))?;
+        let debug_map: serde_json::Value = serde_json::from_str(&text).map_err(|e| invalid_json(debug, e))?;
+        let mut attribution = Attribution::default();
+        for report in &mut reports {
+            report.declaration = attribution.declaration(&report.name, &debug_map);
+        }
+    }
+
+    if args.json {
+        messages::output(&serde_json::to_string_pretty(&reports)?);
+        return Ok(())
+    }
+    for report in &reports {
+        let declaration = report.declaration.as_ref().map(|declaration| format!(" ({})", declaration)).unwrap_or_default();
+        messages::output(&format!(
+            "{}{}: worst {} gas, typical {} gas, {} paths ({} throwing)",
+            report.name, declaration, report.worst, report.typical, report.paths, report.throwing_paths
+        ));
+        for path in &report.dominating {
+            let trace = if path.trace.is_empty() { "straight-line".to_owned() } else { path.trace.join(" -> ") };
+            messages::output(&format!("    {:>8}{} {}", path.gas, if path.throws { " (throws)" } else { "" }, trace));
+        }
+        if report.loops {
+            messages::output(&format!("    note: loops are counted as {} iteration(s)", args.loop_iterations));
+        }
+        if report.recursion {
+            messages::output("    note: recursive calls are not expanded");
+        }
+        if report.truncated {
+            messages::output(&format!("    note: only the {} most expensive paths are kept", MAX_PATHS));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,179 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Compares the gas estimates of two builds function by function. A side is either a
+// directory of artifacts, whose assembly files are analyzed, or the report printed by
+// `sold analyze gas --json` saved to a file named after the contract.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::{invalid_json, Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct GasDiffArgs {
+    /// Artifact directory or gas report of the old build
+    #[clap(value_parser)]
+    pub old: String,
+    /// Artifact directory or gas report of the new build
+    #[clap(value_parser)]
+    pub new: String,
+    /// Ignore changes smaller than this percentage
+    #[clap(long, value_parser, default_value_t = 0.0)]
+    pub threshold: f64,
+    /// Print a Markdown table, e.g. for a review comment
+    #[clap(long, value_parser)]
+    pub markdown: bool,
+    /// Fail if the worst-case gas of any function grew
+    #[clap(long, value_parser)]
+    pub fail_on_regression: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Estimate {
+    worst: u64,
+    typical: u64,
+}
+
+/// Estimates by contract and function
+type Estimates = BTreeMap<(String, String), Estimate>;
+
+fn stem(path: &Path) -> String {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    name.split('.').next().unwrap_or_default().to_owned()
+}
+
+fn add_assembly(estimates: &mut Estimates, path: &Path) -> Status {
+    let text = std::fs::read_to_string(path)?;
+    for (function, worst, typical) in crate::gas::estimate(&text) {
+        estimates.insert((stem(path), function), Estimate { worst, typical });
+    }
+    Ok(())
+}
+
+fn load(path: &str) -> Result<Estimates> {
+    let path = Path::new(path);
+    let mut estimates = Estimates::new();
+    if path.is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| {
+                let name = file.to_string_lossy();
+                name.ends_with(".code") && !name.ends_with(".unstripped.code")
+            })
+            .collect();
+        files.sort();
+        for file in files {
+            add_assembly(&mut estimates, &file)?;
+        }
+    } else if path.extension().map_or(false, |ext| ext == "code") {
+        add_assembly(&mut estimates, path)?;
+    } else {
+        let text = std::fs::read_to_string(path)
+            .map_err(|_| SoldError::SourceNotFound(path.display().to_string()))?;
+        let reports: Value = serde_json::from_str(&text).map_err(|e| invalid_json(path.display(), e))?;
+        for report in reports.as_array().map_or(&[][..], Vec::as_slice) {
+            let (name, worst, typical) = match (report["name"].as_str(), report["worst"].as_u64(), report["typical"].as_u64()) {
+                (Some(name), Some(worst), Some(typical)) => (name, worst, typical),
+                _ => return Err(SoldError::ArgumentError(format!(
+                    "{} is not a report of sold analyze gas --json", path.display()
+                ))),
+            };
+            estimates.insert((stem(path), name.to_owned()), Estimate { worst, typical });
+        }
+    }
+    if estimates.is_empty() {
+        return Err(SoldError::ArgumentError(format!("No gas estimates found in {}", path.display())))
+    }
+    Ok(estimates)
+}
+
+fn change(old: u64, new: u64) -> String {
+    let delta = new as i64 - old as i64;
+    let percent = if old == 0 { 0.0 } else { 100.0 * delta as f64 / old as f64 };
+    format!("{:+} ({:+.1}%)", delta, percent)
+}
+
+pub fn gas_diff(args: GasDiffArgs) -> Status {
+    let old = load(&args.old)?;
+    let new = load(&args.new)?;
+
+    let mut regressions = vec![];
+    let mut improvements = vec![];
+    for (key, new_estimate) in &new {
+        let old_estimate = match old.get(key) {
+            Some(estimate) => estimate,
+            None => continue,
+        };
+        let worst_percent = if old_estimate.worst == 0 {
+            0.0
+        } else {
+            100.0 * (new_estimate.worst as f64 - old_estimate.worst as f64).abs() / old_estimate.worst as f64
+        };
+        if new_estimate.worst == old_estimate.worst || worst_percent < args.threshold {
+            continue
+        }
+        let entry = (key, *old_estimate, *new_estimate);
+        if new_estimate.worst > old_estimate.worst {
+            regressions.push(entry);
+        } else {
+            improvements.push(entry);
+        }
+    }
+    let added: Vec<_> = new.keys().filter(|key| !old.contains_key(*key)).collect();
+    let removed: Vec<_> = old.keys().filter(|key| !new.contains_key(*key)).collect();
+
+    if args.markdown {
+        println!("| Function | Worst | Change | Typical | Change |");
+        println!("|---|---:|---:|---:|---:|");
+        for ((contract, function), old, new) in regressions.iter().chain(&improvements) {
+            println!(
+                "| `{}.{}` | {} | {} | {} | {} |",
+                contract, function, new.worst, change(old.worst, new.worst), new.typical, change(old.typical, new.typical),
+            );
+        }
+    } else {
+        for (title, entries) in [("Regressions", &regressions), ("Improvements", &improvements)] {
+            if entries.is_empty() {
+                continue
+            }
+            println!("{}:", title);
+            for ((contract, function), old, new) in entries.iter() {
+                println!(
+                    "    {}.{}: worst {} -> {} {}, typical {} -> {} {}",
+                    contract, function,
+                    old.worst, new.worst, change(old.worst, new.worst),
+                    old.typical, new.typical, change(old.typical, new.typical),
+                );
+            }
+        }
+        if regressions.is_empty() && improvements.is_empty() {
+            println!("No changes in gas");
+        }
+    }
+    for (contract, function) in added {
+        println!("Added {}.{}", contract, function);
+    }
+    for (contract, function) in removed {
+        println!("Removed {}.{}", contract, function);
+    }
+
+    if args.fail_on_regression && !regressions.is_empty() {
+        return Err(SoldError::GasRegression(
+            regressions.iter().map(|((contract, function), _, _)| format!("{}.{}", contract, function)).collect()
+        ))
+    }
+    Ok(())
+}
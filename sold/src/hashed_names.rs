@@ -0,0 +1,65 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Content-addressed artifact names: `<prefix>.abi.json` becomes `<prefix>.<hash>.abi.json`,
+// where the hash is the first eight hex digits of the code hash, so builds of different
+// versions of a contract coexist in one directory. `<prefix>.latest.json` maps the usual
+// suffixes to the names of the most recent build.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::Result;
+
+const HASH_DIGITS: usize = 8;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Latest<'a> {
+    code_hash: &'a str,
+    /// Hashed file name by the suffix of the usual name, e.g. "tvc" or "abi.json"
+    artifacts: BTreeMap<String, String>,
+}
+
+/// Renames the artifacts named `<prefix>.*` in `output_dir` and writes the latest manifest.
+/// Artifacts written elsewhere with --*-out keep their names. Returns the new paths of the
+/// artifacts and the path of the manifest.
+pub fn apply(artifacts: Vec<PathBuf>, output_dir: &Path, prefix: &str, code_hash: &str) -> Result<(Vec<PathBuf>, PathBuf)> {
+    let hash = &code_hash[..HASH_DIGITS.min(code_hash.len())];
+    let mut latest = Latest { code_hash, artifacts: BTreeMap::new() };
+    let mut renamed = vec![];
+    for path in artifacts {
+        let suffix = path.strip_prefix(output_dir).ok()
+            .and_then(|relative| relative.to_str())
+            .and_then(|name| name.strip_prefix(prefix))
+            .and_then(|rest| rest.strip_prefix('.'))
+            .map(str::to_owned);
+        match suffix {
+            Some(suffix) => {
+                let name = format!("{}.{}.{}", prefix, hash, suffix);
+                std::fs::rename(&path, output_dir.join(&name))?;
+                renamed.push(output_dir.join(&name));
+                latest.artifacts.insert(suffix, name);
+            }
+            None => renamed.push(path),
+        }
+    }
+    let latest_path = output_dir.join(format!("{}.latest.json", prefix));
+    let mut file = std::fs::File::create(&latest_path)?;
+    serde_json::to_writer_pretty(&mut file, &latest)?;
+    writeln!(file)?;
+    Ok((renamed, latest_path))
+}
@@ -0,0 +1,59 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Hashes of the linked StateInit and the address the contract is deployed at with the
+// initial data of the TVC. The account id is the hash of the StateInit cell in every
+// workchain, so the addresses only differ in the workchain id.
+
+use std::io::Write;
+
+use serde::Serialize;
+use ton_block::{Serializable, StateInit};
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hashes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_hash: Option<String>,
+    pub state_init_hash: String,
+    /// Address in the basechain, workchain 0
+    pub address: String,
+    /// Address in the masterchain, workchain -1
+    pub masterchain_address: String,
+}
+
+impl Hashes {
+    pub fn of(state: &StateInit) -> Result<Hashes> {
+        let state_init_hash = state.serialize()
+            .map_err(|e| SoldError::InvalidOutput(e.to_string()))?
+            .repr_hash()
+            .to_hex_string();
+        Ok(Hashes {
+            code_hash: state.code.as_ref().map(|code| code.repr_hash().to_hex_string()),
+            data_hash: state.data.as_ref().map(|data| data.repr_hash().to_hex_string()),
+            address: format!("0:{}", state_init_hash),
+            masterchain_address: format!("-1:{}", state_init_hash),
+            state_init_hash,
+        })
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> Status {
+        serde_json::to_writer_pretty(&mut *out, self)?;
+        writeln!(out)?;
+        Ok(())
+    }
+}
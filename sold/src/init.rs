@@ -0,0 +1,119 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Skeleton of a new project: sold.toml, a contract in src/, a test running it in the TVM
+// of the SDK, and a package.json whose scripts build, format, scaffold a deployment
+// script and test. The files live in templates/init/.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum InitTemplate {
+    /// A counter incremented by its owner
+    Counter,
+    /// A contract with just a constructor
+    Empty,
+}
+
+impl InitTemplate {
+    fn contract(self) -> &'static str {
+        match self {
+            InitTemplate::Counter => include_str!("../templates/init/counter.sol"),
+            InitTemplate::Empty => include_str!("../templates/init/empty.sol"),
+        }
+    }
+
+    fn test(self) -> &'static str {
+        match self {
+            InitTemplate::Counter => include_str!("../templates/init/counter.test.js"),
+            InitTemplate::Empty => include_str!("../templates/init/empty.test.js"),
+        }
+    }
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct InitArgs {
+    /// Project template
+    #[clap(value_enum, default_value = "counter")]
+    pub template: InitTemplate,
+    /// Project directory, created if missing (by default, current directory is used)
+    #[clap(long, value_parser, default_value = ".")]
+    pub dir: String,
+    /// Contract name (by default, the directory name in CamelCase)
+    #[clap(long, value_parser)]
+    pub name: Option<String>,
+    /// Overwrite existing files
+    #[clap(long, value_parser)]
+    pub force: bool,
+}
+
+/// `my-token` and `my_token` become `MyToken`
+fn camel_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word[..1].to_ascii_uppercase() + &word[1..])
+        .collect()
+}
+
+fn project_name(dir: &Path) -> Result<String> {
+    let dir = std::fs::canonicalize(dir)?;
+    dir.file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_owned)
+        .ok_or_else(|| SoldError::ArgumentError(format!("Failed to get the name of {}", dir.display())))
+}
+
+pub fn init(args: InitArgs) -> Status {
+    let dir = Path::new(&args.dir);
+    std::fs::create_dir_all(dir)?;
+    let project = project_name(dir)?;
+    let name = args.name.clone().unwrap_or_else(|| camel_case(&project));
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(SoldError::ArgumentError(format!("Invalid contract name \"{}\", use --name to set one", name)))
+    }
+    let compiler_version = crate::solidity_version();
+    let compiler_version = compiler_version.split('+').next().unwrap_or_default();
+
+    let files: Vec<(PathBuf, &str)> = vec![
+        (PathBuf::from(crate::config::CONFIG_FILE_NAME), include_str!("../templates/init/sold.toml")),
+        (PathBuf::from(".gitignore"), include_str!("../templates/init/gitignore")),
+        (PathBuf::from("package.json"), include_str!("../templates/init/package.json")),
+        (Path::new("src").join(format!("{}.sol", name)), args.template.contract()),
+        (Path::new("tests").join(format!("{}.test.js", name)), args.template.test()),
+    ];
+    if !args.force {
+        let existing: Vec<String> = files.iter()
+            .filter(|(path, _)| dir.join(path).exists())
+            .map(|(path, _)| path.display().to_string())
+            .collect();
+        if !existing.is_empty() {
+            return Err(SoldError::ArgumentError(format!(
+                "{} already exist, use --force to overwrite", existing.join(", ")
+            )))
+        }
+    }
+    for subdir in ["src", "tests", "scripts"] {
+        std::fs::create_dir_all(dir.join(subdir))?;
+    }
+    for (path, template) in &files {
+        let text = template
+            .replace("{{name}}", &name)
+            .replace("{{package}}", &project.to_ascii_lowercase().replace(|c: char| !c.is_ascii_alphanumeric(), "-"))
+            .replace("{{compiler_version}}", compiler_version);
+        std::fs::write(dir.join(path), text)?;
+        println!("Written {}", dir.join(path).display());
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Cache of downloaded frontends. With --auto-install a source whose version pragma no
+// available frontend meets gets the newest matching libsolc listed in the frontend index,
+// a JSON file of the form
+//   {"versions": {"0.66.0": {"linux-x86_64": {"url": "...", "sha256": "..."}}}}
+// set by SOLD_FRONTEND_INDEX or the frontend_index key of sold.toml. Libraries are kept in
+// the cache directory as libsolc-<version>.<ext> and offered to every later build.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, SoldError};
+use crate::messages;
+use crate::pragma;
+
+#[derive(Deserialize)]
+struct Index {
+    versions: BTreeMap<String, BTreeMap<String, Download>>,
+}
+
+#[derive(Deserialize)]
+struct Download {
+    url: String,
+    sha256: String,
+}
+
+const PREFIX: &str = "libsolc-";
+
+fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("SOLD_CACHE_DIR") {
+        return Some(PathBuf::from(dir))
+    }
+    let base = std::env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("sold").join("frontends"))
+}
+
+fn platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+fn file_name(version: &str) -> String {
+    format!("{}{}.{}", PREFIX, version, std::env::consts::DLL_EXTENSION)
+}
+
+/// Frontends in the cache by version
+pub fn cached() -> BTreeMap<String, PathBuf> {
+    let mut res = BTreeMap::new();
+    let entries = match cache_dir().map(std::fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return res,
+    };
+    let suffix = format!(".{}", std::env::consts::DLL_EXTENSION);
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(version) = name.strip_prefix(PREFIX).and_then(|rest| rest.strip_suffix(&suffix)) {
+            res.insert(version.to_owned(), entry.path());
+        }
+    }
+    res
+}
+
+fn failed(e: impl std::fmt::Display) -> SoldError {
+    SoldError::InstallError(e.to_string())
+}
+
+/// Downloads the newest frontend of `index_url` meeting `requirement` into the cache and
+/// returns its path.
+pub fn install(requirement: &str, index_url: &str) -> Result<PathBuf> {
+    let dir = cache_dir()
+        .ok_or_else(|| failed("no cache directory, set SOLD_CACHE_DIR"))?;
+    let index: Index = ureq::get(index_url).call()
+        .map_err(|e| failed(format!("failed to fetch the frontend index {}: {}", index_url, e)))?
+        .into_json()
+        .map_err(|e| failed(format!("invalid frontend index {}: {}", index_url, e)))?;
+    let platform = platform();
+    let (version, download) = index.versions.iter()
+        .filter_map(|(version, downloads)| Some((pragma::parse_version(version)?, version, downloads.get(&platform)?)))
+        .filter(|(parsed, _, _)| pragma::satisfies(requirement, *parsed))
+        .max_by_key(|(parsed, _, _)| *parsed)
+        .map(|(_, version, download)| (version, download))
+        .ok_or_else(|| failed(format!("the frontend index has no {} build meeting \"{}\"", platform, requirement)))?;
+
+    messages::info(&format!("Downloading frontend {} from {}", version, download.url));
+    let mut content = vec![];
+    ureq::get(&download.url).call()
+        .map_err(|e| failed(format!("failed to download {}: {}", download.url, e)))?
+        .into_reader()
+        .read_to_end(&mut content)?;
+    let digest = format!("{:x}", Sha256::digest(&content));
+    if !digest.eq_ignore_ascii_case(&download.sha256) {
+        return Err(failed(format!(
+            "checksum mismatch for {}: expected {}, got {}", download.url, download.sha256, digest
+        )))
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(file_name(version));
+    // Renamed into place so that concurrent builds never load a partial file
+    let partial = dir.join(format!("{}.{}.part", file_name(version), std::process::id()));
+    std::fs::write(&partial, &content)?;
+    std::fs::rename(&partial, &path)?;
+    Ok(path)
+}
@@ -0,0 +1,126 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The public interface of a contract as --interface prints it, for reviewers and front-end
+// developers who don't read ABI JSON. Functions and events come from the ABI, with the
+// state mutability of the definitions in the AST; public state variables and constants
+// have accessors in the ABI that look like any other function, so they are found in the
+// AST of the contract and its bases and listed apart with their types.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use serde_json::Value;
+
+use crate::abi::{array, params};
+use crate::ast;
+use crate::error::Status;
+
+/// Public and external members of `contract` and its bases, the most derived first
+fn members<'a>(contract: &str, sources: &BTreeMap<&str, &'a Value>) -> Vec<&'a Value> {
+    let mut contracts = HashMap::new();
+    for ast in sources.values() {
+        ast::walk(ast, &mut |node| {
+            if node["nodeType"] == "ContractDefinition" {
+                if let Some(id) = node["id"].as_u64() {
+                    contracts.insert(id, node);
+                }
+            }
+        });
+    }
+    let bases = contracts.values()
+        .find(|node| node["name"] == contract)
+        .and_then(|node| node["linearizedBaseContracts"].as_array())
+        .map_or(&[][..], Vec::as_slice);
+
+    let mut res = vec![];
+    for base in bases.iter().filter_map(|id| contracts.get(&id.as_u64()?)) {
+        for member in base["nodes"].as_array().map_or(&[][..], Vec::as_slice) {
+            if matches!(member["visibility"].as_str(), Some("public" | "external")) {
+                res.push(*member);
+            }
+        }
+    }
+    res
+}
+
+fn type_string(node: &Value) -> &str {
+    node["typeDescriptions"]["typeString"].as_str().unwrap_or_default()
+}
+
+/// Writes the interface of `contract` given its ABI and the ASTs of all compiled sources.
+pub fn write(out: &mut impl Write, contract: &str, abi: &Value, sources: &BTreeMap<&str, &Value>) -> Status {
+    let members = members(contract, sources);
+    let named = |kind: &str, name: &str| members.iter()
+        .find(|member| member["nodeType"] == kind && member["name"] == name)
+        .copied();
+    let variables: Vec<&Value> = members.iter()
+        .filter(|member| member["nodeType"] == "VariableDeclaration" && member["stateVariable"] == true)
+        .copied()
+        .collect();
+    let is_variable = |name: &str| variables.iter().any(|variable| variable["name"] == name);
+
+    writeln!(out, "contract {}", contract)?;
+    let functions = array(abi, "functions");
+    if functions.iter().any(|function| !function["name"].as_str().map_or(false, is_variable)) {
+        writeln!(out, "\nfunctions:")?;
+    }
+    for function in functions {
+        let name = function["name"].as_str().unwrap_or_default();
+        if is_variable(name) {
+            continue
+        }
+        write!(out, "  {}({})", name, params(array(function, "inputs"))?)?;
+        let outputs = array(function, "outputs");
+        if !outputs.is_empty() {
+            write!(out, " returns ({})", params(outputs)?)?;
+        }
+        let mutability = named("FunctionDefinition", name).and_then(|definition| definition["stateMutability"].as_str());
+        if let Some(mutability @ ("view" | "pure")) = mutability {
+            write!(out, " {}", mutability)?;
+        }
+        writeln!(out)?;
+    }
+
+    let events = array(abi, "events");
+    if !events.is_empty() {
+        writeln!(out, "\nevents:")?;
+    }
+    for event in events {
+        writeln!(out, "  {}({})", event["name"].as_str().unwrap_or_default(), params(array(event, "inputs"))?)?;
+    }
+
+    let (constants, variables): (Vec<&Value>, Vec<&Value>) = variables.into_iter()
+        .partition(|variable| variable["constant"] == true);
+    if !variables.is_empty() {
+        writeln!(out, "\nstate variables:")?;
+    }
+    for variable in variables {
+        writeln!(out, "  {} {}", type_string(variable), variable["name"].as_str().unwrap_or_default())?;
+    }
+    if !constants.is_empty() {
+        writeln!(out, "\nconstants:")?;
+    }
+    for constant in constants {
+        write!(out, "  {} {}", type_string(constant), constant["name"].as_str().unwrap_or_default())?;
+        let value = &constant["value"];
+        if value["nodeType"] == "Literal" {
+            match value["kind"].as_str() {
+                Some("string") => write!(out, " = {:?}", value["value"].as_str().unwrap_or_default())?,
+                _ => write!(out, " = {}", value["value"].as_str().unwrap_or_default())?,
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
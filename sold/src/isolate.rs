@@ -0,0 +1,114 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// With --isolate the frontend runs in a re-executed copy of sold, so an abort() or a failed
+// assertion inside libsolc only kills the worker. The worker reads sources as the build
+// would: it gets the allowed directories and the sources that aren't files, those read
+// from stdin or given to the compiler. Everything the read callback records in the worker
+// (line offsets, applied defines) is sent back along with the output.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SoldError, Status};
+use crate::{compiler, defines, CompilerOutput};
+
+pub const WORKER_COMMAND: &str = "compile-worker";
+
+#[derive(Serialize, Deserialize)]
+struct WorkerRequest {
+    input: String,
+    defines: BTreeMap<String, String>,
+    /// Frontend library loaded with --solc-lib
+    solc_lib: Option<String>,
+    /// Directories sources may be read from, see sandbox.rs
+    sandbox: Option<Vec<PathBuf>>,
+    /// Sources the worker can't read from disk
+    sources: Vec<(PathBuf, Vec<u8>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WorkerResponse {
+    output: String,
+    lines: HashMap<String, Vec<usize>>,
+    applied_defines: Vec<String>,
+}
+
+pub fn run_compiler_isolated(input: &str) -> Result<CompilerOutput> {
+    let _span = tracing::info_span!("compile", isolated = true).entered();
+    let request = serde_json::to_vec(&WorkerRequest {
+        input: input.to_owned(),
+        defines: defines::current(),
+        solc_lib: crate::frontend::get().path.clone(),
+        sandbox: compiler::current().state().sandbox.lock().unwrap().clone(),
+        sources: crate::source_cache::virtual_sources(),
+    })?;
+
+    let mut child = Command::new(std::env::current_exe()?)
+        .arg(WORKER_COMMAND)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take()
+        .ok_or_else(|| SoldError::ArgumentError("Failed to open stdin of compiler worker".to_owned()))?;
+    let writer = std::thread::spawn(move || stdin.write_all(&request));
+    let output = child.wait_with_output()?;
+    // A crashed worker closes the pipe early, the resulting write error is not interesting
+    let _ = writer.join();
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+    if !output.status.success() {
+        return Err(SoldError::FrontendCrash { status: output.status.to_string(), stderr })
+    }
+    let response: WorkerResponse = serde_json::from_slice(&output.stdout)
+        .map_err(|e| SoldError::FrontendCrash {
+            status: format!("malformed response: {}", e),
+            stderr,
+        })?;
+
+    compiler::current().state().lines.lock().unwrap().extend(response.lines);
+    defines::mark_applied(response.applied_defines);
+    tracing::debug!(bytes = response.output.len(), "received compilation result");
+    Ok(serde_json::from_str(&response.output)?)
+}
+
+pub fn worker_main() -> Status {
+    let mut request = String::new();
+    std::io::stdin().read_to_string(&mut request)?;
+    let request: WorkerRequest = serde_json::from_str(&request)?;
+    defines::set(request.defines)?;
+    *compiler::current().state().sandbox.lock().unwrap() = request.sandbox;
+    for (path, content) in request.sources {
+        crate::source_cache::add_virtual(&path, content);
+    }
+    if let Some(path) = &request.solc_lib {
+        crate::frontend::load(path);
+    }
+
+    let output = crate::call_compiler(&request.input, |bytes| String::from_utf8_lossy(bytes).into_owned());
+    let response = WorkerResponse {
+        output,
+        lines: std::mem::take(&mut *compiler::current().state().lines.lock().unwrap()),
+        applied_defines: defines::applied(),
+    };
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    serde_json::to_writer(&mut stdout, &response)?;
+    stdout.flush()?;
+    Ok(())
+}
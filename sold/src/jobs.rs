@@ -0,0 +1,81 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Builds of several inputs on a pool of threads, for --jobs. Every build gets a compiler
+// of its own, so the line tables and defines of concurrent builds don't mix; afterwards
+// the line tables go to the compiler of the caller, where --watch finds the sources read.
+// Calls into one frontend library are still serialized, the compilations run side by
+// side only with --isolate, everything else a build does runs in parallel anyway.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::compiler::{self, Compiler};
+use crate::error::{Result, SoldError};
+use crate::watchdog::WORKER_STACK_SIZE;
+
+/// Results of `job` on `items` in their order, computed on up to `jobs` threads. Once
+/// `stop` holds for a result no more items are started, those left have no result.
+pub fn run<I, T>(
+    jobs: usize,
+    items: Vec<I>,
+    job: impl Fn(I) -> T + Send + Sync + 'static,
+    stop: impl Fn(&T) -> bool + Send + Sync + 'static,
+) -> Result<Vec<Option<T>>>
+where
+    I: Send + 'static,
+    T: Send + 'static,
+{
+    let count = items.len();
+    let queue = Arc::new(Mutex::new(items.into_iter().enumerate().collect::<VecDeque<_>>()));
+    let results = Arc::new(Mutex::new((0..count).map(|_| None).collect::<Vec<Option<T>>>()));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let job = Arc::new(job);
+    let stop = Arc::new(stop);
+    let parent = compiler::current();
+    let mut workers = vec![];
+    for _ in 0..jobs.max(1).min(count) {
+        let (queue, results, stopped) = (queue.clone(), results.clone(), stopped.clone());
+        let (job, stop, parent) = (job.clone(), stop.clone(), parent.clone());
+        let worker = std::thread::Builder::new()
+            .name("build job".to_owned())
+            .stack_size(WORKER_STACK_SIZE)
+            .spawn(move || {
+                while !stopped.load(Ordering::SeqCst) {
+                    let next = queue.lock().unwrap().pop_front();
+                    let (index, item) = match next {
+                        Some(next) => next,
+                        None => break,
+                    };
+                    let compiler = Compiler::fork(&parent);
+                    let result = compiler.enter(|| job(item));
+                    parent.state().lines.lock().unwrap()
+                        .extend(compiler.state().lines.lock().unwrap().drain());
+                    if stop(&result) {
+                        stopped.store(true, Ordering::SeqCst);
+                    }
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            })?;
+        workers.push(worker);
+    }
+    for worker in workers {
+        if let Err(panic) = worker.join() {
+            std::panic::resume_unwind(panic)
+        }
+    }
+    let results = Arc::try_unwrap(results)
+        .map_err(|_| SoldError::InvalidOutput("A build job is still running".to_owned()))?;
+    Ok(results.into_inner().unwrap())
+}
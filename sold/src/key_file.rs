@@ -0,0 +1,126 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Secret key files encrypted with a password. With --key-password, --gen-key writes the
+// keypair as JSON holding it encrypted with ChaCha20-Poly1305, under a key stretched from
+// the password with PBKDF2 and a random salt; the public key file stays as it was.
+// --set-key tells encrypted files from the raw 64 bytes of plain ones and asks for the
+// password of the former. The password is taken from SOLD_KEY_PASSWORD, so CI can pass it
+// as a secret, or else asked for on the terminal.
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::Keypair;
+use hmac::Hmac;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::{Result, SoldError, Status};
+
+/// Environment variable the password is read from
+const PASSWORD_VAR: &str = "SOLD_KEY_PASSWORD";
+
+/// PBKDF2 iterations of files written now; those read take theirs from the file, which
+/// must not be fewer
+const ITERATIONS: u32 = 600_000;
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedKey {
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn key_error(message: &str) -> SoldError {
+    SoldError::KeyError(message.to_owned())
+}
+
+/// Password of SOLD_KEY_PASSWORD, or the one typed on the terminal, twice if `confirm`
+fn password(confirm: bool) -> Result<String> {
+    if let Ok(password) = std::env::var(PASSWORD_VAR) {
+        return Ok(password)
+    }
+    if !atty::is(atty::Stream::Stdin) {
+        return Err(SoldError::KeyError(format!("Set {} to the key password, there is no terminal to ask for it", PASSWORD_VAR)))
+    }
+    let password = rpassword::prompt_password("Key password: ")?;
+    if confirm && rpassword::prompt_password("Repeat the key password: ")? != password {
+        return Err(key_error("The passwords don't match"))
+    }
+    Ok(password)
+}
+
+fn cipher(password: &str, salt: &[u8], iterations: u32) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, iterations, &mut key);
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// Writes the secret and public key of `pair` to `file`, encrypted with a password.
+pub fn store(pair: &Keypair, file: &str) -> Status {
+    let password = password(true)?;
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher(&password, &salt, ITERATIONS)
+        .encrypt(Nonce::from_slice(&nonce), pair.to_bytes().as_slice())
+        .map_err(|_| key_error("Failed to encrypt the secret key"))?;
+    let encrypted = EncryptedKey {
+        version: 1,
+        kdf: "pbkdf2-sha256".to_owned(),
+        iterations: ITERATIONS,
+        salt: base64::encode(salt),
+        cipher: "chacha20-poly1305".to_owned(),
+        nonce: base64::encode(nonce),
+        ciphertext: base64::encode(ciphertext),
+    };
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Readable by its owner only
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut out = options.open(file)?;
+    serde_json::to_writer_pretty(&mut out, &encrypted)?;
+    Ok(())
+}
+
+/// Keypair of `content`, the content of a key file, if it is encrypted
+pub fn load(content: &[u8]) -> Result<Option<Keypair>> {
+    let encrypted: EncryptedKey = match serde_json::from_slice(content) {
+        Ok(encrypted) => encrypted,
+        Err(_) => return Ok(None),
+    };
+    if encrypted.version != 1 || encrypted.kdf != "pbkdf2-sha256" || encrypted.cipher != "chacha20-poly1305" {
+        return Err(key_error("Unsupported key file encryption"))
+    }
+    if encrypted.iterations < ITERATIONS {
+        return Err(SoldError::KeyError(format!(
+            "Key file uses {} PBKDF2 iterations, fewer than the {} required", encrypted.iterations, ITERATIONS
+        )))
+    }
+    let decode = |text: &str| base64::decode(text).map_err(|_| key_error("Corrupted key file"));
+    let (salt, nonce, ciphertext) = (decode(&encrypted.salt)?, decode(&encrypted.nonce)?, decode(&encrypted.ciphertext)?);
+    if nonce.len() != 12 {
+        return Err(key_error("Corrupted key file"))
+    }
+    let bytes = cipher(&password(false)?, &salt, encrypted.iterations)
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| key_error("Wrong key password or corrupted key file"))?;
+    Keypair::from_bytes(&bytes).map(Some).map_err(|e| SoldError::KeyError(e.to_string()))
+}
@@ -0,0 +1,137 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// A lossless tokenizer for Solidity sources: concatenating the text of all tokens gives
+// back the input. It knows just enough of the grammar to tell code from comments and
+// string literals, which is what the source rewriting tools need.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    Identifier,
+    Number,
+    String,
+    Punct,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset of the token in the source
+    pub start: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment)
+    }
+}
+
+// Longest first, so that the first match is the right one
+const PUNCTS: &[&str] = &[
+    ">>>=", ">>>", "<<=", ">>=", "**", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=",
+    "*=", "/=", "%=", "|=", "&=", "^=", "=>", "<<", ">>",
+];
+
+pub fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let bytes = src.as_bytes();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let c = bytes[pos];
+        let rest = &src[pos..];
+        let kind = if c.is_ascii_whitespace() {
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            TokenKind::Whitespace
+        } else if rest.starts_with("//") {
+            pos += rest.find('\n').unwrap_or(rest.len());
+            TokenKind::LineComment
+        } else if rest.starts_with("/*") {
+            pos += rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+            TokenKind::BlockComment
+        } else if c == b'"' || c == b'\'' {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos] != c && bytes[pos] != b'\n' {
+                pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+            }
+            if pos < bytes.len() && bytes[pos] == c {
+                pos += 1;
+            }
+            pos = pos.min(bytes.len());
+            TokenKind::String
+        } else if c.is_ascii_digit() {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || bytes[pos] == b'.') {
+                // `1.5` is a number, but `x.1` and `1..` are not part of one
+                if bytes[pos] == b'.' && !bytes.get(pos + 1).map_or(false, u8::is_ascii_digit) {
+                    break
+                }
+                let exponent = (bytes[pos] == b'e' || bytes[pos] == b'E') && !rest.starts_with("0x");
+                pos += 1;
+                if exponent && pos < bytes.len() && bytes[pos] == b'-' {
+                    pos += 1;
+                }
+            }
+            TokenKind::Number
+        } else if c.is_ascii_alphabetic() || c == b'_' || c == b'$' {
+            while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || bytes[pos] == b'$') {
+                pos += 1;
+            }
+            TokenKind::Identifier
+        } else {
+            pos += PUNCTS.iter()
+                .find(|p| rest.starts_with(*p))
+                .map_or_else(|| rest.chars().next().map_or(1, char::len_utf8), |p| p.len());
+            TokenKind::Punct
+        };
+        tokens.push(Token { kind, text: &src[start..pos], start });
+    }
+    tokens
+}
+
+/// Tells code from comments and string literals by byte offset
+pub struct CodeMap {
+    skipped: Vec<(usize, usize)>,
+}
+
+impl CodeMap {
+    pub fn new(src: &str) -> CodeMap {
+        let skipped = tokenize(src).iter()
+            .filter(|t| matches!(t.kind, TokenKind::LineComment | TokenKind::BlockComment | TokenKind::String))
+            .map(|t| (t.start, t.end()))
+            .collect();
+        CodeMap { skipped }
+    }
+
+    pub fn is_code(&self, offset: usize) -> bool {
+        let i = self.skipped.partition_point(|(start, _)| *start <= offset);
+        i == 0 || self.skipped[i - 1].1 <= offset
+    }
+}
+
+/// One-based line and column of a byte offset
+pub fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src[..offset.min(src.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(before.len(), |nl| before.len() - nl - 1) + 1;
+    (line, column)
+}
@@ -0,0 +1,1548 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{Write, BufRead, BufReader};
+use std::os::raw::{c_char, c_void};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use ton_block::{Serializable, StateInit};
+use ton_types::BagOfCells;
+use ton_utils::keyman::KeypairManager;
+use ton_utils::parser::{ParseEngine, ParseEngineInput};
+use ton_utils::program::Program;
+
+macro_rules! parse_error {
+    () => {
+        $crate::SoldError::InvalidOutput("Failed to parse compilation result".to_owned())
+    };
+}
+
+mod abi;
+mod abi_split;
+mod archive;
+mod asm;
+mod ast;
+mod ast_import;
+mod bench;
+mod bindgen;
+mod build_config;
+mod boc;
+mod bundle;
+mod commands;
+mod compiler;
+mod config;
+mod constants;
+mod contract;
+mod decode_error;
+mod defines;
+mod deploy_payload;
+pub mod diagnostics;
+mod dispatch;
+mod duplicates;
+mod emit;
+pub mod error;
+mod errors;
+mod exceptions;
+mod fmt;
+mod frontend;
+mod function_ids;
+mod gas;
+mod gas_diff;
+mod hashed_names;
+mod hashes;
+mod init;
+mod install;
+mod interface;
+mod isolate;
+mod jobs;
+mod key_file;
+mod lexer;
+mod libsolc;
+mod limits;
+pub mod logging;
+mod lsp;
+mod manifest;
+pub mod messages;
+mod metadata;
+mod migrate;
+mod mnemonic;
+mod mutate;
+mod new;
+mod optimizer;
+mod plugins;
+mod pragma;
+mod presets;
+mod printer;
+mod publish;
+mod remappings;
+mod rename;
+mod report;
+mod repro_check;
+pub mod response_file;
+mod sandbox;
+mod scaffold;
+mod schema;
+mod serve;
+mod size_diff;
+mod source_cache;
+mod source_map;
+mod standard_json;
+mod stdlib_report;
+mod sources;
+mod storage;
+mod symbols;
+mod vanity;
+mod verify;
+mod version;
+mod via_ast;
+mod viz;
+mod watch;
+pub mod watchdog;
+mod worker;
+
+pub use error::{Result, SoldError, Status};
+pub use compiler::{CompilationOutput, Compiler};
+pub use contract::{ContractArtifacts, FunctionId};
+pub use diagnostics::{Diagnostic, SourceLocation};
+pub use report::BuildOutput;
+pub use standard_json::{compile_standard_json, FileResolver, ImportResolver, MemoryResolver};
+pub use worker::compile_async;
+
+use diagnostics::ErrorFormat;
+
+fn compute_line_info(compiler: &compiler::State, filename: String, buf: &[u8]) {
+    let mut info = vec!();
+    let reader = BufReader::new(buf);
+    let mut byte = 0;
+    for line in reader.lines() {
+        if let Ok(line) = line {
+            byte += line.len() + 1;
+            info.push(byte);
+        } else {
+            return
+        }
+    }
+    compiler.lines.lock().unwrap().insert(filename, info);
+}
+
+fn get_line_column(filename: &str, pos: usize) -> Option<(usize, usize)> {
+    let compiler = compiler::current();
+    let lines = compiler.state().lines.lock().unwrap();
+    let info = lines.get(filename)?;
+    let mut line = 1;
+    let mut last = 0;
+    for byte in info {
+        if pos >= *byte {
+            line += 1;
+            last = *byte;
+        } else {
+            return Some((line, pos - last + 1))
+        }
+    }
+    None
+}
+
+// Most of the work of locating an import is implemented in CompilerStack::loadMissingSources().
+// This callback receives an already resolved path, and the only thing left to do is to read
+// the file at the specified path. The context is a CallbackContext.
+unsafe extern "C" fn read_callback(
+    context: *mut c_void,
+    kind: *const c_char,
+    data: *const c_char,
+    o_contents: *mut *mut c_char,
+    o_error: *mut *mut c_char,
+) {
+    let context = &*(context as *const CallbackContext);
+    let compiler = context.compiler;
+    let kind = std::ffi::CStr::from_ptr(kind)
+        .to_string_lossy()
+        .into_owned();
+    if kind != "source" {
+        *o_error = make_error(compiler, format!("Unknown kind \"{}\"", kind));
+        return
+    }
+    let filename = std::ffi::CStr::from_ptr(data)
+        .to_string_lossy()
+        .into_owned();
+    let _span = tracing::debug_span!("read", file = %filename).entered();
+    if let Err(e) = sandbox::check(compiler, &filename) {
+        *o_error = make_error(compiler, e);
+        return
+    }
+    let buf = match context.resolver.read(&filename) {
+        Ok(buf) => buf,
+        Err(e) => {
+            *o_error = make_error(compiler, e);
+            return
+        }
+    };
+    let buf = compiler.defines.apply(buf);
+    let size = buf.len();
+    compute_line_info(compiler, filename, &buf);
+    let ptr = (frontend::selected(compiler).alloc)(size as u64);
+    std::ptr::copy(buf.as_ptr(), ptr as *mut u8, size);
+    *o_contents = ptr;
+}
+
+/// What the read callback works with: the state of the compiler of the build, and where
+/// the sources come from
+struct CallbackContext<'a> {
+    compiler: &'a compiler::State,
+    resolver: &'a dyn ImportResolver,
+}
+
+unsafe fn make_error(compiler: &compiler::State, msg: String) -> *mut c_char {
+    let ptr = (frontend::selected(compiler).alloc)(msg.len() as u64);
+    std::ptr::copy(msg.as_ptr(), ptr as *mut u8, msg.len());
+    ptr
+}
+
+pub fn solidity_version() -> String {
+    unsafe {
+        std::ffi::CStr::from_ptr((frontend::get().version)())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Standard JSON input building `input` with the settings of `args`
+fn standard_json_input(args: &Args, input: &str) -> String {
+    // Imports are looked up in the base path first
+    let include_paths = args.base_path.iter().chain(&args.include_path)
+        .map(|x| format!("\"{}\"", x)).collect::<Vec<_>>()
+        .join(", ");
+    let show_function_ids = if args.function_ids || args.function_ids_json || args.dispatch_report {
+        ", \"showFunctionIds\""
+    } else {
+        ""
+    };
+    let assembly = if args.abi_json || args.ast_json || args.ast_compact_json {
+        ""
+    } else {
+        ", \"assembly\""
+    };
+    let ast = if args.ast_json || args.ast_compact_json || args.errors || args.messages || args.exceptions || args.split_abi || args.interface {
+        r#", "": [ "ast" ]"#
+    } else {
+        ""
+    };
+    let force_remote_update = args.tvm_refresh_remote;
+    let main_contract = args.contract.clone().unwrap_or_default();
+    let dispatch = dispatch::settings(args.dispatch, &args.dispatch_priority)
+        .map(|settings| format!(r#", "dispatch": {}"#, settings))
+        .unwrap_or_default();
+    let optimizer = optimizer::settings(args.optimize, args.optimize_runs, args.no_peephole)
+        .map(|settings| format!(r#", "optimizer": {}"#, settings))
+        .unwrap_or_default();
+    let remappings = remappings::settings(&args.remap)
+        .map(|settings| format!(r#", "remappings": {}"#, settings))
+        .unwrap_or_default();
+    format!(r#"
+        {{
+            "language": "Solidity",
+            "settings": {{
+                "includePaths": [ {include_paths} ],
+                "forceRemoteUpdate": {force_remote_update},
+                "mainContract": "{main_contract}"{dispatch}{optimizer}{remappings},
+                "outputSelection": {{
+                    "{input}": {{
+                        "*": [ "abi"{assembly}{show_function_ids} ]{ast}
+                    }}
+                }}
+            }},
+            "sources": {{
+                "{input}": {{
+                    "urls": [ "{input}" ]
+                }}
+            }}
+        }}
+    "#)
+}
+
+/// Standard JSON input of the source or exported AST `input`
+fn compiler_input(args: &Args, input: &str) -> Result<String> {
+    if ast_import::is_ast(input) {
+        ast_import::standard_json_input(args, input)
+    } else {
+        Ok(standard_json_input(args, input))
+    }
+}
+
+/// Runs the frontend on standard JSON `input`, in a separate process with --isolate
+fn compile_json(args: &Args, input: &str) -> Result<CompilerOutput> {
+    if args.isolate {
+        isolate::run_compiler_isolated(input)
+    } else {
+        run_compiler(input)
+    }
+}
+
+fn compile(args: &Args, input: &str) -> Result<CompilerOutput> {
+    compile_json(args, &compiler_input(args, input)?)
+}
+
+/// The parts of the standard JSON output sold makes use of. The output of big projects
+/// can take hundreds of megabytes, so it is deserialized right from the buffer returned
+/// by the compiler, and everything not listed here is skipped without being materialized.
+#[derive(Default, Deserialize)]
+struct CompilerOutput {
+    #[serde(default)]
+    errors: Vec<serde_json::Value>,
+    #[serde(default)]
+    contracts: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    #[serde(default)]
+    sources: BTreeMap<String, SourceOutput>,
+}
+
+#[derive(Deserialize)]
+struct SourceOutput {
+    #[serde(default)]
+    ast: Option<serde_json::Value>,
+}
+
+/// Passes standard JSON `input` to the frontend and hands its raw output to `consume`
+/// before releasing it.
+fn call_compiler<T>(input: &str, consume: impl FnOnce(&[u8]) -> T) -> T {
+    let input_cstring = std::ffi::CString::new(input).expect("Failed to create CString");
+    call_compiler_with(&input_cstring, &FileResolver, consume)
+}
+
+/// Same as `call_compiler`, reading the sources with `resolver`
+fn call_compiler_with<T>(input: &std::ffi::CStr, resolver: &dyn ImportResolver, consume: impl FnOnce(&[u8]) -> T) -> T {
+    let compiler = compiler::current();
+    let frontend = frontend::selected(compiler.state());
+    let context = CallbackContext { compiler: compiler.state(), resolver };
+    let _exclusive = frontend.exclusive();
+    let output = unsafe {
+        (frontend.compile)(
+            input.as_ptr(),
+            Some(read_callback),
+            &context as *const CallbackContext as *mut c_void,
+        )
+    };
+    let res = consume(unsafe { std::ffi::CStr::from_ptr(output) }.to_bytes());
+    unsafe { (frontend.free)(output) };
+    res
+}
+
+fn run_compiler(input: &str) -> Result<CompilerOutput> {
+    let _span = tracing::info_span!("compile").entered();
+    tracing::trace!(input, "standard json input");
+    let res = call_compiler(input, |bytes| {
+        tracing::debug!(bytes = bytes.len(), "received compilation result");
+        serde_json::from_slice(bytes)
+    });
+    Ok(res?)
+}
+
+fn parse_comp_result(
+    res: &mut CompilerOutput,
+    input: &str,
+    contract: Option<String>,
+    compile: bool,
+    format: ErrorFormat,
+) -> Result<(String, ContractArtifacts)> {
+    let _span = tracing::info_span!("parse").entered();
+    diagnostics::check_errors(&res.errors, format)?;
+
+    let all = res.contracts.remove(input)
+        .ok_or_else(|| parse_error!())?;
+    let mut all = all.into_iter()
+        .map(|(name, output)| {
+            let artifacts = serde_json::from_value(output)
+                .map_err(|e| SoldError::InvalidOutput(format!("Invalid output of contract \"{}\": {}", name, e)))?;
+            Ok((name, artifacts))
+        })
+        .collect::<Result<BTreeMap<String, ContractArtifacts>>>()?;
+
+    if let Some(ref contract) = contract {
+        all.remove(contract)
+            .map(|artifacts| (contract.clone(), artifacts))
+            .ok_or_else(|| SoldError::ArgumentError(format!("Source file doesn't contain the desired contract \"{}\"", contract)))
+    } else {
+        let mut candidates = all.into_iter()
+            .filter(|(_, artifacts)| !compile || artifacts.assembly.is_some())
+            .collect::<Vec<_>>();
+        let qualification = if compile { "deployable " } else { "" };
+        match candidates.len() {
+            0 => Err(SoldError::ArgumentError(format!("Source file contains no {}contracts", qualification))),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(SoldError::ArgumentError(format!("Source file contains at least two {}contracts. Consider adding the option --contract in compiler command line to select the desired contract", qualification))),
+        }
+    }
+}
+
+static STDLIB: &[u8] = include_bytes!("../../lib/stdlib_sol.tvm");
+
+/// Name of the source read from stdin unless --stdin-name is given
+const STDIN_NAME: &str = "stdin.sol";
+
+/// Runs the subcommand selected in `args`, or builds the input file if there is none.
+pub fn run(mut args: Args) -> Status {
+    if let Some(path) = args.from_config.take() {
+        args = build_config::load(&path)?;
+    }
+    diagnostics::set_color(args.color);
+    if let Some(path) = &args.solc_lib {
+        frontend::load(path);
+    }
+    match args.command.take() {
+        Some(Command::Publish(publish_args)) => publish::publish(publish_args),
+        Some(Command::Abi { command: AbiCommand::Show(show_args) }) => abi::show(show_args),
+        Some(Command::Abi { command: AbiCommand::Schema(schema_args) }) => schema::schema(schema_args),
+        Some(Command::Abi { command: AbiCommand::EventId(event_id_args) }) => abi::event_id(event_id_args),
+        Some(Command::Analyze { command: AnalyzeCommand::Gas(gas_args) }) => gas::analyze(gas_args),
+        Some(Command::Bench(bench_args)) => bench::bench(bench_args),
+        Some(Command::Boc { command: BocCommand::Inspect(inspect_args) }) => boc::inspect(inspect_args),
+        Some(Command::DecodeError(decode_args)) => decode_error::decode_error(decode_args),
+        Some(Command::Fmt(fmt_args)) => fmt::fmt(fmt_args),
+        Some(Command::GasDiff(gas_diff_args)) => gas_diff::gas_diff(gas_diff_args),
+        Some(Command::Init(init_args)) => init::init(init_args),
+        Some(Command::Lsp(lsp_args)) => lsp::serve(lsp_args),
+        Some(Command::Migrate(migrate_args)) => migrate::migrate(migrate_args),
+        Some(Command::Mutate(mutate_args)) => mutate::mutate(mutate_args),
+        Some(Command::New(new_args)) => new::new(new_args),
+        Some(Command::Pragma { command: PragmaCommand::Set(set_args) }) => pragma::set(set_args),
+        Some(Command::Rename(rename_args)) => rename::rename(rename_args),
+        Some(Command::ReproCheck(repro_args)) => repro_check::repro_check(repro_args),
+        Some(Command::Scaffold { command: ScaffoldCommand::Deploy(deploy_args) }) => scaffold::deploy(deploy_args),
+        Some(Command::SizeDiff(size_diff_args)) => size_diff::size_diff(size_diff_args),
+        Some(Command::Symbols(symbols_args)) => symbols::symbols(symbols_args),
+        Some(Command::Vanity(vanity_args)) => vanity::vanity(vanity_args),
+        Some(Command::Verify(verify_args)) => verify::verify(verify_args),
+        Some(Command::Viz(viz_args)) => viz::viz(viz_args),
+        Some(Command::CompileWorker) => isolate::worker_main(),
+        Some(Command::BuildWorker { result }) => watchdog::worker_main(&result),
+        None if args.version_json => version::print(),
+        None if args.serve_stdio => serve::serve(),
+        None if args.standard_json.is_some() => standard_json::run(args.standard_json.as_deref().unwrap_or("-"), &args.define),
+        None => {
+            if args.input.iter().filter(|input| *input == "-").count() > 1 {
+                return Err(SoldError::ArgumentError("Standard input can be given only once".to_owned()))
+            }
+            for input in args.input.iter_mut().filter(|input| *input == "-") {
+                *input = sources::stdin(args.stdin_name.as_deref().unwrap_or(STDIN_NAME))?;
+            }
+            args.input = sources::glob(&args.input)?;
+            if args.watch {
+                watch::watch(args, build_inputs)
+            } else {
+                build_inputs(args)
+            }
+        }
+    }
+}
+
+/// Builds all inputs if there are several or they are selected with --only or --skip,
+/// the single input otherwise.
+fn build_inputs(args: Args) -> Status {
+    if args.input.len() > 1 || !args.only.is_empty() || !args.skip.is_empty() {
+        build_all(args)
+    } else {
+        build_one(args).map(|_| ())
+    }
+}
+
+/// Builds a single input, under the resource limits if any are given.
+fn build_one(args: Args) -> Result<report::BuildOutput> {
+    if args.timeout.is_some() || args.max_memory.is_some() {
+        let limits = watchdog::Limits {
+            timeout: args.timeout.map(Duration::from_secs),
+            max_memory: args.max_memory.map(|mb| mb * 1024 * 1024),
+        };
+        watchdog::build_with_limits(args, limits)
+    } else {
+        build(args)
+    }
+}
+
+/// Whether `input` passes --only and --skip: some contract it defines matches a pattern of
+/// `only`, if any are given, and none of `skip`. Inputs whose contracts can't be told are
+/// built and fail or succeed on their own.
+fn selected(input: &str, only: &[String], skip: &[String]) -> bool {
+    let names: Vec<String> = match std::fs::read_to_string(input) {
+        Ok(text) => duplicates::contracts(&text).into_iter().map(|(name, _, _)| name).collect(),
+        Err(_) => return true,
+    };
+    if names.is_empty() {
+        return true
+    }
+    names.iter().any(|name| {
+        (only.is_empty() || only.iter().any(|pattern| abi::matches(pattern, name)))
+            && !skip.iter().any(|pattern| abi::matches(pattern, name))
+    })
+}
+
+/// Builds every input in turn. Stops at the first failure unless --keep-going is given,
+/// in which case all inputs are built and their statuses are summarized at the end.
+fn build_all(args: Args) -> Status {
+    if args.output_prefix.is_some() {
+        return Err(SoldError::ArgumentError("Option --output-prefix can't be used with multiple inputs".to_owned()))
+    }
+    for (option, value) in [("--abi-out", &args.abi_out), ("--tvc-out", &args.tvc_out), ("--code-out", &args.code_out), ("--debug-out", &args.debug_out)] {
+        if value.is_some() {
+            return Err(SoldError::ArgumentError(format!("Option {} can't be used with multiple inputs", option)))
+        }
+    }
+    let built: Vec<String> = args.input.iter()
+        .filter(|input| selected(input, &args.only, &args.skip))
+        .cloned()
+        .collect();
+    duplicates::check(&built, args.error_format, args.deny_duplicate_contracts)?;
+    // Imports shared by the inputs are read once
+    source_cache::enable();
+    let report_path = Path::new(args.output_dir.as_deref().unwrap_or(".")).join(report::REPORT_FILE_NAME);
+    let job_args = args.clone();
+    let keep_going = args.keep_going;
+    let mut results = jobs::run(
+        args.jobs.unwrap_or(1),
+        built.clone(),
+        move |input| {
+            let single = Args { input: vec![input], ..job_args.clone() };
+            let started = Instant::now();
+            let result = build_one(single);
+            (result, started.elapsed())
+        },
+        move |(result, _)| result.is_err() && !keep_going,
+    )?.into_iter();
+    let mut entries = vec![];
+    let mut first_error = None;
+    for input in &args.input {
+        if !built.contains(input) {
+            tracing::info!(input = %input, "skipped by --only or --skip");
+            entries.push(report::Entry::skipped(input));
+            continue
+        }
+        // Inputs left after a failure without --keep-going are not built
+        let (result, duration) = match results.next().flatten() {
+            Some(built) => built,
+            None => break,
+        };
+        match result {
+            Ok(output) => entries.push(report::Entry::ok(input, output, duration)),
+            Err(e) => {
+                entries.push(report::Entry::failed(input, &e, duration));
+                if !args.keep_going {
+                    first_error = Some(e);
+                    break
+                }
+                messages::message(messages::Level::Error, &format!("{}: {}", input, e));
+            }
+        }
+    }
+    report::write(&report_path, &entries)?;
+    if let Some(e) = first_error {
+        return Err(e)
+    }
+    if !args.keep_going {
+        return Ok(())
+    }
+    let mut summary = "Build summary:".to_owned();
+    for entry in &entries {
+        let status = match entry.status {
+            "ok" => "ok    ",
+            "skipped" => "skip  ",
+            _ => "FAILED",
+        };
+        summary += &format!("\n    {} {}", status, entry.input);
+    }
+    messages::info(&summary);
+    let failed: Vec<String> = entries.iter()
+        .filter(|entry| !entry.is_ok())
+        .map(|entry| entry.input.clone())
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(SoldError::InputsFailed { failed, total: entries.len() })
+    }
+}
+
+/// Leaves the ABI file, which holds all functions for the linker, as the build writes it:
+/// with the published functions, or removed if it isn't to be written.
+fn finish_abi_file(abi_path: &Path, emit_abi: bool, abi: &serde_json::Value, published_abi: &serde_json::Value) -> Status {
+    if !emit_abi {
+        std::fs::remove_file(abi_path)?;
+    } else if published_abi != abi {
+        printer::print_abi_json_canonically(&mut File::create(abi_path)?, published_abi)?;
+    }
+    Ok(())
+}
+
+/// Name and content of the library the code is linked with, --lib or the embedded stdlib
+fn linked_library(lib: &Option<String>) -> Result<(String, Vec<u8>)> {
+    Ok(match lib {
+        Some(lib) => (lib.clone(), std::fs::read(lib)?),
+        None => (String::from("stdlib_sol.tvm"), STDLIB.to_vec()),
+    })
+}
+
+/// Path of an artifact: the one given with its --*-out option, whose directory is created if
+/// needed, or `<output_dir>/<prefix>.<extension>`.
+fn artifact_path(custom: &Option<String>, output_path: &Path, output_prefix: &str, extension: &str) -> Result<PathBuf> {
+    match custom {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            Ok(path)
+        }
+        None => Ok(output_path.join(format!("{}.{}", output_prefix, extension))),
+    }
+}
+
+/// Writes `state` to the TVC file at `path`, as the linker saves it but without printing.
+fn save_state(state: &StateInit, path: &str) -> ton_types::Result<()> {
+    let root_cell = state.write_to_new_cell()?.into_cell()?;
+    let mut buffer = vec![];
+    BagOfCells::with_root(&root_cell).write_to(&mut buffer, false)?;
+    std::fs::write(path, buffer)?;
+    Ok(())
+}
+
+/// Value of a JSON option, given inline or as `@file`, checked to be valid JSON.
+fn json_argument(option: &str, value: &str) -> Result<String> {
+    let (json, origin) = match value.strip_prefix('@') {
+        Some(path) => {
+            let json = std::fs::read_to_string(path)
+                .map_err(|e| SoldError::ArgumentError(format!("Failed to read {} file \"{}\": {}", option, path, e)))?;
+            (json, format!("file \"{}\"", path))
+        }
+        None => (value.to_owned(), "value".to_owned()),
+    };
+    serde_json::from_str::<serde_json::Value>(&json)
+        .map_err(|e| SoldError::ArgumentError(format!("Invalid JSON in {} {}: {}", option, origin, e)))?;
+    Ok(json)
+}
+
+pub fn build(mut args: Args) -> Result<report::BuildOutput> {
+    let input = args.input.first()
+        .ok_or_else(|| SoldError::ArgumentError("Source file name is required".to_owned()))?;
+    let input_canonical = if source_cache::is_virtual(Path::new(input)) {
+        PathBuf::from(input)
+    } else {
+        Path::new(input).canonicalize()
+            .map_err(|_| SoldError::SourceNotFound(input.to_owned()))?
+    };
+    // The options as given, a replay merges the project config again
+    let build_info = args.build_info.then(|| build_config::build_info(&args)).transpose()?;
+    let (config, explicit_config) = match &args.config {
+        Some(path) => {
+            let path = Path::new(path).canonicalize().map_err(|_| SoldError::SourceNotFound(path.clone()))?;
+            let config = config::Config::load(&path)?;
+            (Some((path, config)), true)
+        }
+        None => (config::Config::discover(&input_canonical)?, false),
+    };
+    if let Some((config_path, config)) = &config {
+        config.compiler.apply(config_path.parent().unwrap_or_else(|| Path::new(".")), &mut args);
+    }
+    // Taken before the build consumes the options
+    let metadata_options = args.metadata.then(|| serde_json::to_value(&args)).transpose()?;
+    let emit = emit::selection(&args)?;
+    remappings::check(&args.remap)?;
+    sandbox::set(&args, &input_canonical)?;
+    diagnostics::set_color(args.color);
+    let output_dir = args.output_dir.clone().unwrap_or_else(|| String::from("."));
+    let output_path = Path::new(&output_dir);
+    if !output_path.exists() {
+        return Err(SoldError::ArgumentError("Output directory doesn't exist".to_owned()))
+    }
+
+    if let Some(ref output_prefix) = args.output_prefix {
+        if output_prefix.contains(std::path::is_separator) {
+            return Err(SoldError::ArgumentError(format!(
+                "Invalid output prefix \"{}\". Use option -O to set output directory", output_prefix
+            )))
+        }
+    }
+
+    let input = input_canonical.as_os_str().to_str()
+        .ok_or_else(|| SoldError::ArgumentError("Failed to get canonical path".to_owned()))?;
+    let _build_span = tracing::info_span!("build", input).entered();
+
+    let ctor_params = args.ctor_params.as_deref().map(|value| json_argument("--ctor-params", value)).transpose()?;
+    let init = args.init.as_deref().map(|value| json_argument("--init", value)).transpose()?;
+    let deploy_params = args.deploy_params.as_deref().map(|value| json_argument("--deploy-params", value)).transpose()?;
+
+    let mut constants = BTreeMap::new();
+    if let Some(ref network) = args.network {
+        let (_, config) = config.as_ref()
+            .ok_or_else(|| SoldError::ConfigError(format!(
+                "Network \"{}\" is selected, but no {} found", network, config::CONFIG_FILE_NAME
+            )))?;
+        constants = config.network_constants(network)?;
+    }
+    for define in &args.define {
+        let (name, value) = defines::parse(define)?;
+        constants.insert(name, value);
+    }
+    defines::set(constants)?;
+
+    if args.solc_lib.is_none() && !ast_import::is_ast(input) {
+        let mut frontends = install::cached();
+        if let Some((config_path, config)) = &config {
+            let dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+            frontends.extend(config.frontends.iter().map(|(version, path)| (version.clone(), dir.join(path))));
+        }
+        if let Some(requirement) = frontend::select(&source_cache::read_to_string(&input_canonical)?, &frontends) {
+            if args.auto_install {
+                let index = std::env::var("SOLD_FRONTEND_INDEX").ok()
+                    .or_else(|| config.as_ref().and_then(|(_, config)| config.frontend_index.clone()))
+                    .ok_or_else(|| SoldError::InstallError(format!(
+                        "no frontend meets \"{}\" and no frontend index is set, \
+                        set SOLD_FRONTEND_INDEX or frontend_index in {}", requirement, config::CONFIG_FILE_NAME
+                    )))?;
+                frontend::load(&install::install(&requirement, &index)?.to_string_lossy());
+            } else {
+                messages::info(&format!("Note: no available frontend meets \"{}\", pass --auto-install to download one", requirement));
+            }
+        }
+    }
+
+    if args.via_ast && !ast_import::is_ast(input) {
+        via_ast::check(&args, input)?;
+    }
+    let mut res = compile(&args, input)?;
+    let metadata_input: Option<serde_json::Value> = if args.metadata {
+        Some(serde_json::from_str(&compiler_input(&args, input)?)?)
+    } else {
+        None
+    };
+    for name in defines::unused() {
+        messages::warning(&format!("Warning: define \"{}\" does not match any constant declaration", name));
+    }
+    if args.commands_json {
+        let sources: Vec<&str> = res.sources.keys().map(String::as_str).collect();
+        commands::record(output_path, input, &compiler_input(&args, input)?, &sources)?;
+    }
+    // The outputs of an exported AST are those of the source it was exported from
+    let main_source = if ast_import::is_ast(input) { Some(ast_import::main_source(input)?) } else { None };
+    let input = main_source.as_deref().unwrap_or(input);
+    let mut output = report::BuildOutput {
+        warnings: res.errors.iter().filter(|e| e["severity"] == "warning").count(),
+        ..Default::default()
+    };
+    let (contract_name, out) = parse_comp_result(
+        &mut res,
+        input,
+        args.contract,
+        !(args.abi_json || args.ast_json || args.ast_compact_json),
+        args.error_format,
+    )?;
+
+    output.contract = contract_name;
+
+    if args.function_ids {
+        messages::output(&serde_json::to_string_pretty(&out.function_ids)?);
+        return Ok(output)
+    }
+    if args.interface {
+        let sources: BTreeMap<&str, &serde_json::Value> = res.sources.iter()
+            .filter_map(|(path, source)| Some((path.as_str(), source.ast.as_ref()?)))
+            .collect();
+        let mut text = vec![];
+        interface::write(&mut text, &output.contract, &out.abi, &sources)?;
+        messages::output(String::from_utf8_lossy(&text).trim_end());
+        return Ok(output)
+    }
+
+    let input_file_stem = Path::new(input).file_stem()
+        .ok_or_else(|| SoldError::ArgumentError("Failed to extract file stem".to_owned()))?
+        .to_str()
+        .ok_or_else(|| SoldError::ArgumentError("Failed to get file stem".to_owned()))?
+        .to_string();
+    let output_prefix = args.output_prefix.unwrap_or(input_file_stem);
+
+    if args.ast_json || args.ast_compact_json {
+        let mut array = vec!();
+        for (_, source) in res.sources {
+            array.push(source.ast.ok_or_else(|| parse_error!())?);
+        }
+
+        let ast = serde_json::Value::Array(array);
+        let ast_file_name = format!("{}.ast.json", output_prefix);
+        let mut ast_file = File::create(output_path.join(&ast_file_name))?;
+
+        if args.ast_json {
+            serde_json::to_writer_pretty(&mut ast_file, &ast)?;
+        } else {
+            serde_json::to_writer(&mut ast_file, &ast)?;
+        }
+        writeln!(ast_file)?;
+        output.artifacts.push(output_path.join(ast_file_name));
+        return Ok(output)
+    }
+
+    let abi = &out.abi;
+    let published_abi = abi::published(abi, &args.abi_include, &args.abi_exclude);
+    let abi_path = artifact_path(&args.abi_out, output_path, &output_prefix, "abi.json")?;
+    let mut abi_file = File::create(&abi_path)?;
+    // The linker reads the ABI file, so it holds all functions until linking is done
+    printer::print_abi_json_canonically(&mut abi_file, if args.abi_json { &published_abi } else { abi })?;
+    let emit_abi = args.abi_json || emit::enabled(&emit, emit::Artifact::Abi);
+    if emit_abi {
+        output.artifacts.push(abi_path.clone());
+    }
+    if args.split_abi {
+        let sources: BTreeMap<&str, &serde_json::Value> = res.sources.iter()
+            .filter_map(|(path, source)| Some((path.as_str(), source.ast.as_ref()?)))
+            .collect();
+        let getters = abi_split::getters(&output.contract, &sources);
+        let onchain_path = output_path.join(format!("{}.onchain.abi.json", output_prefix));
+        printer::print_abi_json_canonically(&mut File::create(&onchain_path)?, &abi_split::onchain(&published_abi, &getters))?;
+        output.artifacts.push(onchain_path);
+    }
+    if args.abi_json {
+        return Ok(output)
+    }
+
+    let assembly = out.assembly.clone()
+        .ok_or_else(|| parse_error!())?;
+    let assembly = if args.pool_constants { constants::pool(&assembly) } else { assembly };
+    let code_path = artifact_path(&args.code_out, output_path, &output_prefix, "code")?;
+    if emit::enabled(&emit, emit::Artifact::Code) {
+        let mut assembly_text = if args.asm_canonical {
+            asm::Assembly::parse(&assembly).to_canonical_string()
+        } else {
+            assembly.clone()
+        };
+        // The .loc directives the source map is made of
+        let unstripped_text = assembly_text.clone();
+        if args.strip {
+            let unstripped_path = code_path.with_extension("unstripped.code");
+            let mut unstripped_file = File::create(&unstripped_path)?;
+            unstripped_file.write_all(asm::annotate(&assembly_text).as_bytes())?;
+            output.artifacts.push(unstripped_path);
+            assembly_text = asm::strip(&assembly_text);
+        }
+        let mut assembly_file = File::create(&code_path)?;
+        assembly_file.write_all(assembly_text.as_bytes())?;
+        output.artifacts.push(code_path.clone());
+        if args.source_map {
+            let map_path = output_path.join(format!("{}.map.json", output_prefix));
+            let code_file = code_path.file_name().unwrap_or_default().to_string_lossy();
+            source_map::write(&mut File::create(&map_path)?, &code_file, &unstripped_text, args.strip)?;
+            output.artifacts.push(map_path);
+        }
+    }
+
+    let sources: BTreeMap<&str, &serde_json::Value> = res.sources.iter()
+        .filter_map(|(path, source)| Some((path.as_str(), source.ast.as_ref()?)))
+        .collect();
+    if args.errors {
+        let errors_file_name = format!("{}.errors.json", output_prefix);
+        let mut errors_file = File::create(output_path.join(&errors_file_name))?;
+        errors::write(&mut errors_file, &output.contract, &sources)?;
+        output.artifacts.push(output_path.join(errors_file_name));
+    }
+    if args.messages {
+        let messages_file_name = format!("{}.messages.json", output_prefix);
+        let mut messages_file = File::create(output_path.join(&messages_file_name))?;
+        errors::write_messages(&mut messages_file, &output.contract, &sources)?;
+        output.artifacts.push(output_path.join(messages_file_name));
+    }
+    if args.exceptions {
+        let exceptions_file_name = format!("{}.exceptions.json", output_prefix);
+        let mut exceptions_file = File::create(output_path.join(&exceptions_file_name))?;
+        exceptions::write(&mut exceptions_file, &output.contract, &sources)?;
+        output.artifacts.push(output_path.join(exceptions_file_name));
+    }
+    if args.constants_report {
+        let report_file_name = format!("{}.constants.json", output_prefix);
+        let mut report_file = File::create(output_path.join(&report_file_name))?;
+        constants::write(&mut report_file, &assembly)?;
+        output.artifacts.push(output_path.join(report_file_name));
+    }
+    if args.function_ids_json {
+        let ids_file_name = format!("{}.funcids.json", output_prefix);
+        let mut ids_file = File::create(output_path.join(&ids_file_name))?;
+        function_ids::write(&mut ids_file, &out.abi, &out.function_ids)?;
+        output.artifacts.push(output_path.join(ids_file_name));
+    }
+    if args.dispatch_report {
+        let report_file_name = format!("{}.dispatch.json", output_prefix);
+        let mut report_file = File::create(output_path.join(&report_file_name))?;
+        dispatch::write(&mut report_file, args.dispatch, &args.dispatch_priority, &out.function_ids)?;
+        output.artifacts.push(output_path.join(report_file_name));
+    }
+    if let Some(build_info) = build_info {
+        let build_info_file_name = format!("{}.build-info.json", output_prefix);
+        let mut build_info_file = File::create(output_path.join(&build_info_file_name))?;
+        serde_json::to_writer_pretty(&mut build_info_file, &build_info)?;
+        writeln!(build_info_file)?;
+        output.artifacts.push(output_path.join(build_info_file_name));
+    }
+    if !emit::links(&args, &emit) {
+        finish_abi_file(&abi_path, emit_abi, abi, &published_abi)?;
+        return Ok(output)
+    }
+
+    let link_span = tracing::info_span!("link").entered();
+    let mut inputs = Vec::new();
+    if let Some(lib) = &args.lib {
+        let lib_file = File::open(lib)
+            .map_err(|_| SoldError::SourceNotFound(lib.clone()))?;
+        inputs.push(ParseEngineInput { buf: Box::new(lib_file), name: lib.clone() });
+    } else {
+        inputs.push(ParseEngineInput { buf: Box::new(STDLIB), name: String::from("stdlib_sol.tvm") });
+    }
+    inputs.push(ParseEngineInput { buf: Box::new(assembly.as_bytes()), name: code_path.to_string_lossy().into_owned() });
+
+    let engine = ParseEngine::new_generic(inputs, Some(format!("{}", abi)))
+        .map_err(|e| SoldError::LinkError(e.to_string()))?;
+    let mut prog = Program::new(engine);
+
+    let mnemonic_pair = mnemonic::from_options(
+        args.mnemonic.as_deref(),
+        args.mnemonic_file.as_deref(),
+        args.mnemonic_kind,
+        args.derivation_path.as_deref(),
+    )?;
+    match args.gen_key {
+        Some(file) => {
+            let pair = KeypairManager::new();
+            pair.store_public(&(file.to_string() + ".pub"))
+                .map_err(|e| SoldError::KeyError(e.to_string()))?;
+            if args.key_password {
+                let pair = pair.drain();
+                key_file::store(&pair, &file)?;
+                prog.set_keypair(pair);
+            } else {
+                pair.store_secret(&file)
+                    .map_err(|e| SoldError::KeyError(e.to_string()))?;
+                prog.set_keypair(pair.drain());
+            }
+        }
+        None => if let Some(pair) = mnemonic_pair {
+            let public: String = pair.public.as_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+            messages::info(&format!("Public key derived from the seed phrase: {}", public));
+            prog.set_keypair(pair);
+        } else if let Some(file) = args.set_key {
+            let content = std::fs::read(&file)
+                .map_err(|_| SoldError::KeyError(format!("Failed to read keypair from \"{}\"", file)))?;
+            match key_file::load(&content)? {
+                Some(pair) => prog.set_keypair(pair),
+                None => {
+                    let pair = KeypairManager::from_secret_file(&file)
+                        .ok_or_else(|| SoldError::KeyError(format!("Failed to read keypair from \"{}\"", file)))?;
+                    prog.set_keypair(pair.drain());
+                }
+            }
+        } else if args.init_pubkey == Some(InitPubkey::Random) {
+            prog.set_keypair(KeypairManager::new().drain());
+        }
+    }
+
+    let tvc_path = artifact_path(&args.tvc_out, output_path, &output_prefix, "tvc")?;
+    let output_filename = tvc_path.to_string_lossy().into_owned();
+
+    if ctor_params.is_some() {
+        // Only the linker runs the constructor; it reports the saved contract on stdout
+        messages::capture_stdout(|| prog.compile_to_file_ex(
+            -1,
+            Some(&abi_path.to_string_lossy()),
+            ctor_params.as_deref(),
+            Some(&output_filename),
+            false,
+            None
+        )).map_err(|e| SoldError::LinkError(e.to_string()))?;
+    } else {
+        let state = prog.compile_to_state()
+            .map_err(|e| SoldError::LinkError(e.to_string()))?;
+        save_state(&state, &output_filename).map_err(|e| SoldError::LinkError(e.to_string()))?;
+    }
+    messages::output(&format!("Contract successfully compiled. Saved to file {}.", output_filename));
+
+    let emit_tvc = emit::enabled(&emit, emit::Artifact::Tvc);
+    if emit_tvc {
+        output.artifacts.push(tvc_path.clone());
+    }
+    if emit::enabled(&emit, emit::Artifact::Debug) {
+        let debug_path = artifact_path(&args.debug_out, output_path, &output_prefix, "debug.json")?;
+        let mut dbg_file = File::create(&debug_path)?;
+        serde_json::to_writer_pretty(&mut dbg_file, &prog.dbgmap)?;
+        writeln!(dbg_file)?;
+        output.artifacts.push(debug_path);
+    }
+    link_span.exit();
+
+    let zero_pubkey = args.init_pubkey == Some(InitPubkey::Zero);
+    if init.is_some() || zero_pubkey {
+        let _span = tracing::info_span!("init").entered();
+        let mut state = ton_utils::program::load_from_file(&output_filename)
+            .map_err(|e| SoldError::InitError(e.to_string()))?;
+        if let Some(params_data) = init {
+            let new_data = ton_abi::json_abi::update_contract_data(
+                &serde_json::to_string(abi)?,
+                &params_data,
+                state.data.clone().unwrap_or_default().into(),
+            ).map_err(|e| SoldError::InitError(e.to_string()))?;
+            state.set_data(new_data.into_cell());
+        }
+        if zero_pubkey {
+            let new_data = ton_abi::Contract::insert_pubkey(state.data.clone().unwrap_or_default().into(), &[0; 32])
+                .map_err(|e| SoldError::InitError(e.to_string()))?;
+            state.set_data(new_data.into_cell());
+        }
+
+        save_state(&state, &output_filename).map_err(|e| SoldError::InitError(e.to_string()))?;
+    }
+
+    let state = ton_utils::program::load_from_file(&output_filename)
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+    let tvc = std::fs::read(&output_filename)?;
+    // The linker works on files, so the ABI and TVC are written anyway and removed if not asked for
+    if !emit_tvc {
+        std::fs::remove_file(&output_filename)?;
+    }
+    finish_abi_file(&abi_path, emit_abi, abi, &published_abi)?;
+    output.tvc_size = Some(tvc.len());
+    if let Some(code) = &state.code {
+        let mut code_boc = vec![];
+        BagOfCells::with_root(code).write_to(&mut code_boc, false)
+            .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+        output.code_size = Some(code_boc.len());
+        output.code_hash = Some(code.repr_hash().to_hex_string());
+    }
+    let hashes = hashes::Hashes::of(&state)?;
+    if let Some(code_hash) = &hashes.code_hash {
+        messages::info(&format!("Code hash: {}", code_hash));
+    }
+    messages::info(&format!("Address: {} (masterchain {})", hashes.address, hashes.masterchain_address));
+    output.address = Some(hashes.address.clone());
+    if args.hash_json {
+        let hash_path = output_path.join(format!("{}.hash.json", output_prefix));
+        hashes.write(&mut File::create(&hash_path)?)?;
+        output.artifacts.push(hash_path);
+    }
+    let preset_limits = args.network_preset.map_or_else(limits::StateLimits::default, presets::NetworkPreset::limits);
+    limits::check(
+        &state,
+        output.code_size.unwrap_or_default(),
+        config.as_ref().and_then(|(_, config)| config.limits.as_ref()).unwrap_or(&preset_limits),
+        &assembly,
+        &serde_json::to_value(&prog.dbgmap)?,
+    )?;
+    if args.emit_deploy_payload {
+        let payload_file_name = format!("{}.deploy.json", output_prefix);
+        let mut payload_file = File::create(output_path.join(&payload_file_name))?;
+        deploy_payload::write(&mut payload_file, abi, &state, deploy_params.as_deref().unwrap_or("{}"))?;
+        output.artifacts.push(output_path.join(payload_file_name));
+    }
+    if args.bundle {
+        let bundle_file_name = format!("{}.contract.json", output_prefix);
+        let mut bundle_file = File::create(output_path.join(&bundle_file_name))?;
+        bundle::write(&mut bundle_file, &output_prefix, &published_abi, &tvc, &state)?;
+        output.artifacts.push(output_path.join(bundle_file_name));
+    }
+    if args.stdlib_report {
+        let (library_name, library) = linked_library(&args.lib)?;
+        let library = String::from_utf8_lossy(&library).into_owned();
+        let report_file_name = format!("{}.stdlib.json", output_prefix);
+        let mut report_file = File::create(output_path.join(&report_file_name))?;
+        stdlib_report::write(
+            &mut report_file, &library_name, &library, &assembly, state.code.as_ref(), &serde_json::to_value(&prog.dbgmap)?,
+        )?;
+        output.artifacts.push(output_path.join(report_file_name));
+    }
+    if let (Some(options), Some(input)) = (&metadata_options, &metadata_input) {
+        let (library_name, library) = linked_library(&args.lib)?;
+        let metadata_path = output_path.join(format!("{}.meta.json", output_prefix));
+        metadata::write(&mut File::create(&metadata_path)?, metadata::Build {
+            contract: &output.contract,
+            options,
+            input,
+            sources: res.sources.keys().map(String::as_str).collect(),
+            library_name: &library_name,
+            library: &library,
+            code_hash: output.code_hash.as_deref(),
+        })?;
+        output.artifacts.push(metadata_path);
+    }
+    if let Some(target) = args.bindgen {
+        let file_name = format!("{}.{}", output_prefix, target.file_extension());
+        let mut bindings_file = File::create(output_path.join(&file_name))?;
+        bindgen::generate(target, &mut bindings_file, &output_prefix, &published_abi, &tvc, &state)?;
+        output.artifacts.push(output_path.join(file_name));
+    }
+    if args.storage_fees {
+        let root = state.serialize()
+            .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+        let preset_prices = args.network_preset.map_or_else(storage::StoragePrices::default, presets::NetworkPreset::storage_prices);
+        let prices = config.as_ref().and_then(|(_, config)| config.storage.as_ref()).unwrap_or(&preset_prices);
+        storage::report(&output_prefix, &root, abi, prices)?;
+    }
+
+    // Plugins run programs of the project, so they are run only when asked for, from a
+    // configuration named on the command line and not from one found next to a source
+    match config.filter(|(_, config)| !config.plugins.is_empty()) {
+        Some((config_path, _)) if !(explicit_config && args.plugins) => messages::warning(&format!(
+            "Warning: plugins of {} are not run, name the file with --config and give --plugins to run them",
+            config_path.display()
+        )),
+        Some((config_path, config)) => {
+            let work_dir = config_path.parent()
+                .ok_or_else(|| SoldError::ConfigError("Failed to get config directory".to_owned()))?;
+            let input = plugins::PluginInput {
+                version: plugins::PROTOCOL_VERSION,
+                output_prefix: &output_prefix,
+                abi,
+                assembly: &assembly,
+                tvc: base64::encode(&tvc),
+                debug_map: serde_json::to_value(&prog.dbgmap)?,
+            };
+            output.artifacts.extend(plugins::run_all(&config.plugins, work_dir, &input, output_path)?);
+        }
+        None => {}
+    }
+
+    if args.hashed_names {
+        if let Some(code_hash) = output.code_hash.clone() {
+            let (artifacts, latest_path) = hashed_names::apply(output.artifacts, output_path, &output_prefix, &code_hash)?;
+            output.artifacts = artifacts;
+            output.artifacts.push(latest_path);
+        }
+    }
+    if args.manifest || args.archive.is_some() {
+        let manifest_path = output_path.join(format!("{}.manifest.json", output_prefix));
+        manifest::write(&manifest_path, &output.contract, &output.artifacts, output_path, &state)?;
+        output.artifacts.push(manifest_path);
+    }
+    if let Some(archive_path) = &args.archive {
+        archive::write(Path::new(archive_path), &output.artifacts, output_path)?;
+        output.artifacts.push(PathBuf::from(archive_path));
+    }
+
+    Ok(output)
+}
+
+use once_cell::sync::OnceCell;
+static VERSION: OnceCell<String> = OnceCell::new();
+
+// Build configurations are also read from and written to JSON and TOML, see build_config.rs
+#[derive(Parser, Clone, Debug, Default, Serialize, Deserialize)]
+#[clap(author, about, long_about = None)]
+#[clap(version = VERSION.get_or_init(frontend::embedded_version).as_str())]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[serde(default, deny_unknown_fields)]
+pub struct Args {
+    #[clap(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+    /// Source file names, `-` reads a source from stdin
+    #[clap(value_parser, required_unless_present_any = &["serve-stdio", "standard-json", "from-config", "version-json"])]
+    pub input: Vec<String>,
+    /// Name of the source read from stdin, relative to the current directory, used in imports and diagnostics
+    #[clap(long, value_parser)]
+    pub stdin_name: Option<String>,
+    /// Contract to build if sources define more than one contract
+    #[clap(short, long, value_parser)]
+    pub contract: Option<String>,
+    /// Output directory (by default, current directory is used)
+    #[clap(short('O'), long, value_parser)]
+    pub output_dir: Option<String>,
+    /// Output prefix (by default, input file stem is used as prefix)
+    #[clap(short('P'), long, value_parser)]
+    pub output_prefix: Option<String>,
+    /// Include additional path to search for imports
+    #[clap(short('I'), long, value_parser)]
+    pub include_path: Vec<String>,
+    /// Look up imports here first and read sources only from the allowed directories
+    #[clap(long, value_parser)]
+    pub base_path: Option<String>,
+    /// Directories sources may be read from, besides the base path, the include paths and the directory of the input
+    #[clap(long, value_parser, value_delimiter = ',')]
+    pub allow_paths: Vec<String>,
+    /// Remap imports starting with a prefix, e.g. --remap @openzeppelin/=node_modules/@openzeppelin/
+    #[clap(long, value_parser)]
+    pub remap: Vec<remappings::Remapping>,
+    /// Library to use instead of default
+    #[clap(short('L'), long, value_parser)]
+    pub lib: Option<String>,
+    /// Execute constructor with provided parameters, inline JSON or @file
+    #[clap(short('p'), long, value_parser, hide = true)] // deprecated
+    pub ctor_params: Option<String>,
+    /// Set newly generated keypair
+    #[clap(short, long, value_parser, conflicts_with = "set-key", hide = true)] // deprecated
+    pub gen_key: Option<String>,
+    /// Set keypair from file
+    #[clap(short, long, value_parser, conflicts_with = "gen-key", hide = true)] // deprecated
+    pub set_key: Option<String>,
+    /// Encrypt the secret key --gen-key writes with a password, taken from SOLD_KEY_PASSWORD or asked for
+    #[clap(long, value_parser, requires = "gen-key")]
+    pub key_password: bool,
+    /// Initialize static fields with the given JSON, or with the JSON of @file
+    #[clap(long, value_parser)]
+    pub init: Option<String>,
+    /// Put a zero or a random public key into the initial data, without writing key files
+    #[clap(long, value_enum, conflicts_with_all = &["gen-key", "set-key", "mnemonic", "mnemonic-file"])]
+    pub init_pubkey: Option<InitPubkey>,
+    /// Derive the keypair from the given seed phrase
+    #[clap(long, value_parser, conflicts_with_all = &["gen-key", "set-key", "mnemonic-file"])]
+    #[serde(skip)]
+    pub mnemonic: Option<String>,
+    /// Derive the keypair from the seed phrase in the given file
+    #[clap(long, value_parser, conflicts_with_all = &["gen-key", "set-key"])]
+    pub mnemonic_file: Option<String>,
+    /// Word list and derivation scheme of the seed phrase, bip39 by default
+    #[clap(long, value_enum)]
+    pub mnemonic_kind: Option<mnemonic::Kind>,
+    /// BIP32 path the keypair is derived along, m/44'/396'/0'/0/0 by default
+    #[clap(long, value_parser)]
+    pub derivation_path: Option<String>,
+    /// Print name and id for each public function
+    #[clap(long, value_parser)]
+    pub function_ids: bool,
+    /// Also write the id, kind, signature and parameters of each public function to <prefix>.funcids.json
+    #[clap(long, value_parser)]
+    pub function_ids_json: bool,
+    /// Print the public functions, events, state variables and constants of the contract with their types
+    #[clap(long, value_parser)]
+    pub interface: bool,
+    /// Get AST of all source files in JSON format
+    #[clap(long, value_parser, conflicts_with = "ast-compact-json")]
+    pub ast_json: bool,
+    /// Get AST of all source files in compact JSON format
+    #[clap(long, value_parser, conflicts_with = "ast-json")]
+    pub ast_compact_json: bool,
+    /// Get ABI without actually compiling
+    #[clap(long, value_parser)]
+    pub abi_json: bool,
+    /// Force download and rewrite remote import files
+    #[clap(long, value_parser)]
+    pub tvm_refresh_remote: bool,
+    /// Override the value of a constant: NAME=VALUE
+    #[clap(short('D'), long, value_parser)]
+    pub define: Vec<String>,
+    /// Read the project configuration from the given sold.toml or sold.json instead of looking
+    /// it up from the input
+    #[clap(long, value_parser)]
+    pub config: Option<String>,
+    /// Run the output generator plugins of the configuration given with --config
+    #[clap(long, value_parser, requires = "config")]
+    pub plugins: bool,
+    /// Use constants of the network defined in sold.toml
+    #[clap(long, value_parser)]
+    pub network: Option<String>,
+    /// Check limits and estimate fees with the settings of a well-known network
+    #[clap(long, value_enum)]
+    pub network_preset: Option<presets::NetworkPreset>,
+    /// Log verbosity; RUST_LOG-style filters are accepted as well
+    #[clap(long, value_parser)]
+    pub log_level: Option<String>,
+    /// Write log records as JSON
+    #[clap(long, value_parser)]
+    pub log_json: bool,
+    /// Abort the build if it takes longer than the given number of seconds
+    #[clap(long, value_parser)]
+    pub timeout: Option<u64>,
+    /// Abort the build if it uses more than the given number of megabytes
+    #[clap(long, value_parser)]
+    pub max_memory: Option<u64>,
+    /// Run the compiler frontend in a separate process, so that its crash can't take sold down
+    #[clap(long, value_parser)]
+    pub isolate: bool,
+    /// Estimate storage fees of the initial state and of mapping growth
+    #[clap(long, value_parser)]
+    pub storage_fees: bool,
+    /// Also write <prefix>.deploy.json with the parameters of a multisig transaction deploying the contract
+    #[clap(long, value_parser)]
+    pub emit_deploy_payload: bool,
+    /// Constructor parameters for --emit-deploy-payload, inline JSON or @file
+    #[clap(long, value_parser, requires = "emit-deploy-payload")]
+    pub deploy_params: Option<String>,
+    /// Also write <prefix>.contract.json with ABI, TVC, code and code hash
+    #[clap(long, value_parser)]
+    pub bundle: bool,
+    /// Also write <prefix>.errors.json mapping exception codes to their require and revert calls
+    #[clap(long, value_parser)]
+    pub errors: bool,
+    /// Also write <prefix>.messages.json listing the messages of require and revert with their codes
+    #[clap(long, value_parser)]
+    pub messages: bool,
+    /// Also write <prefix>.exceptions.json mapping the exception codes of TVM and of generated code to their meaning and the constructs raising them
+    #[clap(long, value_parser)]
+    pub exceptions: bool,
+    /// Also write <prefix>.stdlib.json listing the runtime library functions linked, with their size, and those stripped
+    #[clap(long, value_parser)]
+    pub stdlib_report: bool,
+    /// Also write <prefix>.constants.json listing the constants embedded at several sites of the code and the bits the copies waste
+    #[clap(long, value_parser)]
+    pub constants_report: bool,
+    /// Push slice constants of at least 64 bits used at several sites from a single shared cell
+    #[clap(long, value_parser)]
+    pub pool_constants: bool,
+    /// How the public function selector searches the function ID
+    #[clap(long, value_enum)]
+    pub dispatch: Option<dispatch::Strategy>,
+    /// Compare the ID of the given public function first, before searching the others
+    #[clap(long, value_parser)]
+    pub dispatch_priority: Vec<String>,
+    /// Also write <prefix>.dispatch.json with the comparisons the selector takes to reach each public function
+    #[clap(long, value_parser)]
+    pub dispatch_report: bool,
+    /// Enable the optimizer of the frontend
+    #[clap(long, value_parser)]
+    pub optimize: bool,
+    /// Times the code is expected to run, low values optimize for size and high ones for gas; implies --optimize
+    #[clap(long, value_parser)]
+    pub optimize_runs: Option<u32>,
+    /// Disable the peephole optimizer of the generated code
+    #[clap(long, value_parser)]
+    pub no_peephole: bool,
+    /// Generate bindings for the given target
+    #[clap(long, value_enum)]
+    pub bindgen: Option<bindgen::BindgenTarget>,
+    /// Also write <prefix>.manifest.json with SHA-256 and size of every artifact
+    #[clap(long, value_parser)]
+    pub manifest: bool,
+    /// Also write <prefix>.hash.json with the code, data and StateInit hashes and the addresses of the contract
+    #[clap(long, value_parser)]
+    pub hash_json: bool,
+    /// Name artifacts <prefix>.<codehash8>.tvc etc., and list them in <prefix>.latest.json
+    #[clap(long, value_parser)]
+    pub hashed_names: bool,
+    /// Pack all artifacts and the manifest into the given .tar.gz file
+    #[clap(long, value_parser)]
+    pub archive: Option<String>,
+    /// Publish in the ABI only the functions matching the pattern, `*` and `?` are wildcards
+    #[clap(long, value_parser)]
+    pub abi_include: Vec<String>,
+    /// Leave the functions matching the pattern out of the ABI, they stay in the code
+    #[clap(long, value_parser)]
+    pub abi_exclude: Vec<String>,
+    /// Also write <prefix>.onchain.abi.json, the ABI without the getters run off-chain
+    #[clap(long, value_parser)]
+    pub split_abi: bool,
+    /// Write the ABI to the given path instead of <output_dir>/<prefix>.abi.json
+    #[clap(long, value_parser)]
+    pub abi_out: Option<String>,
+    /// Write the TVC to the given path instead of <output_dir>/<prefix>.tvc
+    #[clap(long, value_parser)]
+    pub tvc_out: Option<String>,
+    /// Write the assembly to the given path instead of <output_dir>/<prefix>.code
+    #[clap(long, value_parser)]
+    pub code_out: Option<String>,
+    /// Write the debug map to the given path instead of <output_dir>/<prefix>.debug.json
+    #[clap(long, value_parser)]
+    pub debug_out: Option<String>,
+    /// Write only the given artifacts, e.g. --emit abi,tvc (all of them by default)
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub emit: Vec<emit::Artifact>,
+    /// Don't write <prefix>.abi.json
+    #[clap(long, value_parser)]
+    pub no_abi: bool,
+    /// Don't write <prefix>.tvc
+    #[clap(long, value_parser)]
+    pub no_tvc: bool,
+    /// Write only <prefix>.code, without linking
+    #[clap(long, value_parser, conflicts_with_all = &["emit", "no-abi", "no-tvc"])]
+    pub asm_only: bool,
+    /// Also write <prefix>.map.json, a source map of the assembly in the Source Map v3 format
+    #[clap(long, value_parser)]
+    pub source_map: bool,
+    /// Write assembly without comments and version, with functions in a stable order, for diffing
+    #[clap(long, value_parser)]
+    pub asm_canonical: bool,
+    /// Write <prefix>.code without debug directives and <prefix>.unstripped.code annotated with source lines
+    #[clap(long, value_parser, overrides_with = "no-strip")]
+    pub strip: bool,
+    /// Write <prefix>.code with debug directives (default)
+    #[clap(long, value_parser, overrides_with = "strip")]
+    pub no_strip: bool,
+    /// With several inputs, number of inputs built at once (one by default)
+    #[clap(short('j'), long, value_parser)]
+    pub jobs: Option<usize>,
+    /// With several inputs, build all of them even if some fail
+    #[clap(short('k'), long, value_parser)]
+    pub keep_going: bool,
+    /// With several inputs, fail if two of them define contracts of the same name
+    #[clap(long, value_parser)]
+    pub deny_duplicate_contracts: bool,
+    /// Build only the inputs defining a contract whose name matches the pattern, `*` and `?` are wildcards
+    #[clap(long, value_parser)]
+    pub only: Vec<String>,
+    /// Don't build the inputs whose contracts all match the pattern, `*` and `?` are wildcards
+    #[clap(long, value_parser)]
+    pub skip: Vec<String>,
+    /// How to print compiler errors and warnings
+    #[clap(long, value_enum, default_value = "human")]
+    pub error_format: ErrorFormat,
+    /// When to color compiler errors and warnings
+    #[clap(long, value_enum, default_value = "auto")]
+    pub color: diagnostics::ColorChoice,
+    /// Use the frontend of the given libsolc shared library instead of the embedded one
+    #[clap(long, value_parser)]
+    pub solc_lib: Option<String>,
+    /// Download a frontend meeting the version pragma of the input when no available one does
+    #[clap(long, value_parser, conflicts_with = "solc-lib")]
+    pub auto_install: bool,
+    /// Check that compiling the exported AST gives the same AST, ABI and assembly as compiling the sources
+    #[clap(long, value_parser)]
+    pub via_ast: bool,
+    /// Serve compile, abi, version and cancel requests, one JSON object per line, on stdin and stdout
+    #[clap(long, value_parser)]
+    pub serve_stdio: bool,
+    /// Print the versions of sold, the frontend, the runtime library and the linker in JSON
+    #[clap(long, value_parser, conflicts_with = "input")]
+    #[serde(skip)]
+    pub version_json: bool,
+    /// Rebuild whenever the inputs or the files they import change
+    #[clap(long, value_parser)]
+    #[serde(skip)]
+    pub watch: bool,
+    /// Compile the standard JSON input read from the given file, or stdin, and print the standard JSON output
+    #[clap(long, value_parser, min_values = 0, default_missing_value = "-", conflicts_with_all = &["input", "serve-stdio"])]
+    #[serde(skip)]
+    pub standard_json: Option<String>,
+    /// Record the settings and imports of each compiled source in sold_commands.json in the output directory
+    #[clap(long, value_parser)]
+    pub commands_json: bool,
+    /// Write a profile of the build in Chrome trace event format to the given file
+    #[clap(long, value_parser)]
+    pub profile_json: Option<String>,
+    /// Write the compiler version and the build options given to <prefix>.build-info.json
+    #[clap(long, value_parser)]
+    pub build_info: bool,
+    /// Write <prefix>.meta.json with versions, settings and source and library hashes for verification
+    #[clap(long, value_parser)]
+    pub metadata: bool,
+    /// Build with the configuration of the given JSON or TOML file, e.g. a build-info.json
+    #[clap(long, value_parser, exclusive = true)]
+    #[serde(skip)]
+    pub from_config: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InitPubkey {
+    /// All-zero key, for contracts deployed by other contracts or getting their key later
+    Zero,
+    /// Key of a freshly generated keypair, which is thrown away
+    Random,
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Submit sources of a deployed contract to a verification service
+    Publish(publish::PublishArgs),
+    /// Work with contract ABIs
+    Abi {
+        #[clap(subcommand)]
+        command: AbiCommand,
+    },
+    /// Inspect the produced code
+    Analyze {
+        #[clap(subcommand)]
+        command: AnalyzeCommand,
+    },
+    /// Run a getter of a built contract repeatedly and report gas and wall time statistics
+    Bench(bench::BenchArgs),
+    /// Work with bags of cells
+    Boc {
+        #[clap(subcommand)]
+        command: BocCommand,
+    },
+    /// Explain the exit code of a failed transaction with the table written by --errors
+    DecodeError(decode_error::DecodeErrorArgs),
+    /// Format the sources
+    Fmt(fmt::FmtArgs),
+    /// Compare the gas estimates of two builds function by function
+    GasDiff(gas_diff::GasDiffArgs),
+    /// Create a project skeleton: sold.toml, a contract, a test and npm scripts
+    Init(init::InitArgs),
+    /// Run a language server on stdin and stdout
+    Lsp(lsp::LspArgs),
+    /// Report or rewrite constructs deprecated in newer compiler versions
+    Migrate(migrate::MigrateArgs),
+    /// Measure how many small changes to the contract the tests catch
+    Mutate(mutate::MutateArgs),
+    /// Generate the source of a contract implementing a standard
+    New(new::NewArgs),
+    /// Manage the compiler version pragma of the sources
+    Pragma {
+        #[clap(subcommand)]
+        command: PragmaCommand,
+    },
+    /// Rename a contract, function or state variable everywhere it is used
+    Rename(rename::RenameArgs),
+    /// Build a contract twice and check that the artifacts are byte-identical
+    ReproCheck(repro_check::ReproCheckArgs),
+    /// Generate scripts working with the build artifacts
+    Scaffold {
+        #[clap(subcommand)]
+        command: ScaffoldCommand,
+    },
+    /// Attribute the change of the code size between two builds to functions and constants
+    SizeDiff(size_diff::SizeDiffArgs),
+    /// Print the symbol index and semantic tokens of a source file as JSON
+    Symbols(symbols::SymbolsArgs),
+    /// Search for a public key, salt or initial data giving an address that starts with given digits
+    Vanity(vanity::VanityArgs),
+    /// Check that deployed code was built from the given sources
+    Verify(verify::VerifyArgs),
+    /// Draw the cell tree of the code of a contract as Graphviz DOT or SVG
+    Viz(viz::VizArgs),
+    /// Serve a single compiler request for --isolate
+    #[clap(name = "compile-worker", hide = true)]
+    CompileWorker,
+    /// Serve a single build under --timeout or --max-memory
+    #[clap(name = "build-worker", hide = true)]
+    BuildWorker {
+        /// File the result is written to
+        #[clap(value_parser)]
+        result: String,
+    },
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum AbiCommand {
+    /// Print an ABI in human-readable format, or as JSON with --json
+    Show(abi::ShowArgs),
+    /// Print JSON Schemas of the parameters of each function and event
+    Schema(schema::SchemaArgs),
+    /// Print the ID and signature of each event, as found in external outbound messages
+    EventId(abi::EventIdArgs),
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum AnalyzeCommand {
+    /// Estimate worst-case and typical gas of each public function
+    Gas(gas::GasArgs),
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum BocCommand {
+    /// Print the roots, cell statistics and hashes of a BOC, and dump selected cells
+    Inspect(boc::InspectArgs),
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum ScaffoldCommand {
+    /// Generate a script computing the address of the contract, funding and deploying it
+    Deploy(scaffold::DeployArgs),
+}
+
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum PragmaCommand {
+    /// Set the version requirement of all sources and check they still compile
+    Set(pragma::PragmaSetArgs),
+}
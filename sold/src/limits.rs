@@ -0,0 +1,160 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Validation of the linked StateInit against the limits of the network. The defaults are
+// those of config param 43: a state init has to fit into a deploy message (8192 cells),
+// cells may not be nested deeper than 512 and an external message is at most 64 KB.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::Value;
+use ton_block::StateInit;
+use ton_types::Cell;
+
+use crate::asm::{self, Assembly, Loc};
+use crate::error::{SoldError, Status};
+
+const SHOWN_CONTRIBUTORS: usize = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct StateLimits {
+    #[serde(default = "default_max_cells")]
+    pub max_cells: u64,
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u64,
+    /// Size of the serialized code, in bytes
+    #[serde(default = "default_max_code_size")]
+    pub max_code_size: u64,
+}
+
+fn default_max_cells() -> u64 { 1 << 13 }
+fn default_max_depth() -> u64 { 512 }
+fn default_max_code_size() -> u64 { 65535 }
+
+impl Default for StateLimits {
+    fn default() -> Self {
+        StateLimits {
+            max_cells: default_max_cells(),
+            max_depth: default_max_depth(),
+            max_code_size: default_max_code_size(),
+        }
+    }
+}
+
+fn ton_error(e: impl std::fmt::Display) -> SoldError {
+    SoldError::InvalidOutput(format!("Failed to inspect the contract state: {}", e))
+}
+
+/// Fails with a description of every exceeded limit and of the functions taking most of the code.
+pub fn check(
+    state: &StateInit,
+    code_size: usize,
+    limits: &StateLimits,
+    assembly: &str,
+    debug_map: &Value,
+) -> Status {
+    let root = ton_block::Serializable::serialize(state).map_err(ton_error)?;
+    let (_, cells) = crate::storage::count_unique(&root).map_err(ton_error)?;
+    let depth = root.repr_depth() as u64;
+    let code_cells = match &state.code {
+        Some(code) => crate::storage::count_unique(code).map_err(ton_error)?.1,
+        None => 0,
+    };
+    let data_cells = match &state.data {
+        Some(data) => crate::storage::count_unique(data).map_err(ton_error)?.1,
+        None => 0,
+    };
+
+    let mut violations = vec![];
+    if cells > limits.max_cells {
+        violations.push(format!(
+            "State has {} cells, the limit is {} (code {} cells, data {} cells)",
+            cells, limits.max_cells, code_cells, data_cells
+        ));
+    }
+    if depth > limits.max_depth {
+        let depth_of = |cell: &Option<Cell>| cell.as_ref().map_or(0, |c| c.repr_depth());
+        violations.push(format!(
+            "State has depth {}, the limit is {} (code depth {}, data depth {})",
+            depth, limits.max_depth, depth_of(&state.code), depth_of(&state.data)
+        ));
+    }
+    if code_size as u64 > limits.max_code_size {
+        violations.push(format!(
+            "Code takes {} bytes, the limit is {}", code_size, limits.max_code_size
+        ));
+    }
+    if violations.is_empty() {
+        return Ok(())
+    }
+
+    let mut message = violations.join("\n");
+    if let Some(code) = &state.code {
+        let contributors = code_contributors(code, assembly, debug_map).map_err(ton_error)?;
+        if !contributors.is_empty() {
+            message.push_str("\nBiggest contributors to the code:");
+            for (name, cells) in contributors.iter().take(SHOWN_CONTRIBUTORS) {
+                message.push_str(&format!("\n    {} cells  {}", cells, name));
+            }
+        }
+    }
+    Err(SoldError::LimitExceeded(message))
+}
+
+/// Attributes every code cell to the function most of its debug locations belong to.
+fn code_contributors(code: &Cell, assembly: &str, debug_map: &Value) -> ton_types::Result<Vec<(String, u64)>> {
+    let mut owners: HashMap<Loc, &str> = HashMap::new();
+    let parsed = Assembly::parse(assembly);
+    for function in &parsed.functions {
+        for line in &function.body {
+            if let Some(loc) = line.trim_start().strip_prefix(".loc ").and_then(asm::parse_loc) {
+                owners.entry(loc).or_insert(&function.name);
+            }
+        }
+    }
+
+    let mut sizes: HashMap<&str, u64> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![code.clone()];
+    while let Some(cell) = stack.pop() {
+        if !visited.insert(cell.repr_hash()) {
+            continue
+        }
+        let mut votes: HashMap<&str, usize> = HashMap::new();
+        if let Some(positions) = debug_map[cell.repr_hash().to_hex_string()].as_object() {
+            for position in positions.values() {
+                let loc = Loc {
+                    file: position["filename"].as_str().unwrap_or_default().to_owned(),
+                    line: position["line"].as_u64().unwrap_or_default() as usize,
+                };
+                if let Some(owner) = owners.get(&loc) {
+                    *votes.entry(*owner).or_default() += 1;
+                }
+            }
+        }
+        let owner = votes.into_iter()
+            .max_by_key(|(name, count)| (*count, std::cmp::Reverse(*name)))
+            .map_or("<no debug info>", |(name, _)| name);
+        *sizes.entry(owner).or_default() += 1;
+        for i in 0..cell.references_count() {
+            stack.push(cell.reference(i)?);
+        }
+    }
+
+    let mut res: Vec<(String, u64)> = sizes.into_iter()
+        .map(|(name, cells)| (name.to_owned(), cells))
+        .collect();
+    res.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(res)
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+use crate::error::{Result, SoldError};
+
+const DEFAULT_FILTER: &str = "warn";
+
+/// Keeps the profile open; it is completed when the guard is dropped.
+pub struct Guard {
+    _profile: Option<FlushGuard>,
+}
+
+// Log records go to stderr, so they never mix with the output of e.g. --function-ids.
+// An explicit --log-level wins over RUST_LOG. The profile is filtered separately, so that
+// it records the phases of the build whatever the log level is.
+pub fn init(level: Option<&str>, json: bool, profile: Option<&str>) -> Result<Guard> {
+    let filter = match level {
+        Some(level) => EnvFilter::try_new(level)
+            .map_err(|e| SoldError::ArgumentError(format!("Invalid log level \"{}\": {}", level, e)))?,
+        None => EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER)),
+    };
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+    let fmt_layer = if json {
+        fmt_layer.json().with_filter(filter).boxed()
+    } else {
+        fmt_layer.with_filter(filter).boxed()
+    };
+    let (profile_layer, profile_guard) = match profile {
+        Some(path) => {
+            let (layer, guard) = ChromeLayerBuilder::new()
+                .file(path)
+                .include_args(true)
+                .build();
+            (Some(layer.with_filter(LevelFilter::DEBUG)), Some(guard))
+        }
+        None => (None, None),
+    };
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(profile_layer)
+        .try_init()
+        .map_err(|e| SoldError::ArgumentError(format!("Failed to initialize logging: {}", e)))?;
+    Ok(Guard { _profile: profile_guard })
+}
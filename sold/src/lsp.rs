@@ -0,0 +1,213 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Language server over stdio. It serves renames with the engine of `sold rename`:
+// `textDocument/prepareRename` tells whether the name under the cursor can be renamed,
+// `textDocument/rename` returns the edits for the whole workspace. Documents are read
+// from disk, so unsaved changes in the editor are not taken into account.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::ast::Project;
+use crate::error::{Result, SoldError, Status};
+use crate::rename::{self, Target};
+use crate::symbols::{LineIndex, Position};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct LspArgs {
+    /// Include additional path to search for imports
+    #[clap(short('I'), long, value_parser)]
+    pub include_path: Vec<String>,
+}
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32803;
+
+struct Server {
+    root: PathBuf,
+    include_paths: Vec<String>,
+}
+
+fn error(code: i64, message: impl ToString) -> Value {
+    json!({ "code": code, "message": message.to_string() })
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let bytes = path.as_bytes();
+    let mut decoded = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            decoded.push(u8::from_str_radix(std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+fn path_to_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+impl Server {
+    fn handle(&mut self, method: &str, params: &Value) -> std::result::Result<Value, Value> {
+        match method {
+            "initialize" => {
+                let root = params["rootUri"].as_str().and_then(uri_to_path)
+                    .or_else(|| params["rootPath"].as_str().map(PathBuf::from));
+                if let Some(root) = root {
+                    self.root = root;
+                }
+                Ok(json!({
+                    "capabilities": {
+                        "renameProvider": { "prepareProvider": true },
+                    },
+                    "serverInfo": { "name": "sold", "version": env!("CARGO_PKG_VERSION") },
+                }))
+            }
+            "shutdown" => Ok(Value::Null),
+            "textDocument/prepareRename" => {
+                let (project, path, offset) = self.locate(params)?;
+                let target = Target::at(&project, &path, offset).map_err(|e| error(REQUEST_FAILED, e))?;
+                Ok(match target {
+                    Some((target, range)) => {
+                        let text = std::fs::read_to_string(&path).map_err(|e| error(REQUEST_FAILED, e))?;
+                        json!({ "range": LineIndex::new(&text).range(range), "placeholder": target.name })
+                    }
+                    None => Value::Null,
+                })
+            }
+            "textDocument/rename" => {
+                let new_name = params["newName"].as_str()
+                    .ok_or_else(|| error(INVALID_PARAMS, "newName is missing"))?;
+                let (project, path, offset) = self.locate(params)?;
+                let (target, _) = Target::at(&project, &path, offset)
+                    .map_err(|e| error(REQUEST_FAILED, e))?
+                    .ok_or_else(|| error(REQUEST_FAILED, "There is no contract, function or variable to rename here"))?;
+                let edits = rename::plan(&project, &target, new_name).map_err(|e| error(REQUEST_FAILED, e))?;
+                workspace_edit(&edits, new_name).map_err(|e| error(REQUEST_FAILED, e))
+            }
+            _ => Err(error(METHOD_NOT_FOUND, format!("Unsupported method {}", method))),
+        }
+    }
+
+    /// Loads the workspace with the document of the request and finds the offset of the position.
+    fn locate(&self, params: &Value) -> std::result::Result<(Project, String, usize), Value> {
+        let path = params["textDocument"]["uri"].as_str()
+            .and_then(uri_to_path)
+            .and_then(|path| std::fs::canonicalize(path).ok())
+            .ok_or_else(|| error(INVALID_PARAMS, "Unknown document"))?;
+        let position: Position = serde_json::from_value(params["position"].clone())
+            .map_err(|e| error(INVALID_PARAMS, e))?;
+        let project = self.load(&path).map_err(|e| error(REQUEST_FAILED, e))?;
+        let text = std::fs::read_to_string(&path).map_err(|e| error(REQUEST_FAILED, e))?;
+        let offset = LineIndex::new(&text).offset(position);
+        Ok((project, path.to_string_lossy().into_owned(), offset))
+    }
+
+    fn load(&self, document: &Path) -> Result<Project> {
+        let inputs = [self.root.to_string_lossy().into_owned(), document.to_string_lossy().into_owned()];
+        let files = crate::sources::collect(&inputs)?;
+        Project::load(&files, &self.include_paths)
+    }
+}
+
+fn workspace_edit(edits: &rename::Edits, new_name: &str) -> Result<Value> {
+    let mut changes = BTreeMap::new();
+    for (path, ranges) in edits {
+        let text = std::fs::read_to_string(path)?;
+        let lines = LineIndex::new(&text);
+        let text_edits: Vec<Value> = ranges.iter()
+            .map(|(start, end)| json!({ "range": lines.range(*start..*end), "newText": new_name }))
+            .collect();
+        changes.insert(path_to_uri(path), text_edits);
+    }
+    Ok(json!({ "changes": changes }))
+}
+
+/// Reads one message, None at the end of input
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None)
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| SoldError::ArgumentError("Message without Content-Length".to_owned()))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Status {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()?;
+    Ok(())
+}
+
+pub fn serve(args: LspArgs) -> Status {
+    crate::source_cache::enable();
+    let mut server = Server {
+        root: std::env::current_dir()?,
+        include_paths: args.include_path,
+    };
+    let stdin = std::io::stdin();
+    let mut input = stdin.lock();
+    let stdout = std::io::stdout();
+    let mut output = stdout.lock();
+    while let Some(message) = read_message(&mut input)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        if method == "exit" {
+            break
+        }
+        // Notifications and responses to our requests need no answer
+        let id = match message.get("id") {
+            Some(id) if !method.is_empty() => id.clone(),
+            _ => continue,
+        };
+        let response = match server.handle(method, &message["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        write_message(&mut output, &response)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The manifest lists the artifacts of a build with their SHA-256 and size, so that they
+// can be verified after being moved between machines. It also keeps what the address
+// of the contract is computed from: the address is the hash of the StateInit cell, which
+// references the code and the data cells.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use ton_block::{Serializable, StateInit};
+use ton_types::Cell;
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(Serialize)]
+struct Artifact {
+    /// Relative to the output directory if the file is there
+    path: PathBuf,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CellInfo {
+    hash: String,
+    depth: u16,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Address {
+    code: Option<CellInfo>,
+    data: Option<CellInfo>,
+    /// Account id of the contract in any workchain
+    state_init_hash: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest<'a> {
+    contract: &'a str,
+    compiler: serde_json::Value,
+    artifacts: Vec<Artifact>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_hash: Option<String>,
+    address: Address,
+}
+
+fn cell_info(cell: &Option<Cell>) -> Option<CellInfo> {
+    cell.as_ref().map(|cell| CellInfo {
+        hash: cell.repr_hash().to_hex_string(),
+        depth: cell.repr_depth(),
+    })
+}
+
+fn describe(path: &Path, output_dir: &Path) -> Result<Artifact> {
+    let content = std::fs::read(path)?;
+    Ok(Artifact {
+        path: path.strip_prefix(output_dir).unwrap_or(path).to_path_buf(),
+        size: content.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(&content)),
+    })
+}
+
+pub fn write(
+    path: &Path,
+    contract: &str,
+    artifacts: &[PathBuf],
+    output_dir: &Path,
+    state: &StateInit,
+) -> Status {
+    let state_init_hash = state.serialize()
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))?
+        .repr_hash()
+        .to_hex_string();
+    let manifest = Manifest {
+        contract,
+        compiler: serde_json::json!({
+            "name": "sold",
+            "version": crate::solidity_version(),
+            "driver": env!("CARGO_PKG_VERSION"),
+        }),
+        artifacts: artifacts.iter()
+            .map(|artifact| describe(artifact, output_dir))
+            .collect::<Result<Vec<_>>>()?,
+        code_hash: state.code.as_ref().map(|code| code.repr_hash().to_hex_string()),
+        address: Address {
+            code: cell_info(&state.code),
+            data: cell_info(&state.data),
+            state_init_hash,
+        },
+    };
+    let mut file = File::create(path)?;
+    serde_json::to_writer_pretty(&mut file, &manifest)?;
+    writeln!(file)?;
+    Ok(())
+}
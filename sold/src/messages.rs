@@ -0,0 +1,140 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Everything a build tells its user goes through the message handler: diagnostics, notes
+// and reports. The command line tool keeps the default handler, which prints to stdout and
+// stderr; embedders install their own with `set_handler`, or give one to a Compiler for its
+// builds only, to get the messages programmatically with nothing written to the process
+// streams. The linker run of --ctor-params prints to stdout itself, so its output is
+// captured and passed on as info messages.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use lazy_static::lazy_static;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// Progress and output of the tools the build runs
+    Info,
+    Warning,
+    Error,
+}
+
+pub trait MessageHandler: Send + Sync {
+    /// Result the build was asked for, e.g. by --function-ids; printed to stdout
+    fn output(&self, text: &str);
+    /// Diagnostic, note or progress message; printed to stderr
+    fn message(&self, level: Level, text: &str);
+}
+
+/// The handler of the command line tool
+struct Console;
+
+impl MessageHandler for Console {
+    fn output(&self, text: &str) {
+        println!("{}", text);
+    }
+
+    fn message(&self, _level: Level, text: &str) {
+        eprintln!("{}", text);
+    }
+}
+
+lazy_static! {
+    static ref HANDLER: RwLock<Option<Arc<dyn MessageHandler>>> = RwLock::new(None);
+    /// Held while stdout is redirected
+    static ref CAPTURE: Mutex<()> = Mutex::new(());
+}
+
+/// Sends the messages of the builds from now on to `handler` instead of the process streams,
+/// except for those of compilers with a handler of their own.
+pub fn set_handler(handler: Arc<dyn MessageHandler>) {
+    *HANDLER.write().unwrap_or_else(|e| e.into_inner()) = Some(handler);
+}
+
+fn installed() -> Option<Arc<dyn MessageHandler>> {
+    crate::compiler::current().state().handler.clone()
+        .or_else(|| HANDLER.read().unwrap_or_else(|e| e.into_inner()).clone())
+}
+
+fn handler() -> Arc<dyn MessageHandler> {
+    installed().unwrap_or_else(|| Arc::new(Console))
+}
+
+/// Tells whether messages go to the process streams, so that they may be colored
+pub fn to_console() -> bool {
+    installed().is_none()
+}
+
+pub fn output(text: &str) {
+    handler().output(text);
+}
+
+pub fn message(level: Level, text: &str) {
+    handler().message(level, text);
+}
+
+pub fn info(text: &str) {
+    message(Level::Info, text);
+}
+
+pub fn warning(text: &str) {
+    message(Level::Warning, text);
+}
+
+/// Runs `f`, which prints to stdout with no way to tell it otherwise, and passes what it
+/// printed to the handler as info messages. Stdout is shared by the whole process, so
+/// captures wait for each other, and what other threads print meanwhile is captured too.
+/// Elsewhere than on unix the output is let through as is.
+#[cfg(unix)]
+pub fn capture_stdout<T>(f: impl FnOnce() -> T) -> T {
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use std::os::unix::io::AsRawFd;
+
+    let path = std::env::temp_dir().join(format!("sold-stdout-{}-{:?}", std::process::id(), std::thread::current().id()));
+    let mut file = match std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path) {
+        Ok(file) => file,
+        Err(_) => return f(),
+    };
+    let _ = std::fs::remove_file(&path);
+    let _capturing = CAPTURE.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = std::io::stdout().flush();
+    // Safety: plain descriptor operations, the saved descriptor is restored and closed below
+    let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+    if saved < 0 {
+        return f()
+    }
+    if unsafe { libc::dup2(file.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+        unsafe { libc::close(saved) };
+        return f()
+    }
+    let res = f();
+    let _ = std::io::stdout().flush();
+    unsafe {
+        libc::dup2(saved, libc::STDOUT_FILENO);
+        libc::close(saved);
+    }
+
+    let mut captured = String::new();
+    if file.seek(SeekFrom::Start(0)).is_ok() && file.read_to_string(&mut captured).is_ok() {
+        for line in captured.lines().filter(|line| !line.trim().is_empty()) {
+            info(line);
+        }
+    }
+    res
+}
+
+#[cfg(not(unix))]
+pub fn capture_stdout<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The metadata written by --metadata has everything a verification service needs to
+// rebuild a contract and compare the code hash: the versions of the frontend and of sold,
+// the options and the standard JSON settings of the build, the defines, the SHA-256 of
+// every source the frontend read and of the library the code was linked with. Sources are
+// hashed as read from disk, before the defines are applied.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::Status;
+
+#[derive(Serialize)]
+struct Compiler {
+    frontend: String,
+    sold: &'static str,
+}
+
+#[derive(Serialize)]
+struct File {
+    size: usize,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata<'a> {
+    compiler: Compiler,
+    language: &'static str,
+    contract: &'a str,
+    /// Options of the command line, as --from-config takes them
+    options: &'a Value,
+    /// Settings of the standard JSON input passed to the frontend
+    settings: &'a Value,
+    defines: BTreeMap<String, String>,
+    remappings: Value,
+    sources: BTreeMap<&'a str, File>,
+    library: BTreeMap<&'a str, File>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_hash: Option<&'a str>,
+}
+
+fn describe(content: &[u8]) -> File {
+    File { size: content.len(), sha256: format!("{:x}", Sha256::digest(content)) }
+}
+
+/// Inputs of the build `write` describes
+pub struct Build<'a> {
+    pub contract: &'a str,
+    pub options: &'a Value,
+    /// Standard JSON input of the frontend
+    pub input: &'a Value,
+    /// Paths of the sources the frontend read
+    pub sources: Vec<&'a str>,
+    pub library_name: &'a str,
+    pub library: &'a [u8],
+    pub code_hash: Option<&'a str>,
+}
+
+/// Writes the metadata of `build` to `out`.
+pub fn write(out: &mut impl Write, build: Build) -> Status {
+    let mut sources = BTreeMap::new();
+    for path in build.sources {
+        sources.insert(path, describe(&crate::source_cache::read(Path::new(path))?));
+    }
+    let settings = &build.input["settings"];
+    let metadata = Metadata {
+        compiler: Compiler { frontend: crate::solidity_version(), sold: env!("CARGO_PKG_VERSION") },
+        language: "Solidity",
+        contract: build.contract,
+        options: build.options,
+        settings,
+        defines: crate::defines::current(),
+        remappings: settings.get("remappings").cloned().unwrap_or_else(|| Value::Array(vec![])),
+        sources,
+        library: BTreeMap::from([(build.library_name, describe(build.library))]),
+        code_hash: build.code_hash,
+    };
+    serde_json::to_writer_pretty(&mut *out, &metadata)?;
+    writeln!(out)?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Migration of sources written for older compiler versions. Each rule recognizes a
+// construct that was deprecated or removed (see Changelog_TON.md) and, when the new form
+// is a mechanical rewrite, knows the replacement. Rules only fire in code, never inside
+// comments or string literals.
+
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::error::{Result, Status};
+use crate::lexer;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MigrateArgs {
+    /// Source files or directories, imported files are migrated as well
+    #[clap(value_parser, required = true)]
+    pub inputs: Vec<String>,
+    /// Rewrite the sources instead of only reporting what needs to change
+    #[clap(long, value_parser)]
+    pub apply: bool,
+}
+
+struct Rule {
+    name: &'static str,
+    /// Compiler version that changed the construct
+    since: &'static str,
+    pattern: Regex,
+    /// Expanded with the captures of `pattern`, None if the rewrite needs a human
+    replacement: Option<&'static str>,
+    message: &'static str,
+}
+
+impl Rule {
+    fn new(name: &'static str, since: &'static str, pattern: &str, replacement: Option<&'static str>, message: &'static str) -> Rule {
+        Rule { name, since, pattern: Regex::new(pattern).unwrap(), replacement, message }
+    }
+}
+
+lazy_static! {
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new("pragma", "0.59.0",
+            r"\bpragma\s+(?:ton-)?solidity\b", Some("pragma ever-solidity"),
+            "`pragma solidity` and `pragma ton-solidity` are replaced by `pragma ever-solidity`"),
+        Rule::new("build-empty-data", "0.51.0",
+            r"\btvm\s*\.\s*buildEmptyData\s*\(\s*([^()]*?)\s*\)", Some("tvm.buildDataInit({pubkey: $1})"),
+            "`tvm.buildEmptyData(pubkey)` is deleted, use `tvm.buildDataInit({pubkey: ...})`"),
+        Rule::new("bits-and-refs", "0.50.0",
+            r"\.\s*bitsAndRefs\s*\(\s*\)", Some(".size()"),
+            "`bitsAndRefs()` is replaced by `size()`"),
+        Rule::new("deploy", "0.50.0",
+            r"\btvm\s*\.\s*deploy\s*\(", None,
+            "`tvm.deploy()` is deleted, use `<address>.transfer({stateInit: ..., ...})`"),
+        Rule::new("answer-id", "0.48.0",
+            r"\b_answer_id\b", Some("answerId"),
+            "`_answer_id` of responsible functions is renamed to `answerId`"),
+        Rule::new("ext-addr", "0.46.0",
+            r"\.\s*extAddr\s*\(", None,
+            "`.extAddr(...)` in emit statements is replaced by `{dest: ...}`"),
+        Rule::new("abi-ver", "0.54.0",
+            r"\babiVer\s*:", None,
+            "the `abiVer` option of `tvm.buildExtMsg()` is deleted"),
+        Rule::new("hexstring", "0.37.0",
+            r"\bhexstring\s*\(", None,
+            "`hexstring()` is deleted, use `format()`"),
+        Rule::new("call-modifiers", "0.35.0",
+            r"\.\s*(?:value|flag)\s*\([^()]*\)\s*(?:\.\s*(?:value|flag)\s*\([^()]*\)\s*)*\(", None,
+            "`.value(...)` and `.flag(...)` call modifiers are replaced by `{value: ..., flag: ...}` options"),
+    ];
+}
+
+struct Finding {
+    rule: &'static Rule,
+    start: usize,
+    end: usize,
+    replacement: Option<String>,
+}
+
+/// Finds all deprecated constructs in the code parts of `text`.
+fn scan(text: &str) -> Vec<Finding> {
+    let code = lexer::CodeMap::new(text);
+    let mut findings: Vec<Finding> = vec![];
+    for rule in RULES.iter() {
+        for captures in rule.pattern.captures_iter(text) {
+            let m = captures.get(0).unwrap();
+            if !code.is_code(m.start()) {
+                continue
+            }
+            let replacement = rule.replacement.map(|template| {
+                let mut res = String::new();
+                captures.expand(template, &mut res);
+                res
+            });
+            findings.push(Finding { rule, start: m.start(), end: m.end(), replacement });
+        }
+    }
+    findings.sort_by_key(|f| f.start);
+    findings
+}
+
+/// Applies the rewritable findings, skipping those that overlap an earlier one.
+fn rewrite(text: &str, findings: &[Finding]) -> (String, usize) {
+    let mut res = String::with_capacity(text.len());
+    let mut pos = 0;
+    let mut applied = 0;
+    for finding in findings {
+        if let Some(replacement) = &finding.replacement {
+            if finding.start >= pos {
+                res.push_str(&text[pos..finding.start]);
+                res.push_str(replacement);
+                pos = finding.end;
+                applied += 1;
+            }
+        }
+    }
+    res.push_str(&text[pos..]);
+    (res, applied)
+}
+
+fn migrate_file(path: &Path, apply: bool) -> Result<(usize, usize)> {
+    let text = std::fs::read_to_string(path)?;
+    let findings = scan(&text);
+    for finding in &findings {
+        let (line, column) = lexer::line_column(&text, finding.start);
+        let action = match (&finding.replacement, apply) {
+            (Some(_), true) => "fixed",
+            (Some(_), false) => "fixable",
+            (None, _) => "manual",
+        };
+        println!("{}:{}:{}: [{}, since {}, {}] {}",
+            path.display(), line, column, finding.rule.name, finding.rule.since, action, finding.rule.message);
+    }
+    let mut applied = 0;
+    if apply {
+        let (migrated, count) = rewrite(&text, &findings);
+        if count > 0 {
+            std::fs::write(path, migrated)?;
+        }
+        applied = count;
+    }
+    Ok((findings.len(), applied))
+}
+
+pub fn migrate(args: MigrateArgs) -> Status {
+    let files = crate::sources::collect(&args.inputs)?;
+    let mut found = 0;
+    let mut applied = 0;
+    for file in &files {
+        let (file_found, file_applied) = migrate_file(file, args.apply)?;
+        found += file_found;
+        applied += file_applied;
+    }
+    if args.apply {
+        println!("{} of {} deprecated constructs rewritten in {} files", applied, found, files.len());
+    } else {
+        println!("{} deprecated constructs found in {} files, run with --apply to rewrite the fixable ones",
+            found, files.len());
+    }
+    Ok(())
+}
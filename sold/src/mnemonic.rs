@@ -0,0 +1,145 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Deploy keypairs derived from seed phrases, as wallets and tonos-cli derive them, so a
+// contract can be built for a key nobody has to store in a file. A BIP39 phrase gives a
+// seed that is derived along a BIP32 path, m/44'/396'/0'/0/0 by default as in tonos-cli,
+// and the secret key at its end is the seed of the Ed25519 keypair. A phrase of the TON
+// dictionary is stretched into the keypair seed directly and takes no path.
+
+use std::path::Path;
+
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+
+use crate::error::{Result, SoldError};
+
+/// Derivation path of tonos-cli and of the wallets of the SDK
+pub const DEFAULT_PATH: &str = "m/44'/396'/0'/0/0";
+
+/// Iterations of PBKDF2 stretching a phrase of the TON dictionary
+const TON_ITERATIONS: u32 = 100_000;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    /// BIP39 phrase of the English word list, derived along a BIP32 path
+    Bip39,
+    /// 24-word phrase of the TON dictionary
+    Ton,
+}
+
+fn key_error(e: impl std::fmt::Display) -> SoldError {
+    SoldError::KeyError(e.to_string())
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC takes keys of any size");
+    for part in data {
+        mac.update(part);
+    }
+    let mut res = [0u8; 64];
+    res.copy_from_slice(&mac.finalize().into_bytes());
+    res
+}
+
+/// Indexes of `path`, with the hardened bit set for those marked with '
+fn parse_path(path: &str) -> Result<Vec<u32>> {
+    let invalid = || SoldError::KeyError(format!("Invalid derivation path \"{}\", expected e.g. {}", path, DEFAULT_PATH));
+    let mut parts = path.split('/');
+    if parts.next() != Some("m") {
+        return Err(invalid())
+    }
+    parts
+        .map(|part| {
+            let (index, hardened) = match part.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (part, false),
+            };
+            let index: u32 = index.parse().ok().filter(|index| index & 0x8000_0000 == 0).ok_or_else(invalid)?;
+            Ok(if hardened { index | 0x8000_0000 } else { index })
+        })
+        .collect()
+}
+
+/// Secret key at `path` of the BIP32 tree of `seed`
+fn derive(seed: &[u8], path: &[u32]) -> Result<[u8; 32]> {
+    let master = hmac_sha512(b"Bitcoin seed", &[seed]);
+    let mut key = libsecp256k1::SecretKey::parse_slice(&master[..32]).map_err(key_error)?;
+    let mut chain_code = master[32..].to_vec();
+    for index in path {
+        let child = if index & 0x8000_0000 != 0 {
+            hmac_sha512(&chain_code, &[&[0], &key.serialize(), &index.to_be_bytes()])
+        } else {
+            let public = libsecp256k1::PublicKey::from_secret_key(&key);
+            hmac_sha512(&chain_code, &[&public.serialize_compressed(), &index.to_be_bytes()])
+        };
+        let tweak = libsecp256k1::SecretKey::parse_slice(&child[..32]).map_err(key_error)?;
+        key.tweak_add_assign(&tweak).map_err(key_error)?;
+        chain_code = child[32..].to_vec();
+    }
+    Ok(key.serialize())
+}
+
+/// Keypair seed of a phrase of the TON dictionary
+fn ton_seed(words: &[&str]) -> Result<[u8; 32]> {
+    if words.len() != 24 {
+        return Err(SoldError::KeyError(format!("A TON seed phrase has 24 words, this one has {}", words.len())))
+    }
+    let entropy = hmac_sha512(words.join(" ").as_bytes(), &[b""]);
+    // Phrases without a password are those whose basic seed starts with a zero byte
+    let mut basic = [0u8; 64];
+    pbkdf2::pbkdf2::<Hmac<Sha512>>(&entropy, b"TON seed version", TON_ITERATIONS / 256, &mut basic);
+    if basic[0] != 0 {
+        return Err(SoldError::KeyError("Invalid TON seed phrase".to_owned()))
+    }
+    let mut seed = [0u8; 64];
+    pbkdf2::pbkdf2::<Hmac<Sha512>>(&entropy, b"TON default seed", TON_ITERATIONS, &mut seed);
+    let mut res = [0u8; 32];
+    res.copy_from_slice(&seed[..32]);
+    Ok(res)
+}
+
+/// Keypair of the seed phrase `phrase`
+pub fn keypair(phrase: &str, kind: Kind, path: Option<&str>) -> Result<Keypair> {
+    let phrase = phrase.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>().join(" ");
+    let words: Vec<&str> = phrase.split(' ').collect();
+    let seed = match kind {
+        Kind::Bip39 => {
+            let mnemonic = bip39::Mnemonic::from_phrase(&phrase, bip39::Language::English)
+                .map_err(|e| SoldError::KeyError(format!("Invalid BIP39 seed phrase: {}", e)))?;
+            derive(bip39::Seed::new(&mnemonic, "").as_bytes(), &parse_path(path.unwrap_or(DEFAULT_PATH))?)?
+        }
+        Kind::Ton if path.is_some() => {
+            return Err(SoldError::KeyError("A TON seed phrase takes no derivation path".to_owned()))
+        }
+        Kind::Ton => ton_seed(&words)?,
+    };
+    let secret = SecretKey::from_bytes(&seed).map_err(key_error)?;
+    let public = PublicKey::from(&secret);
+    Ok(Keypair { secret, public })
+}
+
+/// Keypair of the phrase given with --mnemonic, or read from the file of --mnemonic-file,
+/// if any
+pub fn from_options(phrase: Option<&str>, file: Option<&str>, kind: Option<Kind>, path: Option<&str>) -> Result<Option<Keypair>> {
+    let phrase = match (phrase, file) {
+        (Some(phrase), _) => phrase.to_owned(),
+        (None, Some(file)) => std::fs::read_to_string(Path::new(file))
+            .map_err(|_| SoldError::KeyError(format!("Failed to read seed phrase from \"{}\"", file)))?,
+        (None, None) => return Ok(None),
+    };
+    keypair(&phrase, kind.unwrap_or(Kind::Bip39), path).map(Some)
+}
@@ -0,0 +1,230 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Mutation testing: small changes are made to the contract one at a time, found in its AST,
+// and for each the contract is rebuilt and the tests are run. A mutant the tests still pass
+// with points at behavior they don't check. The mutations are comparison flips, off-by-one
+// changes of the numbers compared with, and require conditions replaced with `true`.
+// The source is rewritten in place and restored after each mutant.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use clap::Parser;
+use serde_json::Value;
+
+use crate::ast::{self, Src};
+use crate::error::{Result, SoldError, Status};
+use crate::lexer;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MutateArgs {
+    /// Source file name
+    #[clap(value_parser)]
+    pub input: String,
+    /// Shell command running the tests, which fails when a test fails
+    #[clap(long, value_parser)]
+    pub test: String,
+    /// Include additional path to search for imports
+    #[clap(short('I'), long, value_parser)]
+    pub include_path: Vec<String>,
+    /// Test at most this many mutants
+    #[clap(long, value_parser)]
+    pub max_mutants: Option<usize>,
+    /// Build options, given after --
+    #[clap(value_parser, last = true)]
+    pub build_args: Vec<String>,
+}
+
+struct Mutant {
+    start: usize,
+    end: usize,
+    replacement: String,
+    description: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Killed,
+    Survived,
+    /// The mutant doesn't build, it isn't counted
+    Invalid,
+}
+
+fn flipped(operator: &str) -> Option<&'static str> {
+    Some(match operator {
+        "<" => ">=",
+        "<=" => ">",
+        ">" => "<=",
+        ">=" => "<",
+        "==" => "!=",
+        "!=" => "==",
+        _ => return None,
+    })
+}
+
+/// Mutants of the binary operation `node`: its operator flipped and its number operands
+/// moved by one
+fn comparison_mutants(text: &str, node: &Value, mutants: &mut Vec<Mutant>) {
+    let operator = match node["operator"].as_str().and_then(|op| flipped(op).map(|flip| (op, flip))) {
+        Some(operator) => operator,
+        None => return,
+    };
+    let (left, right) = match (Src::of(&node["leftExpression"]), Src::of(&node["rightExpression"])) {
+        (Some(left), Some(right)) => (left, right),
+        _ => return,
+    };
+    let gap = match text.get(left.end..right.start) {
+        Some(gap) => gap,
+        None => return,
+    };
+    if let Some(token) = lexer::tokenize(gap).into_iter().find(|t| t.text == operator.0) {
+        mutants.push(Mutant {
+            start: left.end + token.start,
+            end: left.end + token.end(),
+            replacement: operator.1.to_owned(),
+            description: format!("{} replaced with {}", operator.0, operator.1),
+        });
+    }
+    for operand in [&node["leftExpression"], &node["rightExpression"]] {
+        if operand["nodeType"] != "Literal" || operand["kind"] != "number" {
+            continue
+        }
+        let value = match operand["value"].as_str().and_then(|value| value.replace('_', "").parse::<u128>().ok()) {
+            Some(value) => value,
+            None => continue,
+        };
+        let src = match Src::of(operand) {
+            Some(src) => src,
+            None => continue,
+        };
+        mutants.push(Mutant {
+            start: src.start,
+            end: src.end,
+            replacement: (value + 1).to_string(),
+            description: format!("{} replaced with {}", value, value + 1),
+        });
+        if value > 0 {
+            mutants.push(Mutant {
+                start: src.start,
+                end: src.end,
+                replacement: (value - 1).to_string(),
+                description: format!("{} replaced with {}", value, value - 1),
+            });
+        }
+    }
+}
+
+/// Mutants of the source unit `unit`, in source order
+fn mutants(text: &str, unit: &Value) -> Vec<Mutant> {
+    let mut res = vec![];
+    ast::walk(unit, &mut |node| {
+        match node["nodeType"].as_str() {
+            Some("BinaryOperation") => comparison_mutants(text, node, &mut res),
+            Some("FunctionCall") if node["expression"]["name"] == "require"
+                && node["expression"]["referencedDeclaration"].as_i64().map_or(false, |id| id < 0) => {
+                if let Some(condition) = node["arguments"].get(0).and_then(Src::of) {
+                    if text.get(condition.start..condition.end) != Some("true") {
+                        res.push(Mutant {
+                            start: condition.start,
+                            end: condition.end,
+                            replacement: "true".to_owned(),
+                            description: "require condition replaced with true".to_owned(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    });
+    res.sort_by_key(|mutant| (mutant.start, mutant.end));
+    res
+}
+
+/// Puts the original source back when dropped, whatever happened to the mutant
+struct Restore<'a> {
+    path: &'a Path,
+    text: &'a str,
+}
+
+impl Drop for Restore<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::write(self.path, self.text) {
+            eprintln!("Failed to restore {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+fn run_tests(command: &str) -> Result<bool> {
+    let status = if cfg!(windows) {
+        Command::new("cmd").arg("/C").arg(command).output()?.status
+    } else {
+        Command::new("sh").arg("-c").arg(command).output()?.status
+    };
+    Ok(status.success())
+}
+
+fn test_mutant(args: &MutateArgs, path: &Path, text: &str, mutant: &Mutant) -> Result<Outcome> {
+    let _restore = Restore { path, text };
+    let mutated = format!("{}{}{}", &text[..mutant.start], mutant.replacement, &text[mutant.end..]);
+    std::fs::write(path, mutated)?;
+    let mut argv = vec!["sold".to_owned(), args.input.clone()];
+    argv.extend(args.include_path.iter().flat_map(|dir| ["--include-path".to_owned(), dir.clone()]));
+    argv.extend(args.build_args.iter().cloned());
+    let build_args = crate::Args::try_parse_from(argv)
+        .map_err(|e| SoldError::ArgumentError(e.to_string()))?;
+    if crate::build(build_args).is_err() {
+        return Ok(Outcome::Invalid)
+    }
+    Ok(if run_tests(&args.test)? { Outcome::Survived } else { Outcome::Killed })
+}
+
+pub fn mutate(args: MutateArgs) -> Status {
+    let path = PathBuf::from(&args.input).canonicalize()
+        .map_err(|_| SoldError::SourceNotFound(args.input.clone()))?;
+    let text = std::fs::read_to_string(&path)?;
+    if !run_tests(&args.test)? {
+        return Err(SoldError::ArgumentError("The tests fail on the original source".to_owned()))
+    }
+    let project = ast::Project::load(&[path.clone()], &args.include_path)?;
+    let unit = project.units.get(path.to_string_lossy().as_ref())
+        .ok_or_else(|| SoldError::InvalidOutput("No AST for the input".to_owned()))?;
+    let mut mutants = mutants(&text, unit);
+    if let Some(max) = args.max_mutants {
+        mutants.truncate(max);
+    }
+
+    let mut outcomes = vec![];
+    for mutant in &mutants {
+        let outcome = test_mutant(&args, &path, &text, mutant)?;
+        let (line, column) = lexer::line_column(&text, mutant.start);
+        let status = match outcome {
+            Outcome::Killed => "killed",
+            Outcome::Survived => "SURVIVED",
+            Outcome::Invalid => "invalid",
+        };
+        println!("{}:{}:{}: {}: {}", args.input, line, column, mutant.description, status);
+        outcomes.push(outcome);
+    }
+    let killed = outcomes.iter().filter(|outcome| **outcome == Outcome::Killed).count();
+    let survived = outcomes.iter().filter(|outcome| **outcome == Outcome::Survived).count();
+    if killed + survived == 0 {
+        println!("No mutants to test");
+    } else {
+        println!(
+            "{} of {} mutants killed ({:.1}%)",
+            killed, killed + survived, 100.0 * killed as f64 / (killed + survived) as f64,
+        );
+    }
+    Ok(())
+}
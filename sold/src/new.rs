@@ -0,0 +1,83 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Contract sources implementing a standard, kept here so they track the language. The
+// templates in templates/new/ declare the interfaces of the standard and compute the
+// TIP-6 interface IDs with tvm.functionId, and the function IDs the standards fix are set
+// with functionID().
+
+use std::path::Path;
+
+use crate::error::{SoldError, Status};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ContractTemplate {
+    /// TIP-3.1 token root with its wallet
+    #[clap(name = "tip3-root")]
+    Tip3Root,
+    /// TIP-4.1 collection with its token
+    #[clap(name = "tip4-nft")]
+    Tip4Nft,
+    /// Wallet owned by a key pair, with the sendTransaction of the multisig wallets
+    Wallet,
+    /// Contract with just a constructor
+    Empty,
+}
+
+impl ContractTemplate {
+    fn source(self) -> &'static str {
+        match self {
+            ContractTemplate::Tip3Root => include_str!("../templates/new/tip3-root.sol"),
+            ContractTemplate::Tip4Nft => include_str!("../templates/new/tip4-nft.sol"),
+            ContractTemplate::Wallet => include_str!("../templates/new/wallet.sol"),
+            ContractTemplate::Empty => include_str!("../templates/new/empty.sol"),
+        }
+    }
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct NewArgs {
+    /// Contract name
+    #[clap(value_parser)]
+    pub name: String,
+    /// Standard the contract implements
+    #[clap(long, value_enum, default_value = "empty")]
+    pub template: ContractTemplate,
+    /// Directory of the source (by default, current directory is used)
+    #[clap(short('O'), long, value_parser)]
+    pub output_dir: Option<String>,
+    /// Overwrite the source if it exists
+    #[clap(long, value_parser)]
+    pub force: bool,
+}
+
+pub fn new(args: NewArgs) -> Status {
+    let valid = args.name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && args.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(SoldError::ArgumentError(format!("Invalid contract name \"{}\"", args.name)))
+    }
+    let path = Path::new(args.output_dir.as_deref().unwrap_or(".")).join(format!("{}.sol", args.name));
+    if path.exists() && !args.force {
+        return Err(SoldError::ArgumentError(format!(
+            "{} already exists, use --force to overwrite it", path.display()
+        )))
+    }
+    let compiler_version = crate::solidity_version();
+    let text = args.template.source()
+        .replace("{{name}}", &args.name)
+        .replace("{{compiler_version}}", compiler_version.split('+').next().unwrap_or_default());
+    std::fs::write(&path, text)?;
+    println!("Written {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,39 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// The `optimizer` settings of the standard JSON input, which the TVM code generator reads
+// in TVMContractCompiler::optimizeCode. Without any of the options the section is left out
+// and the code is optimized as it is by default. `enabled` runs the stack and peephole
+// optimizers one more round, `details.peephole` turns the peephole optimizer off and
+// `runs` is the number of times the code is expected to run over the lifetime of the
+// contract: from 1000 runs on, the size optimizer, which trades gas for size, is skipped.
+
+use serde_json::{json, Value};
+
+/// Runs assumed when only --optimize is given, as in upstream solc
+const DEFAULT_RUNS: u32 = 200;
+
+/// The `optimizer` settings of the standard JSON input, if any differ from the defaults
+pub fn settings(optimize: bool, runs: Option<u32>, no_peephole: bool) -> Option<Value> {
+    if !optimize && runs.is_none() && !no_peephole {
+        return None
+    }
+    let mut settings = json!({
+        "enabled": optimize || runs.is_some(),
+        "runs": runs.unwrap_or(DEFAULT_RUNS),
+    });
+    if no_peephole {
+        settings["details"] = json!({ "peephole": false });
+    }
+    Some(settings)
+}
@@ -0,0 +1,140 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Output generator plugins are external programs registered in sold.toml:
+//
+//   [[plugins]]
+//   name = "stubs"
+//   command = "python3"
+//   args = [ "tools/stubs.py" ]
+//
+// With --plugins, and the file given with --config, after a successful build each plugin
+// is started in the directory of sold.toml and receives a single JSON document on stdin
+// (see PluginInput). It must print a JSON document on stdout listing the artifacts to
+// write into the output directory:
+//
+//   { "artifacts": [ { "name": "Wallet.stubs.ts", "content": "...", "encoding": "utf8" } ] }
+//
+// The encoding is either "utf8" (default) or "base64".
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SoldError};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PluginConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PluginInput<'a> {
+    pub version: u32,
+    pub output_prefix: &'a str,
+    pub abi: &'a serde_json::Value,
+    pub assembly: &'a str,
+    /// Base64 encoded TVC
+    pub tvc: String,
+    pub debug_map: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct PluginOutput {
+    #[serde(default)]
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    name: String,
+    content: String,
+    #[serde(default)]
+    encoding: Encoding,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Encoding {
+    #[default]
+    Utf8,
+    Base64,
+}
+
+/// Runs the plugins in turn and returns the paths of the artifacts they produced.
+pub fn run_all(plugins: &[PluginConfig], work_dir: &Path, input: &PluginInput, output_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut artifacts = vec![];
+    if plugins.is_empty() {
+        return Ok(artifacts)
+    }
+    let request = serde_json::to_vec(input)?;
+    for plugin in plugins {
+        let _span = tracing::info_span!("plugin", name = %plugin.name).entered();
+        artifacts.extend(run(plugin, work_dir, request.clone(), output_dir)?);
+    }
+    Ok(artifacts)
+}
+
+fn run(plugin: &PluginConfig, work_dir: &Path, request: Vec<u8>, output_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut child = Command::new(&plugin.command)
+        .args(&plugin.args)
+        .current_dir(work_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SoldError::PluginError(format!("Failed to start plugin \"{}\": {}", plugin.name, e)))?;
+
+    // Feed stdin from a separate thread so a plugin that starts writing
+    // before it has read the whole request cannot deadlock us.
+    let mut stdin = child.stdin.take()
+        .ok_or_else(|| SoldError::PluginError(format!("Failed to open stdin of plugin \"{}\"", plugin.name)))?;
+    let writer = std::thread::spawn(move || stdin.write_all(&request));
+    let output = child.wait_with_output()?;
+    writer.join()
+        .map_err(|_| SoldError::PluginError(format!("Failed to send request to plugin \"{}\"", plugin.name)))??;
+
+    if !output.status.success() {
+        return Err(SoldError::PluginError(format!("Plugin \"{}\" failed ({}): {}",
+            plugin.name, output.status, String::from_utf8_lossy(&output.stderr).trim())))
+    }
+
+    let response: PluginOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|e| SoldError::PluginError(format!("Plugin \"{}\" returned malformed response: {}", plugin.name, e)))?;
+    let mut artifacts = vec![];
+    for artifact in response.artifacts {
+        if artifact.name.is_empty() || artifact.name == ".." || artifact.name.contains(std::path::is_separator) {
+            return Err(SoldError::PluginError(format!(
+                "Plugin \"{}\" returned invalid artifact name \"{}\"", plugin.name, artifact.name
+            )))
+        }
+        let content = match artifact.encoding {
+            Encoding::Utf8 => artifact.content.into_bytes(),
+            Encoding::Base64 => base64::decode(&artifact.content)
+                .map_err(|e| SoldError::PluginError(format!(
+                    "Plugin \"{}\" returned bad base64 for \"{}\": {}", plugin.name, artifact.name, e
+                )))?,
+        };
+        let path = output_dir.join(&artifact.name);
+        std::fs::write(&path, content)?;
+        artifacts.push(path);
+    }
+    Ok(artifacts)
+}
@@ -0,0 +1,169 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+use crate::error::{SoldError, Status};
+use crate::lexer::CodeMap;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct PragmaSetArgs {
+    /// New version requirement, e.g. ">=0.72.0"
+    #[clap(value_parser)]
+    pub version: String,
+    /// Source files or directories, imported files are updated as well
+    #[clap(value_parser, default_value = ".")]
+    pub inputs: Vec<String>,
+    /// Include additional path to search for imports
+    #[clap(short('I'), long, value_parser)]
+    pub include_path: Vec<String>,
+    /// Don't compile the updated sources
+    #[clap(long, value_parser)]
+    pub no_verify: bool,
+}
+
+lazy_static! {
+    static ref PRAGMA: Regex = Regex::new(r"\bpragma\s+((?:ton-|ever-)?solidity)\s+([^;]*);").unwrap();
+}
+
+/// Version requirement of the first compiler version pragma of `text`
+pub fn requirement(text: &str) -> Option<String> {
+    let code = CodeMap::new(text);
+    PRAGMA.captures_iter(text)
+        .find(|captures| code.is_code(captures.get(0).unwrap().start()))
+        .map(|captures| captures[2].trim().to_owned())
+}
+
+/// `major.minor.patch`, missing parts being zero
+pub fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    let text = text.trim().split(|c| c == '+' || c == '-').next()?;
+    let mut parts = text.split('.').map(|part| part.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+fn satisfies_comparator(comparator: &str, version: (u64, u64, u64)) -> bool {
+    let operator_len = comparator.find(|c: char| c.is_ascii_digit()).unwrap_or(comparator.len());
+    let (operator, bound) = comparator.split_at(operator_len);
+    let bound = match parse_version(bound) {
+        Some(bound) => bound,
+        None => return false,
+    };
+    // `^` and `~` allow changes below the first nonzero part, and below the minor version
+    let next = |(major, minor, patch): (u64, u64, u64), caret: bool| match (major, minor) {
+        (0, 0) if caret => (0, 0, patch + 1),
+        (0, _) => (0, minor + 1, 0),
+        _ if caret => (major + 1, 0, 0),
+        _ => (major, minor + 1, 0),
+    };
+    match operator.trim() {
+        ">=" => version >= bound,
+        ">" => version > bound,
+        "<=" => version <= bound,
+        "<" => version < bound,
+        "^" => version >= bound && version < next(bound, true),
+        "~" => version >= bound && version < next(bound, false),
+        "=" | "" => version == bound,
+        _ => false,
+    }
+}
+
+/// Tells whether `version` meets a requirement such as `>=0.60.0 <0.70.0` or `^0.66.0`,
+/// alternatives being separated with `||`.
+pub fn satisfies(requirement: &str, version: (u64, u64, u64)) -> bool {
+    requirement.split("||").any(|alternative| {
+        // `>= 0.60.0` is a single comparator
+        let mut comparators = vec![];
+        let mut pending = String::new();
+        for word in alternative.split_whitespace() {
+            pending.push_str(word);
+            if word.ends_with(|c: char| c.is_ascii_digit()) {
+                comparators.push(std::mem::take(&mut pending));
+            }
+        }
+        !comparators.is_empty() && pending.is_empty()
+            && comparators.iter().all(|comparator| satisfies_comparator(comparator, version))
+    })
+}
+
+/// Replaces the version requirement of every compiler version pragma, keeping its keyword.
+fn set_version(text: &str, version: &str) -> String {
+    let code = CodeMap::new(text);
+    PRAGMA.replace_all(text, |captures: &regex::Captures| {
+        let m = captures.get(0).unwrap();
+        if code.is_code(m.start()) {
+            format!("pragma {} {};", &captures[1], version)
+        } else {
+            m.as_str().to_owned()
+        }
+    }).into_owned()
+}
+
+pub fn set(args: PragmaSetArgs) -> Status {
+    let version = args.version.trim();
+    if version.is_empty() || version.contains(';') {
+        return Err(SoldError::ArgumentError(format!("Invalid version requirement \"{}\"", args.version)))
+    }
+
+    let files = crate::sources::collect(&args.inputs)?;
+    let mut originals: Vec<(PathBuf, String)> = vec![];
+    for file in &files {
+        let text = std::fs::read_to_string(file)?;
+        let updated = set_version(&text, version);
+        if updated != text {
+            std::fs::write(file, updated)?;
+            originals.push((file.clone(), text));
+        }
+    }
+
+    if !args.no_verify && !originals.is_empty() {
+        if let Err(e) = verify(&files, &args.include_path) {
+            for (file, text) in &originals {
+                std::fs::write(file, text)?;
+            }
+            eprintln!("The updated sources failed to compile, all changes are reverted");
+            return Err(e)
+        }
+    }
+
+    for (file, _) in &originals {
+        println!("Updated {}", file.display());
+    }
+    println!("{} of {} files updated to pragma {}", originals.len(), files.len(), version);
+    Ok(())
+}
+
+/// Runs the frontend on all sources at once, up to the ABI so that the full analysis is done.
+fn verify(files: &[PathBuf], include_paths: &[String]) -> Status {
+    let mut sources = serde_json::Map::new();
+    for file in files {
+        let path = file.to_string_lossy();
+        sources.insert(path.to_string(), json!({ "urls": [ path ] }));
+    }
+    let request = json!({
+        "language": "Solidity",
+        "settings": {
+            "includePaths": include_paths,
+            "outputSelection": { "*": { "*": [ "abi" ] } },
+        },
+        "sources": sources,
+    });
+    let res = crate::run_compiler(&request.to_string())?;
+    crate::diagnostics::check_errors(&res.errors, crate::diagnostics::ErrorFormat::Human)
+}
@@ -0,0 +1,51 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Settings of well-known networks, so that users don't have to look up config params.
+// A preset is the default for whatever sold.toml doesn't set explicitly. This compiler
+// emits ABI 2.2 and links with its bundled stdlib for every network, so the presets
+// only differ in what the networks configure: size limits (param 43) and storage
+// prices (param 18). The networks currently agree on both, each still has its own arm
+// so that a config vote on one of them is a one-line change.
+
+use serde::{Deserialize, Serialize};
+
+use crate::limits::StateLimits;
+use crate::storage::StoragePrices;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkPreset {
+    EverscaleMainnet,
+    TonMainnet,
+    Venom,
+}
+
+impl NetworkPreset {
+    pub fn limits(self) -> StateLimits {
+        match self {
+            NetworkPreset::EverscaleMainnet => StateLimits { max_cells: 1 << 13, max_depth: 512, max_code_size: 65535 },
+            NetworkPreset::TonMainnet => StateLimits { max_cells: 1 << 13, max_depth: 512, max_code_size: 65535 },
+            NetworkPreset::Venom => StateLimits { max_cells: 1 << 13, max_depth: 512, max_code_size: 65535 },
+        }
+    }
+
+    /// Basechain storage prices
+    pub fn storage_prices(self) -> StoragePrices {
+        match self {
+            NetworkPreset::EverscaleMainnet => StoragePrices { bit_price_ps: 1, cell_price_ps: 500 },
+            NetworkPreset::TonMainnet => StoragePrices { bit_price_ps: 1, cell_price_ps: 500 },
+            NetworkPreset::Venom => StoragePrices { bit_price_ps: 1, cell_price_ps: 500 },
+        }
+    }
+}
@@ -11,16 +11,14 @@
  * limitations under the License.
  */
 
-use std::fs::File;
 use std::io::Write;
 
-use failure::format_err;
 use serde::Serialize;
 
-use ton_types::{Result, Status};
+use crate::error::{Result, SoldError, Status};
 
-pub fn print_abi_json_canonically(out: &mut File, value: &serde_json::Value) -> Status {
-    let root = value.as_object().ok_or_else(|| format_err!("ABI parsing failed"))?;
+pub fn print_abi_json_canonically(out: &mut impl Write, value: &serde_json::Value) -> Status {
+    let root = value.as_object().ok_or_else(|| abi_error())?;
     writeln!(out, "{{")?;
     writeln!(out, "\t\"ABI version\": {},", root["ABI version"])?;
     if let Some(version) = root.get("version") {
@@ -28,7 +26,7 @@ pub fn print_abi_json_canonically(out: &mut File, value: &serde_json::Value) ->
     }
     if let Some(header) = root.get("header") {
         write!(out, "\t\"header\": [")?;
-        let array = header.as_array().ok_or_else(|| format_err!("ABI parsing failed"))?;
+        let array = header.as_array().ok_or_else(|| abi_error())?;
         for i in 0..array.len() {
             write!(out, "{}", array[i])?;
             if i + 1 != array.len() {
@@ -62,8 +60,8 @@ pub fn print_abi_json_canonically(out: &mut File, value: &serde_json::Value) ->
     Ok(())
 }
 
-fn print_data(out: &mut File, value: &serde_json::Value) -> Status {
-    let json = value.as_array().ok_or_else(|| format_err!("ABI parsing failed"))?;
+fn print_data(out: &mut impl Write, value: &serde_json::Value) -> Status {
+    let json = value.as_array().ok_or_else(|| abi_error())?;
     for f in 0..json.len() {
         write!(out, "\t\t")?;
 
@@ -77,10 +75,10 @@ fn print_data(out: &mut File, value: &serde_json::Value) -> Status {
     Ok(())
 }
 
-fn print(out: &mut File, value: &serde_json::Value) -> Status {
-    let json = value.as_array().ok_or_else(|| format_err!("ABI parsing failed"))?;
+fn print(out: &mut impl Write, value: &serde_json::Value) -> Status {
+    let json = value.as_array().ok_or_else(|| abi_error())?;
     for f in 0..json.len() {
-        let function = json[f].as_object().ok_or_else(|| format_err!("ABI parsing failed"))?;
+        let function = json[f].as_object().ok_or_else(|| abi_error())?;
         writeln!(out, "\t\t{{")?;
 
         writeln!(out, "\t\t\t\"name\": {},", function["name"])?;
@@ -91,7 +89,7 @@ fn print(out: &mut File, value: &serde_json::Value) -> Status {
 
         writeln!(out, "\t\t\t\"inputs\": [")?;
         if let Some(inputs) = function.get("inputs") {
-            let array = inputs.as_array().ok_or_else(|| format_err!("ABI parsing failed"))?;
+            let array = inputs.as_array().ok_or_else(|| abi_error())?;
             for i in 0..array.len() {
                 write!(out, "\t\t\t\t")?;
                 write!(out, "{}", to_string_pretty_no_indent(&array[i])?)?;
@@ -106,7 +104,7 @@ fn print(out: &mut File, value: &serde_json::Value) -> Status {
 
         writeln!(out, "\t\t\t\"outputs\": [")?;
         if let Some(outputs) = function.get("outputs") {
-            let array = outputs.as_array().ok_or_else(|| format_err!("ABI parsing failed"))?;
+            let array = outputs.as_array().ok_or_else(|| abi_error())?;
             for o in 0..array.len() {
                 write!(out, "\t\t\t\t")?;
                 write!(out, "{}", to_string_pretty_no_indent(&array[o])?)?;
@@ -133,5 +131,10 @@ fn to_string_pretty_no_indent(value: &serde_json::Value) -> Result<String> {
     let formatter = serde_json::ser::CompactFormatter;
     let mut ser = serde_json::Serializer::with_formatter(buf, formatter);
     value.serialize(&mut ser)?;
-    Ok(String::from_utf8(ser.into_inner())?)
+    String::from_utf8(ser.into_inner())
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))
+}
+
+fn abi_error() -> SoldError {
+    SoldError::InvalidOutput("ABI parsing failed".to_owned())
 }
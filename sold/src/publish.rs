@@ -0,0 +1,190 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Submits the sources of a deployed contract to a verification service. The contract is
+// built as `sold verify` builds it, with the options given after --, and the submission
+// carries what the --metadata of that build records: the compiler versions, the standard
+// JSON settings with the remappings, the defines and the hashes of the linked library,
+// along with the content of every source the frontend read. Paths are made relative to
+// the project root, so the submission holds no file outside the project and no path of
+// this machine. The service is expected to answer the POST to the --verifier URL with
+// the `id` of the submission and report its `status` at the URL followed by `/<id>`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use serde_json::{json, Value};
+
+use crate::error::{Result, SoldError, Status};
+use crate::messages;
+use crate::remappings::Remapping;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Prefix of the artifacts of the build
+const PREFIX: &str = "contract";
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct PublishArgs {
+    /// Source file name
+    #[clap(value_parser)]
+    pub input: String,
+    /// URL the submission is posted to
+    #[clap(long, value_parser)]
+    pub verifier: String,
+    /// Address of the deployed contract
+    #[clap(long, value_parser)]
+    pub address: String,
+    /// Directory of the project, which all sources must be in [default: current directory]
+    #[clap(long, value_parser)]
+    pub root: Option<String>,
+    /// Maximum time in seconds to wait for the verdict
+    #[clap(long, value_parser, default_value_t = 600)]
+    pub wait: u64,
+    /// Build options, given after --
+    #[clap(value_parser, last = true)]
+    pub build_args: Vec<String>,
+}
+
+fn verification_error(text: String) -> SoldError {
+    SoldError::VerificationError(text)
+}
+
+/// `path` relative to `root`, with a trailing separator kept, failing for paths outside it
+fn relative(root: &Path, path: &str) -> Result<String> {
+    let canonical = Path::new(path).canonicalize()
+        .map_err(|_| SoldError::SourceNotFound(path.to_owned()))?;
+    let relative = canonical.strip_prefix(root)
+        .map_err(|_| SoldError::ArgumentError(format!(
+            "\"{}\" is outside of the project directory {}, the verification service couldn't place it",
+            path, root.display(),
+        )))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    Ok(if path.ends_with('/') && !relative.is_empty() { relative + "/" } else { relative })
+}
+
+/// Metadata the build of `args` writes with --metadata
+fn build_metadata(args: &PublishArgs) -> Result<Value> {
+    let mut argv = vec!["sold".to_owned(), args.input.clone()];
+    argv.extend(args.build_args.iter().cloned());
+    let mut build_args = crate::Args::try_parse_from(argv)
+        .map_err(|e| SoldError::ArgumentError(e.to_string()))?;
+    if let Some(path) = build_args.from_config.take() {
+        build_args = crate::build_config::load(&path)?;
+        build_args.input = vec![args.input.clone()];
+    }
+    let dir = crate::compiler::temp_dir()?;
+    build_args.output_dir = Some(dir.to_string_lossy().into_owned());
+    build_args.output_prefix = Some(PREFIX.to_owned());
+    build_args.metadata = true;
+    let res = crate::compiler::Compiler::new().build(build_args)
+        .and_then(|_| Ok(std::fs::read_to_string(dir.join(format!("{}.meta.json", PREFIX)))?));
+    std::fs::remove_dir_all(&dir).ok();
+    Ok(serde_json::from_str(&res?)?)
+}
+
+/// Settings of `metadata` with the paths they hold made relative to `root`
+fn relative_settings(root: &Path, metadata: &Value, input: &str, main: &str) -> Result<Value> {
+    let mut settings = metadata["settings"].clone();
+    if let Some(paths) = settings["includePaths"].as_array_mut() {
+        for path in paths {
+            *path = relative(root, path.as_str().unwrap_or_default())?.into();
+        }
+    }
+    if let Some(remappings) = settings["remappings"].as_array_mut() {
+        for remapping in remappings {
+            let mut parsed: Remapping = remapping.as_str().unwrap_or_default().parse()
+                .map_err(SoldError::InvalidOutput)?;
+            if !parsed.target.is_empty() {
+                parsed.target = relative(root, &parsed.target)?;
+            }
+            *remapping = parsed.to_string().into();
+        }
+    }
+    let selection = settings["outputSelection"].as_object_mut()
+        .and_then(|selection| selection.remove(input))
+        .unwrap_or(Value::Null);
+    settings["outputSelection"] = json!({ main: selection });
+    Ok(settings)
+}
+
+pub fn publish(args: PublishArgs) -> Status {
+    let root = match &args.root {
+        Some(root) => PathBuf::from(root),
+        None => std::env::current_dir()?,
+    };
+    let root = root.canonicalize().map_err(|_| SoldError::SourceNotFound(root.display().to_string()))?;
+    let input = Path::new(&args.input).canonicalize()
+        .map_err(|_| SoldError::SourceNotFound(args.input.clone()))?;
+    let input = input.to_string_lossy();
+    let main = relative(&root, &input)?;
+
+    let metadata = build_metadata(&args)?;
+    let mut sources = BTreeMap::new();
+    for path in metadata["sources"].as_object().map(|sources| sources.keys()).into_iter().flatten() {
+        let content = crate::source_cache::read(Path::new(path))?;
+        sources.insert(relative(&root, path)?, json!({ "content": String::from_utf8_lossy(&content) }));
+    }
+
+    let submission = json!({
+        "address": args.address,
+        "contract": metadata["contract"],
+        "compiler": metadata["compiler"],
+        "language": metadata["language"],
+        "main": main,
+        "settings": relative_settings(&root, &metadata, &input, &main)?,
+        "defines": metadata["defines"],
+        "library": metadata["library"],
+        "codeHash": metadata["codeHash"],
+        "sources": sources,
+    });
+
+    let url = args.verifier.trim_end_matches('/');
+    let response: Value = ureq::post(url)
+        .send_json(submission)
+        .map_err(|e| verification_error(format!("Failed to submit sources: {}", e)))?
+        .into_json()?;
+    let id = response["id"].as_str()
+        .ok_or_else(|| verification_error("Verification service returned no submission id".to_owned()))?
+        .to_owned();
+    messages::info(&format!("Submitted sources for verification, id {}", id));
+
+    let deadline = Instant::now() + Duration::from_secs(args.wait);
+    loop {
+        let status: Value = ureq::get(&format!("{}/{}", url, id))
+            .call()
+            .map_err(|e| verification_error(format!("Failed to query verification status: {}", e)))?
+            .into_json()?;
+        let message = status["message"].as_str().unwrap_or_default();
+        match status["status"].as_str() {
+            Some("verified") => {
+                messages::output(&format!("Contract {} successfully verified", args.address));
+                return Ok(())
+            }
+            Some("failed") => return Err(verification_error(format!("Verification failed: {}", message))),
+            Some("pending") | Some("queued") | Some("running") => {}
+            _ => return Err(verification_error(format!(
+                "Unexpected response from verification service: {}", status
+            ))),
+        }
+        if Instant::now() >= deadline {
+            return Err(verification_error(format!(
+                "Timed out waiting for verification of submission {}", id
+            )))
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Import remappings in the solc format, `[context:]prefix=target`, e.g.
+// `@openzeppelin/=node_modules/@openzeppelin/`. They go to the frontend in the
+// `remappings` settings, and the frontend rewrites the imports they match before asking
+// the read callback for the file. Of several remappings matching an import the one with
+// the longest context wins, then the one with the longest prefix.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{SoldError, Status};
+use crate::messages;
+
+/// A remapping given with --remap
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Remapping {
+    /// Directory the importing files must be in, all files if empty
+    pub context: String,
+    pub prefix: String,
+    pub target: String,
+}
+
+impl FromStr for Remapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (key, target) = s.split_once('=')
+            .ok_or_else(|| format!("expected [context:]prefix=target, found \"{}\"", s))?;
+        let (context, prefix) = key.split_once(':').unwrap_or(("", key));
+        if prefix.is_empty() {
+            return Err(format!("remapping \"{}\" has an empty prefix", s))
+        }
+        Ok(Remapping { context: context.to_owned(), prefix: prefix.to_owned(), target: target.to_owned() })
+    }
+}
+
+impl TryFrom<String> for Remapping {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, String> {
+        s.parse()
+    }
+}
+
+impl From<Remapping> for String {
+    fn from(remapping: Remapping) -> String {
+        remapping.to_string()
+    }
+}
+
+impl fmt::Display for Remapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.context.is_empty() {
+            write!(f, "{}:", self.context)?;
+        }
+        write!(f, "{}={}", self.prefix, self.target)
+    }
+}
+
+/// Fails on remappings of the same prefix in the same context to different targets, which
+/// the frontend would silently resolve to the last one, and warns of missing targets.
+pub fn check(remappings: &[Remapping]) -> Status {
+    for (i, remapping) in remappings.iter().enumerate() {
+        let conflicting = remappings[..i].iter().find(|other| {
+            other.context == remapping.context && other.prefix == remapping.prefix && other.target != remapping.target
+        });
+        if let Some(other) = conflicting {
+            return Err(SoldError::ArgumentError(format!(
+                "Conflicting remappings \"{}\" and \"{}\": the prefix \"{}\" can be mapped to only one target{}",
+                other, remapping, remapping.prefix,
+                if remapping.context.is_empty() { String::new() } else { format!(" in context \"{}\"", remapping.context) },
+            )))
+        }
+        if !remapping.target.is_empty() && !Path::new(&remapping.target).exists() {
+            messages::warning(&format!("Target of remapping \"{}\" doesn't exist", remapping));
+        }
+    }
+    Ok(())
+}
+
+/// The `remappings` settings of the standard JSON input, if there are any
+pub fn settings(remappings: &[Remapping]) -> Option<Value> {
+    (!remappings.is_empty())
+        .then(|| Value::from(remappings.iter().map(ToString::to_string).collect::<Vec<_>>()))
+}
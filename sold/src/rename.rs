@@ -0,0 +1,295 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Renaming of a declaration across the project. The targets are resolved on the AST:
+// the declaration itself, the functions overriding it or overridden by it, and every
+// identifier, member access, type name and import alias that refers to one of them.
+// Nothing is changed unless the source text at a range is exactly the old name, and the
+// result is compiled again before it is kept.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::ast::{self, Project};
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct RenameArgs {
+    /// Declaration to rename: <file>:<name> or <file>:<Contract>.<name>
+    #[clap(long, value_parser)]
+    pub from: String,
+    /// New name
+    #[clap(long, value_parser)]
+    pub to: String,
+    /// Project sources: files or directories that may refer to the declaration
+    #[clap(value_parser, default_value = ".")]
+    pub inputs: Vec<String>,
+    /// Include additional path to search for imports
+    #[clap(short('I'), long, value_parser)]
+    pub include_path: Vec<String>,
+}
+
+const RENAMEABLE: &[&str] = &[
+    "ContractDefinition", "FunctionDefinition", "VariableDeclaration", "ModifierDefinition",
+    "EventDefinition", "StructDefinition", "EnumDefinition",
+];
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+fn same_file(a: &str, b: &Path) -> bool {
+    std::fs::canonicalize(a).map_or(false, |a| a == b)
+}
+
+/// Declarations named `name` at the top level of the unit or inside its contracts
+fn find_declarations<'a>(unit: &'a Value, contract: Option<&str>, name: &str) -> Vec<&'a Value> {
+    let mut res = vec![];
+    for node in unit["nodes"].as_array().into_iter().flatten() {
+        let is_contract = node["nodeType"] == "ContractDefinition";
+        if contract.is_none() && node["name"] == name && is_renameable(node) {
+            res.push(node);
+        }
+        if is_contract && contract.map_or(true, |c| node["name"] == c) {
+            for member in node["nodes"].as_array().into_iter().flatten() {
+                if member["name"] == name && is_renameable(member) {
+                    res.push(member);
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Adds the functions and modifiers related to the targets through overriding.
+fn add_overrides(project: &Project, targets: &mut HashSet<u64>) {
+    loop {
+        let mut added = vec![];
+        for unit in project.units.values() {
+            ast::walk(unit, &mut |node| {
+                let id = match node["id"].as_u64() {
+                    Some(id) => id,
+                    None => return,
+                };
+                let bases: Vec<u64> = ["baseFunctions", "baseModifiers"].iter()
+                    .flat_map(|key| node[*key].as_array().into_iter().flatten())
+                    .filter_map(Value::as_u64)
+                    .collect();
+                if targets.contains(&id) {
+                    added.extend(bases.iter().filter(|base| !targets.contains(base)));
+                } else if bases.iter().any(|base| targets.contains(base)) {
+                    added.push(id);
+                }
+            });
+        }
+        if added.is_empty() {
+            return
+        }
+        targets.extend(added);
+    }
+}
+
+fn refers_to_target(node: &Value, targets: &HashSet<u64>) -> bool {
+    if node["referencedDeclaration"].as_u64().map_or(false, |id| targets.contains(&id)) {
+        return true
+    }
+    // Calls of overloaded functions are resolved only in the enclosing call
+    node["referencedDeclaration"].is_null() && node["overloadedDeclarations"].as_array().into_iter().flatten()
+        .filter_map(Value::as_u64)
+        .any(|id| targets.contains(&id))
+}
+
+/// Ranges to replace in every file
+fn collect_edits(project: &Project, targets: &HashSet<u64>, old: &str, top_level_unit: Option<u64>) -> Result<Edits> {
+    let mut edits = Edits::new();
+    for (path, unit) in &project.units {
+        let text = std::fs::read_to_string(path)?;
+        let mut ranges = BTreeSet::new();
+        let mut add = |range: Option<Range<usize>>| {
+            if let Some(range) = range {
+                ranges.insert((range.start, range.end));
+            }
+        };
+        ast::walk(unit, &mut |node| {
+            if node["id"].as_u64().map_or(false, |id| targets.contains(&id)) {
+                add(ast::declaration_name(&text, node));
+            } else if refers_to_target(node, targets) {
+                add(ast::reference_name(&text, node, old));
+            } else if node["nodeType"] == "ImportDirective" && top_level_unit.is_some() && node["sourceUnit"].as_u64() == top_level_unit {
+                for alias in node["symbolAliases"].as_array().into_iter().flatten() {
+                    if alias["foreign"]["name"] == old {
+                        add(ast::reference_name(&text, &alias["foreign"], old));
+                    }
+                }
+            }
+        });
+        if !ranges.is_empty() {
+            edits.insert(path.clone(), ranges);
+        }
+    }
+    Ok(edits)
+}
+
+/// What a rename applies to: declarations of one name in one scope, overloads included
+pub struct Target {
+    pub name: String,
+    ids: HashSet<u64>,
+    scope: Option<u64>,
+}
+
+/// Ranges of the old name to replace, by file
+pub type Edits = BTreeMap<String, BTreeSet<(usize, usize)>>;
+
+fn is_renameable(node: &Value) -> bool {
+    RENAMEABLE.contains(&node["nodeType"].as_str().unwrap_or_default())
+        && node["name"].as_str().map_or(false, |name| !name.is_empty())
+}
+
+impl Target {
+    fn of(declaration: &Value) -> Target {
+        Target {
+            name: declaration["name"].as_str().unwrap_or_default().to_owned(),
+            ids: declaration["id"].as_u64().into_iter().collect(),
+            scope: declaration["scope"].as_u64(),
+        }
+    }
+
+    /// The declaration whose name is at `offset` in `path`, or is referred to by the name there.
+    /// Returns the range of that name as well.
+    pub fn at(project: &Project, path: &str, offset: usize) -> Result<Option<(Target, Range<usize>)>> {
+        let unit = match project.units.get(path) {
+            Some(unit) => unit,
+            None => return Ok(None),
+        };
+        let text = std::fs::read_to_string(path)?;
+        let mut res = None;
+        ast::walk(unit, &mut |node| {
+            if res.is_some() {
+                return
+            }
+            if is_renameable(node) {
+                if let Some(range) = ast::declaration_name(&text, node).filter(|r| r.start <= offset && offset <= r.end) {
+                    res = Some((Target::of(node), range));
+                }
+            } else if let Some(id) = node["referencedDeclaration"].as_u64() {
+                let name = node["name"].as_str().or_else(|| node["memberName"].as_str())
+                    .and_then(|name| name.rsplit('.').next())
+                    .unwrap_or_default();
+                let range = match ast::reference_name(&text, node, name).filter(|r| r.start <= offset && offset <= r.end) {
+                    Some(range) => range,
+                    None => return,
+                };
+                if let Some(declaration) = project.node_by_id(id).filter(|d| is_renameable(d) && d["name"] == name) {
+                    res = Some((Target::of(declaration), range));
+                }
+            }
+        });
+        Ok(res)
+    }
+}
+
+/// Finds every occurrence of the target, refusing names that are taken or invalid.
+pub fn plan(project: &Project, target: &Target, new_name: &str) -> Result<Edits> {
+    if !is_identifier(new_name) {
+        return Err(SoldError::ArgumentError(format!("\"{}\" is not a valid identifier", new_name)))
+    }
+    let mut taken = false;
+    for unit in project.units.values() {
+        ast::walk(unit, &mut |node| {
+            taken |= is_renameable(node) && node["name"] == new_name && node["scope"].as_u64() == target.scope;
+        });
+    }
+    if taken {
+        return Err(SoldError::ArgumentError(format!("\"{}\" is already declared in the same scope", new_name)))
+    }
+
+    let mut ids = target.ids.clone();
+    add_overrides(project, &mut ids);
+    // Top level declarations may also be named in `import {Name} from "..."`
+    let top_level_unit = target.scope
+        .filter(|scope| project.units.values().any(|unit| unit["id"].as_u64() == Some(*scope)));
+    collect_edits(project, &ids, &target.name, top_level_unit)
+}
+
+/// Writes the edits and keeps them only if the sources still compile.
+pub fn apply(files: &[PathBuf], include_paths: &[String], edits: &Edits, new_name: &str) -> Status {
+    let mut originals: Vec<(PathBuf, String)> = vec![];
+    for (path, ranges) in edits {
+        let text = std::fs::read_to_string(path)?;
+        let mut renamed = text.clone();
+        for (start, end) in ranges.iter().rev() {
+            renamed.replace_range(start..end, new_name);
+        }
+        std::fs::write(path, renamed)?;
+        originals.push((PathBuf::from(path), text));
+    }
+
+    if let Err(e) = Project::load(files, include_paths) {
+        for (path, text) in &originals {
+            std::fs::write(path, text)?;
+        }
+        eprintln!("The renamed sources failed to compile, all changes are reverted");
+        return Err(e)
+    }
+    Ok(())
+}
+
+pub fn rename(args: RenameArgs) -> Status {
+    let (file, qualified) = args.from.rsplit_once(':')
+        .ok_or_else(|| SoldError::ArgumentError(format!("Invalid --from \"{}\", expected <file>:<name>", args.from)))?;
+    let (contract, old) = match qualified.split_once('.') {
+        Some((contract, name)) => (Some(contract), name),
+        None => (None, qualified),
+    };
+    if !is_identifier(old) {
+        return Err(SoldError::ArgumentError(format!("\"{}\" is not a valid identifier", old)))
+    }
+    let file = std::fs::canonicalize(file)
+        .map_err(|_| SoldError::SourceNotFound(file.to_owned()))?;
+
+    let mut inputs = args.inputs.clone();
+    inputs.push(file.to_string_lossy().into_owned());
+    let files = crate::sources::collect(&inputs)?;
+    let project = Project::load(&files, &args.include_path)?;
+
+    let unit = project.units.iter()
+        .find(|(path, _)| same_file(path, &file))
+        .map(|(_, unit)| unit)
+        .ok_or_else(|| SoldError::SourceNotFound(file.display().to_string()))?;
+    let declarations = find_declarations(unit, contract, old);
+    let scopes: BTreeSet<u64> = declarations.iter().filter_map(|d| d["scope"].as_u64()).collect();
+    match (declarations.len(), scopes.len()) {
+        (0, _) => return Err(SoldError::ArgumentError(format!("No contract, function or state variable \"{}\" in {}", qualified, file.display()))),
+        (_, n) if n > 1 => return Err(SoldError::ArgumentError(format!("\"{}\" is declared in more than one contract, use <file>:<Contract>.{}", old, old))),
+        _ => {}
+    }
+    let target = Target {
+        name: old.to_owned(),
+        ids: declarations.iter().filter_map(|d| d["id"].as_u64()).collect(),
+        scope: scopes.into_iter().next(),
+    };
+
+    let edits = plan(&project, &target, &args.to)?;
+    apply(&files, &args.include_path, &edits, &args.to)?;
+    let count: usize = edits.values().map(BTreeSet::len).sum();
+    for (path, ranges) in &edits {
+        println!("{}: {} occurrences", path, ranges.len());
+    }
+    println!("Renamed {} to {}: {} occurrences in {} files", old, args.to, count, edits.len());
+    Ok(())
+}
@@ -0,0 +1,96 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SoldError, Status};
+
+pub const REPORT_FILE_NAME: &str = "build-report.json";
+
+/// What a successful build produced
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BuildOutput {
+    pub contract: String,
+    pub artifacts: Vec<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_hash: Option<String>,
+    /// Address in workchain 0 with the initial data of the TVC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Size of the serialized code in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tvc_size: Option<usize>,
+    pub warnings: usize,
+}
+
+/// Status of one input of a multi-input build
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub input: String,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub output: Option<BuildOutput>,
+    pub duration_ms: u128,
+}
+
+impl Entry {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn ok(input: &str, output: BuildOutput, duration: Duration) -> Entry {
+        Entry {
+            input: input.to_owned(),
+            status: "ok",
+            error: None,
+            output: Some(output),
+            duration_ms: duration.as_millis(),
+        }
+    }
+
+    pub fn skipped(input: &str) -> Entry {
+        Entry {
+            input: input.to_owned(),
+            status: "skipped",
+            error: None,
+            output: None,
+            duration_ms: 0,
+        }
+    }
+
+    pub fn failed(input: &str, error: &SoldError, duration: Duration) -> Entry {
+        Entry {
+            input: input.to_owned(),
+            status: "failed",
+            error: Some(error.to_string()),
+            output: None,
+            duration_ms: duration.as_millis(),
+        }
+    }
+}
+
+pub fn write(path: &Path, entries: &[Entry]) -> Status {
+    let mut file = File::create(path)?;
+    serde_json::to_writer_pretty(&mut file, entries)?;
+    writeln!(file)?;
+    Ok(())
+}
@@ -0,0 +1,131 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Builds a contract twice and compares the artifacts byte by byte. With --separate-dirs the
+// two builds write to different directories, which also catches output paths leaking into
+// the artifacts.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct ReproCheckArgs {
+    /// Source file name
+    #[clap(value_parser)]
+    pub input: String,
+    /// Build into two different temporary directories
+    #[clap(long, value_parser)]
+    pub separate_dirs: bool,
+    /// Build options, given after --
+    #[clap(value_parser, last = true)]
+    pub build_args: Vec<String>,
+}
+
+/// Differing bytes shown per artifact
+const SHOWN_DIFFERENCES: usize = 8;
+
+fn temp_dir(n: usize) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("sold-repro-{}-{}", std::process::id(), n));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Builds the input into `dir` and reads back everything the build wrote there.
+fn build(args: &ReproCheckArgs, dir: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut argv = vec!["sold".to_owned(), args.input.clone()];
+    argv.extend(args.build_args.iter().cloned());
+    argv.push("--output-dir".to_owned());
+    argv.push(dir.display().to_string());
+    let build_args = crate::Args::try_parse_from(argv)
+        .map_err(|e| SoldError::ArgumentError(e.to_string()))?;
+    crate::build(build_args)?;
+
+    let mut artifacts = BTreeMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            artifacts.insert(entry.file_name().to_string_lossy().into_owned(), std::fs::read(entry.path())?);
+        }
+    }
+    for name in artifacts.keys() {
+        std::fs::remove_file(dir.join(name))?;
+    }
+    Ok(artifacts)
+}
+
+/// Describes where `a` and `b` differ, None if they are identical.
+fn compare(a: &[u8], b: &[u8]) -> Option<String> {
+    let differing: Vec<usize> = a.iter().zip(b).enumerate()
+        .filter(|(_, (x, y))| x != y)
+        .map(|(offset, _)| offset)
+        .collect();
+    if differing.is_empty() && a.len() == b.len() {
+        return None
+    }
+    let mut res = String::new();
+    if a.len() != b.len() {
+        res.push_str(&format!("  sizes differ: {} and {} bytes\n", a.len(), b.len()));
+    }
+    if !differing.is_empty() {
+        res.push_str(&format!("  {} bytes differ, first at offset {}\n", differing.len(), differing[0]));
+    }
+    for offset in differing.iter().take(SHOWN_DIFFERENCES) {
+        res.push_str(&format!("  0x{:08x}: {:02x} != {:02x}\n", offset, a[*offset], b[*offset]));
+    }
+    if differing.len() > SHOWN_DIFFERENCES {
+        res.push_str("  ...\n");
+    }
+    Some(res)
+}
+
+pub fn repro_check(args: ReproCheckArgs) -> Status {
+    let first_dir = temp_dir(0)?;
+    let second_dir = if args.separate_dirs { temp_dir(1)? } else { first_dir.clone() };
+    let first = build(&args, &first_dir);
+    let second = first.is_ok().then(|| build(&args, &second_dir));
+    std::fs::remove_dir_all(&first_dir)?;
+    if args.separate_dirs {
+        std::fs::remove_dir_all(&second_dir)?;
+    }
+    let (first, second) = (first?, second.unwrap()?);
+
+    let mut differing = vec![];
+    for (name, bytes) in &first {
+        match second.get(name) {
+            Some(other) => if let Some(diff) = compare(bytes, other) {
+                eprint!("{} differs:\n{}", name, diff);
+                differing.push(name.clone());
+            },
+            None => {
+                eprintln!("{} is missing from the second build", name);
+                differing.push(name.clone());
+            }
+        }
+    }
+    for name in second.keys().filter(|name| !first.contains_key(*name)) {
+        eprintln!("{} is missing from the first build", name);
+        differing.push(name.clone());
+    }
+    if !differing.is_empty() {
+        return Err(SoldError::NotReproducible(differing))
+    }
+    println!("{} artifacts are identical", first.len());
+    Ok(())
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+
+use clap::CommandFactory;
+
+use crate::error::{Result, SoldError};
+use crate::Args;
+
+/// Options of sold and its subcommands that take a value, which may itself start with `@`,
+/// as the JSON files of --init and the remappings of --remap do
+fn value_options() -> HashSet<String> {
+    fn collect(command: &clap::Command, res: &mut HashSet<String>) {
+        for arg in command.get_arguments().filter(|arg| arg.is_takes_value_set() && !arg.is_positional()) {
+            res.extend(arg.get_long().map(|long| format!("--{}", long)));
+            res.extend(arg.get_short().map(|short| format!("-{}", short)));
+        }
+        for subcommand in command.get_subcommands() {
+            collect(subcommand, res);
+        }
+    }
+    let mut res = HashSet::new();
+    collect(&Args::command(), &mut res);
+    res
+}
+
+/// Replaces every `@file` argument that isn't the value of an option with the arguments
+/// listed in the file, one per line. Blank lines and lines starting with `#` are skipped.
+pub fn expand(args: impl IntoIterator<Item = OsString>) -> Result<Vec<OsString>> {
+    let value_options = value_options();
+    let mut res: Vec<OsString> = vec![];
+    for (i, arg) in args.into_iter().enumerate() {
+        let after_option = i > 0 && res.last().and_then(|prev| prev.to_str()).map_or(false, |prev| value_options.contains(prev));
+        let path = match arg.to_str().and_then(|arg| arg.strip_prefix('@')) {
+            Some(path) if i > 0 && !path.is_empty() && !after_option => path.to_owned(),
+            _ => {
+                res.push(arg);
+                continue
+            }
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| SoldError::ArgumentError(format!("Failed to read response file \"{}\": {}", path, e)))?;
+        res.extend(text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(OsString::from));
+    }
+    Ok(res)
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Directories the read callback may read sources from, as upstream solc restricts them.
+// With --base-path or --allow-paths, a build reads only files under the base path, the
+// allowed paths, the include paths and the directory of the input; an import of anything
+// else fails with a diagnostic of the frontend. Paths are compared canonicalized, so
+// neither `..` nor symbolic links lead out of the sandbox. Without the options any file
+// can be read, as before.
+
+use std::path::{Path, PathBuf};
+
+use crate::compiler::{self, State};
+use crate::error::{Result, SoldError, Status};
+use crate::Args;
+
+fn canonical(path: &Path) -> Result<PathBuf> {
+    std::fs::canonicalize(path)
+        .map_err(|_| SoldError::ArgumentError(format!("Allowed path \"{}\" doesn't exist", path.display())))
+}
+
+/// Restricts the reads of the builds of the current compiler to the directories `args`
+/// allow, or lifts the restriction if it has no --base-path and --allow-paths.
+pub fn set(args: &Args, input: &Path) -> Status {
+    let dirs = if args.base_path.is_none() && args.allow_paths.is_empty() {
+        None
+    } else {
+        let mut dirs = vec![];
+        for dir in args.base_path.iter().chain(&args.allow_paths).chain(&args.include_path) {
+            dirs.push(canonical(Path::new(dir))?);
+        }
+        // A source read from stdin has none
+        if let Some(dir) = input.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            dirs.push(canonical(dir)?);
+        }
+        Some(dirs)
+    };
+    *compiler::current().state().sandbox.lock().unwrap() = dirs;
+    Ok(())
+}
+
+/// Fails with the message the frontend reports if `path` is outside the allowed directories
+pub(crate) fn check(compiler: &State, path: &str) -> std::result::Result<(), String> {
+    let sandbox = compiler.sandbox.lock().unwrap();
+    let dirs = match sandbox.as_ref() {
+        Some(dirs) => dirs,
+        None => return Ok(()),
+    };
+    if crate::source_cache::is_virtual(Path::new(path)) {
+        return Ok(())
+    }
+    // A missing file fails to be read anyway, with the usual message
+    let canonical = match std::fs::canonicalize(path) {
+        Ok(canonical) => canonical,
+        Err(_) => return Ok(()),
+    };
+    if dirs.iter().any(|dir| canonical.starts_with(dir)) {
+        return Ok(())
+    }
+    Err(format!(
+        "File \"{}\" is outside of the allowed directories, add its directory with --allow-paths to read it",
+        path
+    ))
+}
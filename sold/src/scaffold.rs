@@ -0,0 +1,177 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Starting points for scripts working with the artifacts of a build. The templates live in
+// templates/, their `{{placeholders}}` are filled from the ABI: the constructor parameters
+// and static variables get a value of the right shape for each type.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::error::{SoldError, Status};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ScaffoldLang {
+    /// TypeScript with @eversdk/core
+    Ts,
+    /// JavaScript with @eversdk/core
+    Js,
+    /// Rust with ton_client
+    Rust,
+}
+
+impl ScaffoldLang {
+    fn template(self) -> &'static str {
+        match self {
+            ScaffoldLang::Ts => include_str!("../templates/deploy.ts"),
+            ScaffoldLang::Js => include_str!("../templates/deploy.js"),
+            ScaffoldLang::Rust => include_str!("../templates/deploy.rs"),
+        }
+    }
+
+    fn file_name(self, prefix: &str) -> String {
+        match self {
+            ScaffoldLang::Ts => format!("deploy-{}.ts", prefix),
+            ScaffoldLang::Js => format!("deploy-{}.js", prefix),
+            ScaffoldLang::Rust => format!("deploy_{}.rs", prefix.replace('-', "_")),
+        }
+    }
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct DeployArgs {
+    /// Output prefix of the contract, whose <prefix>.abi.json and <prefix>.tvc are deployed
+    #[clap(value_parser)]
+    pub prefix: String,
+    /// Language of the script
+    #[clap(long, value_enum)]
+    pub lang: ScaffoldLang,
+    /// Directory of the artifacts (by default, current directory is used)
+    #[clap(short('O'), long, value_parser)]
+    pub output_dir: Option<String>,
+    /// Script file name (by default, deploy-<prefix> with the extension of the language)
+    #[clap(short, long, value_parser)]
+    pub output: Option<String>,
+    /// Overwrite the script if it exists
+    #[clap(long, value_parser)]
+    pub force: bool,
+}
+
+/// A value of the given ABI type to start editing from
+fn placeholder(ty: &str, components: &[Value]) -> Value {
+    if ty.ends_with(']') {
+        return json!([])
+    }
+    if ty.starts_with("optional(") {
+        return Value::Null
+    }
+    if ty.starts_with("map(") {
+        return json!({})
+    }
+    if let Some(inner) = ty.strip_prefix("ref(").and_then(|ty| ty.strip_suffix(')')) {
+        return placeholder(inner, components)
+    }
+    match ty {
+        "tuple" => params(components),
+        "bool" => json!(false),
+        "address" | "address_std" => json!(format!("0:{}", "0".repeat(64))),
+        "string" | "bytes" | "cell" => json!(""),
+        _ if ty.starts_with("fixedbytes") => json!(""),
+        _ => json!("0"),
+    }
+}
+
+fn params(list: &[Value]) -> Value {
+    let mut res = Map::new();
+    for param in list {
+        let name = param["name"].as_str().unwrap_or_default();
+        let ty = param["type"].as_str().unwrap_or_default();
+        let components = param["components"].as_array().map_or(&[][..], Vec::as_slice);
+        res.insert(name.to_owned(), placeholder(ty, components));
+    }
+    Value::Object(res)
+}
+
+/// Static variables, listed in `data` up to ABI 2.3 and marked in `fields` since 2.4
+fn initial_data(abi: &Value) -> Value {
+    let fields: Vec<Value> = abi["fields"].as_array().map_or(vec![], |fields| {
+        fields.iter().filter(|field| field["init"] == true).cloned().collect()
+    });
+    let data = abi["data"].as_array().cloned().unwrap_or_default();
+    params(if fields.is_empty() { &data } else { &fields })
+}
+
+/// Path of `target` relative to the directory `base`, both being absolute
+fn relative(target: &Path, base: &Path) -> PathBuf {
+    let target: Vec<Component> = target.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let common = target.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut res = PathBuf::new();
+    for _ in common..base.len() {
+        res.push("..");
+    }
+    for component in &target[common..] {
+        res.push(component);
+    }
+    if res.as_os_str().is_empty() {
+        res.push(".");
+    }
+    res
+}
+
+/// Pretty JSON indented to continue a line indented with `indent`
+fn json_text(value: &Value, indent: &str) -> String {
+    let text = serde_json::to_string_pretty(value).unwrap_or_default();
+    text.lines().collect::<Vec<_>>().join(&format!("\n{}", indent))
+}
+
+pub fn deploy(args: DeployArgs) -> Status {
+    let output_dir = args.output_dir.clone().unwrap_or_else(|| String::from("."));
+    let abi_path = Path::new(&output_dir).join(format!("{}.abi.json", args.prefix));
+    let abi = crate::abi::load(&abi_path.to_string_lossy())?;
+    let constructor = abi["functions"].as_array()
+        .and_then(|functions| functions.iter().find(|function| function["name"] == "constructor"))
+        .map_or(json!({}), |constructor| params(constructor["inputs"].as_array().map_or(&[][..], Vec::as_slice)));
+
+    let script = PathBuf::from(args.output.clone().unwrap_or_else(|| args.lang.file_name(&args.prefix)));
+    if script.exists() && !args.force {
+        return Err(SoldError::ArgumentError(format!(
+            "{} already exists, use --force to overwrite it", script.display()
+        )))
+    }
+    let artifacts = match args.lang {
+        // Scripts find the artifacts next to themselves
+        ScaffoldLang::Ts | ScaffoldLang::Js => {
+            let script_dir = std::env::current_dir()?.join(&script);
+            let script_dir = script_dir.parent().unwrap_or_else(|| Path::new("/"));
+            let artifacts_dir = std::fs::canonicalize(&output_dir)?;
+            relative(&artifacts_dir, script_dir).to_string_lossy().into_owned()
+        }
+        ScaffoldLang::Rust => output_dir.clone(),
+    };
+    let indent = match args.lang {
+        ScaffoldLang::Rust => "    ",
+        _ => "",
+    };
+    let text = args.lang.template()
+        .replace("{{name}}", &args.prefix)
+        .replace("{{version}}", env!("CARGO_PKG_VERSION"))
+        .replace("{{script}}", &script.to_string_lossy())
+        .replace("{{artifacts}}", &artifacts.replace('\\', "/"))
+        .replace("{{initial_data}}", &json_text(&initial_data(&abi), indent))
+        .replace("{{constructor_params}}", &json_text(&constructor, indent));
+    std::fs::write(&script, text)?;
+    println!("Written {}", script.display());
+    Ok(())
+}
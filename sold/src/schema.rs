@@ -0,0 +1,190 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// JSON Schema of the parameters of ABI functions and events, in the JSON form taken by
+// the SDKs: integers as numbers or decimal/hex strings, addresses as `wc:hex`, bytes as
+// hex, cells as base64, tuples as objects and maps as objects keyed by the stringified key.
+
+use serde_json::{json, Map, Value};
+
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct SchemaArgs {
+    /// ABI file, in JSON or human-readable format
+    #[clap(value_parser)]
+    pub input: String,
+    /// Only the function or event with this name
+    #[clap(long, value_parser)]
+    pub function: Option<String>,
+}
+
+const DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+fn integer(signed: bool) -> Value {
+    let pattern = if signed { "^-?(0x[0-9a-fA-F]+|[0-9]+)$" } else { "^(0x[0-9a-fA-F]+|[0-9]+)$" };
+    let mut number = json!({ "type": "integer" });
+    if !signed {
+        number["minimum"] = json!(0);
+    }
+    json!({ "anyOf": [ number, { "type": "string", "pattern": pattern } ] })
+}
+
+fn hex(bytes: Option<usize>) -> Value {
+    match bytes {
+        Some(n) => json!({ "type": "string", "pattern": format!("^[0-9a-fA-F]{{{}}}$", n * 2) }),
+        None => json!({ "type": "string", "pattern": "^[0-9a-fA-F]*$" }),
+    }
+}
+
+/// Pattern of a map key, which JSON gives as a string
+fn key_pattern(ty: &str) -> Option<&'static str> {
+    if ty.starts_with("address") {
+        Some("^-?[0-9]+:[0-9a-fA-F]{64}$")
+    } else if ty.starts_with("uint") {
+        Some("^(0x[0-9a-fA-F]+|[0-9]+)$")
+    } else if ty.starts_with("int") {
+        Some("^-?(0x[0-9a-fA-F]+|[0-9]+)$")
+    } else {
+        None
+    }
+}
+
+/// Splits `outer(inner)` into its parts.
+fn wrapped<'a>(ty: &'a str, outer: &str) -> Option<&'a str> {
+    ty.strip_prefix(outer)?.strip_prefix('(')?.strip_suffix(')')
+}
+
+fn split_map(inner: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => return Some((inner[..i].trim(), inner[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn type_schema(ty: &str, components: &[Value]) -> Result<Value> {
+    let unknown = || SoldError::InvalidOutput(format!("Unsupported ABI type \"{}\"", ty));
+    if let Some(prefix) = ty.strip_suffix(']') {
+        let open = prefix.rfind('[').ok_or_else(unknown)?;
+        let items = type_schema(&prefix[..open], components)?;
+        let size = &prefix[open + 1..];
+        return Ok(if size.is_empty() {
+            json!({ "type": "array", "items": items })
+        } else {
+            let size: usize = size.parse().map_err(|_| unknown())?;
+            json!({ "type": "array", "items": items, "minItems": size, "maxItems": size })
+        })
+    }
+    if let Some(inner) = wrapped(ty, "optional") {
+        return Ok(json!({ "anyOf": [ type_schema(inner, components)?, { "type": "null" } ] }))
+    }
+    if let Some(inner) = wrapped(ty, "ref") {
+        return type_schema(inner, components)
+    }
+    if let Some(inner) = wrapped(ty, "map") {
+        let (key, value) = split_map(inner).ok_or_else(unknown)?;
+        let mut schema = json!({ "type": "object", "additionalProperties": type_schema(value, components)? });
+        if let Some(pattern) = key_pattern(key) {
+            schema["propertyNames"] = json!({ "pattern": pattern });
+        }
+        return Ok(schema)
+    }
+    Ok(match ty {
+        "tuple" => params_schema(components)?,
+        "bool" => json!({ "type": "boolean" }),
+        "string" => json!({ "type": "string" }),
+        "bytes" => hex(None),
+        "cell" => json!({ "type": "string", "contentEncoding": "base64" }),
+        "address" | "address_std" => json!({ "type": "string", "pattern": "^-?[0-9]+:[0-9a-fA-F]{64}$" }),
+        "gram" | "token" => integer(false),
+        _ => {
+            if let Some(n) = ty.strip_prefix("fixedbytes") {
+                hex(Some(n.parse().map_err(|_| unknown())?))
+            } else if ty.starts_with("uint") || ty.starts_with("varuint") {
+                integer(false)
+            } else if ty.starts_with("int") || ty.starts_with("varint") {
+                integer(true)
+            } else {
+                return Err(unknown())
+            }
+        }
+    })
+}
+
+/// Object with a property per parameter, all of them required
+fn params_schema(params: &[Value]) -> Result<Value> {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for param in params {
+        let name = param["name"].as_str().unwrap_or_default();
+        let ty = param["type"].as_str().unwrap_or_default();
+        let components = param["components"].as_array().map_or(&[][..], Vec::as_slice);
+        properties.insert(name.to_owned(), type_schema(ty, components)?);
+        required.push(name.to_owned());
+    }
+    Ok(json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    }))
+}
+
+fn with_draft(mut schema: Value, title: String) -> Value {
+    schema["$schema"] = json!(DRAFT);
+    schema["title"] = json!(title);
+    schema
+}
+
+fn list(abi: &Value, key: &str) -> Vec<Value> {
+    abi[key].as_array().cloned().unwrap_or_default()
+}
+
+/// Schemas of `inputs` and `outputs` of each function and of `inputs` of each event.
+pub fn generate(abi: &Value, only: Option<&str>) -> Result<Value> {
+    let selected = |item: &Value| only.map_or(true, |name| item["name"] == name);
+    let mut functions = Map::new();
+    for function in list(abi, "functions").iter().filter(|item| selected(item)) {
+        let name = function["name"].as_str().unwrap_or_default();
+        functions.insert(name.to_owned(), json!({
+            "inputs": with_draft(params_schema(&list(function, "inputs"))?, format!("{} inputs", name)),
+            "outputs": with_draft(params_schema(&list(function, "outputs"))?, format!("{} outputs", name)),
+        }));
+    }
+    let mut events = Map::new();
+    for event in list(abi, "events").iter().filter(|item| selected(item)) {
+        let name = event["name"].as_str().unwrap_or_default();
+        events.insert(name.to_owned(), json!({
+            "inputs": with_draft(params_schema(&list(event, "inputs"))?, format!("{} event", name)),
+        }));
+    }
+    if let Some(name) = only {
+        if functions.is_empty() && events.is_empty() {
+            return Err(SoldError::ArgumentError(format!("No function or event named \"{}\" in the ABI", name)))
+        }
+    }
+    Ok(json!({ "functions": functions, "events": events }))
+}
+
+pub fn schema(args: SchemaArgs) -> Status {
+    let abi = crate::abi::load(&args.input)?;
+    let schemas = generate(&abi, args.function.as_deref())?;
+    println!("{}", serde_json::to_string_pretty(&schemas)?);
+    Ok(())
+}
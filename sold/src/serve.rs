@@ -0,0 +1,178 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Line-delimited JSON protocol over stdio for tools that keep sold running as a child
+// process. Every line of stdin is a request `{"id": ..., "method": ..., "params": {...}}`
+// and every line of stdout a response `{"id": ..., "result": ...}` or
+// `{"id": ..., "error": {"message": ..., "diagnostics": [...]}}`. Methods:
+//
+//   version  compiler and sold versions
+//   compile  builds with `params.args`, the command line without `sold`; the result is the
+//            build output, as in build-report.json
+//   abi      ABI of `params.input`, `params.contract` if given, without writing files
+//   cancel   cancels the request `params.id` if it hasn't started yet
+//
+// Requests are served one at a time in order, stdin is read meanwhile so that cancels are
+// seen. Diagnostics are printed on stderr as in a regular build, and so is what a build
+// prints on stdout otherwise, which carries only responses here. Sources unchanged since an
+// earlier request are not read again, see source_cache.rs.
+
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+use clap::Parser;
+use serde_json::{json, Value};
+
+use crate::diagnostics::ErrorFormat;
+use crate::error::{Result, SoldError, Status};
+
+struct Request {
+    id: Value,
+    method: String,
+    params: Value,
+}
+
+fn respond(output: &Mutex<std::io::Stdout>, response: &Value) {
+    let mut output = output.lock().unwrap_or_else(|e| e.into_inner());
+    // A closed stdout means the client is gone, there is no one to tell
+    let _ = writeln!(output, "{}", response).and_then(|_| output.flush());
+}
+
+fn error_response(id: &Value, e: &SoldError) -> Value {
+    let mut error = json!({ "message": e.to_string() });
+    if let SoldError::CompilationFailed { diagnostics } = e {
+        error["diagnostics"] = json!(diagnostics);
+    }
+    json!({ "id": id, "error": error })
+}
+
+fn parse_args(params: &Value) -> Result<crate::Args> {
+    let args: Vec<String> = params["args"].as_array()
+        .ok_or_else(|| SoldError::ArgumentError("params.args must be an array of strings".to_owned()))?
+        .iter()
+        .map(|arg| arg.as_str().map(str::to_owned))
+        .collect::<Option<_>>()
+        .ok_or_else(|| SoldError::ArgumentError("params.args must be an array of strings".to_owned()))?;
+    let args = crate::Args::try_parse_from(std::iter::once("sold".to_owned()).chain(args))
+        .map_err(|e| SoldError::ArgumentError(e.to_string()))?;
+    // These write to stdout, which carries the protocol
+    if args.command.is_some() || args.function_ids || args.interface || args.storage_fees || args.via_ast || args.serve_stdio
+        || args.watch || args.error_format != ErrorFormat::Human {
+        return Err(SoldError::ArgumentError(
+            "Subcommands, --function-ids, --interface, --storage-fees, --via-ast, --watch and --error-format other than human are not supported here".to_owned()
+        ))
+    }
+    Ok(args)
+}
+
+fn abi(params: &Value) -> Result<Value> {
+    let input = params["input"].as_str()
+        .ok_or_else(|| SoldError::ArgumentError("params.input must be a string".to_owned()))?;
+    let mut argv = vec!["sold".to_owned(), input.to_owned(), "--abi-json".to_owned()];
+    for path in params["includePaths"].as_array().map_or(&[][..], Vec::as_slice) {
+        argv.push("--include-path".to_owned());
+        argv.push(path.as_str().unwrap_or_default().to_owned());
+    }
+    let args = crate::Args::try_parse_from(argv)
+        .map_err(|e| SoldError::ArgumentError(e.to_string()))?;
+    let canonical = Path::new(input).canonicalize()
+        .map_err(|_| SoldError::SourceNotFound(input.to_owned()))?;
+    let canonical = canonical.to_string_lossy();
+    let mut res = crate::compile(&args, &canonical)?;
+    let contract = params["contract"].as_str().map(str::to_owned);
+    let (name, out) = crate::parse_comp_result(&mut res, &canonical, contract, false, args.error_format)?;
+    Ok(json!({ "contract": name, "abi": out.abi }))
+}
+
+fn handle(request: &Request) -> Result<Value> {
+    match request.method.as_str() {
+        "version" => Ok(json!({
+            "compiler": crate::solidity_version(),
+            "sold": env!("CARGO_PKG_VERSION"),
+        })),
+        "compile" => Ok(serde_json::to_value(crate::build(parse_args(&request.params)?)?)?),
+        "abi" => abi(&request.params),
+        method => Err(SoldError::ArgumentError(format!("Unknown method \"{}\"", method))),
+    }
+}
+
+/// Keeps stdout for the responses
+struct Stderr;
+
+impl crate::messages::MessageHandler for Stderr {
+    fn output(&self, text: &str) {
+        eprintln!("{}", text);
+    }
+
+    fn message(&self, _level: crate::messages::Level, text: &str) {
+        eprintln!("{}", text);
+    }
+}
+
+pub fn serve() -> Status {
+    crate::source_cache::enable();
+    crate::messages::set_handler(Arc::new(Stderr));
+    let output = Arc::new(Mutex::new(std::io::stdout()));
+    let cancelled = Arc::new(Mutex::new(HashSet::new()));
+    let (sender, receiver) = mpsc::channel::<Request>();
+
+    let reader = {
+        let output = output.clone();
+        let cancelled = cancelled.clone();
+        std::thread::spawn(move || -> Result<()> {
+            for line in std::io::stdin().lock().lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue
+                }
+                let message: Value = match serde_json::from_str(&line) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        respond(&output, &error_response(&Value::Null, &SoldError::JsonError(e)));
+                        continue
+                    }
+                };
+                let request = Request {
+                    id: message["id"].clone(),
+                    method: message["method"].as_str().unwrap_or_default().to_owned(),
+                    params: message["params"].clone(),
+                };
+                if request.method == "cancel" {
+                    let target = request.params["id"].to_string();
+                    cancelled.lock().unwrap_or_else(|e| e.into_inner()).insert(target);
+                    respond(&output, &json!({ "id": request.id, "result": null }));
+                } else if sender.send(request).is_err() {
+                    break
+                }
+            }
+            Ok(())
+        })
+    };
+
+    for request in receiver {
+        let was_cancelled = cancelled.lock().unwrap_or_else(|e| e.into_inner()).remove(&request.id.to_string());
+        let response = if was_cancelled {
+            json!({ "id": request.id, "error": { "message": "Cancelled" } })
+        } else {
+            match handle(&request) {
+                Ok(result) => json!({ "id": request.id, "result": result }),
+                Err(e) => error_response(&request.id, &e),
+            }
+        };
+        respond(&output, &response);
+    }
+    reader.join().unwrap_or(Ok(()))
+}
@@ -0,0 +1,229 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Explains how the code of a contract changed in size between two builds. Every code cell
+// is attributed to the owner most of its debug locations belong to: the function, modifier
+// or constant declared at those lines of the source, found by scanning the source files
+// named in the debug map. Cells and bits are then summed per owner and compared.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde_json::Value;
+use ton_types::Cell;
+
+use crate::error::{Result, SoldError, Status};
+use crate::lexer::{self, TokenKind};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct SizeDiffArgs {
+    /// TVC of the old build
+    #[clap(value_parser)]
+    pub old: String,
+    /// TVC of the new build
+    #[clap(value_parser)]
+    pub new: String,
+    /// Debug maps of the old and the new build
+    #[clap(long, value_parser, number_of_values = 2, required = true)]
+    pub debug: Vec<String>,
+    /// Print only the owners whose size changed by at least this many bits
+    #[clap(long, value_parser, default_value_t = 1)]
+    pub min_bits: u64,
+}
+
+const NO_DEBUG_INFO: &str = "<no debug info>";
+
+#[derive(Clone, Copy, Default)]
+struct Size {
+    cells: u64,
+    bits: u64,
+}
+
+/// Declaration spanning the lines `first..=last` of a source
+struct Owner {
+    first: usize,
+    last: usize,
+    name: String,
+}
+
+const MEMBER_KEYWORDS: &[&str] = &["function", "constructor", "modifier", "receive", "fallback", "onBounce", "onTickTock"];
+
+/// Functions, modifiers and constants of the contracts of `text`, with their lines
+fn source_owners(text: &str) -> Vec<Owner> {
+    let tokens: Vec<_> = lexer::tokenize(text).into_iter().filter(|t| !t.is_trivia()).collect();
+    let mut owners = vec![];
+    let mut contract = String::new();
+    let mut depth = 0;
+    // First line and name of the member being scanned
+    let mut member: Option<(usize, String)> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match (token.kind, token.text) {
+            (TokenKind::Punct, "{") => depth += 1,
+            (TokenKind::Punct, "}") => {
+                depth -= 1;
+                if depth == 1 {
+                    if let Some((first, name)) = member.take() {
+                        owners.push(Owner { first, last: lexer::line_column(text, token.start).0, name });
+                    }
+                }
+            }
+            (TokenKind::Punct, ";") if depth == 1 => {
+                // Declarations without a body
+                member = None;
+            }
+            (TokenKind::Identifier, "contract" | "library" | "interface") if depth == 0 => {
+                if let Some(name) = tokens.get(i + 1) {
+                    contract = name.text.to_owned();
+                }
+            }
+            (TokenKind::Identifier, keyword) if depth == 1 && member.is_none() && MEMBER_KEYWORDS.contains(&keyword) => {
+                let name = match tokens.get(i + 1) {
+                    Some(next) if next.kind == TokenKind::Identifier && matches!(keyword, "function" | "modifier") => next.text,
+                    _ => keyword,
+                };
+                member = Some((lexer::line_column(text, token.start).0, format!("{}.{}", contract, name)));
+            }
+            (TokenKind::Identifier, "constant") if depth == 1 => {
+                // `type constant NAME = value;`
+                let name = tokens.get(i + 1).map_or("", |t| t.text);
+                let end = tokens[i..].iter().find(|t| t.text == ";").map_or(token.start, |t| t.start);
+                let first = tokens[..i].iter().rev()
+                    .find(|t| t.text == ";" || t.text == "{" || t.text == "}")
+                    .map_or(token.start, |t| t.end());
+                owners.push(Owner {
+                    first: lexer::line_column(text, first).0,
+                    last: lexer::line_column(text, end).0,
+                    name: format!("{}.{} (constant)", contract, name),
+                });
+            }
+            _ => {}
+        }
+    }
+    owners
+}
+
+/// Attribution of code to the declarations of the sources named in a debug map
+#[derive(Default)]
+pub struct Attribution {
+    owners: HashMap<String, Vec<Owner>>,
+}
+
+impl Attribution {
+    fn owners(&mut self, file: &str) -> &[Owner] {
+        self.owners.entry(file.to_owned())
+            .or_insert_with(|| std::fs::read_to_string(file).map_or(vec![], |text| source_owners(&text)))
+    }
+
+    fn owner(&mut self, file: &str, line: usize) -> String {
+        // The narrowest declaration containing the line
+        self.owners(file).iter()
+            .filter(|owner| owner.first <= line && line <= owner.last)
+            .min_by_key(|owner| owner.last - owner.first)
+            .map_or_else(|| format!("{}:{}", file, line), |owner| owner.name.clone())
+    }
+
+    /// The owner most of the debug locations of `cell` belong to
+    pub fn cell_owner(&mut self, cell: &Cell, debug_map: &Value) -> String {
+        let mut votes: BTreeMap<String, usize> = BTreeMap::new();
+        if let Some(positions) = debug_map[cell.repr_hash().to_hex_string()].as_object() {
+            for position in positions.values() {
+                let file = position["filename"].as_str().unwrap_or_default();
+                let line = position["line"].as_u64().unwrap_or_default() as usize;
+                *votes.entry(self.owner(file, line)).or_default() += 1;
+            }
+        }
+        votes.into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map_or_else(|| NO_DEBUG_INFO.to_owned(), |(name, _)| name)
+    }
+
+    /// Declaration of the function `name` in the sources named in `debug_map`, as
+    /// `Contract.name at file:line`
+    pub fn declaration(&mut self, name: &str, debug_map: &Value) -> Option<String> {
+        let files: BTreeSet<String> = debug_map.as_object()?.values()
+            .filter_map(Value::as_object)
+            .flat_map(|positions| positions.values())
+            .filter_map(|position| position["filename"].as_str().map(str::to_owned))
+            .collect();
+        files.into_iter().find_map(|file| {
+            self.owners(&file).iter()
+                .find(|owner| owner.name.rsplit('.').next() == Some(name))
+                .map(|owner| format!("{} at {}:{}", owner.name, file, owner.first))
+        })
+    }
+}
+
+fn load(tvc: &str, debug: &str, attribution: &mut Attribution) -> Result<(Size, BTreeMap<String, Size>)> {
+    let state = ton_utils::program::load_from_file(tvc)
+        .map_err(|_| SoldError::SourceNotFound(tvc.to_owned()))?;
+    let code = state.code.clone()
+        .ok_or_else(|| SoldError::InvalidOutput(format!("{} has no code", tvc)))?;
+    let debug_map: Value = serde_json::from_str(&std::fs::read_to_string(debug)
+        .map_err(|_| SoldError::SourceNotFound(debug.to_owned()))?)?;
+    let cells = cells(&code).map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+
+    let mut total = Size::default();
+    let mut sizes: BTreeMap<String, Size> = BTreeMap::new();
+    for cell in cells {
+        let owner = attribution.cell_owner(&cell, &debug_map);
+        let size = sizes.entry(owner).or_default();
+        size.cells += 1;
+        size.bits += cell.bit_length() as u64;
+        total.cells += 1;
+        total.bits += cell.bit_length() as u64;
+    }
+    Ok((total, sizes))
+}
+
+/// Distinct cells of the tree under `root`
+pub fn cells(root: &Cell) -> ton_types::Result<Vec<Cell>> {
+    let mut res = vec![];
+    let mut visited = HashSet::new();
+    let mut stack = vec![root.clone()];
+    while let Some(cell) = stack.pop() {
+        if !visited.insert(cell.repr_hash()) {
+            continue
+        }
+        for i in 0..cell.references_count() {
+            stack.push(cell.reference(i)?);
+        }
+        res.push(cell);
+    }
+    Ok(res)
+}
+
+pub fn size_diff(args: SizeDiffArgs) -> Status {
+    let mut attribution = Attribution::default();
+    let (old_total, old) = load(&args.old, &args.debug[0], &mut attribution)?;
+    let (new_total, new) = load(&args.new, &args.debug[1], &mut attribution)?;
+
+    println!(
+        "Code: {} -> {} cells ({:+}), {} -> {} bits ({:+})",
+        old_total.cells, new_total.cells, new_total.cells as i64 - old_total.cells as i64,
+        old_total.bits, new_total.bits, new_total.bits as i64 - old_total.bits as i64,
+    );
+    let mut changes: Vec<(&String, i64, i64)> = old.keys().chain(new.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|name| {
+            let before = old.get(name).copied().unwrap_or_default();
+            let after = new.get(name).copied().unwrap_or_default();
+            (name, after.cells as i64 - before.cells as i64, after.bits as i64 - before.bits as i64)
+        })
+        .filter(|(_, _, bits)| *bits != 0 && bits.unsigned_abs() >= args.min_bits)
+        .collect();
+    changes.sort_by(|a, b| b.2.abs().cmp(&a.2.abs()).then_with(|| a.0.cmp(b.0)));
+    for (name, cells, bits) in changes {
+        println!("    {:>+8} bits {:>+5} cells  {}", bits, cells, name);
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Contents of the source files read by the long-running modes, --serve-stdio and the
+// language server, and by multi-input builds, kept across builds. An entry is reused
+// while the size and modification time of its file are unchanged, so an edit to one
+// contract only re-reads that contract rather than every library it imports. The frontend still parses each build afresh, its
+// ASTs are annotated in place by the analysis and can't be shared between builds.
+//
+// Virtual files, the source read from stdin, exist only here and are served whether the
+// cache is enabled or not; so are the in-memory sources of the current compiler, which
+// keeps them in its state.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use lazy_static::lazy_static;
+
+struct Entry {
+    len: u64,
+    modified: SystemTime,
+    content: Arc<Vec<u8>>,
+}
+
+lazy_static! {
+    /// None until a long-running mode enables the cache
+    static ref CACHE: Mutex<Option<HashMap<PathBuf, Entry>>> = Mutex::new(None);
+    static ref VIRTUAL: Mutex<HashMap<PathBuf, Arc<Vec<u8>>>> = Mutex::new(HashMap::new());
+}
+
+/// Serves `content` as the file at `path`, which need not exist.
+pub fn add_virtual(path: &Path, content: Vec<u8>) {
+    VIRTUAL.lock().unwrap().insert(path.to_owned(), Arc::new(content));
+}
+
+/// Content of the virtual file at `path`, a source read from stdin or one given to the
+/// current compiler with `Compiler::add_source`
+pub(crate) fn virtual_content(path: &Path) -> Option<Arc<Vec<u8>>> {
+    if let Some(content) = VIRTUAL.lock().unwrap().get(path) {
+        return Some(content.clone())
+    }
+    crate::compiler::current().state().sources.lock().unwrap().get(path).cloned()
+}
+
+/// Every virtual file, the sources read from stdin and those of the current compiler
+pub(crate) fn virtual_sources() -> Vec<(PathBuf, Vec<u8>)> {
+    let mut sources: HashMap<PathBuf, Arc<Vec<u8>>> = crate::compiler::current().state().sources.lock().unwrap().clone();
+    sources.extend(VIRTUAL.lock().unwrap().iter().map(|(path, content)| (path.clone(), content.clone())));
+    sources.into_iter().map(|(path, content)| (path, content.to_vec())).collect()
+}
+
+pub fn is_virtual(path: &Path) -> bool {
+    virtual_content(path).is_some()
+}
+
+/// Keeps the files read from now on for later builds.
+pub fn enable() {
+    CACHE.lock().unwrap().get_or_insert_with(HashMap::new);
+}
+
+/// Reads `path`, from the cache if it is enabled and the file hasn't changed since.
+pub fn read(path: &Path) -> std::io::Result<Arc<Vec<u8>>> {
+    if let Some(content) = virtual_content(path) {
+        return Ok(content)
+    }
+    let mut cache = CACHE.lock().unwrap();
+    let cache = match cache.as_mut() {
+        Some(cache) => cache,
+        None => return Ok(Arc::new(std::fs::read(path)?)),
+    };
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata.modified()?;
+    if let Some(entry) = cache.get(path) {
+        if entry.len == metadata.len() && entry.modified == modified {
+            tracing::trace!(path = %path.display(), "source cache hit");
+            return Ok(entry.content.clone())
+        }
+    }
+    let content = Arc::new(std::fs::read(path)?);
+    cache.insert(path.to_owned(), Entry { len: metadata.len(), modified, content: content.clone() });
+    Ok(content)
+}
+
+/// Reads `path` as UTF-8 text, see `read`.
+pub fn read_to_string(path: &Path) -> std::io::Result<String> {
+    String::from_utf8(read(path)?.to_vec())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,120 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Source map of the assembly in the Source Map v3 format, which debuggers and coverage
+// tools read for any generated file: every instruction of <prefix>.code is mapped to the
+// source position of the `.loc` directive before it. The directives only name a line, so
+// instructions are mapped to its first non-blank column, found with the line tables the
+// read callback computed for the sources of the build. The debug map maps code cells
+// instead, and so can't tell apart the instructions of a cell.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::asm;
+use crate::compiler;
+use crate::error::Status;
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Serialize)]
+struct SourceMap<'a> {
+    version: u8,
+    file: &'a str,
+    sources: Vec<String>,
+    names: Vec<String>,
+    mappings: String,
+}
+
+/// Appends `value` in the base64 VLQ encoding of source maps
+fn vlq(out: &mut String, value: i64) {
+    let mut rest = if value < 0 { ((-value) << 1) | 1 } else { value << 1 };
+    loop {
+        let mut digit = (rest & 0x1f) as usize;
+        rest >>= 5;
+        if rest > 0 {
+            digit |= 0x20;
+        }
+        out.push(BASE64[digit] as char);
+        if rest == 0 {
+            break
+        }
+    }
+}
+
+/// Column of the first non-blank character of the 1-based `line` of `file`
+fn first_column(file: &str, line: usize, lines: &BTreeMap<String, (Vec<usize>, Option<String>)>) -> usize {
+    let (ends, text) = match lines.get(file) {
+        Some(entry) => entry,
+        None => return 0,
+    };
+    let start = match line {
+        0 | 1 => 0,
+        _ => ends.get(line - 2).copied().unwrap_or_default(),
+    };
+    text.as_deref()
+        .and_then(|text| text.get(start..))
+        .and_then(|rest| rest.lines().next())
+        .map_or(0, |source_line| source_line.len() - source_line.trim_start().len())
+}
+
+/// Writes the source map of `text`, the assembly with its `.loc` directives, as written to
+/// `file`, with the directives and comments removed if `stripped`.
+pub fn write(out: &mut impl Write, file: &str, text: &str, stripped: bool) -> Status {
+    let lines: BTreeMap<String, (Vec<usize>, Option<String>)> = compiler::current().state().lines.lock().unwrap()
+        .iter()
+        .map(|(path, ends)| {
+            let text = crate::source_cache::read_to_string(std::path::Path::new(path)).ok();
+            (path.clone(), (ends.clone(), text))
+        })
+        .collect();
+    let mut sources: Vec<String> = vec![];
+    let mut mappings = String::new();
+    let mut loc = None;
+    // Fields relative to the previous segment, as the format has them
+    let (mut prev_source, mut prev_line, mut prev_column) = (0i64, 0i64, 0i64);
+    let mut first_line = true;
+    for line in text.lines() {
+        if let Some(rest) = line.trim_start().strip_prefix(".loc ") {
+            loc = asm::parse_loc(rest);
+        }
+        if stripped && asm::is_stripped(line) {
+            continue
+        }
+        if !first_line {
+            mappings.push(';');
+        }
+        first_line = false;
+        let code = asm::strip_comment(line);
+        let is_instruction = !code.trim().is_empty() && !code.trim_start().starts_with('.');
+        if let (true, Some(loc)) = (is_instruction, &loc) {
+            if !sources.contains(&loc.file) {
+                sources.push(loc.file.clone());
+            }
+            let source = sources.iter().position(|source| *source == loc.file).unwrap_or_default() as i64;
+            let source_line = loc.line.saturating_sub(1) as i64;
+            let source_column = first_column(&loc.file, loc.line, &lines) as i64;
+            vlq(&mut mappings, (code.len() - code.trim_start().len()) as i64);
+            vlq(&mut mappings, source - prev_source);
+            vlq(&mut mappings, source_line - prev_line);
+            vlq(&mut mappings, source_column - prev_column);
+            (prev_source, prev_line, prev_column) = (source, source_line, source_column);
+        }
+    }
+    let map = SourceMap { version: 3, file, sources, names: vec![], mappings };
+    serde_json::to_writer_pretty(&mut *out, &map)?;
+    writeln!(out)?;
+    Ok(())
+}
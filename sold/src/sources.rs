@@ -0,0 +1,132 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Discovery of the source files the rewriting tools work on: directories are searched
+// for .sol files and every file pulls in the local files it imports.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, SoldError};
+use crate::lexer::{self, TokenKind};
+
+/// Expands the given files and directories into the .sol files they name.
+pub fn expand(paths: &[String]) -> Result<Vec<PathBuf>> {
+    let mut res = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            find_sol_files(path, &mut res)?;
+        } else if path.is_file() {
+            res.push(path.to_path_buf());
+        } else {
+            return Err(SoldError::ArgumentError(format!("File not found: {}", path.display())))
+        }
+    }
+    res.sort();
+    Ok(res)
+}
+
+/// Reads the source on stdin and makes it the virtual file `name`, relative to the current
+/// directory, which its relative imports are resolved against. Returns its path.
+pub fn stdin(name: &str) -> Result<String> {
+    let mut content = vec![];
+    std::io::Read::read_to_end(&mut std::io::stdin(), &mut content)?;
+    let path = std::env::current_dir()?.join(name);
+    crate::source_cache::add_virtual(&path, content);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Replaces the inputs whose file name has the wildcards `*` or `?` by the files of their
+/// directory matching it, for shells that don't expand them. Other inputs are kept as given.
+pub fn glob(inputs: &[String]) -> Result<Vec<String>> {
+    let mut res = vec![];
+    for input in inputs {
+        let path = Path::new(input);
+        let pattern = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        if !pattern.contains(|c| c == '*' || c == '?') {
+            res.push(input.clone());
+            continue
+        }
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        let mut matched = vec![];
+        for entry in std::fs::read_dir(dir.unwrap_or_else(|| Path::new(".")))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type()?.is_file() && crate::abi::matches(&pattern, &name) {
+                matched.push(dir.map_or_else(|| name.clone(), |dir| dir.join(&name).to_string_lossy().into_owned()));
+            }
+        }
+        if matched.is_empty() {
+            return Err(SoldError::ArgumentError(format!("No file matches {}", input)))
+        }
+        matched.sort();
+        res.extend(matched);
+    }
+    Ok(res)
+}
+
+/// Expands the given files and directories into the set of sources they import.
+pub fn collect(paths: &[String]) -> Result<Vec<PathBuf>> {
+    let mut pending = expand(paths)?;
+    let mut seen = BTreeSet::new();
+    while let Some(path) = pending.pop() {
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        if seen.contains(&path) {
+            continue
+        }
+        let text = crate::source_cache::read_to_string(&path)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for import in imports(&text) {
+            // Imports that don't name a local file are resolved by the compiler
+            // through include paths and remappings, they are left alone.
+            let imported = dir.join(import);
+            if imported.is_file() {
+                pending.push(imported);
+            }
+        }
+        seen.insert(path);
+    }
+    Ok(seen.into_iter().collect())
+}
+
+fn find_sol_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_sol_files(&path, out)?;
+        } else if path.extension().map_or(false, |ext| ext == "sol") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Paths of all import directives in the source
+pub fn imports(text: &str) -> Vec<&str> {
+    let tokens = lexer::tokenize(text);
+    let mut res = vec![];
+    let mut code = tokens.iter().filter(|t| !t.is_trivia());
+    while let Some(token) = code.next() {
+        if token.kind != TokenKind::Identifier || token.text != "import" {
+            continue
+        }
+        let path = code.by_ref()
+            .take_while(|t| t.text != ";")
+            .find(|t| t.kind == TokenKind::String && t.text.len() >= 2);
+        if let Some(path) = path {
+            res.push(&path.text[1..path.text.len() - 1]);
+        }
+    }
+    res
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Raw standard JSON for library users and tools needing settings the typed API doesn't
+// model. The input goes to the frontend as is and its output comes back as is; sources
+// are read through an ImportResolver, and defines and line tables are handled as in a
+// build. --standard-json does the same for a document on stdin or in a file.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::compiler::Compiler;
+use crate::error::{Result, SoldError, Status};
+use crate::{defines, messages};
+
+/// Supplies the sources the frontend asks for, by the path it resolved from the `urls` of
+/// the input, the imports and the include paths.
+pub trait ImportResolver {
+    /// Contents of the source at `path`, or the message the frontend reports on failure
+    fn read(&self, path: &str) -> std::result::Result<Vec<u8>, String>;
+}
+
+/// Reads sources from the file system, as builds do
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileResolver;
+
+impl ImportResolver for FileResolver {
+    fn read(&self, path: &str) -> std::result::Result<Vec<u8>, String> {
+        crate::source_cache::read(std::path::Path::new(path))
+            .map(|buf| buf.to_vec())
+            .map_err(|e| format!("Failed to open file: {}", e))
+    }
+}
+
+/// Reads sources only from a map of path to text, for compilations never touching the
+/// file system
+#[derive(Clone, Debug, Default)]
+pub struct MemoryResolver {
+    pub sources: HashMap<String, String>,
+}
+
+impl ImportResolver for MemoryResolver {
+    fn read(&self, path: &str) -> std::result::Result<Vec<u8>, String> {
+        self.sources.get(path)
+            .map(|text| text.as_bytes().to_vec())
+            .ok_or_else(|| format!("Source \"{}\" not found", path))
+    }
+}
+
+/// Compiles standard JSON `input` with the current compiler, the default one outside
+/// `Compiler::compile_standard_json`, and returns the standard JSON output.
+pub fn compile_standard_json(input: &str, resolver: &dyn ImportResolver) -> Result<String> {
+    let input = std::ffi::CString::new(input)
+        .map_err(|_| SoldError::ArgumentError("Standard JSON input contains a NUL byte".to_owned()))?;
+    crate::call_compiler_with(&input, resolver, |bytes| {
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| SoldError::InvalidOutput("Standard JSON output is not UTF-8".to_owned()))
+    })
+}
+
+/// Compiles the standard JSON in `path`, stdin if it is `-`, with the given `-D` defines
+/// and prints the output.
+pub fn run(path: &str, define: &[String]) -> Status {
+    let mut input = String::new();
+    if path == "-" {
+        std::io::stdin().read_to_string(&mut input)?;
+    } else {
+        input = std::fs::read_to_string(path)
+            .map_err(|_| SoldError::SourceNotFound(path.to_owned()))?;
+    }
+    defines::set(define.iter().map(String::as_str).map(defines::parse).collect::<Result<_>>()?)?;
+    messages::output(&compile_standard_json(&input, &FileResolver)?);
+    Ok(())
+}
+
+impl Compiler {
+    /// Same as `sold_lib::compile_standard_json()`, with the defines and the frontend of
+    /// this compiler
+    pub fn compile_standard_json(&self, input: &str, resolver: &dyn ImportResolver) -> Result<String> {
+        self.enter(|| compile_standard_json(input, resolver))
+    }
+}
@@ -0,0 +1,166 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Which functions of the runtime library (stdlib_sol.tvm or the --lib replacement) a
+// contract links. Generated code calls them as `CALL $name_macro$`, so the linked ones are
+// those referenced from the contract assembly, directly or through other library
+// functions; all others are stripped. The size of a linked function is the bits of the
+// code cells most of whose debug locations fall on the `.loc` lines of its body.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Write;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use ton_types::Cell;
+
+use crate::error::{SoldError, Status};
+
+struct Function {
+    /// Name of the `.globl` entry, or of the macro for macros without one
+    name: String,
+    body: String,
+}
+
+#[derive(Serialize)]
+struct Linked {
+    name: String,
+    /// Call sites in the contract and in other linked functions
+    references: usize,
+    cells: u64,
+    bits: u64,
+}
+
+#[derive(Serialize)]
+struct Report {
+    library: String,
+    linked: Vec<Linked>,
+    stripped: Vec<String>,
+}
+
+/// Macros of the library by macro name
+fn functions(library: &str) -> BTreeMap<String, Function> {
+    let mut res = BTreeMap::new();
+    let mut current: Option<(String, String)> = None;
+    for line in library.lines() {
+        let directive = line.split_whitespace().next().unwrap_or_default();
+        if matches!(directive, ".macro" | ".globl" | ".fragment") {
+            if let Some((macro_name, body)) = current.take() {
+                let name = macro_name.strip_suffix("_macro").unwrap_or(&macro_name).to_owned();
+                res.insert(macro_name, Function { name, body });
+            }
+            if directive == ".macro" {
+                if let Some(name) = line.split_whitespace().nth(1) {
+                    current = Some((name.to_owned(), String::new()));
+                }
+            }
+            continue
+        }
+        if let Some((_, body)) = current.as_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    if let Some((macro_name, body)) = current {
+        let name = macro_name.strip_suffix("_macro").unwrap_or(&macro_name).to_owned();
+        res.insert(macro_name, Function { name, body });
+    }
+    res
+}
+
+/// Names of the macros called in `code`
+fn calls<'a>(code: &'a str, pattern: &Regex) -> Vec<&'a str> {
+    pattern.captures_iter(code).filter_map(|c| c.get(1).map(|m| m.as_str())).collect()
+}
+
+/// `.loc` positions of `body`, but line 0, which every function ends with
+fn locations(body: &str) -> Vec<(String, u64)> {
+    body.lines()
+        .filter_map(|line| line.trim().strip_prefix(".loc"))
+        .filter_map(|rest| {
+            let (file, line) = rest.rsplit_once(',')?;
+            Some((file.trim().to_owned(), line.trim().parse().ok()?))
+        })
+        .filter(|(_, line)| *line != 0)
+        .collect()
+}
+
+/// Writes the library functions `assembly` links with their size in `code`.
+pub fn write(
+    out: &mut impl Write,
+    library_name: &str,
+    library: &str,
+    assembly: &str,
+    code: Option<&Cell>,
+    debug_map: &Value,
+) -> Status {
+    let functions = functions(library);
+    let pattern = Regex::new(r"\$([A-Za-z0-9_]+)\$").unwrap();
+
+    // Call sites of every library function reachable from the contract
+    let mut references: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut pending: Vec<&str> = calls(assembly, &pattern);
+    let mut visited = BTreeSet::new();
+    while let Some(name) = pending.pop() {
+        if !functions.contains_key(name) {
+            continue
+        }
+        *references.entry(name).or_default() += 1;
+        if visited.insert(name) {
+            pending.extend(calls(&functions[name].body, &pattern));
+        }
+    }
+
+    let mut owners: HashMap<(String, u64), &str> = HashMap::new();
+    for macro_name in references.keys() {
+        for location in locations(&functions[*macro_name].body) {
+            owners.insert(location, macro_name);
+        }
+    }
+    let mut sizes: HashMap<&str, (u64, u64)> = HashMap::new();
+    if let Some(code) = code {
+        let cells = crate::size_diff::cells(code).map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+        for cell in cells {
+            let mut votes: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+            if let Some(positions) = debug_map[cell.repr_hash().to_hex_string()].as_object() {
+                for position in positions.values() {
+                    let file = position["filename"].as_str().unwrap_or_default().to_owned();
+                    let line = position["line"].as_u64().unwrap_or_default();
+                    *votes.entry(owners.get(&(file, line)).copied()).or_default() += 1;
+                }
+            }
+            if let Some((Some(owner), _)) = votes.into_iter().max_by_key(|(_, count)| *count) {
+                let size = sizes.entry(owner).or_default();
+                size.0 += 1;
+                size.1 += cell.bit_length() as u64;
+            }
+        }
+    }
+
+    let mut linked: Vec<Linked> = references.iter()
+        .map(|(macro_name, count)| {
+            let (cells, bits) = sizes.get(macro_name).copied().unwrap_or_default();
+            Linked { name: functions[*macro_name].name.clone(), references: *count, cells, bits }
+        })
+        .collect();
+    linked.sort_by(|a, b| b.bits.cmp(&a.bits).then_with(|| a.name.cmp(&b.name)));
+    let stripped = functions.iter()
+        .filter(|(macro_name, _)| !references.contains_key(macro_name.as_str()))
+        .map(|(_, function)| function.name.clone())
+        .collect();
+    let report = Report { library: library_name.to_owned(), linked, stripped };
+    serde_json::to_writer_pretty(&mut *out, &report)?;
+    writeln!(out)?;
+    Ok(())
+}
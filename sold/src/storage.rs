@@ -0,0 +1,192 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Storage fee estimation. The fee of an account is charged for the unique cells and bits
+// of its state: (bits * bit_price_ps + cells * cell_price_ps) * seconds / 2^16 nanotokens,
+// with prices taken from config param 18. Mapping growth is derived from the `fields`
+// section of the ABI, assuming every new dictionary entry adds a leaf and a fork cell.
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde_json::Value;
+use ton_types::Cell;
+
+use crate::error::{Result, SoldError, Status};
+use crate::messages;
+
+const SECONDS_PER_YEAR: u128 = 365 * 24 * 3600;
+const MAX_CELL_BITS: u64 = 1023;
+
+/// Storage prices of the workchain, see config param 18
+#[derive(Debug, Deserialize)]
+pub struct StoragePrices {
+    #[serde(default = "default_bit_price")]
+    pub bit_price_ps: u64,
+    #[serde(default = "default_cell_price")]
+    pub cell_price_ps: u64,
+}
+
+fn default_bit_price() -> u64 { 1 }
+fn default_cell_price() -> u64 { 500 }
+
+impl Default for StoragePrices {
+    fn default() -> Self {
+        StoragePrices { bit_price_ps: default_bit_price(), cell_price_ps: default_cell_price() }
+    }
+}
+
+impl StoragePrices {
+    fn per_year(&self, bits: u64, cells: u64) -> u128 {
+        let units = bits as u128 * self.bit_price_ps as u128 + cells as u128 * self.cell_price_ps as u128;
+        (units * SECONDS_PER_YEAR + 0xffff) >> 16
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Size {
+    bits: u64,
+    refs: u64,
+}
+
+impl Size {
+    fn new(bits: u64, refs: u64) -> Size {
+        Size { bits, refs }
+    }
+
+    fn add(self, other: Size) -> Size {
+        Size::new(self.bits + other.bits, self.refs + other.refs)
+    }
+}
+
+pub fn report(name: &str, state: &Cell, abi: &Value, prices: &StoragePrices) -> Status {
+    let (bits, cells) = count_unique(state)
+        .map_err(|e| SoldError::InvalidOutput(format!("Failed to inspect the contract state: {}", e)))?;
+    let mut text = format!("Storage of {}: {} bits, {} cells", name, bits, cells);
+    text += &format!("\n    {} per year", tokens(prices.per_year(bits, cells)));
+
+    let fields = abi["fields"].as_array().cloned().unwrap_or_default();
+    let mut growth = vec![];
+    for field in &fields {
+        if let Some(entry) = entry_size(field)? {
+            growth.push((field["name"].as_str().unwrap_or_default(), field["type"].as_str().unwrap_or_default(), entry));
+        }
+    }
+    if !growth.is_empty() {
+        text += "\nGrowth per mapping entry:";
+        for (name, ty, (bits, cells)) in growth {
+            text += &format!(
+                "\n    {} {}: +{} bits, +{} cells, +{} per year",
+                name, ty, bits, cells, tokens(prices.per_year(bits, cells))
+            );
+        }
+    }
+    messages::output(&text);
+    Ok(())
+}
+
+pub(crate) fn count_unique(root: &Cell) -> ton_types::Result<(u64, u64)> {
+    let mut visited = HashSet::new();
+    let mut stack = vec![root.clone()];
+    let (mut bits, mut cells) = (0, 0);
+    while let Some(cell) = stack.pop() {
+        if !visited.insert(cell.repr_hash()) {
+            continue
+        }
+        bits += cell.bit_length() as u64;
+        cells += 1;
+        for i in 0..cell.references_count() {
+            stack.push(cell.reference(i)?);
+        }
+    }
+    Ok((bits, cells))
+}
+
+/// Bits and cells added by one more entry of a mapping or array field.
+fn entry_size(field: &Value) -> Result<Option<(u64, u64)>> {
+    let ty = field["type"].as_str().unwrap_or_default();
+    let components = field["components"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let (key, value) = if let Some(args) = ty.strip_prefix("map(").and_then(|t| t.strip_suffix(')')) {
+        let (key, value) = split_map_args(args)
+            .ok_or_else(|| SoldError::InvalidOutput(format!("Unsupported ABI type \"{}\"", ty)))?;
+        (type_size(key, &[])?, type_size(value, components)?)
+    } else if let Some(item) = ty.strip_suffix("[]") {
+        (Size::new(32, 0), type_size(item, components)?)
+    } else {
+        return Ok(None)
+    };
+    // The leaf keeps the remaining key bits as a label; a new fork with a short label is created above it
+    let label_len = 2 * (64 - key.bits.leading_zeros() as u64);
+    let leaf = key.bits + label_len + value.bits;
+    let mut cells = 2 + value.refs;
+    if leaf > MAX_CELL_BITS {
+        cells += 1;
+    }
+    Ok(Some((leaf + label_len, cells)))
+}
+
+fn split_map_args(args: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => return Some((args[..i].trim(), args[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn type_size(ty: &str, components: &[Value]) -> Result<Size> {
+    let number = |prefix: &str| ty.strip_prefix(prefix).and_then(|n| n.parse::<u64>().ok());
+    let size = if ty.ends_with(']') || ty.starts_with("map(") {
+        Size::new(if ty.ends_with(']') { 33 } else { 1 }, 1)
+    } else if let Some(inner) = ty.strip_prefix("optional(").and_then(|t| t.strip_suffix(')')) {
+        Size::new(1, 0).add(type_size(inner, components)?)
+    } else if ty.starts_with("ref(") {
+        Size::new(0, 1)
+    } else if let Some(n) = number("varuint").or_else(|| number("varint")).filter(|n| *n > 0) {
+        let len_bits = 64 - (n - 1).leading_zeros() as u64;
+        Size::new(len_bits + 8 * (n - 1), 0)
+    } else if let Some(n) = number("uint").or_else(|| number("int")) {
+        Size::new(n, 0)
+    } else if let Some(n) = number("fixedbytes") {
+        Size::new(8 * n, 0)
+    } else {
+        match ty {
+            "bool" => Size::new(1, 0),
+            "address" | "address_std" => Size::new(267, 0),
+            "cell" | "bytes" | "string" => Size::new(0, 1),
+            "gram" | "token" => Size::new(124, 0),
+            "time" => Size::new(64, 0),
+            "expire" => Size::new(32, 0),
+            "pubkey" => Size::new(256, 0),
+            "tuple" => {
+                let mut size = Size::default();
+                for component in components {
+                    let ty = component["type"].as_str().unwrap_or_default();
+                    let inner = component["components"].as_array().map(Vec::as_slice).unwrap_or_default();
+                    size = size.add(type_size(ty, inner)?);
+                }
+                size
+            }
+            _ => return Err(SoldError::InvalidOutput(format!("Unsupported ABI type \"{}\"", ty))),
+        }
+    };
+    Ok(size)
+}
+
+fn tokens(nano: u128) -> String {
+    format!("{}.{:09}", nano / 1_000_000_000, nano % 1_000_000_000)
+}
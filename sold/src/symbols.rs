@@ -0,0 +1,323 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Symbol index of a source file for editor plugins: the declarations it contains, the
+// references it makes (with the location of their declaration, possibly in an imported
+// file), and semantic tokens for both. Positions follow the Language Server Protocol:
+// zero-based lines and UTF-16 character offsets, token types from its standard legend.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ast::{self, Project, Src};
+use crate::error::{Result, SoldError, Status};
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct SymbolsArgs {
+    /// Source file name
+    #[clap(value_parser)]
+    pub input: String,
+    /// Include additional path to search for imports
+    #[clap(short('I'), long, value_parser)]
+    pub include_path: Vec<String>,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct SourceRange {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Symbol {
+    name: String,
+    kind: String,
+    /// Whole declaration
+    range: SourceRange,
+    /// Name of the declaration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selection_range: Option<SourceRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    container: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Location {
+    file: String,
+    range: SourceRange,
+}
+
+#[derive(Serialize)]
+struct Reference {
+    name: String,
+    range: SourceRange,
+    definition: Location,
+}
+
+#[derive(Serialize)]
+struct SemanticToken {
+    range: SourceRange,
+    #[serde(rename = "type")]
+    token_type: &'static str,
+    modifiers: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Index {
+    file: String,
+    symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+    tokens: Vec<SemanticToken>,
+}
+
+/// Converts byte offsets of a text into LSP positions and back
+pub struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> LineIndex<'a> {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { text, starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        let prefix = self.text.get(self.starts[line]..offset).unwrap_or_default();
+        Position { line, character: prefix.encode_utf16().count() }
+    }
+
+    pub fn range(&self, range: Range<usize>) -> SourceRange {
+        SourceRange { start: self.position(range.start), end: self.position(range.end) }
+    }
+
+    /// Byte offset of a position, clamped to the end of its line
+    pub fn offset(&self, position: Position) -> usize {
+        let start = match self.starts.get(position.line) {
+            Some(start) => *start,
+            None => return self.text.len(),
+        };
+        let line = self.text[start..].split('\n').next().unwrap_or_default();
+        let mut units = 0;
+        for (i, c) in line.char_indices() {
+            if units >= position.character {
+                return start + i
+            }
+            units += c.len_utf16();
+        }
+        start + line.len()
+    }
+}
+
+/// A declaration anywhere in the project
+struct Declaration<'a> {
+    node: &'a Value,
+    path: &'a str,
+    scope_type: Option<&'a str>,
+}
+
+fn symbol_kind(decl: &Declaration) -> Option<&'static str> {
+    let node = decl.node;
+    Some(match node["nodeType"].as_str()? {
+        "ContractDefinition" => match node["contractKind"].as_str() {
+            Some("interface") => "interface",
+            Some("library") => "library",
+            _ => "contract",
+        },
+        "FunctionDefinition" => match node["kind"].as_str() {
+            Some("function") | None => "function",
+            Some(_) => "specialFunction",
+        },
+        "ModifierDefinition" => "modifier",
+        "EventDefinition" => "event",
+        "StructDefinition" => "struct",
+        "EnumDefinition" => "enum",
+        "EnumValue" => "enumValue",
+        "VariableDeclaration" => match decl.scope_type {
+            Some("ContractDefinition") => "stateVariable",
+            Some("StructDefinition") => "member",
+            Some("FunctionDefinition") | Some("ModifierDefinition") | Some("EventDefinition") => "parameter",
+            _ => "variable",
+        },
+        _ => return None,
+    })
+}
+
+fn token_type(decl: &Declaration) -> Option<&'static str> {
+    Some(match symbol_kind(decl)? {
+        "contract" => "class",
+        "interface" => "interface",
+        "library" => "namespace",
+        "function" if decl.scope_type == Some("SourceUnit") => "function",
+        "function" | "specialFunction" | "modifier" => "method",
+        "event" => "event",
+        "struct" => "struct",
+        "enum" => "enum",
+        "enumValue" => "enumMember",
+        "stateVariable" | "member" => "property",
+        "parameter" => "parameter",
+        _ => "variable",
+    })
+}
+
+fn token_modifiers(decl: &Declaration, declaration: bool) -> Vec<&'static str> {
+    let mut res = vec![];
+    if declaration {
+        res.push("declaration");
+    }
+    if decl.node["constant"] == true || decl.node["mutability"] == "constant" || decl.node["mutability"] == "immutable" {
+        res.push("readonly");
+    }
+    if decl.node["nodeType"] == "ContractDefinition" && decl.node["abstract"] == true {
+        res.push("abstract");
+    }
+    res
+}
+
+/// Name a reference node uses for its declaration
+fn reference_name(node: &Value) -> Option<&str> {
+    let name = node["name"].as_str().or_else(|| node["memberName"].as_str())?;
+    name.rsplit('.').next()
+}
+
+pub fn symbols(args: SymbolsArgs) -> Status {
+    let input = std::fs::canonicalize(&args.input)
+        .map_err(|_| SoldError::SourceNotFound(args.input.clone()))?;
+    let files = crate::sources::collect(&[input.to_string_lossy().into_owned()])?;
+    let project = Project::load(&files, &args.include_path)?;
+    let index = build_index(&project, &input)?;
+    println!("{}", serde_json::to_string_pretty(&index)?);
+    Ok(())
+}
+
+fn build_index(project: &Project, input: &Path) -> Result<Index> {
+    let mut node_types: HashMap<u64, &str> = HashMap::new();
+    for unit in project.units.values() {
+        ast::walk(unit, &mut |node| {
+            if let (Some(id), Some(node_type)) = (node["id"].as_u64(), node["nodeType"].as_str()) {
+                node_types.insert(id, node_type);
+            }
+        });
+    }
+    let mut declarations: HashMap<u64, Declaration> = HashMap::new();
+    for (path, unit) in &project.units {
+        ast::walk(unit, &mut |node| {
+            let decl = Declaration {
+                node,
+                path,
+                scope_type: node["scope"].as_u64().and_then(|scope| node_types.get(&scope).copied()),
+            };
+            if let (Some(id), Some(_)) = (node["id"].as_u64(), symbol_kind(&decl)) {
+                declarations.insert(id, decl);
+            }
+        });
+    }
+
+    let (path, unit) = project.units.iter()
+        .find(|(path, _)| std::fs::canonicalize(path).map_or(false, |p| p == input))
+        .ok_or_else(|| SoldError::SourceNotFound(input.display().to_string()))?;
+    let mut texts: HashMap<&str, String> = HashMap::new();
+    for decl in declarations.values() {
+        if !texts.contains_key(decl.path) {
+            texts.insert(decl.path, std::fs::read_to_string(decl.path)?);
+        }
+    }
+    if !texts.contains_key(path.as_str()) {
+        texts.insert(path.as_str(), std::fs::read_to_string(path)?);
+    }
+    let text = &texts[path.as_str()];
+    let lines = LineIndex::new(text);
+
+    let mut index = Index { file: path.clone(), symbols: vec![], references: vec![], tokens: vec![] };
+    let mut containers: Vec<(usize, String)> = vec![];
+    ast::walk(unit, &mut |node| {
+        let src = match Src::of(node) {
+            Some(src) => src,
+            None => return,
+        };
+        if let Some(decl) = node["id"].as_u64().and_then(|id| declarations.get(&id)) {
+            let selection = ast::declaration_name(text, node);
+            while containers.last().map_or(false, |(end, _)| *end <= src.start) {
+                containers.pop();
+            }
+            index.symbols.push(Symbol {
+                name: node["name"].as_str().unwrap_or_default().to_owned(),
+                kind: symbol_kind(decl).unwrap_or_default().to_owned(),
+                range: lines.range(src.start..src.end),
+                selection_range: selection.clone().map(|range| lines.range(range)),
+                container: containers.last().map(|(_, name)| name.clone()),
+            });
+            if let (Some(range), Some(token_type)) = (selection, token_type(decl)) {
+                index.tokens.push(SemanticToken {
+                    range: lines.range(range),
+                    token_type,
+                    modifiers: token_modifiers(decl, true),
+                });
+            }
+            if matches!(node["nodeType"].as_str(), Some("ContractDefinition") | Some("StructDefinition") | Some("EnumDefinition")) {
+                containers.push((src.end, node["name"].as_str().unwrap_or_default().to_owned()));
+            }
+            return
+        }
+
+        let decl = match node["referencedDeclaration"].as_u64().and_then(|id| declarations.get(&id)) {
+            Some(decl) => decl,
+            None => return,
+        };
+        let name = match reference_name(node) {
+            Some(name) => name,
+            None => return,
+        };
+        let range = match ast::reference_name(text, node, name) {
+            Some(range) => range,
+            None => return,
+        };
+        let decl_text = &texts[decl.path];
+        let decl_src = Src::of(decl.node);
+        let decl_range = ast::declaration_name(decl_text, decl.node)
+            .or_else(|| decl_src.map(|src| src.start..src.end));
+        if let Some(decl_range) = decl_range {
+            index.references.push(Reference {
+                name: name.to_owned(),
+                range: lines.range(range.clone()),
+                definition: Location {
+                    file: decl.path.to_owned(),
+                    range: LineIndex::new(decl_text).range(decl_range),
+                },
+            });
+        }
+        if let Some(token_type) = token_type(decl) {
+            index.tokens.push(SemanticToken {
+                range: lines.range(range),
+                token_type,
+                modifiers: token_modifiers(decl, false),
+            });
+        }
+    });
+    index.tokens.sort_by_key(|token| (token.range.start.line, token.range.start.character));
+    Ok(index)
+}
@@ -0,0 +1,243 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Searches for a StateInit of a built contract whose address starts with given hex digits.
+// The address is the hash of the StateInit, so one of its parts is varied until the hash
+// matches: the public key in the data, the code salt, or a numeric static variable. Each
+// additional digit makes the search 16 times longer, it runs on all cores.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ton_block::{Serializable, StateInit};
+use ton_types::{BagOfCells, BuilderData, Cell};
+use ton_utils::keyman::KeypairManager;
+
+use crate::error::{Result, SoldError, Status};
+use crate::messages;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct VanityArgs {
+    /// Output prefix of the contract, whose <prefix>.tvc (and <prefix>.abi.json with initfield) is used
+    #[clap(value_parser)]
+    pub contract: String,
+    /// Directory of the artifacts (by default, current directory is used)
+    #[clap(short('O'), long, value_parser)]
+    pub output_dir: Option<String>,
+    /// Address pattern, hex digits the address is to start with, optionally after the workchain, e.g. 0:abc
+    #[clap(long, value_parser)]
+    pub prefix: AddressPrefix,
+    /// Part of the StateInit to vary: pubkey, salt or initfield=<static variable>
+    #[clap(long, value_parser)]
+    pub vary: Vary,
+    /// Give up after this number of attempts
+    #[clap(long, value_parser)]
+    pub max_attempts: Option<u64>,
+    /// Number of threads (by default, one per core)
+    #[clap(long, value_parser)]
+    pub threads: Option<usize>,
+    /// Write the TVC to this file instead of <prefix>.vanity.tvc
+    #[clap(long, value_parser)]
+    pub tvc_out: Option<String>,
+}
+
+/// Address pattern given with --prefix
+#[derive(Clone, Debug)]
+pub struct AddressPrefix {
+    workchain: i32,
+    digits: String,
+}
+
+impl FromStr for AddressPrefix {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        let (workchain, digits) = match s.split_once(':') {
+            Some((workchain, digits)) => (
+                workchain.parse().map_err(|_| format!("invalid workchain \"{}\"", workchain))?,
+                digits,
+            ),
+            None => (0, s),
+        };
+        if digits.is_empty() || digits.len() > 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("\"{}\" is not a prefix of hex digits", digits))
+        }
+        Ok(AddressPrefix { workchain, digits: digits.to_ascii_lowercase() })
+    }
+}
+
+/// Part of the StateInit given with --vary
+#[derive(Clone, Debug, PartialEq)]
+pub enum Vary {
+    Pubkey,
+    Salt,
+    InitField(String),
+}
+
+impl FromStr for Vary {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        match s {
+            "pubkey" => Ok(Vary::Pubkey),
+            "salt" => Ok(Vary::Salt),
+            _ => match s.strip_prefix("initfield=") {
+                Some(field) if !field.is_empty() => Ok(Vary::InitField(field.to_owned())),
+                _ => Err(format!("expected pubkey, salt or initfield=<name>, found \"{}\"", s)),
+            },
+        }
+    }
+}
+
+/// What the winning StateInit was found with
+enum Found {
+    /// Secret and public key, as --gen-key writes them
+    Keypair([u8; 64]),
+    Salt(u64),
+    Field(u64),
+}
+
+fn invalid(e: impl std::fmt::Display) -> SoldError {
+    SoldError::InitError(e.to_string())
+}
+
+/// `code` with `salt` as its third reference, where the selector of the compiler keeps
+/// the salt that `tvm.codeSalt()` reads
+fn set_salt(code: &Cell, salt: Cell) -> Result<Cell> {
+    let mut builder = BuilderData::from(code);
+    if builder.references().len() > 2 {
+        builder.replace_reference_cell(2, salt);
+    } else if builder.references().len() == 2 {
+        builder.checked_append_reference(salt).map_err(invalid)?;
+    } else {
+        return Err(SoldError::InitError("The code has no selector to put the salt into".to_owned()))
+    }
+    builder.into_cell().map_err(invalid)
+}
+
+/// StateInit of one attempt, with what it was varied with
+fn attempt(args: &VanityArgs, abi: &str, state: &StateInit, n: u64) -> Result<(StateInit, Found)> {
+    let mut state = state.clone();
+    let found = match &args.vary {
+        Vary::Pubkey => {
+            let pair = KeypairManager::new().drain();
+            let data = ton_abi::Contract::insert_pubkey(state.data.clone().unwrap_or_default().into(), pair.public.as_bytes())
+                .map_err(invalid)?;
+            state.set_data(data.into_cell());
+            Found::Keypair(pair.to_bytes())
+        }
+        Vary::Salt => {
+            let mut salt = BuilderData::new();
+            salt.append_u64(n).map_err(invalid)?;
+            let code = state.code.clone()
+                .ok_or_else(|| SoldError::InitError("The TVC has no code".to_owned()))?;
+            state.code = Some(set_salt(&code, salt.into_cell().map_err(invalid)?)?);
+            Found::Salt(n)
+        }
+        Vary::InitField(field) => {
+            let data = ton_abi::json_abi::update_contract_data(
+                abi,
+                &serde_json::json!({ field.as_str(): n.to_string() }).to_string(),
+                state.data.clone().unwrap_or_default().into(),
+            ).map_err(invalid)?;
+            state.set_data(data.into_cell());
+            Found::Field(n)
+        }
+    };
+    Ok((state, found))
+}
+
+pub fn vanity(args: VanityArgs) -> Status {
+    let output_dir = Path::new(args.output_dir.as_deref().unwrap_or("."));
+    let tvc_path = output_dir.join(format!("{}.tvc", args.contract));
+    let state = ton_utils::program::load_from_file(&tvc_path.to_string_lossy())
+        .map_err(|_| SoldError::SourceNotFound(tvc_path.display().to_string()))?;
+    let abi = match &args.vary {
+        Vary::InitField(_) => crate::abi::load(&output_dir.join(format!("{}.abi.json", args.contract)).to_string_lossy())?
+            .to_string(),
+        _ => String::new(),
+    };
+    // A broken field or code shows up in the first attempt, rather than in every thread
+    attempt(&args, &abi, &state, 0)?;
+
+    let threads = args.threads
+        .or_else(|| std::thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .max(1);
+    messages::info(&format!(
+        "Searching for {}:{}..., about {} attempts expected",
+        args.prefix.workchain, args.prefix.digits, 16f64.powi(args.prefix.digits.len() as i32),
+    ));
+
+    let done = Arc::new(AtomicBool::new(false));
+    let attempts = Arc::new(AtomicU64::new(0));
+    let winner = Arc::new(Mutex::new(None));
+    let handles: Vec<_> = (0..threads as u64).map(|start| {
+        let (args, abi, state) = (args.clone(), abi.clone(), state.clone());
+        let (done, attempts, winner) = (done.clone(), attempts.clone(), winner.clone());
+        std::thread::spawn(move || -> Status {
+            let mut n = start;
+            while !done.load(Ordering::Relaxed) {
+                let total = attempts.fetch_add(1, Ordering::Relaxed);
+                if args.max_attempts.map_or(false, |max| total >= max) {
+                    break
+                }
+                let (candidate, found) = attempt(&args, &abi, &state, n)?;
+                let hash = candidate.hash().map_err(invalid)?.to_hex_string();
+                if hash.starts_with(&args.prefix.digits) && !done.swap(true, Ordering::SeqCst) {
+                    *winner.lock().unwrap() = Some((candidate, found, hash));
+                }
+                n += threads as u64;
+            }
+            Ok(())
+        })
+    }).collect();
+    for handle in handles {
+        handle.join().map_err(|_| SoldError::InitError("A search thread panicked".to_owned()))??;
+    }
+
+    let (state, found, hash) = winner.lock().unwrap().take().ok_or_else(|| SoldError::LimitExceeded(format!(
+        "No address starting with {} in {} attempts",
+        args.prefix.digits, args.max_attempts.unwrap_or_default(),
+    )))?;
+    let tvc_out = args.tvc_out.clone().map(PathBuf::from)
+        .unwrap_or_else(|| output_dir.join(format!("{}.vanity.tvc", args.contract)));
+    let root = state.serialize().map_err(invalid)?;
+    let mut tvc = vec![];
+    BagOfCells::with_root(&root).write_to(&mut tvc, false).map_err(invalid)?;
+    std::fs::write(&tvc_out, tvc)?;
+
+    messages::output(&format!("Address: {}:{}", args.prefix.workchain, hash));
+    match found {
+        Found::Keypair(pair) => {
+            // Same files as --gen-key writes, so the TVC is deployed with --set-key <file>
+            let keys = tvc_out.with_extension("keys");
+            std::fs::write(&keys, pair)?;
+            std::fs::write(format!("{}.pub", keys.display()), &pair[32..])?;
+            messages::output(&format!("Public key: {}", hex(&pair[32..])));
+            messages::output(&format!("Keys: {}", keys.display()));
+        }
+        Found::Salt(salt) => messages::output(&format!("Salt: {} (uint64)", salt)),
+        Found::Field(value) => if let Vary::InitField(field) = &args.vary {
+            messages::output(&format!("{}: {}", field, value))
+        },
+    }
+    messages::output(&format!("TVC: {}", tvc_out.display()));
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
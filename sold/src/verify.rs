@@ -0,0 +1,144 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Checks that deployed code was built from given sources: the sources are compiled in
+// memory with the given options and the code cell compared with that of a TVC, a BOC of
+// the code, or just a code hash. The code cells are walked side by side from the root,
+// skipping subtrees of equal hashes, and the first cells that differ are shown with their
+// path, where they differ and the declaration the rebuilt cell comes from, e.g. after a
+// change of a function or of the compiler version.
+
+use clap::Parser;
+use ton_block::{Deserializable, StateInit};
+use ton_types::Cell;
+
+use crate::compiler::Compiler;
+use crate::error::{Result, SoldError, Status};
+use crate::messages;
+use crate::size_diff::Attribution;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct VerifyArgs {
+    /// Source file name
+    #[clap(value_parser)]
+    pub input: String,
+    /// Deployed TVC, or BOC of the code cell, binary or base64 encoded
+    #[clap(long, value_parser, required_unless_present = "code-hash")]
+    pub tvc: Option<String>,
+    /// Hash of the deployed code
+    #[clap(long, value_parser, conflicts_with = "tvc")]
+    pub code_hash: Option<String>,
+    /// Build options, given after --
+    #[clap(value_parser, last = true)]
+    pub build_args: Vec<String>,
+}
+
+/// Differing cells shown
+const SHOWN_DIFFERENCES: usize = 16;
+
+fn invalid(e: impl std::fmt::Display) -> SoldError {
+    SoldError::InvalidOutput(format!("Invalid BOC: {}", e))
+}
+
+/// Root cell of the BOC in `bytes`, base64 if it is text
+fn root(bytes: Vec<u8>) -> Result<Cell> {
+    let bytes = match std::str::from_utf8(&bytes) {
+        Ok(text) => base64::decode(text.trim()).map_err(invalid)?,
+        Err(_) => bytes,
+    };
+    ton_types::deserialize_tree_of_cells(&mut std::io::Cursor::new(bytes)).map_err(invalid)
+}
+
+/// The code of a StateInit, or the cell itself if it is not one
+fn code(cell: Cell) -> Result<Cell> {
+    match StateInit::construct_from_cell(cell.clone()) {
+        Ok(state) => state.code.ok_or_else(|| SoldError::InvalidOutput("The TVC has no code".to_owned())),
+        Err(_) => Ok(cell),
+    }
+}
+
+/// Where `a` and `b` differ, None if the data and the number of references are the same
+fn difference(a: &Cell, b: &Cell) -> Option<String> {
+    if a.references_count() != b.references_count() {
+        return Some(format!("{} and {} references", a.references_count(), b.references_count()))
+    }
+    let bit = |cell: &Cell, i: usize| (cell.data()[i / 8] >> (7 - i % 8)) & 1;
+    let common = a.bit_length().min(b.bit_length());
+    match (0..common).find(|i| bit(a, *i) != bit(b, *i)) {
+        Some(i) => Some(format!("data differs from bit {}", i)),
+        None if a.bit_length() != b.bit_length() => Some(format!("{} and {} bits", a.bit_length(), b.bit_length())),
+        None => None,
+    }
+}
+
+/// Paths and descriptions of the topmost differing cells of the trees under `a` and `b`
+fn diff(a: &Cell, b: &Cell, path: String, res: &mut Vec<(String, Cell, String)>) -> Result<()> {
+    if a.repr_hash() == b.repr_hash() || res.len() >= SHOWN_DIFFERENCES {
+        return Ok(())
+    }
+    if let Some(difference) = difference(a, b) {
+        res.push((path, b.clone(), difference));
+        return Ok(())
+    }
+    // Same data, so some reference differs
+    for i in 0..a.references_count() {
+        diff(&a.reference(i).map_err(invalid)?, &b.reference(i).map_err(invalid)?, format!("{}.{}", path, i), res)?;
+    }
+    Ok(())
+}
+
+pub fn verify(args: VerifyArgs) -> Status {
+    let mut argv = vec!["sold".to_owned(), args.input.clone()];
+    argv.extend(args.build_args.iter().cloned());
+    let mut build_args = crate::Args::try_parse_from(argv)
+        .map_err(|e| SoldError::ArgumentError(e.to_string()))?;
+    // The options of a build info or metadata, for the sources given here
+    if let Some(path) = build_args.from_config.take() {
+        build_args = crate::build_config::load(&path)?;
+        build_args.input = vec![args.input.clone()];
+    }
+    let output = Compiler::new().compile(build_args)?;
+    let rebuilt = code(root(output.tvc)?)?;
+    let rebuilt_hash = rebuilt.repr_hash().to_hex_string();
+
+    let deployed = match (&args.tvc, &args.code_hash) {
+        (Some(tvc), _) => Some(code(root(std::fs::read(tvc).map_err(|_| SoldError::SourceNotFound(tvc.clone()))?)?)?),
+        _ => None,
+    };
+    let deployed_hash = match (&deployed, &args.code_hash) {
+        (Some(deployed), _) => deployed.repr_hash().to_hex_string(),
+        (None, Some(hash)) => hash.trim_start_matches("0x").to_ascii_lowercase(),
+        (None, None) => unreachable!("clap requires --tvc or --code-hash"),
+    };
+    if deployed_hash == rebuilt_hash {
+        messages::output(&format!("Code of {} matches the sources, hash {}", output.contract, rebuilt_hash));
+        return Ok(())
+    }
+
+    messages::output(&format!("Code of {} doesn't match the sources", output.contract));
+    messages::output(&format!("  deployed: {}", deployed_hash));
+    messages::output(&format!("  rebuilt:  {}", rebuilt_hash));
+    let mut differing = vec![];
+    if let Some(deployed) = &deployed {
+        let mut cells = vec![];
+        diff(deployed, &rebuilt, "0".to_owned(), &mut cells)?;
+        let mut attribution = Attribution::default();
+        for (path, cell, difference) in cells {
+            messages::output(&format!(
+                "  cell {}: {}, rebuilt from {}", path, difference, attribution.cell_owner(&cell, &output.debug_map)
+            ));
+            differing.push(path);
+        }
+    }
+    Err(SoldError::CodeMismatch(differing))
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Versions of all the components of sold as --version-json prints them, for build systems
+// that pin a toolchain: --version only names the frontend, while the code a build gives
+// also depends on the runtime library and the linker. The commit is that of the
+// repository sold and the embedded frontend were built from; the linker version is the
+// one the lock file pins, with the commit of its source.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::Status;
+use crate::messages;
+
+#[derive(Serialize)]
+struct Stdlib {
+    name: &'static str,
+    size: usize,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Version {
+    sold: &'static str,
+    /// Version of the frontend in use, the embedded one unless --solc-lib is given
+    frontend: String,
+    embedded_frontend: String,
+    commit: &'static str,
+    commit_date: &'static str,
+    stdlib: Stdlib,
+    linker: &'static str,
+}
+
+pub fn print() -> Status {
+    let version = Version {
+        sold: env!("CARGO_PKG_VERSION"),
+        frontend: crate::solidity_version(),
+        embedded_frontend: crate::frontend::embedded_version(),
+        commit: env!("BUILD_GIT_COMMIT").trim(),
+        commit_date: env!("BUILD_GIT_DATE").trim(),
+        stdlib: Stdlib {
+            name: "stdlib_sol.tvm",
+            size: crate::STDLIB.len(),
+            sha256: format!("{:x}", Sha256::digest(crate::STDLIB)),
+        },
+        linker: env!("BUILD_LINKER_VERSION"),
+    };
+    messages::output(&serde_json::to_string_pretty(&version)?);
+    Ok(())
+}
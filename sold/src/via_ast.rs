@@ -0,0 +1,139 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// --via-ast: compiles the input, feeds the exported ASTs back to the frontend with the
+// "SolidityAST" language and checks that the re-exported ASTs and the ABI and assembly of
+// every contract come out the same. AST differences are reported by node path, e.g.
+// `nodes[1]<ContractDefinition>.nodes[0]<FunctionDefinition>.kind`.
+
+use serde_json::Value;
+
+use crate::error::{SoldError, Status};
+use crate::messages::{self, Level};
+
+/// At most this many differences are shown per output
+const SHOWN_DIFFERENCES: usize = 10;
+
+fn short(value: &Value) -> String {
+    let text = value.to_string();
+    match text.char_indices().nth(60) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+/// Collects the paths of the nodes where `a` and `b` differ.
+fn diff(path: &str, a: &Value, b: &Value, out: &mut Vec<String>) {
+    match (a, b) {
+        (Value::Object(a_map), Value::Object(b_map)) => {
+            for (key, a_value) in a_map {
+                let child = format!("{}.{}", path, key);
+                match b_map.get(key) {
+                    Some(b_value) => diff(&child, a_value, b_value, out),
+                    None => out.push(format!("{}: missing", child)),
+                }
+            }
+            for key in b_map.keys().filter(|key| !a_map.contains_key(*key)) {
+                out.push(format!("{}.{}: unexpected {}", path, key, short(&b_map[key])));
+            }
+        }
+        (Value::Array(a_items), Value::Array(b_items)) if a_items.len() == b_items.len() => {
+            for (i, (a_item, b_item)) in a_items.iter().zip(b_items).enumerate() {
+                let child = match a_item["nodeType"].as_str() {
+                    Some(node_type) => format!("{}[{}]<{}>", path, i, node_type),
+                    None => format!("{}[{}]", path, i),
+                };
+                diff(&child, a_item, b_item, out);
+            }
+        }
+        // Assembly is compared line by line
+        (Value::String(a_text), Value::String(b_text)) if a_text != b_text && a_text.contains('\n') => {
+            let line = a_text.lines().zip(b_text.lines()).position(|(x, y)| x != y)
+                .unwrap_or_else(|| a_text.lines().count().min(b_text.lines().count()));
+            out.push(format!("{}: line {}: {:?} != {:?}", path, line + 1,
+                a_text.lines().nth(line).unwrap_or_default(), b_text.lines().nth(line).unwrap_or_default()));
+        }
+        _ if a != b => out.push(format!("{}: {} != {}", path, short(a), short(b))),
+        _ => {}
+    }
+}
+
+/// Reports the differences of `name` and records it in `differing` if there are any.
+fn report(name: String, differences: Vec<String>, differing: &mut Vec<String>) {
+    if differences.is_empty() {
+        return
+    }
+    let mut text = format!("{} differs:", name);
+    for difference in differences.iter().take(SHOWN_DIFFERENCES) {
+        text += &format!("\n  {}", difference.trim_start_matches('.'));
+    }
+    if differences.len() > SHOWN_DIFFERENCES {
+        text += &format!("\n  ... and {} more", differences.len() - SHOWN_DIFFERENCES);
+    }
+    messages::message(Level::Error, &text);
+    differing.push(name);
+}
+
+fn has_errors(output: &crate::CompilerOutput) -> bool {
+    output.errors.iter().any(|error| error["severity"] == "error")
+}
+
+/// Compiles `input` directly and via its AST and compares the results. Errors of the
+/// direct compilation are left for the build itself to report.
+pub fn check(args: &crate::Args, input: &str) -> Status {
+    let mut json: Value = serde_json::from_str(&crate::standard_json_input(args, input))?;
+    json["settings"]["outputSelection"][input][""] = serde_json::json!(["ast"]);
+    let direct = crate::compile_json(args, &json.to_string())?;
+    if has_errors(&direct) {
+        return Ok(())
+    }
+
+    let units = direct.sources.iter()
+        .map(|(path, source)| (path.clone(), source.ast.clone().unwrap_or_default()))
+        .collect();
+    let mut json = crate::ast_import::import_input(args, input, units)?;
+    json["settings"]["outputSelection"][input][""] = serde_json::json!(["ast"]);
+    let imported = crate::compile_json(args, &json.to_string())?;
+    if has_errors(&imported) {
+        for error in imported.errors.iter().filter(|error| error["severity"] == "error") {
+            messages::message(Level::Error, error["formattedMessage"].as_str().or_else(|| error["message"].as_str()).unwrap_or_default());
+        }
+        return Err(SoldError::AstMismatch(vec!["compilation".to_owned()]))
+    }
+
+    let mut differing = vec![];
+    for (path, source) in &direct.sources {
+        let mut differences = vec![];
+        match imported.sources.get(path) {
+            Some(other) => diff("", source.ast.as_ref().unwrap_or(&Value::Null), other.ast.as_ref().unwrap_or(&Value::Null), &mut differences),
+            None => differences.push("missing from the compilation via the AST".to_owned()),
+        }
+        report(format!("AST of {}", path), differences, &mut differing);
+    }
+    let no_contracts = serde_json::Map::new();
+    let direct_contracts = direct.contracts.get(input).unwrap_or(&no_contracts);
+    let imported_contracts = imported.contracts.get(input).unwrap_or(&no_contracts);
+    for (name, output) in direct_contracts {
+        let mut differences = vec![];
+        match imported_contracts.get(name) {
+            Some(other) => diff("", output, other, &mut differences),
+            None => differences.push("missing from the compilation via the AST".to_owned()),
+        }
+        report(format!("Output of {}", name), differences, &mut differing);
+    }
+    if !differing.is_empty() {
+        return Err(SoldError::AstMismatch(differing))
+    }
+    messages::output("Compilation via the AST matches direct compilation");
+    Ok(())
+}
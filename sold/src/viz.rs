@@ -0,0 +1,241 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Draws the cell tree of the code of a contract, as Graphviz DOT or as SVG. Every cell
+// shows its bits and references and the size of its subtree, and with a debug map the
+// function it is attributed to, as by size-diff; cells of the same function share a color.
+// A cell referenced several times is drawn once, with an edge from each parent.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde_json::Value;
+use ton_types::{Cell, UInt256};
+
+use crate::error::{Result, SoldError, Status};
+use crate::size_diff::Attribution;
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct VizArgs {
+    /// TVC of the contract
+    #[clap(value_parser)]
+    pub tvc: String,
+    /// Output file, .dot for Graphviz or .svg
+    #[clap(short, long, value_parser)]
+    pub output: String,
+    /// Debug map of the build, to show the function each cell belongs to
+    #[clap(long, value_parser)]
+    pub debug: Option<String>,
+}
+
+const PALETTE: &[&str] = &[
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462",
+    "#b3de69", "#fccde5", "#d9d9d9", "#bc80bd", "#ccebc5", "#ffed6f",
+];
+
+struct Node {
+    bits: usize,
+    refs: usize,
+    owner: Option<String>,
+    depth: usize,
+    /// Indices of the nodes of the references
+    children: Vec<usize>,
+    /// Distinct cells of the subtree, this one included
+    subtree_cells: usize,
+    subtree_bits: usize,
+}
+
+struct Tree {
+    nodes: Vec<Node>,
+    /// Edges to cells drawn under another parent
+    shared: Vec<(usize, usize)>,
+}
+
+fn build(root: &Cell, debug_map: Option<&Value>) -> Result<Tree> {
+    fn visit(
+        cell: &Cell,
+        depth: usize,
+        tree: &mut Tree,
+        indices: &mut HashMap<UInt256, usize>,
+        attribution: &mut Option<(Attribution, &Value)>,
+    ) -> ton_types::Result<usize> {
+        let index = tree.nodes.len();
+        indices.insert(cell.repr_hash(), index);
+        let owner = attribution.as_mut().map(|(attribution, debug_map)| attribution.cell_owner(cell, *debug_map));
+        tree.nodes.push(Node {
+            bits: cell.bit_length(),
+            refs: cell.references_count(),
+            owner,
+            depth,
+            children: vec![],
+            subtree_cells: 1,
+            subtree_bits: cell.bit_length(),
+        });
+        for i in 0..cell.references_count() {
+            let child = cell.reference(i)?;
+            match indices.get(&child.repr_hash()) {
+                Some(&existing) => tree.shared.push((index, existing)),
+                None => {
+                    let child_index = visit(&child, depth + 1, tree, indices, attribution)?;
+                    tree.nodes[index].children.push(child_index);
+                    tree.nodes[index].subtree_cells += tree.nodes[child_index].subtree_cells;
+                    tree.nodes[index].subtree_bits += tree.nodes[child_index].subtree_bits;
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    let mut tree = Tree { nodes: vec![], shared: vec![] };
+    let mut attribution = debug_map.map(|debug_map| (Attribution::default(), debug_map));
+    visit(root, 0, &mut tree, &mut HashMap::new(), &mut attribution)
+        .map_err(|e| SoldError::InvalidOutput(e.to_string()))?;
+    Ok(tree)
+}
+
+/// Colors by owner, in the order owners first appear
+fn colors(tree: &Tree) -> HashMap<&str, &'static str> {
+    let mut res = HashMap::new();
+    for owner in tree.nodes.iter().filter_map(|node| node.owner.as_deref()) {
+        let next = PALETTE[res.len() % PALETTE.len()];
+        res.entry(owner).or_insert(next);
+    }
+    res
+}
+
+fn label_lines(node: &Node) -> Vec<String> {
+    let mut res = vec![];
+    if let Some(owner) = &node.owner {
+        res.push(owner.clone());
+    }
+    res.push(format!("{} bits, {} refs", node.bits, node.refs));
+    if !node.children.is_empty() {
+        res.push(format!("subtree {} cells, {} bits", node.subtree_cells, node.subtree_bits));
+    }
+    res
+}
+
+fn dot(tree: &Tree) -> String {
+    let colors = colors(tree);
+    let mut out = String::new();
+    writeln!(out, "digraph code {{").unwrap();
+    writeln!(out, "    node [shape=box, style=filled, fillcolor=\"#ffffff\", fontname=\"monospace\"];").unwrap();
+    for (index, node) in tree.nodes.iter().enumerate() {
+        let label = label_lines(node).iter()
+            .map(|line| line.replace('\\', "\\\\").replace('"', "\\\""))
+            .collect::<Vec<_>>()
+            .join("\\n");
+        let color = node.owner.as_deref().and_then(|owner| colors.get(owner)).unwrap_or(&"#ffffff");
+        writeln!(out, "    c{} [label=\"{}\", fillcolor=\"{}\"];", index, label, color).unwrap();
+    }
+    for (index, node) in tree.nodes.iter().enumerate() {
+        for child in &node.children {
+            writeln!(out, "    c{} -> c{};", index, child).unwrap();
+        }
+    }
+    for (parent, child) in &tree.shared {
+        writeln!(out, "    c{} -> c{} [style=dashed];", parent, child).unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+const BOX_WIDTH: f64 = 200.0;
+const BOX_HEIGHT: f64 = 54.0;
+const SLOT_WIDTH: f64 = 220.0;
+const ROW_HEIGHT: f64 = 90.0;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Horizontal position of every node: leaves take the next free slot, parents are centered
+/// over their children
+fn layout(tree: &Tree, index: usize, next_slot: &mut f64, x: &mut [f64]) {
+    let node = &tree.nodes[index];
+    if node.children.is_empty() {
+        x[index] = *next_slot;
+        *next_slot += 1.0;
+        return
+    }
+    for &child in &node.children {
+        layout(tree, child, next_slot, x);
+    }
+    x[index] = (x[node.children[0]] + x[*node.children.last().unwrap()]) / 2.0;
+}
+
+fn svg(tree: &Tree) -> String {
+    let colors = colors(tree);
+    let mut x = vec![0.0; tree.nodes.len()];
+    let mut slots = 0.0;
+    layout(tree, 0, &mut slots, &mut x);
+    let center = |index: usize| (x[index] * SLOT_WIDTH + SLOT_WIDTH / 2.0, tree.nodes[index].depth as f64 * ROW_HEIGHT + 10.0);
+    let depth = tree.nodes.iter().map(|node| node.depth).max().unwrap_or_default();
+    let width = slots * SLOT_WIDTH;
+    let height = (depth + 1) as f64 * ROW_HEIGHT;
+
+    let mut out = String::new();
+    writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="11">"#, width, height).unwrap();
+    let edge = |out: &mut String, parent: usize, child: usize, dashed: bool| {
+        let (x1, y1) = center(parent);
+        let (x2, y2) = center(child);
+        let dash = if dashed { r#" stroke-dasharray="4 3""# } else { "" };
+        writeln!(out, r##"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#666"{}/>"##, x1, y1 + BOX_HEIGHT, x2, y2, dash).unwrap();
+    };
+    for (index, node) in tree.nodes.iter().enumerate() {
+        for &child in &node.children {
+            edge(&mut out, index, child, false);
+        }
+    }
+    for &(parent, child) in &tree.shared {
+        edge(&mut out, parent, child, true);
+    }
+    for (index, node) in tree.nodes.iter().enumerate() {
+        let (cx, top) = center(index);
+        let color = node.owner.as_deref().and_then(|owner| colors.get(owner)).unwrap_or(&"#ffffff");
+        writeln!(
+            out, r##"  <rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="{}" stroke="#333"/>"##,
+            cx - BOX_WIDTH / 2.0, top, BOX_WIDTH, BOX_HEIGHT, color,
+        ).unwrap();
+        for (i, line) in label_lines(node).iter().enumerate() {
+            writeln!(
+                out, r#"  <text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                cx, top + 16.0 + i as f64 * 14.0, escape(line),
+            ).unwrap();
+        }
+    }
+    writeln!(out, "</svg>").unwrap();
+    out
+}
+
+pub fn viz(args: VizArgs) -> Status {
+    let state = ton_utils::program::load_from_file(&args.tvc)
+        .map_err(|_| SoldError::SourceNotFound(args.tvc.clone()))?;
+    let code = state.code
+        .ok_or_else(|| SoldError::InvalidOutput(format!("{} has no code", args.tvc)))?;
+    let debug_map: Option<Value> = match &args.debug {
+        Some(debug) => Some(serde_json::from_str(&std::fs::read_to_string(debug)
+            .map_err(|_| SoldError::SourceNotFound(debug.clone()))?)?),
+        None => None,
+    };
+    let tree = build(&code, debug_map.as_ref())?;
+    let text = match std::path::Path::new(&args.output).extension().and_then(|ext| ext.to_str()) {
+        Some("dot") => dot(&tree),
+        Some("svg") => svg(&tree),
+        _ => return Err(SoldError::ArgumentError(format!(
+            "Can't tell the format of \"{}\", use a .dot or .svg file", args.output
+        ))),
+    };
+    std::fs::write(&args.output, text)?;
+    Ok(())
+}
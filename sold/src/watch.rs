@@ -0,0 +1,78 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Rebuilds whenever a source of the last build changes. The sources are those the read
+// callback served during the build, i.e. the inputs and everything they import, as
+// recorded in the line tables of the compiler. Modification times are polled, which works
+// the same on every platform and network file system and costs nothing noticeable for the
+// few hundred files of a project.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::error::{SoldError, Status};
+use crate::messages::{self, Level};
+use crate::{compiler, Args};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Modification times of the watched files, None for those missing
+fn snapshot(files: &[PathBuf]) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    files.iter()
+        .map(|file| (file.clone(), std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok()))
+        .collect()
+}
+
+/// Builds `args` with `build` and again whenever a source changes, until interrupted.
+pub fn watch(args: Args, build: impl Fn(Args) -> Status) -> Status {
+    if args.input.iter().any(|input| crate::source_cache::is_virtual(Path::new(input))) {
+        return Err(SoldError::ArgumentError("Option --watch can't be used with a source read from stdin".to_owned()))
+    }
+    let compiler = compiler::current();
+    loop {
+        compiler.state().lines.lock().unwrap().clear();
+        let started = Instant::now();
+        match build(args.clone()) {
+            Ok(()) => messages::info(&format!("Build finished in {} ms", started.elapsed().as_millis())),
+            Err(e) => messages::message(Level::Error, &format!("Build failed: {}", e)),
+        }
+
+        let mut files: Vec<PathBuf> = args.input.iter().map(PathBuf::from).collect();
+        files.extend(compiler.state().lines.lock().unwrap().keys().map(PathBuf::from));
+        for file in &mut files {
+            if let Ok(canonical) = std::fs::canonicalize(&file) {
+                *file = canonical;
+            }
+        }
+        files.sort();
+        files.dedup();
+        messages::info(&format!("Watching {} files for changes", files.len()));
+
+        let before = snapshot(&files);
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if snapshot(&files) != before {
+                break
+            }
+        }
+        // Editors often write a file in several steps, let them finish
+        std::thread::sleep(POLL_INTERVAL);
+        let after = snapshot(&files);
+        let changed: Vec<String> = files.iter()
+            .filter(|file| before.get(*file) != after.get(*file))
+            .map(|file| file.display().to_string())
+            .collect();
+        messages::info(&format!("Changed: {}", changed.join(", ")));
+    }
+}
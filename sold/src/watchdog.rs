@@ -0,0 +1,208 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// A build under --timeout or --max-memory runs in a re-executed copy of sold: a call into
+// the frontend can't be interrupted, and a build left running on a thread would keep
+// holding the frontend and the memory it took from the builds after it. The parent
+// kills the worker when the time is up; the worker watches its own peak memory, which
+// then is that of the one build, and reports a violation as it does any other error.
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SoldError, Status};
+use crate::report::BuildOutput;
+use crate::Args;
+
+pub const WORKER_COMMAND: &str = "build-worker";
+
+// The frontend recurses deeply on big sources; give the worker as much stack as a main thread has.
+pub(crate) const WORKER_STACK_SIZE: usize = 64 * 1024 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Numbers the result files of the workers of this process
+static WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    /// Peak resident set size of the build, in bytes
+    pub max_memory: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WorkerRequest {
+    args: Args,
+    /// Not serialized with the other options, so it never ends up in an artifact
+    mnemonic: Option<String>,
+    max_memory: Option<u64>,
+    /// Inputs read from stdin, which the worker can't read again
+    sources: Vec<(PathBuf, Vec<u8>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WorkerResult {
+    output: Option<BuildOutput>,
+    error: Option<String>,
+    exit_code: i32,
+}
+
+/// Builds in a worker process and kills it if it runs out of time.
+pub fn build_with_limits(args: Args, limits: Limits) -> Result<BuildOutput> {
+    if limits.max_memory.is_some() && peak_rss().is_none() {
+        return Err(SoldError::ArgumentError("Memory limit is not supported on this platform".to_owned()))
+    }
+
+    let sources = args.input.iter()
+        .filter_map(|input| {
+            let path = PathBuf::from(input);
+            crate::source_cache::virtual_content(&path).map(|content| (path, content.to_vec()))
+        })
+        .collect();
+    let mut args = args;
+    let request = serde_json::to_vec(&WorkerRequest {
+        mnemonic: args.mnemonic.take(),
+        args,
+        max_memory: limits.max_memory,
+        sources,
+    })?;
+    let result_path = std::env::temp_dir().join(format!(
+        "sold-build-{}-{}.json", std::process::id(), WORKERS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_file(&result_path);
+
+    // Diagnostics and other messages of the worker go straight to ours
+    let mut child = Command::new(std::env::current_exe()?)
+        .arg(WORKER_COMMAND)
+        .arg(&result_path)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take()
+        .ok_or_else(|| SoldError::ArgumentError("Failed to open stdin of build worker".to_owned()))?;
+    // A worker that died early closes the pipe, the resulting write error is not interesting
+    let _ = stdin.write_all(&request);
+    drop(stdin);
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status
+        }
+        if let Some(timeout) = limits.timeout {
+            if started.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                let _ = std::fs::remove_file(&result_path);
+                return Err(SoldError::Timeout(timeout))
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let result = std::fs::read(&result_path);
+    let _ = std::fs::remove_file(&result_path);
+    let result: WorkerResult = match result {
+        Ok(result) => serde_json::from_slice(&result).map_err(|e| SoldError::FrontendCrash {
+            status: format!("malformed result of build worker: {}", e),
+            stderr: String::new(),
+        })?,
+        Err(_) => return Err(SoldError::FrontendCrash { status: status.to_string(), stderr: String::new() }),
+    };
+    match (result.output, result.error) {
+        (Some(output), _) => Ok(output),
+        (None, error) => Err(SoldError::BuildFailed {
+            message: error.unwrap_or_default(),
+            exit_code: result.exit_code,
+        }),
+    }
+}
+
+/// Serves a single build for `build_with_limits`, writing the result to `result_path`.
+/// Exits the process when done, since a build over the memory limit may still be running.
+pub fn worker_main(result_path: &str) -> Status {
+    let mut request = String::new();
+    std::io::stdin().read_to_string(&mut request)?;
+    let request: WorkerRequest = serde_json::from_str(&request)?;
+    for (path, content) in request.sources {
+        crate::source_cache::add_virtual(&path, content);
+    }
+    let mut args = request.args;
+    args.mnemonic = request.mnemonic;
+    crate::diagnostics::set_color(args.color);
+    if let Some(path) = &args.solc_lib {
+        crate::frontend::load(path);
+    }
+
+    let result = match watch_memory(args, request.max_memory) {
+        Ok(output) => WorkerResult { output: Some(output), error: None, exit_code: 0 },
+        Err(e) => WorkerResult { output: None, error: Some(e.to_string()), exit_code: e.exit_code() },
+    };
+    std::fs::write(result_path, serde_json::to_vec(&result)?)?;
+    std::process::exit(0)
+}
+
+/// Builds on a thread of its own while the calling thread watches the peak memory of the
+/// process. On violation the build is left running for the caller to exit.
+fn watch_memory(args: Args, max_memory: Option<u64>) -> Result<BuildOutput> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let compiler = crate::compiler::current();
+    let worker = std::thread::Builder::new()
+        .name("build".to_owned())
+        .stack_size(WORKER_STACK_SIZE)
+        .spawn(move || {
+            let _ = sender.send(compiler.build(args));
+        })?;
+
+    loop {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(result) => return result,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => match worker.join() {
+                Err(panic) => std::panic::resume_unwind(panic),
+                Ok(()) => unreachable!("build worker exited without sending a result"),
+            },
+        }
+        if let (Some(limit), Some(used)) = (max_memory, peak_rss()) {
+            if used > limit {
+                return Err(SoldError::MemoryLimitExceeded { limit, used })
+            }
+        }
+    }
+}
+
+// getrusage() accounts for everything the process allocated, including the heap
+// of the C++ frontend, which a Rust global allocator would never see.
+#[cfg(unix)]
+fn peak_rss() -> Option<u64> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) } != 0 {
+        return None
+    }
+    let max_rss = unsafe { usage.assume_init() }.ru_maxrss as u64;
+    if cfg!(target_os = "macos") {
+        Some(max_rss)
+    } else {
+        Some(max_rss * 1024)
+    }
+}
+
+#[cfg(not(unix))]
+fn peak_rss() -> Option<u64> {
+    None
+}
@@ -0,0 +1,101 @@
+/*
+ * Copyright 2022 TON DEV SOLUTIONS LTD.
+ *
+ * Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+ * this file except in compliance with the License.
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific TON DEV software governing permissions and
+ * limitations under the License.
+ */
+
+// Builds for async code. Those of a compiler run one at a time, in the order they were
+// requested, on a worker thread of the compiler started on first use, so the blocking calls
+// into the frontend never take a runtime thread. The result comes back through a tokio
+// oneshot channel, which any executor can poll.
+//
+// Dropping the future cancels the build. A build still queued is skipped; a running one
+// can't be interrupted, it completes and its result is discarded.
+
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use crate::compiler::Compiler;
+use crate::error::Result;
+use crate::report::BuildOutput;
+use crate::watchdog::WORKER_STACK_SIZE;
+use crate::Args;
+
+type Outcome = std::thread::Result<Result<BuildOutput>>;
+
+struct Job {
+    compiler: Compiler,
+    args: Args,
+    cancelled: Arc<AtomicBool>,
+    result: oneshot::Sender<Outcome>,
+}
+
+/// Queue of the worker of a compiler. Jobs hold the compiler rather than the worker
+/// thread, which exits once the compiler is dropped.
+#[derive(Default)]
+pub(crate) struct Worker {
+    queue: Mutex<Option<mpsc::Sender<Job>>>,
+}
+
+fn work(jobs: mpsc::Receiver<Job>) {
+    for job in jobs {
+        if job.cancelled.load(Ordering::Relaxed) {
+            continue
+        }
+        // A panicking build fails its own future, not the ones queued after it
+        let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| job.compiler.build(job.args)));
+        let _ = job.result.send(outcome);
+    }
+}
+
+fn submit(job: Job) -> std::io::Result<()> {
+    let state = job.compiler.state().clone();
+    let mut queue = state.worker.queue.lock().unwrap_or_else(|e| e.into_inner());
+    if queue.is_none() {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("async build".to_owned())
+            .stack_size(WORKER_STACK_SIZE)
+            .spawn(move || work(receiver))?;
+        *queue = Some(sender);
+    }
+    queue.as_ref().unwrap().send(job).expect("the build worker runs as long as its compiler");
+    Ok(())
+}
+
+/// Marks the job cancelled when the future waiting for it is dropped
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Same as `build()`, on the build worker. Dropping the future cancels the build, e.g. when
+/// it loses a `tokio::select!` or its task is aborted.
+pub async fn compile_async(args: Args) -> Result<BuildOutput> {
+    compile(crate::compiler::current(), args).await
+}
+
+pub(crate) async fn compile(compiler: Compiler, args: Args) -> Result<BuildOutput> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = oneshot::channel();
+    submit(Job { compiler, args, cancelled: cancelled.clone(), result: sender })?;
+    let _guard = CancelOnDrop(cancelled);
+    let outcome = receiver.await.expect("the build worker answers every job it doesn't skip");
+    match outcome {
+        Ok(result) => result,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
@@ -0,0 +1,90 @@
+// Deploys {{name}} with the artifacts built by sold.
+// Generated by sold {{version}}, edit to taste.
+//
+//   npm install @eversdk/core @eversdk/lib-node
+//   ENDPOINT=https://... GIVER_ADDRESS=0:... GIVER_ABI=Giver.abi.json GIVER_KEYS=giver.keys.json node {{script}}
+
+const { abiContract, signerKeys, signerNone, TonClient } = require("@eversdk/core");
+const { libNode } = require("@eversdk/lib-node");
+const fs = require("fs");
+const path = require("path");
+
+TonClient.useBinaryLibrary(libNode);
+
+const ARTIFACTS = process.env.ARTIFACTS ?? path.join(__dirname, "{{artifacts}}");
+const ENDPOINT = process.env.ENDPOINT ?? "http://localhost";
+// Amount sent to the address before deploying, in nano
+const DEPLOY_VALUE = 1_000_000_000;
+// Keys of the contract, generated on the first run
+const KEYS_FILE = path.join(ARTIFACTS, "{{name}}.keys.json");
+
+const abi = abiContract(JSON.parse(fs.readFileSync(path.join(ARTIFACTS, "{{name}}.abi.json"), "utf8")));
+const tvc = fs.readFileSync(path.join(ARTIFACTS, "{{name}}.tvc")).toString("base64");
+
+// Values of the static variables
+const initialData = {{initial_data}};
+
+// Parameters of the constructor
+const constructorParams = {{constructor_params}};
+
+async function loadKeys(client) {
+	if (fs.existsSync(KEYS_FILE)) {
+		return JSON.parse(fs.readFileSync(KEYS_FILE, "utf8"));
+	}
+	const keys = await client.crypto.generate_random_sign_keys();
+	fs.writeFileSync(KEYS_FILE, JSON.stringify(keys, null, "\t"));
+	return keys;
+}
+
+// Sends value to the address from a giver with a sendTransaction(dest, value, bounce) function
+async function fund(client, address, value) {
+	const giverAbi = abiContract(JSON.parse(fs.readFileSync(process.env.GIVER_ABI, "utf8")));
+	const giverKeys = process.env.GIVER_KEYS
+		? JSON.parse(fs.readFileSync(process.env.GIVER_KEYS, "utf8"))
+		: undefined;
+	await client.processing.process_message({
+		message_encode_params: {
+			address: process.env.GIVER_ADDRESS,
+			abi: giverAbi,
+			call_set: { function_name: "sendTransaction", input: { dest: address, value, bounce: false } },
+			signer: giverKeys ? signerKeys(giverKeys) : signerNone(),
+		},
+		send_events: false,
+	});
+	await client.net.wait_for_collection({
+		collection: "accounts",
+		filter: { id: { eq: address }, balance: { gt: "0" } },
+		result: "id",
+	});
+}
+
+async function main() {
+	const client = new TonClient({ network: { endpoints: [ENDPOINT] } });
+	try {
+		const keys = await loadKeys(client);
+		const deployParams = {
+			abi,
+			deploy_set: { tvc, initial_data: initialData },
+			call_set: { function_name: "constructor", input: constructorParams },
+			signer: signerKeys(keys),
+		};
+		const { address } = await client.abi.encode_message(deployParams);
+		console.log(`Address: ${address}`);
+
+		await fund(client, address, DEPLOY_VALUE);
+		console.log(`Funded with ${DEPLOY_VALUE} nano`);
+
+		const { transaction } = await client.processing.process_message({
+			message_encode_params: deployParams,
+			send_events: false,
+		});
+		console.log(`Deployed in transaction ${transaction.id}`);
+	} finally {
+		client.close();
+	}
+}
+
+main().catch((error) => {
+	console.error(error);
+	process.exit(1);
+});
@@ -0,0 +1,116 @@
+// Deploys {{name}} with the artifacts built by sold.
+// Generated by sold {{version}}, edit to taste.
+//
+// Dependencies:
+//   base64 = "0.13"
+//   serde_json = "1.0"
+//   tokio = { version = "1", features = ["macros", "rt-multi-thread"] }
+//   ton_client = { git = "https://github.com/tonlabs/ever-sdk.git" }
+//
+//   ENDPOINT=https://... GIVER_ADDRESS=0:... GIVER_ABI=Giver.abi.json GIVER_KEYS=giver.keys.json cargo run
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use ton_client::abi::{Abi, AbiContract, CallSet, DeploySet, ParamsOfEncodeMessage, Signer};
+use ton_client::crypto::KeyPair;
+use ton_client::net::ParamsOfWaitForCollection;
+use ton_client::processing::ParamsOfProcessMessage;
+use ton_client::{ClientConfig, ClientContext};
+
+// Amount sent to the address before deploying, in nano
+const DEPLOY_VALUE: u64 = 1_000_000_000;
+
+type Error = Box<dyn std::error::Error>;
+
+fn artifacts() -> PathBuf {
+    // Relative to the working directory
+    PathBuf::from(std::env::var("ARTIFACTS").unwrap_or_else(|_| "{{artifacts}}".to_owned()))
+}
+
+fn read_json(path: impl AsRef<Path>) -> Result<Value, Error> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn read_abi(path: impl AsRef<Path>) -> Result<Abi, Error> {
+    let contract: AbiContract = serde_json::from_value(read_json(path)?)?;
+    Ok(Abi::Contract(contract))
+}
+
+/// Keys of the contract, generated on the first run
+fn load_keys(context: &Arc<ClientContext>) -> Result<KeyPair, Error> {
+    let path = artifacts().join("{{name}}.keys.json");
+    if path.exists() {
+        return Ok(serde_json::from_value(read_json(path)?)?)
+    }
+    let keys = ton_client::crypto::generate_random_sign_keys(context.clone())?;
+    std::fs::write(path, serde_json::to_string_pretty(&keys)?)?;
+    Ok(keys)
+}
+
+/// Sends value to the address from a giver with a sendTransaction(dest, value, bounce) function
+async fn fund(context: &Arc<ClientContext>, address: &str, value: u64) -> Result<(), Error> {
+    let signer = match std::env::var("GIVER_KEYS") {
+        Ok(path) => Signer::Keys { keys: serde_json::from_value(read_json(path)?)? },
+        Err(_) => Signer::None,
+    };
+    let params = ParamsOfEncodeMessage {
+        abi: read_abi(std::env::var("GIVER_ABI")?)?,
+        address: Some(std::env::var("GIVER_ADDRESS")?),
+        call_set: CallSet::some_with_function_and_input(
+            "sendTransaction",
+            json!({ "dest": address, "value": value, "bounce": false }),
+        ),
+        signer,
+        ..Default::default()
+    };
+    ton_client::processing::process_message(
+        context.clone(),
+        ParamsOfProcessMessage { message_encode_params: params, send_events: false },
+        |_| async {},
+    ).await?;
+    ton_client::net::wait_for_collection(context.clone(), ParamsOfWaitForCollection {
+        collection: "accounts".to_owned(),
+        filter: Some(json!({ "id": { "eq": address }, "balance": { "gt": "0" } })),
+        result: "id".to_owned(),
+        timeout: None,
+    }).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let endpoint = std::env::var("ENDPOINT").unwrap_or_else(|_| "http://localhost".to_owned());
+    let mut config = ClientConfig::default();
+    config.network.endpoints = Some(vec![endpoint]);
+    let context = Arc::new(ClientContext::new(config)?);
+
+    let tvc = base64::encode(std::fs::read(artifacts().join("{{name}}.tvc"))?);
+    // Values of the static variables
+    let initial_data = json!({{initial_data}});
+    // Parameters of the constructor
+    let constructor_params = json!({{constructor_params}});
+
+    let keys = load_keys(&context)?;
+    let deploy_params = ParamsOfEncodeMessage {
+        abi: read_abi(artifacts().join("{{name}}.abi.json"))?,
+        deploy_set: Some(DeploySet { tvc: Some(tvc), initial_data: Some(initial_data), ..Default::default() }),
+        call_set: CallSet::some_with_function_and_input("constructor", constructor_params),
+        signer: Signer::Keys { keys },
+        ..Default::default()
+    };
+    let message = ton_client::abi::encode_message(context.clone(), deploy_params.clone()).await?;
+    println!("Address: {}", message.address);
+
+    fund(&context, &message.address, DEPLOY_VALUE).await?;
+    println!("Funded with {} nano", DEPLOY_VALUE);
+
+    let result = ton_client::processing::process_message(
+        context.clone(),
+        ParamsOfProcessMessage { message_encode_params: deploy_params, send_events: false },
+        |_| async {},
+    ).await?;
+    println!("Deployed in transaction {}", result.transaction["id"]);
+    Ok(())
+}
@@ -0,0 +1,24 @@
+pragma ever-solidity >={{compiler_version}};
+pragma AbiHeader expire;
+
+// A counter only the owner can increment
+contract {{name}} {
+    uint16 constant ERR_NOT_OWNER = 101;
+
+    uint64 public count;
+
+    constructor() {
+        require(tvm.pubkey() != 0, ERR_NOT_OWNER);
+        tvm.accept();
+    }
+
+    modifier onlyOwner() {
+        require(msg.pubkey() == tvm.pubkey(), ERR_NOT_OWNER, "Only the owner can call this");
+        tvm.accept();
+        _;
+    }
+
+    function increment(uint64 delta) external onlyOwner {
+        count += delta;
+    }
+}
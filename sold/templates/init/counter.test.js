@@ -0,0 +1,68 @@
+// Runs {{name}} locally with the TVM of the SDK, no node is needed.
+//
+//   npm test
+
+const { abiContract, signerKeys, signerNone, TonClient } = require("@eversdk/core");
+const { libNode } = require("@eversdk/lib-node");
+const assert = require("assert");
+const fs = require("fs");
+const path = require("path");
+const test = require("node:test");
+
+TonClient.useBinaryLibrary(libNode);
+
+const BUILD = path.join(__dirname, "..", "build");
+const abi = abiContract(JSON.parse(fs.readFileSync(path.join(BUILD, "{{name}}.abi.json"), "utf8")));
+const tvc = fs.readFileSync(path.join(BUILD, "{{name}}.tvc")).toString("base64");
+
+// Account of the deployed contract, after running the constructor
+async function deploy(client, keys) {
+	const { message } = await client.abi.encode_message({
+		abi,
+		signer: signerKeys(keys),
+		deploy_set: { tvc },
+		call_set: { function_name: "constructor" },
+	});
+	const { account } = await client.abi.encode_account({
+		state_init: { tvc: { tvc, public_key: keys.public } },
+		balance: "1000000000",
+	});
+	return (await client.tvm.run_executor({ message, account: { type: "Account", boc: account }, skip_transaction_check: true, return_updated_account: true })).account;
+}
+
+async function address(client, account) {
+	return (await client.boc.parse_account({ boc: account })).parsed.id;
+}
+
+async function call(client, account, keys, function_name, input) {
+	const { message } = await client.abi.encode_message({
+		abi,
+		address: await address(client, account),
+		signer: signerKeys(keys),
+		call_set: { function_name, input },
+	});
+	return (await client.tvm.run_executor({ message, account: { type: "Account", boc: account }, skip_transaction_check: true, return_updated_account: true })).account;
+}
+
+async function get(client, account, function_name) {
+	const { message } = await client.abi.encode_message({
+		abi,
+		address: await address(client, account),
+		signer: signerNone(),
+		call_set: { function_name },
+	});
+	return (await client.tvm.run_tvm({ message, account, abi })).decoded.output;
+}
+
+test("increment adds to the count", async () => {
+	const client = new TonClient();
+	try {
+		const keys = await client.crypto.generate_random_sign_keys();
+		let account = await deploy(client, keys);
+		account = await call(client, account, keys, "increment", { delta: 2 });
+		const { count } = await get(client, account, "count");
+		assert.strictEqual(count, "2");
+	} finally {
+		client.close();
+	}
+});
@@ -0,0 +1,8 @@
+pragma ever-solidity >={{compiler_version}};
+pragma AbiHeader expire;
+
+contract {{name}} {
+    constructor() {
+        tvm.accept();
+    }
+}
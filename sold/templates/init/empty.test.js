@@ -0,0 +1,37 @@
+// Runs {{name}} locally with the TVM of the SDK, no node is needed.
+//
+//   npm test
+
+const { abiContract, signerKeys, TonClient } = require("@eversdk/core");
+const { libNode } = require("@eversdk/lib-node");
+const assert = require("assert");
+const fs = require("fs");
+const path = require("path");
+const test = require("node:test");
+
+TonClient.useBinaryLibrary(libNode);
+
+const BUILD = path.join(__dirname, "..", "build");
+const abi = abiContract(JSON.parse(fs.readFileSync(path.join(BUILD, "{{name}}.abi.json"), "utf8")));
+const tvc = fs.readFileSync(path.join(BUILD, "{{name}}.tvc")).toString("base64");
+
+test("constructor succeeds", async () => {
+	const client = new TonClient();
+	try {
+		const keys = await client.crypto.generate_random_sign_keys();
+		const { message } = await client.abi.encode_message({
+			abi,
+			signer: signerKeys(keys),
+			deploy_set: { tvc },
+			call_set: { function_name: "constructor" },
+		});
+		const { account } = await client.abi.encode_account({
+			state_init: { tvc: { tvc, public_key: keys.public } },
+			balance: "1000000000",
+		});
+		const result = await client.tvm.run_executor({ message, account: { type: "Account", boc: account }, skip_transaction_check: true });
+		assert.ok(result.transaction.compute.success);
+	} finally {
+		client.close();
+	}
+});
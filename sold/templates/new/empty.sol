@@ -0,0 +1,10 @@
+pragma ever-solidity >={{compiler_version}};
+pragma AbiHeader expire;
+pragma AbiHeader pubkey;
+
+contract {{name}} {
+    constructor() {
+        require(tvm.pubkey() != 0, 101);
+        tvm.accept();
+    }
+}
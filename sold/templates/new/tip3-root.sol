@@ -0,0 +1,298 @@
+pragma ever-solidity >={{compiler_version}};
+pragma AbiHeader expire;
+pragma AbiHeader pubkey;
+pragma AbiHeader time;
+
+// TIP-3.1 fungible token: the root {{name}} and the wallet {{name}}Wallet. Build both:
+//
+//   sold {{name}}.sol --contract {{name}}Wallet
+//   sold {{name}}.sol --contract {{name}} --init '{"name_": "...", "symbol_": "...", "decimals_": 9,
+//       "rootOwner_": "0:...", "walletCode_": "<code of {{name}}Wallet>"}'
+//
+// The function IDs of acceptMint, acceptTransfer and acceptBurn are fixed by the standard, and
+// the interface IDs of TIP-6 are computed from the function IDs, so they stay correct.
+
+interface SID {
+    function supportsInterface(bytes4 interfaceID) external view responsible returns (bool);
+}
+
+interface ITokenRoot {
+    function name() external view responsible returns (string);
+    function symbol() external view responsible returns (string);
+    function decimals() external view responsible returns (uint8);
+    function totalSupply() external view responsible returns (uint128);
+    function walletCode() external view responsible returns (TvmCell);
+    function rootOwner() external view responsible returns (address);
+    function walletOf(address owner) external view responsible returns (address);
+    function deployWallet(address owner, uint128 deployWalletValue) external responsible returns (address);
+}
+
+interface ITokenWallet {
+    function root() external view responsible returns (address);
+    function balance() external view responsible returns (uint128);
+    function walletCode() external view responsible returns (TvmCell);
+}
+
+interface IAcceptTokensTransferCallback {
+    function onAcceptTokensTransfer(address tokenRoot, uint128 amount, address sender, address senderWallet, address remainingGasTo, TvmCell payload) external;
+}
+
+interface IAcceptTokensMintCallback {
+    function onAcceptTokensMint(address tokenRoot, uint128 amount, address remainingGasTo, TvmCell payload) external;
+}
+
+library Errors {
+    uint16 constant NOT_OWNER = 1000;
+    uint16 constant NOT_ROOT = 1001;
+    uint16 constant NOT_WALLET = 1002;
+    uint16 constant LOW_BALANCE = 1003;
+    uint16 constant WRONG_RECIPIENT = 1004;
+    uint16 constant LOW_GAS = 1005;
+}
+
+library Gas {
+    uint128 constant TARGET_BALANCE = 0.1 ever;
+    uint128 constant DEPLOY_WALLET_VALUE = 0.1 ever;
+}
+
+contract {{name}} is SID, ITokenRoot {
+    string static name_;
+    string static symbol_;
+    uint8 static decimals_;
+    address static rootOwner_;
+    TvmCell static walletCode_;
+
+    uint128 totalSupply_;
+
+    modifier onlyRootOwner() {
+        require(msg.sender == rootOwner_ && msg.sender.value != 0, Errors.NOT_OWNER);
+        _;
+    }
+
+    constructor(address remainingGasTo) {
+        require(msg.sender == rootOwner_ || msg.pubkey() == tvm.pubkey() && msg.pubkey() != 0, Errors.NOT_OWNER);
+        tvm.accept();
+        if (remainingGasTo.value != 0) {
+            tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+            remainingGasTo.transfer({ value: 0, flag: 128, bounce: false });
+        }
+    }
+
+    function supportsInterface(bytes4 interfaceID) override external view responsible returns (bool) {
+        return { value: 0, flag: 64, bounce: false } (
+            interfaceID == bytes4(tvm.functionId(SID.supportsInterface)) ||
+            interfaceID == bytes4(
+                tvm.functionId(ITokenRoot.name) ^
+                tvm.functionId(ITokenRoot.symbol) ^
+                tvm.functionId(ITokenRoot.decimals) ^
+                tvm.functionId(ITokenRoot.totalSupply) ^
+                tvm.functionId(ITokenRoot.walletCode) ^
+                tvm.functionId({{name}}.acceptBurn)
+            ) ||
+            interfaceID == bytes4(
+                tvm.functionId(ITokenRoot.rootOwner) ^
+                tvm.functionId(ITokenRoot.walletOf) ^
+                tvm.functionId({{name}}.mint) ^
+                tvm.functionId(ITokenRoot.deployWallet)
+            )
+        );
+    }
+
+    function name() override external view responsible returns (string) {
+        return { value: 0, flag: 64, bounce: false } name_;
+    }
+
+    function symbol() override external view responsible returns (string) {
+        return { value: 0, flag: 64, bounce: false } symbol_;
+    }
+
+    function decimals() override external view responsible returns (uint8) {
+        return { value: 0, flag: 64, bounce: false } decimals_;
+    }
+
+    function totalSupply() override external view responsible returns (uint128) {
+        return { value: 0, flag: 64, bounce: false } totalSupply_;
+    }
+
+    function walletCode() override external view responsible returns (TvmCell) {
+        return { value: 0, flag: 64, bounce: false } walletCode_;
+    }
+
+    function rootOwner() override external view responsible returns (address) {
+        return { value: 0, flag: 64, bounce: false } rootOwner_;
+    }
+
+    function walletOf(address owner) override external view responsible returns (address) {
+        return { value: 0, flag: 64, bounce: false } _walletAddress(owner);
+    }
+
+    function deployWallet(address owner, uint128 deployWalletValue) override external responsible returns (address) {
+        require(owner.value != 0, Errors.WRONG_RECIPIENT);
+        tvm.rawReserve(_reserve(), 0);
+        address wallet = _deployWallet(owner, deployWalletValue);
+        return { value: 0, flag: 128, bounce: false } wallet;
+    }
+
+    function mint(uint128 amount, address recipient, uint128 deployWalletValue, address remainingGasTo, bool notify, TvmCell payload) external onlyRootOwner {
+        require(amount > 0, Errors.LOW_BALANCE);
+        require(recipient.value != 0, Errors.WRONG_RECIPIENT);
+        tvm.rawReserve(_reserve(), 0);
+        totalSupply_ += amount;
+        address wallet = deployWalletValue > 0 ? _deployWallet(recipient, deployWalletValue) : _walletAddress(recipient);
+        {{name}}Wallet(wallet).acceptMint{ value: 0, flag: 128, bounce: true }(amount, remainingGasTo, notify, payload);
+    }
+
+    function acceptBurn(uint128 amount, address walletOwner, address remainingGasTo, address callbackTo, TvmCell payload) external functionID(0x192B51B1) {
+        require(msg.sender == _walletAddress(walletOwner), Errors.NOT_WALLET);
+        tvm.rawReserve(_reserve(), 0);
+        totalSupply_ -= amount;
+        if (callbackTo.value == 0) {
+            remainingGasTo.transfer({ value: 0, flag: 128, bounce: false });
+        } else {
+            callbackTo.transfer({ value: 0, flag: 128, bounce: false, body: payload });
+        }
+    }
+
+    onBounce(TvmSlice body) external {
+        // A mint to a wallet that doesn't exist
+        if (body.decode(uint32) == tvm.functionId({{name}}Wallet.acceptMint)) {
+            totalSupply_ -= body.decode(uint128);
+        }
+    }
+
+    function _reserve() private view returns (uint128) {
+        return math.max(address(this).balance - msg.value, Gas.TARGET_BALANCE);
+    }
+
+    function _walletStateInit(address owner) private view returns (TvmCell) {
+        return tvm.buildStateInit({
+            contr: {{name}}Wallet,
+            varInit: { root_: address(this), owner_: owner },
+            pubkey: 0,
+            code: walletCode_
+        });
+    }
+
+    function _walletAddress(address owner) private view returns (address) {
+        return address(tvm.hash(_walletStateInit(owner)));
+    }
+
+    function _deployWallet(address owner, uint128 deployWalletValue) private view returns (address) {
+        return new {{name}}Wallet{
+            stateInit: _walletStateInit(owner),
+            value: deployWalletValue,
+            flag: 1,
+            bounce: false
+        }();
+    }
+}
+
+contract {{name}}Wallet is SID, ITokenWallet {
+    address static root_;
+    address static owner_;
+
+    uint128 balance_;
+
+    modifier onlyOwner() {
+        require(msg.sender == owner_ && msg.sender.value != 0, Errors.NOT_OWNER);
+        _;
+    }
+
+    constructor() {
+        require(msg.sender == root_ || msg.sender == owner_ && msg.sender.value != 0, Errors.NOT_ROOT);
+    }
+
+    function supportsInterface(bytes4 interfaceID) override external view responsible returns (bool) {
+        return { value: 0, flag: 64, bounce: false } (
+            interfaceID == bytes4(tvm.functionId(SID.supportsInterface)) ||
+            interfaceID == bytes4(
+                tvm.functionId(ITokenWallet.root) ^
+                tvm.functionId(ITokenWallet.balance) ^
+                tvm.functionId(ITokenWallet.walletCode) ^
+                tvm.functionId({{name}}Wallet.acceptTransfer) ^
+                tvm.functionId({{name}}Wallet.acceptMint)
+            ) ||
+            interfaceID == bytes4(tvm.functionId({{name}}Wallet.owner))
+        );
+    }
+
+    function root() override external view responsible returns (address) {
+        return { value: 0, flag: 64, bounce: false } root_;
+    }
+
+    function balance() override external view responsible returns (uint128) {
+        return { value: 0, flag: 64, bounce: false } balance_;
+    }
+
+    function walletCode() override external view responsible returns (TvmCell) {
+        return { value: 0, flag: 64, bounce: false } tvm.code();
+    }
+
+    function owner() external view responsible returns (address) {
+        return { value: 0, flag: 64, bounce: false } owner_;
+    }
+
+    function transfer(uint128 amount, address recipient, uint128 deployWalletValue, address remainingGasTo, bool notify, TvmCell payload) external onlyOwner {
+        require(amount > 0 && amount <= balance_, Errors.LOW_BALANCE);
+        require(recipient.value != 0 && recipient != owner_, Errors.WRONG_RECIPIENT);
+        tvm.rawReserve(_reserve(), 0);
+        TvmCell stateInit = tvm.buildStateInit({
+            contr: {{name}}Wallet,
+            varInit: { root_: root_, owner_: recipient },
+            pubkey: 0,
+            code: tvm.code()
+        });
+        address wallet = deployWalletValue > 0
+            ? new {{name}}Wallet{ stateInit: stateInit, value: deployWalletValue, flag: 1, bounce: false }()
+            : address(tvm.hash(stateInit));
+        balance_ -= amount;
+        {{name}}Wallet(wallet).acceptTransfer{ value: 0, flag: 128, bounce: true }(amount, owner_, remainingGasTo, notify, payload);
+    }
+
+    function acceptTransfer(uint128 amount, address sender, address remainingGasTo, bool notify, TvmCell payload) external functionID(0x67A0B95F) {
+        TvmCell stateInit = tvm.buildStateInit({
+            contr: {{name}}Wallet,
+            varInit: { root_: root_, owner_: sender },
+            pubkey: 0,
+            code: tvm.code()
+        });
+        require(msg.sender == address(tvm.hash(stateInit)), Errors.NOT_WALLET);
+        tvm.rawReserve(_reserve(), 2);
+        balance_ += amount;
+        if (notify) {
+            IAcceptTokensTransferCallback(owner_).onAcceptTokensTransfer{ value: 0, flag: 128, bounce: false }(root_, amount, sender, msg.sender, remainingGasTo, payload);
+        } else {
+            remainingGasTo.transfer({ value: 0, flag: 128, bounce: false });
+        }
+    }
+
+    function acceptMint(uint128 amount, address remainingGasTo, bool notify, TvmCell payload) external functionID(0x4384F298) {
+        require(msg.sender == root_, Errors.NOT_ROOT);
+        tvm.rawReserve(_reserve(), 2);
+        balance_ += amount;
+        if (notify) {
+            IAcceptTokensMintCallback(owner_).onAcceptTokensMint{ value: 0, flag: 128, bounce: false }(root_, amount, remainingGasTo, payload);
+        } else {
+            remainingGasTo.transfer({ value: 0, flag: 128, bounce: false });
+        }
+    }
+
+    function burn(uint128 amount, address remainingGasTo, address callbackTo, TvmCell payload) external onlyOwner {
+        require(amount > 0 && amount <= balance_, Errors.LOW_BALANCE);
+        tvm.rawReserve(_reserve(), 0);
+        balance_ -= amount;
+        {{name}}(root_).acceptBurn{ value: 0, flag: 128, bounce: true }(amount, owner_, remainingGasTo, callbackTo, payload);
+    }
+
+    onBounce(TvmSlice body) external {
+        // A transfer or a burn that failed returns the tokens
+        uint32 functionId = body.decode(uint32);
+        if (functionId == tvm.functionId({{name}}Wallet.acceptTransfer) || functionId == tvm.functionId({{name}}.acceptBurn)) {
+            balance_ += body.decode(uint128);
+        }
+    }
+
+    function _reserve() private view returns (uint128) {
+        return math.max(address(this).balance - msg.value, Gas.TARGET_BALANCE);
+    }
+}
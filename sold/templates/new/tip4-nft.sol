@@ -0,0 +1,240 @@
+pragma ever-solidity >={{compiler_version}};
+pragma AbiHeader expire;
+pragma AbiHeader pubkey;
+pragma AbiHeader time;
+
+// TIP-4.1 non-fungible tokens: the collection {{name}} and the token {{name}}Nft. Build both:
+//
+//   sold {{name}}.sol --contract {{name}}Nft
+//   sold {{name}}.sol --contract {{name}} --ctor-params '{"codeNft": "<code of {{name}}Nft>"}'
+//
+// The interface IDs of TIP-6 are computed from the function IDs, so they stay correct.
+
+interface SID {
+    function supportsInterface(bytes4 interfaceID) external view responsible returns (bool);
+}
+
+interface ITIP4_1Collection {
+    event NftCreated(uint256 id, address nft, address owner, address manager, address creator);
+    event NftBurned(uint256 id, address nft, address owner, address manager);
+
+    function totalSupply() external view responsible returns (uint128 count);
+    function nftCode() external view responsible returns (TvmCell code);
+    function nftCodeHash() external view responsible returns (uint256 codeHash);
+    function nftAddress(uint256 id) external view responsible returns (address nft);
+}
+
+interface ITIP4_1NFT {
+    struct CallbackParams {
+        uint128 value;
+        TvmCell payload;
+    }
+
+    event NftCreated(uint256 id, address owner, address manager, address collection);
+    event OwnerChanged(address oldOwner, address newOwner);
+    event ManagerChanged(address oldManager, address newManager);
+    event NftBurned(uint256 id, address owner, address manager, address collection);
+
+    function getInfo() external view responsible returns (uint256 id, address owner, address manager, address collection);
+    function changeOwner(address newOwner, address sendGasTo, mapping(address => CallbackParams) callbacks) external;
+    function changeManager(address newManager, address sendGasTo, mapping(address => CallbackParams) callbacks) external;
+    function transfer(address to, address sendGasTo, mapping(address => CallbackParams) callbacks) external;
+}
+
+interface INftChangeOwner {
+    function onNftChangeOwner(uint256 id, address owner, address oldOwner, address newOwner, address collection, address sendGasTo, TvmCell payload) external;
+}
+
+interface INftChangeManager {
+    function onNftChangeManager(uint256 id, address owner, address oldManager, address newManager, address collection, address sendGasTo, TvmCell payload) external;
+}
+
+interface INftTransfer {
+    function onNftTransfer(uint256 id, address oldOwner, address newOwner, address oldManager, address newManager, address collection, address gasReceiver, TvmCell payload) external;
+}
+
+library Errors {
+    uint16 constant NOT_OWNER = 1000;
+    uint16 constant NOT_MANAGER = 1001;
+    uint16 constant NOT_COLLECTION = 1002;
+    uint16 constant LOW_VALUE = 1003;
+}
+
+library Gas {
+    uint128 constant TARGET_BALANCE = 0.1 ever;
+    uint128 constant DEPLOY_NFT_VALUE = 0.2 ever;
+}
+
+contract {{name}} is SID, ITIP4_1Collection {
+    TvmCell codeNft_;
+    uint128 totalSupply_;
+    uint256 nextId_;
+
+    modifier onlyOwner() {
+        require(msg.pubkey() == tvm.pubkey(), Errors.NOT_OWNER);
+        tvm.accept();
+        _;
+    }
+
+    constructor(TvmCell codeNft) onlyOwner {
+        codeNft_ = codeNft;
+    }
+
+    function supportsInterface(bytes4 interfaceID) override external view responsible returns (bool) {
+        return { value: 0, flag: 64, bounce: false } (
+            interfaceID == bytes4(tvm.functionId(SID.supportsInterface)) ||
+            interfaceID == bytes4(
+                tvm.functionId(ITIP4_1Collection.totalSupply) ^
+                tvm.functionId(ITIP4_1Collection.nftCode) ^
+                tvm.functionId(ITIP4_1Collection.nftCodeHash) ^
+                tvm.functionId(ITIP4_1Collection.nftAddress)
+            )
+        );
+    }
+
+    /// Deploys a token owned and managed by `owner`
+    function mint(address owner) external {
+        require(msg.value >= Gas.DEPLOY_NFT_VALUE + Gas.TARGET_BALANCE, Errors.LOW_VALUE);
+        tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+        uint256 id = nextId_;
+        nextId_++;
+        totalSupply_++;
+        address nft = new {{name}}Nft{
+            stateInit: _nftStateInit(id),
+            value: Gas.DEPLOY_NFT_VALUE,
+            flag: 0
+        }(owner, msg.sender);
+        emit NftCreated(id, nft, owner, owner, msg.sender);
+        msg.sender.transfer({ value: 0, flag: 128, bounce: false });
+    }
+
+    /// Called by a token being burned
+    function acceptNftBurn(uint256 id, address owner, address manager, address sendGasTo) external {
+        require(msg.sender == _nftAddress(id), Errors.NOT_COLLECTION);
+        tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+        totalSupply_--;
+        emit NftBurned(id, msg.sender, owner, manager);
+        sendGasTo.transfer({ value: 0, flag: 128, bounce: false });
+    }
+
+    function totalSupply() override external view responsible returns (uint128 count) {
+        return { value: 0, flag: 64, bounce: false } totalSupply_;
+    }
+
+    function nftCode() override external view responsible returns (TvmCell code) {
+        return { value: 0, flag: 64, bounce: false } codeNft_;
+    }
+
+    function nftCodeHash() override external view responsible returns (uint256 codeHash) {
+        return { value: 0, flag: 64, bounce: false } tvm.hash(codeNft_);
+    }
+
+    function nftAddress(uint256 id) override external view responsible returns (address nft) {
+        return { value: 0, flag: 64, bounce: false } _nftAddress(id);
+    }
+
+    function _nftStateInit(uint256 id) private view returns (TvmCell) {
+        return tvm.buildStateInit({
+            contr: {{name}}Nft,
+            varInit: { id_: id, collection_: address(this) },
+            pubkey: 0,
+            code: codeNft_
+        });
+    }
+
+    function _nftAddress(uint256 id) private view returns (address) {
+        return address(tvm.hash(_nftStateInit(id)));
+    }
+}
+
+contract {{name}}Nft is SID, ITIP4_1NFT {
+    uint256 static id_;
+    address static collection_;
+
+    address owner_;
+    address manager_;
+
+    modifier onlyManager() {
+        require(msg.sender == manager_, Errors.NOT_MANAGER);
+        _;
+    }
+
+    constructor(address owner, address sendGasTo) {
+        require(msg.sender == collection_, Errors.NOT_COLLECTION);
+        tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+        owner_ = owner;
+        manager_ = owner;
+        emit NftCreated(id_, owner_, manager_, collection_);
+        sendGasTo.transfer({ value: 0, flag: 128, bounce: false });
+    }
+
+    function supportsInterface(bytes4 interfaceID) override external view responsible returns (bool) {
+        return { value: 0, flag: 64, bounce: false } (
+            interfaceID == bytes4(tvm.functionId(SID.supportsInterface)) ||
+            interfaceID == bytes4(
+                tvm.functionId(ITIP4_1NFT.getInfo) ^
+                tvm.functionId(ITIP4_1NFT.changeOwner) ^
+                tvm.functionId(ITIP4_1NFT.changeManager) ^
+                tvm.functionId(ITIP4_1NFT.transfer)
+            )
+        );
+    }
+
+    function getInfo() override external view responsible returns (uint256 id, address owner, address manager, address collection) {
+        return { value: 0, flag: 64, bounce: false } (id_, owner_, manager_, collection_);
+    }
+
+    function changeOwner(address newOwner, address sendGasTo, mapping(address => CallbackParams) callbacks) override external onlyManager {
+        tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+        address oldOwner = owner_;
+        owner_ = newOwner;
+        emit OwnerChanged(oldOwner, newOwner);
+        for ((address dest, CallbackParams params) : callbacks) {
+            INftChangeOwner(dest).onNftChangeOwner{ value: params.value, flag: 0, bounce: false }(
+                id_, owner_, oldOwner, newOwner, collection_, sendGasTo, params.payload
+            );
+        }
+        _returnGas(sendGasTo);
+    }
+
+    function changeManager(address newManager, address sendGasTo, mapping(address => CallbackParams) callbacks) override external onlyManager {
+        tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+        address oldManager = manager_;
+        manager_ = newManager;
+        emit ManagerChanged(oldManager, newManager);
+        for ((address dest, CallbackParams params) : callbacks) {
+            INftChangeManager(dest).onNftChangeManager{ value: params.value, flag: 0, bounce: false }(
+                id_, owner_, oldManager, newManager, collection_, sendGasTo, params.payload
+            );
+        }
+        _returnGas(sendGasTo);
+    }
+
+    function transfer(address to, address sendGasTo, mapping(address => CallbackParams) callbacks) override external onlyManager {
+        tvm.rawReserve(Gas.TARGET_BALANCE, 0);
+        address oldOwner = owner_;
+        address oldManager = manager_;
+        owner_ = to;
+        manager_ = to;
+        emit OwnerChanged(oldOwner, to);
+        emit ManagerChanged(oldManager, to);
+        for ((address dest, CallbackParams params) : callbacks) {
+            INftTransfer(dest).onNftTransfer{ value: params.value, flag: 0, bounce: false }(
+                id_, oldOwner, to, oldManager, to, collection_, sendGasTo, params.payload
+            );
+        }
+        _returnGas(sendGasTo);
+    }
+
+    /// Destroys the token, sending its balance to `sendGasTo`
+    function burn(address sendGasTo) external onlyManager {
+        emit NftBurned(id_, owner_, manager_, collection_);
+        {{name}}(collection_).acceptNftBurn{ value: 0, flag: 128 + 32, bounce: false }(id_, owner_, manager_, sendGasTo);
+    }
+
+    function _returnGas(address sendGasTo) private pure {
+        if (sendGasTo.value != 0) {
+            sendGasTo.transfer({ value: 0, flag: 128, bounce: false });
+        }
+    }
+}
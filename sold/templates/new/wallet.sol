@@ -0,0 +1,29 @@
+pragma ever-solidity >={{compiler_version}};
+pragma AbiHeader expire;
+pragma AbiHeader pubkey;
+pragma AbiHeader time;
+
+// A wallet owned by a key pair. sendTransaction has the signature of the multisig
+// wallets, so tools funding addresses from them work with this one as well.
+contract {{name}} {
+    uint16 constant ERR_NOT_OWNER = 100;
+    uint16 constant ERR_LOW_BALANCE = 101;
+
+    modifier onlyOwner() {
+        require(msg.pubkey() == tvm.pubkey(), ERR_NOT_OWNER, "Only the owner can call this");
+        tvm.accept();
+        _;
+    }
+
+    constructor() onlyOwner {
+    }
+
+    /// Sends `value` nanoevers to `dest`, with `payload` as the body of the message
+    function sendTransaction(address dest, uint128 value, bool bounce, uint8 flags, TvmCell payload) public onlyOwner {
+        require(address(this).balance >= value || (flags & 128) != 0, ERR_LOW_BALANCE, "Not enough balance");
+        dest.transfer(value, bounce, flags, payload);
+    }
+
+    receive() external {
+    }
+}
@@ -0,0 +1,22 @@
+pragma ever-solidity >=0.50.0;
+
+contract Constants {
+    uint public balance;
+
+    function withdraw(uint value) public returns (string) {
+        tvm.accept();
+        if (value > balance) {
+            return "Not enough balance for the transfer";
+        }
+        balance -= value;
+        return "Done";
+    }
+
+    function transfer(uint value) public returns (string) {
+        tvm.accept();
+        if (value > balance) {
+            return "Not enough balance for the transfer";
+        }
+        return "Done";
+    }
+}
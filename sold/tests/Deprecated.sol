@@ -0,0 +1,8 @@
+pragma ton-solidity >=0.50.0;
+
+contract Deprecated {
+    // tvm.deploy() in a comment is not reported
+    function getSize(TvmSlice s) public pure returns (uint16, uint8) {
+        return s.bitsAndRefs();
+    }
+}
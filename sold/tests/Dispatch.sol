@@ -0,0 +1,25 @@
+pragma ever-solidity >=0.50.0;
+
+contract Dispatch {
+    uint public counter;
+
+    function add(uint value) public {
+        tvm.accept();
+        counter += value;
+    }
+
+    function reset() public {
+        tvm.accept();
+        counter = 0;
+    }
+
+    function twice() public {
+        tvm.accept();
+        counter *= 2;
+    }
+
+    function transfer(address dest, uint128 value) public {
+        tvm.accept();
+        dest.transfer(value);
+    }
+}
@@ -0,0 +1,4 @@
+pragma ever-solidity >=0.50.0;
+// Defines the contract of Trivial.sol again
+contract Trivial {
+}
@@ -0,0 +1,14 @@
+pragma ever-solidity >=0.50.0;
+
+contract Errors {
+    uint16 constant ERR_NOT_OWNER = 101;
+
+    uint256 owner;
+
+    function transfer(uint128 value) public view {
+        require(msg.pubkey() == owner, ERR_NOT_OWNER, "Not owner");
+        if (value == 0) {
+            revert(102);
+        }
+    }
+}
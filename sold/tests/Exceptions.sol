@@ -0,0 +1,14 @@
+pragma ever-solidity >=0.50.0;
+
+contract Exceptions {
+    uint[] values;
+
+    function push(uint value) public {
+        tvm.accept();
+        values.push(value + 1);
+    }
+
+    function last() public view returns (uint) {
+        return values[values.length - 1];
+    }
+}
@@ -0,0 +1,11 @@
+pragma ever-solidity >=0.50.0;
+
+contract Pooled {
+    function isOwner(address a) public view {
+        require(a == address(0x1111111111111111111111111111111111111111111111111111111111111111), 101);
+    }
+
+    function isOwnerToo(address a) public view {
+        require(a == address(0x1111111111111111111111111111111111111111111111111111111111111111), 102);
+    }
+}
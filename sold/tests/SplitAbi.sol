@@ -0,0 +1,20 @@
+pragma ever-solidity >=0.50.0;
+
+contract Base {
+    function owner() public pure returns (uint) {
+        return 1;
+    }
+}
+
+contract SplitAbi is Base {
+    uint public counter;
+
+    function add(uint value) public {
+        tvm.accept();
+        counter += value;
+    }
+
+    function total(uint value) external view returns (uint) {
+        return counter + value;
+    }
+}
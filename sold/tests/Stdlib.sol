@@ -0,0 +1,6 @@
+pragma ever-solidity >=0.50.0;
+contract Stdlib {
+    function greet(string name) public pure returns (string) {
+        return "Hello, " + name;
+    }
+}
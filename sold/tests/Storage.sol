@@ -0,0 +1,10 @@
+pragma ever-solidity >=0.50.0;
+
+contract Storage {
+    mapping(uint32 => uint64) public balances;
+
+    function deposit(uint32 key, uint64 value) public {
+        tvm.accept();
+        balances[key] += value;
+    }
+}
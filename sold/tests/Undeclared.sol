@@ -0,0 +1,6 @@
+pragma ever-solidity >=0.50.0;
+contract Undeclared {
+    function foo() public pure returns (uint) {
+        return missing;
+    }
+}
@@ -0,0 +1,6 @@
+pragma ever-solidity >=0.50.0;
+import "@lib/Owned.sol";
+contract Helper {
+}
+contract Project is Owned {
+}
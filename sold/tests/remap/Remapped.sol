@@ -0,0 +1,4 @@
+pragma ever-solidity >=0.50.0;
+import "@lib/Owned.sol";
+contract Remapped is Owned {
+}
@@ -0,0 +1,4 @@
+pragma ever-solidity >=0.50.0;
+import "../remap/vendor/lib/Owned.sol";
+contract Escape is Owned {
+}
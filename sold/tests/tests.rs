@@ -13,6 +13,7 @@
 
 use predicates::prelude::*;
 use assert_cmd::Command;
+use std::path::Path;
 
 type Status = Result<(), Box<dyn std::error::Error>>;
 const BIN_NAME: &str = "sold";
@@ -86,6 +87,59 @@ fn test_abi_json() -> Status {
     Ok(())
 }
 
+#[test]
+fn test_abi_show() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("abi")
+        .arg("show")
+        .arg("tests/Token.abi")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"transfer\""))
+        .stdout(predicate::str::contains("\"components\":[{\"name\":\"addr\",\"type\":\"address\"}"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("abi")
+        .arg("show")
+        .arg("tests/Token.abi")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("function getOwner() external returns (tuple(address addr, uint32 since) owner)"));
+    Ok(())
+}
+
+#[test]
+fn test_abi_schema() -> Status {
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("abi")
+        .arg("schema")
+        .arg("tests/Token.abi")
+        .arg("--function")
+        .arg("transfer")
+        .output()?;
+    assert!(output.status.success());
+
+    let schemas: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let inputs = &schemas["functions"]["transfer"]["inputs"];
+    assert_eq!(inputs["required"], serde_json::json!(["dest", "amount"]));
+    assert_eq!(inputs["properties"]["dest"]["type"], "string");
+    Ok(())
+}
+
+#[test]
+fn test_abi_event_id() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("abi")
+        .arg("event-id")
+        .arg("--abi")
+        .arg("tests/Token.abi")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("^Transferred  0x0cf2fcf5  Transferred\\(address,uint128\\)v2\n$")?);
+    Ok(())
+}
+
 #[test]
 fn test_library() -> Status {
     Command::cargo_bin(BIN_NAME)?
@@ -127,6 +181,208 @@ fn test_error_reporting() -> Status {
     Ok(())
 }
 
+#[test]
+fn test_color() -> Status {
+    let stderr = |args: &[&str], env: &[(&str, &str)]| -> Result<String, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        cmd.env_remove("NO_COLOR")
+            .env_remove("CLICOLOR_FORCE")
+            .env("TERM", "xterm")
+            .envs(env.iter().copied())
+            .arg("tests/ErrorReporting.sol")
+            .arg("--output-dir")
+            .arg("tests")
+            .args(args);
+        let output = cmd.assert().failure().get_output().clone();
+        Ok(String::from_utf8(output.stderr)?)
+    };
+    const ESCAPE: &str = "\u{1b}[";
+
+    // stderr is a pipe here, so auto colors only when forced
+    assert!(!stderr(&[], &[])?.contains(ESCAPE));
+    assert!(stderr(&["--color", "always"], &[])?.contains(ESCAPE));
+    assert!(stderr(&[], &[("CLICOLOR_FORCE", "1")])?.contains(ESCAPE));
+    assert!(!stderr(&[], &[("CLICOLOR_FORCE", "0")])?.contains(ESCAPE));
+    assert!(!stderr(&[], &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")])?.contains(ESCAPE));
+    assert!(!stderr(&["--color", "never"], &[("CLICOLOR_FORCE", "1")])?.contains(ESCAPE));
+    assert!(!stderr(&[], &[("TERM", "dumb")])?.contains(ESCAPE));
+    // An explicit choice wins over the environment
+    assert!(stderr(&["--color", "always"], &[("NO_COLOR", "1"), ("TERM", "dumb")])?.contains(ESCAPE));
+
+    Ok(())
+}
+
+#[test]
+fn test_error_format_github() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/ErrorReporting.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--error-format")
+        .arg("github")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("::error file=tests/ErrorReporting.sol,line=4,"));
+
+    Ok(())
+}
+
+#[test]
+fn test_error_format_json() -> Status {
+    let assert = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/ErrorReporting.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--error-format")
+        .arg("json")
+        .assert()
+        .failure();
+
+    let diagnostics: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    let error = &diagnostics.as_array().unwrap()[0];
+    assert_eq!(error["severity"], "error");
+    assert_eq!(error["file"], "tests/ErrorReporting.sol");
+    assert_eq!(error["line"], 4);
+    assert!(error["code"].is_string());
+    assert!(error["message"].is_string());
+    Ok(())
+}
+
+#[test]
+fn test_error_format_json_position() -> Status {
+    let assert = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Undeclared.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--error-format")
+        .arg("json")
+        .assert()
+        .failure();
+
+    let diagnostics: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    let error = &diagnostics.as_array().unwrap()[0];
+    assert!(error["message"].as_str().unwrap().contains("Undeclared identifier"));
+    // `missing` on line 4 after eight spaces and "return "
+    assert_eq!(error["line"], 4);
+    assert_eq!(error["column"], 16);
+    assert_eq!(error["endLine"], 4);
+    assert_eq!(error["endColumn"], 23);
+    Ok(())
+}
+
+#[test]
+fn test_error_format_sarif() -> Status {
+    let assert = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/ErrorReporting.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--error-format")
+        .arg("sarif")
+        .assert()
+        .failure();
+
+    let log: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "sold");
+    let result = &run["results"][0];
+    assert_eq!(result["level"], "error");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], result["ruleId"]);
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "tests/ErrorReporting.sol");
+    assert_eq!(location["region"]["startLine"], 4);
+    assert!(location["region"]["charOffset"].is_u64());
+    Ok(())
+}
+
+#[test]
+fn test_error_format_sarif_region() -> Status {
+    let assert = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Undeclared.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--error-format")
+        .arg("sarif")
+        .assert()
+        .failure();
+
+    let log: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    let region = &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startLine"], 4);
+    assert_eq!(region["startColumn"], 16);
+    assert_eq!(region["endLine"], 4);
+    assert_eq!(region["endColumn"], 23);
+    // Offset of `missing` in the file
+    let text = std::fs::read_to_string("tests/Undeclared.sol")?;
+    assert_eq!(region["charOffset"], text.find("missing").unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_migrate() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("migrate")
+        .arg("tests/Deprecated.sol")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Deprecated.sol:1:1: [pragma, since 0.59.0, fixable]"))
+        .stdout(predicate::str::contains("Deprecated.sol:6:17: [bits-and-refs, since 0.50.0, fixable]"))
+        .stdout(predicate::str::contains("2 deprecated constructs found in 1 files"));
+
+    Ok(())
+}
+
+#[test]
+fn test_fmt_check() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("fmt")
+        .arg("--check")
+        .arg("tests/Trivial.sol")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("fmt")
+        .arg("--check")
+        .arg("tests/Init.sol")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Not formatted: tests/Init.sol"));
+
+    Ok(())
+}
+
+#[test]
+fn test_symbols() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("symbols")
+        .arg("tests/Trivial.sol")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""kind": "contract""#))
+        .stdout(predicate::str::contains(r#""type": "class""#));
+
+    Ok(())
+}
+
+#[test]
+fn test_lsp_initialize() -> Status {
+    let message = |body: &str| format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let input = [
+        message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#),
+        message(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#),
+        message(r#"{"jsonrpc":"2.0","method":"exit"}"#),
+    ].concat();
+    Command::cargo_bin(BIN_NAME)?
+        .arg("lsp")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""renameProvider":{"prepareProvider":true}"#));
+
+    Ok(())
+}
+
 #[test]
 fn test_cycle() -> Status {
     Command::cargo_bin(BIN_NAME)?
@@ -159,3 +415,3368 @@ fn test_init() -> Status {
     remove_all_outputs("Init")?;
     Ok(())
 }
+
+#[test]
+fn test_unused_define() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialDefine")
+        .arg("-D")
+        .arg("NO_SUCH_CONSTANT=1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("define \"NO_SUCH_CONSTANT\" does not match any constant declaration"));
+
+    remove_all_outputs("TrivialDefine")?;
+    Ok(())
+}
+
+#[test]
+fn test_storage_fees() -> Status {
+    std::fs::create_dir_all("tests/storage")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Storage.sol")
+        .arg("--output-dir")
+        .arg("tests/storage")
+        .arg("--storage-fees")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Storage of Storage: \d+ bits, \d+ cells\n    \d+\.\d{9} per year")?)
+        // A 32-bit key needs a 12-bit label, with the 64-bit value 108 bits in the leaf and 12 in the fork;
+        // 120 bits and 2 cells at the default prices
+        .stdout(predicate::str::contains("Growth per mapping entry:\n    balances map(uint32,uint64): +120 bits, +2 cells, +0.000538946 per year"));
+
+    std::fs::remove_dir_all("tests/storage")?;
+    Ok(())
+}
+
+#[test]
+fn test_bindgen() -> Status {
+    std::fs::create_dir_all("tests/bindgen")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests/bindgen")
+        .arg("--output-prefix")
+        .arg("my-wallet")
+        .arg("--bindgen")
+        .arg("inpage-provider")
+        .arg("--hash-json")
+        .assert()
+        .success();
+
+    let bindings = std::fs::read_to_string("tests/bindgen/my-wallet.abi.ts")?;
+    // The ABI is the one published, asserted const
+    let abi = bindings.split("export const MyWalletAbi = ").nth(1).unwrap().split(" as const;").next().unwrap();
+    let abi: serde_json::Value = serde_json::from_str(abi)?;
+    let published: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/bindgen/my-wallet.abi.json")?)?;
+    assert_eq!(abi, published);
+
+    let tvc = base64::encode(std::fs::read("tests/bindgen/my-wallet.tvc")?);
+    assert!(bindings.contains(&format!("export const MyWalletTvc = '{}';", tvc)));
+    let hashes: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/bindgen/my-wallet.hash.json")?)?;
+    assert!(bindings.contains(&format!("export const MyWalletCodeHash = '{}';", hashes["codeHash"].as_str().unwrap())));
+    assert!(bindings.contains("export type MyWalletDeployParams = Omit<GetExpectedAddressParams<typeof MyWalletAbi>, 'tvc'>;"));
+    assert!(bindings.contains("export function MyWalletContract(provider: ProviderRpcClient, address: Address | string) {"));
+    assert!(bindings.contains("export async function getMyWalletStateInit(provider: ProviderRpcClient, params: MyWalletDeployParams) {"));
+
+    std::fs::remove_dir_all("tests/bindgen")?;
+    Ok(())
+}
+
+#[test]
+fn test_asm_canonical() -> Status {
+    // Dispatch.sol with its functions declared in another order
+    let reordered = "\
+pragma ever-solidity >=0.50.0;
+
+contract Dispatch {
+    uint public counter;
+
+    function transfer(address dest, uint128 value) public {
+        tvm.accept();
+        dest.transfer(value);
+    }
+
+    function twice() public {
+        tvm.accept();
+        counter *= 2;
+    }
+
+    function reset() public {
+        tvm.accept();
+        counter = 0;
+    }
+
+    function add(uint value) public {
+        tvm.accept();
+        counter += value;
+    }
+}
+";
+    std::fs::create_dir_all("tests/canonical/reordered")?;
+    std::fs::write("tests/canonical/reordered/Dispatch.sol", reordered)?;
+
+    for (input, dir) in [("tests/Dispatch.sol", "tests/canonical"), ("tests/canonical/reordered/Dispatch.sol", "tests/canonical/reordered")] {
+        Command::cargo_bin(BIN_NAME)?
+            .arg(input)
+            .arg("--output-dir")
+            .arg(dir)
+            .arg("--asm-canonical")
+            .arg("--strip")
+            .assert()
+            .success();
+    }
+    let canonical = std::fs::read_to_string("tests/canonical/Dispatch.code")?;
+    assert_eq!(canonical, std::fs::read_to_string("tests/canonical/reordered/Dispatch.code")?);
+    assert!(!canonical.contains(".version"));
+    assert!(!canonical.contains(';'));
+
+    // Internal functions come first, then macros and private functions, each sorted by name
+    let macros: Vec<&str> = canonical.lines().filter_map(|line| line.strip_prefix(".macro ")).collect();
+    let mut sorted = macros.clone();
+    sorted.sort_unstable();
+    assert_eq!(macros, sorted);
+    let first_macro = canonical.find(".macro ").unwrap();
+    assert!(canonical.rfind(".internal").map_or(true, |internal| internal < first_macro));
+    assert!(canonical.find(".globl").map_or(true, |private| private > first_macro));
+
+    std::fs::remove_dir_all("tests/canonical")?;
+    Ok(())
+}
+
+#[test]
+fn test_strip() -> Status {
+    std::fs::create_dir_all("tests/strip")?;
+    // Of both options the last one wins
+    let builds: [(&str, &[&str]); 3] = [("Stripped", &["--strip"]), ("Unstripped", &["--no-strip"]), ("Last", &["--strip", "--no-strip"])];
+    for (prefix, options) in builds {
+        Command::cargo_bin(BIN_NAME)?
+            .arg("tests/Dispatch.sol")
+            .arg("--output-dir")
+            .arg("tests/strip")
+            .arg("--output-prefix")
+            .arg(prefix)
+            .args(options)
+            .assert()
+            .success();
+    }
+
+    let stripped = std::fs::read_to_string("tests/strip/Stripped.code")?;
+    assert!(!stripped.contains(".loc "));
+    assert!(!stripped.contains(';'));
+    // The annotated assembly keeps the directives, with the source line they point at
+    let annotated = std::fs::read_to_string("tests/strip/Stripped.unstripped.code")?;
+    assert!(annotated.lines().any(|line| line.trim_start().starts_with(".loc ") && line.ends_with("; counter += value;")));
+
+    let unstripped = std::fs::read_to_string("tests/strip/Unstripped.code")?;
+    assert!(unstripped.contains(".loc "));
+    assert!(!Path::new("tests/strip/Unstripped.unstripped.code").exists());
+    assert!(!Path::new("tests/strip/Last.unstripped.code").exists());
+
+    // Only debug directives are dropped, the code is the same
+    assert_eq!(std::fs::read("tests/strip/Stripped.tvc")?, std::fs::read("tests/strip/Unstripped.tvc")?);
+
+    std::fs::remove_dir_all("tests/strip")?;
+    Ok(())
+}
+
+#[test]
+fn test_profile_json() -> Status {
+    std::fs::create_dir_all("tests/profile")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/profile")
+        .arg("--profile-json")
+        .arg("tests/profile/trace.json")
+        .assert()
+        .success();
+
+    // Every phase of the build is a span, begun and ended within the one of the build
+    let trace: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/profile/trace.json")?)?;
+    let events = trace.as_array().unwrap();
+    let time = |phase: &str, name: &str| events.iter()
+        .find(|event| event["ph"] == phase && event["name"] == name)
+        .and_then(|event| event["ts"].as_f64())
+        .unwrap_or_else(|| panic!("no {} event of {}", phase, name));
+    let (start, end) = (time("B", "build"), time("E", "build"));
+    for name in ["compile", "parse", "link"] {
+        assert!(start <= time("B", name));
+        assert!(time("B", name) <= time("E", name));
+        assert!(time("E", name) <= end);
+    }
+    let build = events.iter().find(|event| event["ph"] == "B" && event["name"] == "build").unwrap();
+    assert!(build["args"]["input"].as_str().unwrap().ends_with("Trivial.sol"));
+
+    std::fs::remove_dir_all("tests/profile")?;
+    Ok(())
+}
+
+#[test]
+fn test_log_level() -> Status {
+    std::fs::create_dir_all("tests/log")?;
+    let stderr = |args: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN_NAME)?
+            .env_remove("RUST_LOG")
+            .arg("tests/Trivial.sol")
+            .arg("--output-dir")
+            .arg("tests/log")
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        Ok(String::from_utf8(output.stderr)?)
+    };
+
+    let debug = stderr(&["--log-level", "debug"])?;
+    assert!(debug.contains("received compilation result"));
+    for name in ["compile", "parse", "link"] {
+        assert!(debug.lines().any(|line| line.contains(name) && line.contains("close")));
+    }
+    assert!(!stderr(&[])?.contains("received compilation result"));
+
+    // One record per line, with the span it was recorded in
+    let json = stderr(&["--log-level", "debug", "--log-json"])?;
+    let records: Vec<serde_json::Value> = json.lines()
+        .filter(|line| line.starts_with('{'))
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    let received = records.iter()
+        .find(|record| record["fields"]["message"] == "received compilation result")
+        .unwrap();
+    assert_eq!(received["level"], "DEBUG");
+    assert_eq!(received["span"]["name"], "compile");
+    assert!(records.iter().any(|record| record["fields"]["message"] == "close" && record["span"]["name"] == "link"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/log")
+        .arg("--log-level")
+        .arg("sold=verbose")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Invalid log level \"sold=verbose\""));
+
+    std::fs::remove_dir_all("tests/log")?;
+    Ok(())
+}
+
+#[test]
+fn test_network_preset() -> Status {
+    std::fs::create_dir_all("tests/preset")?;
+    let growth = |config: Option<&str>| -> Result<String, Box<dyn std::error::Error>> {
+        let mut command = Command::cargo_bin(BIN_NAME)?;
+        command
+            .arg("tests/Storage.sol")
+            .arg("--output-dir")
+            .arg("tests/preset")
+            .arg("--network-preset")
+            .arg("ton-mainnet")
+            .arg("--storage-fees");
+        if let Some(config) = config {
+            command.arg("--config").arg(config);
+        }
+        let output = command.assert().success().get_output().stdout.clone();
+        let output = String::from_utf8(output)?;
+        Ok(output.lines().find(|line| line.contains("balances")).unwrap_or_default().trim().to_owned())
+    };
+
+    // The prices of the network...
+    assert_eq!(growth(None)?, "balances map(uint32,uint64): +120 bits, +2 cells, +0.000538946 per year");
+    // ...unless the config sets its own
+    std::fs::write("tests/preset/sold.toml", "[storage]\nbit_price_ps = 2\ncell_price_ps = 1000\n")?;
+    assert_eq!(growth(Some("tests/preset/sold.toml"))?, "balances map(uint32,uint64): +120 bits, +2 cells, +0.001077891 per year");
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Storage.sol")
+        .arg("--output-dir")
+        .arg("tests/preset")
+        .arg("--network-preset")
+        .arg("testnet")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("everscale-mainnet, ton-mainnet, venom"));
+
+    std::fs::remove_dir_all("tests/preset")?;
+    Ok(())
+}
+
+#[test]
+fn test_state_limits() -> Status {
+    std::fs::create_dir_all("tests/limits")?;
+    std::fs::write("tests/limits/sold.toml", "[limits]\nmax_cells = 2\nmax_code_size = 100\n")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests/limits")
+        .arg("--config")
+        .arg("tests/limits/sold.toml")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::is_match(r"State has \d+ cells, the limit is 2 \(code \d+ cells, data \d+ cells\)")?)
+        .stderr(predicate::str::is_match(r"Code takes \d+ bytes, the limit is 100")?)
+        .stderr(predicate::str::contains("Biggest contributors to the code:"))
+        .stderr(predicate::str::contains("State has depth").not());
+
+    // The defaults of the network take any contract of this size
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests/limits")
+        .assert()
+        .success();
+
+    std::fs::remove_dir_all("tests/limits")?;
+    Ok(())
+}
+
+#[test]
+fn test_artifact_paths() -> Status {
+    std::fs::create_dir_all("tests/outs/default")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests/outs/default")
+        .assert()
+        .success();
+    // Directories of the given paths are created
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests/outs")
+        .arg("--abi-out")
+        .arg("tests/outs/abi/wallet.abi.json")
+        .arg("--tvc-out")
+        .arg("tests/outs/bin/wallet.tvc")
+        .arg("--code-out")
+        .arg("tests/outs/asm/wallet.code")
+        .arg("--debug-out")
+        .arg("tests/outs/wallet.debug.json")
+        .assert()
+        .success();
+
+    for (custom, default) in [
+        ("abi/wallet.abi.json", "Dispatch.abi.json"),
+        ("bin/wallet.tvc", "Dispatch.tvc"),
+        ("asm/wallet.code", "Dispatch.code"),
+        ("wallet.debug.json", "Dispatch.debug.json"),
+    ] {
+        let custom = Path::new("tests/outs").join(custom);
+        assert_eq!(std::fs::read(&custom)?, std::fs::read(Path::new("tests/outs/default").join(default))?, "{}", custom.display());
+        assert!(!Path::new("tests/outs").join(default).exists());
+    }
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/outs")
+        .arg("--tvc-out")
+        .arg("tests/outs/bin/wallet.tvc")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Option --tvc-out can't be used with multiple inputs"));
+
+    std::fs::remove_dir_all("tests/outs")?;
+    Ok(())
+}
+
+#[test]
+fn test_abi_filter() -> Status {
+    use clap::Parser;
+
+    std::fs::create_dir_all("tests/abi_filter")?;
+    for (prefix, filter) in [("All", &[][..]), ("Filtered", &["--abi-include", "t*", "--abi-exclude", "twice", "--abi-exclude", "mint"][..])] {
+        let assert = Command::cargo_bin(BIN_NAME)?
+            .arg("tests/Dispatch.sol")
+            .arg("--output-dir")
+            .arg("tests/abi_filter")
+            .arg("--output-prefix")
+            .arg(prefix)
+            .args(filter)
+            .assert()
+            .success();
+        if prefix == "Filtered" {
+            assert.stderr(predicate::str::contains("Warning: ABI pattern \"mint\" does not match any function"));
+        }
+    }
+
+    let abi: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/abi_filter/Filtered.abi.json")?)?;
+    let names: Vec<&str> = abi["functions"].as_array().unwrap().iter().filter_map(|function| function["name"].as_str()).collect();
+    assert_eq!(names, ["transfer"]);
+    // The functions left out are still in the code
+    assert_eq!(std::fs::read("tests/abi_filter/Filtered.tvc")?, std::fs::read("tests/abi_filter/All.tvc")?);
+
+    // Library builds get the warning through their message handler
+    let handler = std::sync::Arc::new(Collect::default());
+    let args = sold_lib::Args::parse_from(["sold", "tests/Dispatch.sol", "--output-dir", "tests/abi_filter", "--abi-exclude", "mint"]);
+    sold_lib::Compiler::with_message_handler(handler.clone()).build(args)?;
+    assert!(handler.0.lock().unwrap().iter().any(|(level, text)| {
+        *level == sold_lib::messages::Level::Warning && text.contains("ABI pattern \"mint\" does not match any function")
+    }));
+
+    std::fs::remove_dir_all("tests/abi_filter")?;
+    Ok(())
+}
+
+#[test]
+fn test_init_pubkey() -> Status {
+    use ton_block::Deserializable;
+
+    std::fs::create_dir_all("tests/pubkey")?;
+    let pubkey = |prefix: &str, kind: &str| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Command::cargo_bin(BIN_NAME)?
+            .arg("tests/Trivial.sol")
+            .arg("--output-dir")
+            .arg("tests/pubkey")
+            .arg("--output-prefix")
+            .arg(prefix)
+            .arg("--init-pubkey")
+            .arg(kind)
+            .assert()
+            .success();
+        let state = ton_block::StateInit::construct_from_bytes(&std::fs::read(format!("tests/pubkey/{}.tvc", prefix))?)
+            .map_err(|e| e.to_string())?;
+        let key = ton_abi::Contract::get_pubkey(&state.data.unwrap_or_default().into())
+            .map_err(|e| e.to_string())?;
+        Ok(key.map(|key| key.to_vec()).unwrap_or_default())
+    };
+
+    assert_eq!(pubkey("Zero", "zero")?, [0; 32]);
+    // A new key each time, and no key files are left behind
+    let (first, second) = (pubkey("First", "random")?, pubkey("Second", "random")?);
+    assert_eq!(first.len(), 32);
+    assert_ne!(first, [0; 32]);
+    assert_ne!(first, second);
+    let files: Vec<_> = std::fs::read_dir("tests/pubkey")?.collect::<Result<_, _>>()?;
+    assert!(files.iter().all(|file| !file.file_name().to_string_lossy().contains("keys")));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/pubkey")
+        .arg("--init-pubkey")
+        .arg("zero")
+        .arg("--gen-key")
+        .arg("tests/pubkey/Trivial.keys")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    std::fs::remove_dir_all("tests/pubkey")?;
+    Ok(())
+}
+
+#[test]
+fn test_emit_deploy_payload() -> Status {
+    std::fs::create_dir_all("tests/deploy")?;
+    std::fs::write("tests/deploy/Deployed.sol", "\
+pragma ever-solidity >=0.50.0;
+contract Deployed {
+    uint32 limit;
+    constructor(uint32 value) {
+        tvm.accept();
+        limit = value;
+    }
+}
+")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/deploy/Deployed.sol")
+        .arg("--output-dir")
+        .arg("tests/deploy")
+        .arg("--emit-deploy-payload")
+        .arg("--deploy-params")
+        .arg(r#"{"value": 7}"#)
+        .arg("--hash-json")
+        .assert()
+        .success();
+
+    let payload: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/deploy/Deployed.deploy.json")?)?;
+    let hashes: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/deploy/Deployed.hash.json")?)?;
+    assert_eq!(payload["dest"], hashes["address"]);
+    assert!(payload["value"].is_null());
+    assert_eq!(payload["bounce"], false);
+    assert_eq!(payload["allBalance"], false);
+    assert_eq!(payload["stateInit"], base64::encode(std::fs::read("tests/deploy/Deployed.tvc")?));
+
+    // The payload is an internal call of the constructor with the parameters given
+    let abi = std::fs::read_to_string("tests/deploy/Deployed.abi.json")?;
+    let contract = ton_abi::Contract::load(abi.as_bytes()).map_err(|e| e.to_string())?;
+    let body = base64::decode(payload["payload"].as_str().unwrap())?;
+    let body = ton_types::deserialize_tree_of_cells(&mut std::io::Cursor::new(body)).map_err(|e| e.to_string())?;
+    let call = contract.decode_input(body.into(), true).map_err(|e| e.to_string())?;
+    assert_eq!(call.function_name, "constructor");
+    assert_eq!(ton_abi::token::Detokenizer::detokenize(&call.tokens).map_err(|e| e.to_string())?, r#"{"value":"7"}"#);
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/deploy/Deployed.sol")
+        .arg("--output-dir")
+        .arg("tests/deploy")
+        .arg("--emit-deploy-payload")
+        .arg("--deploy-params")
+        .arg(r#"{"limit": 7}"#)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Failed to encode the constructor call"));
+
+    std::fs::remove_dir_all("tests/deploy")?;
+    Ok(())
+}
+
+#[test]
+fn test_isolate() -> Status {
+    std::fs::create_dir_all("tests/isolate")?;
+    for (prefix, isolate) in [("Direct", false), ("Isolated", true)] {
+        let mut command = Command::cargo_bin(BIN_NAME)?;
+        command
+            .arg("tests/Trivial.sol")
+            .arg("--output-dir")
+            .arg("tests/isolate")
+            .arg("--output-prefix")
+            .arg(prefix);
+        if isolate {
+            command.arg("--isolate");
+        }
+        command.assert().success();
+    }
+    assert_eq!(std::fs::read("tests/isolate/Direct.tvc")?, std::fs::read("tests/isolate/Isolated.tvc")?);
+
+    // Diagnostics are located with the line offsets the worker read
+    let assert = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/ErrorReporting.sol")
+        .arg("--output-dir")
+        .arg("tests/isolate")
+        .arg("--isolate")
+        .arg("--error-format")
+        .arg("json")
+        .assert()
+        .code(1);
+    let diagnostics: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    let error = diagnostics.as_array().unwrap().iter().find(|diagnostic| diagnostic["severity"] == "error").unwrap();
+    assert_eq!(error["file"], "tests/ErrorReporting.sol");
+    assert_eq!(error["line"], 4);
+
+    // Defines are sent to the worker and those it applied come back
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/isolate")
+        .arg("--isolate")
+        .arg("-D")
+        .arg("NO_SUCH_CONSTANT=1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("define \"NO_SUCH_CONSTANT\" does not match any constant declaration"));
+
+    // The worker reads within the allowed directories only, and gets the sources read from stdin
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/sandbox/Escape.sol")
+        .arg("--output-dir")
+        .arg("tests/isolate")
+        .arg("--base-path")
+        .arg("tests/sandbox")
+        .arg("--isolate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is outside of the allowed directories"));
+    Command::cargo_bin(BIN_NAME)?
+        .arg("-")
+        .arg("--stdin-name")
+        .arg("tests/isolate/Piped.sol")
+        .arg("--output-dir")
+        .arg("tests/isolate")
+        .arg("--output-prefix")
+        .arg("Piped")
+        .arg("--isolate")
+        .write_stdin("pragma ever-solidity >=0.50.0;\ncontract Piped {\n}\n")
+        .assert()
+        .success();
+    assert!(Path::new("tests/isolate/Piped.tvc").exists());
+    assert!(!Path::new("tests/isolate/Piped.sol").exists());
+
+    std::fs::remove_dir_all("tests/isolate")?;
+    Ok(())
+}
+
+#[test]
+fn test_timeout() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialTimeout")
+        .arg("--timeout")
+        .arg("0")
+        .assert()
+        .code(124)
+        .stderr(predicate::str::contains("Build timed out"));
+
+    Ok(())
+}
+
+#[test]
+fn test_timeout_then_next_input() -> Status {
+    // Big enough to take the frontend several seconds
+    let mut source = String::from("pragma ever-solidity >=0.50.0;\ncontract Slow {\n");
+    for i in 0..5000 {
+        source += &format!("    function f{0}(uint a) public pure returns (uint) {{ return a * {0} + {0}; }}\n", i);
+    }
+    source += "}\n";
+    std::fs::write("tests/Slow.sol", source)?;
+    std::fs::create_dir_all("tests/timeout")?;
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Slow.sol")
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/timeout")
+        .arg("--keep-going")
+        .arg("--timeout")
+        .arg("1")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Build timed out"))
+        .stderr(predicate::str::contains("1 of 2 inputs failed: tests/Slow.sol"));
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/timeout/build-report.json")?)?;
+    assert_eq!(report[0]["status"], "failed");
+    assert_eq!(report[1]["input"], "tests/Trivial.sol");
+    assert_eq!(report[1]["status"], "ok");
+    assert!(Path::new("tests/timeout/Trivial.tvc").exists());
+
+    std::fs::remove_file("tests/Slow.sol")?;
+    std::fs::remove_dir_all("tests/timeout")?;
+    Ok(())
+}
+
+#[test]
+fn test_max_memory() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialMemory")
+        .arg("--max-memory")
+        .arg("1")
+        .assert()
+        .code(125)
+        .stderr(predicate::str::contains("Build exceeded the memory limit of 1 MB"));
+
+    // The limit holds for each build, not for all of them together
+    std::fs::create_dir_all("tests/memory")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("tests/Constants.sol")
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests/memory")
+        .arg("--max-memory")
+        .arg("4096")
+        .assert()
+        .success();
+
+    assert!(Path::new("tests/memory/Trivial.tvc").exists());
+    assert!(Path::new("tests/memory/Dispatch.tvc").exists());
+    std::fs::remove_dir_all("tests/memory")?;
+    Ok(())
+}
+
+#[test]
+fn test_analyze_gas() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialGas")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("analyze")
+        .arg("gas")
+        .arg("tests/TrivialGas.code")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("worst"));
+
+    remove_all_outputs("TrivialGas")?;
+    Ok(())
+}
+
+#[test]
+fn test_analyze_gas_debug_map() -> Status {
+    // The stripped code has no source lines, the debug map still has them
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("DispatchGas")
+        .arg("--strip")
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("analyze")
+        .arg("gas")
+        .arg("tests/DispatchGas.code")
+        .arg("--debug")
+        .arg("tests/DispatchGas.debug.json")
+        .arg("--function")
+        .arg("transfer")
+        .arg("--json")
+        .output()?;
+    assert!(output.status.success());
+    let reports: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(reports[0]["name"], "transfer");
+    assert!(reports[0]["declaration"].as_str().unwrap().starts_with("Dispatch.transfer at "));
+    assert!(reports[0]["declaration"].as_str().unwrap().ends_with("Dispatch.sol:21"));
+    assert!(reports[0]["worst"].as_u64().unwrap() >= reports[0]["typical"].as_u64().unwrap());
+
+    std::fs::remove_file("tests/DispatchGas.unstripped.code")?;
+    remove_all_outputs("DispatchGas")?;
+    Ok(())
+}
+
+#[test]
+fn test_bundle() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialBundle")
+        .arg("--bundle")
+        .assert()
+        .success();
+
+    let bundle: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/TrivialBundle.contract.json")?)?;
+    assert!(bundle["abi"]["ABI version"].is_number());
+    assert_eq!(bundle["codeHash"].as_str().map(str::len), Some(64));
+
+    std::fs::remove_file("tests/TrivialBundle.contract.json")?;
+    remove_all_outputs("TrivialBundle")?;
+    Ok(())
+}
+
+#[test]
+fn test_emit() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialEmit")
+        .arg("--emit")
+        .arg("tvc")
+        .assert()
+        .success();
+
+    assert!(Path::new("tests/TrivialEmit.tvc").exists());
+    assert!(!Path::new("tests/TrivialEmit.abi.json").exists());
+    assert!(!Path::new("tests/TrivialEmit.code").exists());
+    assert!(!Path::new("tests/TrivialEmit.debug.json").exists());
+
+    std::fs::remove_file("tests/TrivialEmit.tvc")?;
+    Ok(())
+}
+
+#[test]
+fn test_errors() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Errors.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--errors")
+        .arg("--messages")
+        .assert()
+        .success();
+
+    let table: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Errors.errors.json")?)?;
+    let exceptions = &table["exceptions"];
+    assert_eq!(exceptions[0]["code"], 101);
+    assert_eq!(exceptions[0]["constant"], "ERR_NOT_OWNER");
+    assert_eq!(exceptions[0]["message"], "Not owner");
+    assert_eq!(exceptions[0]["function"], "Errors.transfer");
+    assert_eq!(exceptions[1]["code"], 102);
+    assert_eq!(exceptions[1]["kind"], "revert");
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("decode-error")
+        .arg("--errors")
+        .arg("tests/Errors.errors.json")
+        .arg("--exit-code")
+        .arg("101")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("require \"Not owner\" in Errors.transfer at tests/Errors.sol:9 (ERR_NOT_OWNER)"));
+
+    let messages: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Errors.messages.json")?)?;
+    assert_eq!(messages, serde_json::json!([
+        { "code": 101, "message": "Not owner", "source": "tests/Errors.sol", "line": 9, "column": 9 }
+    ]));
+
+    std::fs::remove_file("tests/Errors.errors.json")?;
+    std::fs::remove_file("tests/Errors.messages.json")?;
+    remove_all_outputs("Errors")?;
+    Ok(())
+}
+
+#[test]
+fn test_messages() -> Status {
+    std::fs::create_dir_all("tests/messages")?;
+    std::fs::write("tests/messages/Base.sol", "\
+pragma ever-solidity >=0.50.0;
+contract Base {
+    function checkBase(uint value) public pure {
+        require(value != 0, 200, \"Is zero\");
+    }
+}
+")?;
+    std::fs::write("tests/messages/Messages.sol", "\
+pragma ever-solidity >=0.50.0;
+import \"./Base.sol\";
+contract Messages is Base {
+    function check(uint value, string reason) public pure {
+        require(value != 1, 201, \"Is one\");
+        require(value != 2, 202, reason);
+        require(value != 3, 203);
+    }
+}
+")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/messages/Messages.sol")
+        .arg("--output-dir")
+        .arg("tests/messages")
+        .arg("--messages")
+        .assert()
+        .success();
+
+    // Messages of the bases are included, those that aren't string literals are not
+    let messages: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/messages/Messages.messages.json")?)?;
+    let messages = messages.as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["code"], 200);
+    assert_eq!(messages[0]["message"], "Is zero");
+    assert!(messages[0]["source"].as_str().unwrap().ends_with("Base.sol"));
+    assert_eq!(messages[0]["line"], 4);
+    assert_eq!(messages[0]["column"], 9);
+    assert_eq!(messages[1], serde_json::json!(
+        { "code": 201, "message": "Is one", "source": "tests/messages/Messages.sol", "line": 5, "column": 9 }
+    ));
+    assert!(!Path::new("tests/messages/Messages.errors.json").exists());
+
+    std::fs::remove_dir_all("tests/messages")?;
+    Ok(())
+}
+
+#[test]
+fn test_decode_error() -> Status {
+    std::fs::create_dir_all("tests/decode")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Errors.sol")
+        .arg("--output-dir")
+        .arg("tests/decode")
+        .arg("--errors")
+        .assert()
+        .success();
+
+    // The body of an internal message calling transfer(0)
+    let abi = std::fs::read_to_string("tests/decode/Errors.abi.json")?;
+    let body = ton_abi::json_abi::encode_function_call(&abi, "transfer", None, r#"{"value": "0"}"#, true, None, None)
+        .and_then(|builder| builder.into_cell())
+        .map_err(|e| e.to_string())?;
+    let mut boc = vec![];
+    ton_types::BagOfCells::with_root(&body).write_to(&mut boc, false).map_err(|e| e.to_string())?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("decode-error")
+        .arg("--errors")
+        .arg("tests/decode/Errors.errors.json")
+        .arg("--exit-code")
+        .arg("102")
+        .arg("--abi")
+        .arg("tests/decode/Errors.abi.json")
+        .arg("--body")
+        .arg(base64::encode(boc))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Exit code 102\nCalled Errors.transfer {\"value\":\"0\"}\n  revert in Errors.transfer at tests/Errors.sol:11\n"));
+
+    // Codes of the runtime are explained, and codes nothing throws are told apart
+    Command::cargo_bin(BIN_NAME)?
+        .arg("decode-error")
+        .arg("--errors")
+        .arg("tests/decode/Errors.errors.json")
+        .arg("--exit-code")
+        .arg("9")
+        .assert()
+        .success()
+        .stdout("Exit code 9\n  Cell underflow\n");
+    Command::cargo_bin(BIN_NAME)?
+        .arg("decode-error")
+        .arg("--errors")
+        .arg("tests/decode/Errors.errors.json")
+        .arg("--exit-code")
+        .arg("555")
+        .assert()
+        .success()
+        .stdout("Exit code 555\n  No require or revert of the contract throws this code\n");
+
+    std::fs::remove_dir_all("tests/decode")?;
+    Ok(())
+}
+
+#[test]
+fn test_malformed_json_input() -> Status {
+    std::fs::write("tests/Malformed.errors.json", "[{\"code\": 101,")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("decode-error")
+        .arg("--errors")
+        .arg("tests/Malformed.errors.json")
+        .arg("--exit-code")
+        .arg("101")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Invalid JSON in \"tests/Malformed.errors.json\""));
+
+    std::fs::remove_file("tests/Malformed.errors.json")?;
+    Ok(())
+}
+
+#[test]
+fn test_exceptions() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Exceptions.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--exceptions")
+        .assert()
+        .success();
+
+    let table: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Exceptions.exceptions.json")?)?;
+    assert_eq!(table["contract"], "Exceptions");
+    let exceptions = table["exceptions"].as_array().unwrap();
+    let code = |code: i64| exceptions.iter().find(|exception| exception["code"] == code).unwrap();
+    assert_eq!(code(4)["origin"], "tvm");
+    let overflows: Vec<&str> = code(4)["sites"].as_array().unwrap().iter()
+        .filter_map(|site| site["function"].as_str())
+        .collect();
+    assert_eq!(overflows, ["Exceptions.push", "Exceptions.last"]);
+    assert_eq!(code(50)["origin"], "compiler");
+    assert_eq!(code(50)["sites"][0]["construct"], "array index");
+    assert_eq!(code(50)["sites"][0]["line"], 12);
+    // Raised by the selector, whatever the contract does
+    assert!(code(60)["sites"].as_array().unwrap().is_empty());
+
+    std::fs::remove_file("tests/Exceptions.exceptions.json")?;
+    remove_all_outputs("Exceptions")?;
+    Ok(())
+}
+
+#[test]
+fn test_init_from_file() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--init")
+        .arg("@tests/Trivial.sol")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid JSON in --init file \"tests/Trivial.sol\": expected value at line 1 column 1"));
+    Ok(())
+}
+
+#[test]
+fn test_scaffold_deploy() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialScaffold")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("scaffold")
+        .arg("deploy")
+        .arg("TrivialScaffold")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--lang")
+        .arg("ts")
+        .arg("--output")
+        .arg("tests/deploy-TrivialScaffold.ts")
+        .assert()
+        .success();
+
+    let script = std::fs::read_to_string("tests/deploy-TrivialScaffold.ts")?;
+    assert!(script.contains(r#"path.join(__dirname, ".")"#));
+    assert!(script.contains(r#""TrivialScaffold.abi.json""#));
+    assert!(script.contains("const constructorParams = {};"));
+
+    std::fs::remove_file("tests/deploy-TrivialScaffold.ts")?;
+    remove_all_outputs("TrivialScaffold")?;
+    Ok(())
+}
+
+#[test]
+fn test_response_file() -> Status {
+    std::fs::write("tests/trivial.args", "# Build options\ntests/Trivial.sol\n--output-dir\ntests\n\n--output-prefix\nTrivialArgs\n")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("@tests/trivial.args")
+        .assert()
+        .success();
+
+    std::fs::remove_file("tests/trivial.args")?;
+    remove_all_outputs("TrivialArgs")?;
+    Ok(())
+}
+
+#[test]
+fn test_response_file_with_remap() -> Status {
+    std::fs::write("tests/remap.args", "tests/remap/Remapped.sol\n--output-dir\ntests\n--output-prefix\nRemapArgs\n")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("@tests/remap.args")
+        .arg("--remap")
+        .arg("@lib/=tests/remap/vendor/lib/")
+        .assert()
+        .success();
+
+    std::fs::remove_file("tests/remap.args")?;
+    remove_all_outputs("RemapArgs")?;
+    Ok(())
+}
+
+#[test]
+fn test_keep_going() -> Status {
+    std::fs::create_dir_all("tests/keep_going")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Missing1.sol")
+        .arg("tests/Missing2.sol")
+        .arg("--output-dir")
+        .arg("tests/keep_going")
+        .arg("--keep-going")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("File \"tests/Missing2.sol\" not found"))
+        .stderr(predicate::str::contains("2 of 2 inputs failed"));
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/keep_going/build-report.json")?)?;
+    assert_eq!(report[1]["input"], "tests/Missing2.sol");
+    assert_eq!(report[1]["status"], "failed");
+
+    std::fs::remove_dir_all("tests/keep_going")?;
+    Ok(())
+}
+
+#[test]
+fn test_build_report() -> Status {
+    std::fs::create_dir_all("tests/report")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("tests/ErrorReporting.sol")
+        .arg("--output-dir")
+        .arg("tests/report")
+        .arg("--hash-json")
+        .arg("--keep-going")
+        .assert()
+        .code(1);
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/report/build-report.json")?)?;
+    let entries = report.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+
+    // A built input has what the build produced
+    let built = &entries[0];
+    assert_eq!(built["input"], "tests/Trivial.sol");
+    assert_eq!(built["status"], "ok");
+    assert_eq!(built["contract"], "Trivial");
+    let hashes: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/report/Trivial.hash.json")?)?;
+    assert_eq!(built["code_hash"], hashes["codeHash"]);
+    assert_eq!(built["address"], hashes["address"]);
+    assert_eq!(built["tvc_size"], std::fs::metadata("tests/report/Trivial.tvc")?.len());
+    assert!(built["code_size"].as_u64().unwrap() > 0);
+    let artifacts = built["artifacts"].as_array().unwrap();
+    assert!(artifacts.iter().any(|artifact| artifact.as_str().unwrap().ends_with("Trivial.tvc")));
+    assert!(built["duration_ms"].is_u64());
+    assert!(built.get("error").is_none());
+
+    // A failed one has the error instead
+    let failed = &entries[1];
+    assert_eq!(failed["input"], "tests/ErrorReporting.sol");
+    assert_eq!(failed["status"], "failed");
+    assert_eq!(failed["error"], "Compilation failed");
+    assert!(failed.get("contract").is_none());
+
+    std::fs::remove_dir_all("tests/report")?;
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_contracts() -> Status {
+    std::fs::create_dir_all("tests/duplicates")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("tests/Duplicate.sol")
+        .arg("--output-dir")
+        .arg("tests/duplicates")
+        .arg("--color")
+        .arg("never")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: Contract \"Trivial\" is defined in several inputs"))
+        .stderr(predicate::str::contains("--> tests/Trivial.sol:2:10"))
+        .stderr(predicate::str::contains("--> tests/Duplicate.sol:3:10"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("tests/Duplicate.sol")
+        .arg("--output-dir")
+        .arg("tests/duplicates")
+        .arg("--deny-duplicate-contracts")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 contract names are defined in several inputs: Trivial"));
+
+    std::fs::remove_dir_all("tests/duplicates")?;
+    Ok(())
+}
+
+#[test]
+fn test_only_skip() -> Status {
+    std::fs::create_dir_all("tests/only_skip")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("tests/Errors.sol")
+        .arg("tests/Stdlib.sol")
+        .arg("--output-dir")
+        .arg("tests/only_skip")
+        .arg("--only")
+        .arg("*r*")
+        .arg("--skip")
+        .arg("Triv?al")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/only_skip/build-report.json")?)?;
+    assert_eq!(report[0]["status"], "skipped");
+    assert_eq!(report[1]["status"], "ok");
+    assert_eq!(report[1]["contract"], "Errors");
+    assert_eq!(report[2]["status"], "skipped");
+    assert!(std::path::Path::new("tests/only_skip/Errors.tvc").exists());
+    assert!(!std::path::Path::new("tests/only_skip/Trivial.tvc").exists());
+    assert!(!std::path::Path::new("tests/only_skip/Stdlib.tvc").exists());
+
+    std::fs::remove_dir_all("tests/only_skip")?;
+    Ok(())
+}
+
+#[test]
+fn test_repro_check() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("repro-check")
+        .arg("tests/Trivial.sol")
+        .arg("--separate-dirs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("artifacts are identical"));
+
+    Ok(())
+}
+
+#[test]
+fn test_hashed_names() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialHashed")
+        .arg("--hashed-names")
+        .assert()
+        .success();
+
+    let latest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/TrivialHashed.latest.json")?)?;
+    let hash = &latest["codeHash"].as_str().unwrap()[..8];
+    assert_eq!(latest["artifacts"]["tvc"], format!("TrivialHashed.{}.tvc", hash));
+
+    remove_all_outputs(&format!("TrivialHashed.{}", hash))?;
+    std::fs::remove_file("tests/TrivialHashed.latest.json")?;
+    Ok(())
+}
+
+#[test]
+fn test_init_project() -> Status {
+    let dir = "tests/my-counter";
+    Command::cargo_bin(BIN_NAME)?
+        .arg("init")
+        .arg("--dir")
+        .arg(dir)
+        .assert()
+        .success();
+
+    let contract = std::fs::read_to_string(format!("{}/src/MyCounter.sol", dir))?;
+    assert!(contract.contains("contract MyCounter {"));
+    assert!(std::path::Path::new(&format!("{}/sold.toml", dir)).exists());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg(format!("{}/src/MyCounter.sol", dir))
+        .arg("--output-dir")
+        .arg(dir)
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("init")
+        .arg("--dir")
+        .arg(dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --force to overwrite"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_new() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("new")
+        .arg("--template")
+        .arg("wallet")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("NewWallet")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/NewWallet.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .assert()
+        .success();
+
+    std::fs::remove_file("tests/NewWallet.sol")?;
+    remove_all_outputs("NewWallet")?;
+    Ok(())
+}
+
+#[test]
+fn test_mutate() -> Status {
+    // A copy, as the other tests read the original while it is mutated
+    std::fs::copy("tests/Errors.sol", "tests/Mutate.sol")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("mutate")
+        .arg("tests/Mutate.sol")
+        .arg("--test")
+        .arg("true")
+        .arg("--")
+        .arg("--output-dir")
+        .arg("tests")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mutate.sol:9:17: require condition replaced with true: SURVIVED"))
+        .stdout(predicate::str::contains("0 of 4 mutants killed"));
+
+    assert_eq!(std::fs::read_to_string("tests/Mutate.sol")?, std::fs::read_to_string("tests/Errors.sol")?);
+    std::fs::remove_file("tests/Mutate.sol")?;
+    remove_all_outputs("Mutate")?;
+    Ok(())
+}
+
+#[test]
+fn test_bench() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Errors.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("BenchErrors")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("bench")
+        .arg("BenchErrors")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--getter")
+        .arg("transfer")
+        .arg("--params")
+        .arg(r#"{"value": 1}"#)
+        .arg("--iters")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("transfer: 10 runs"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("bench")
+        .arg("BenchErrors")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--getter")
+        .arg("transfer")
+        .arg("--params")
+        .arg(r#"{"value": 0}"#)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("transfer failed with exit code 102"));
+
+    remove_all_outputs("BenchErrors")?;
+    Ok(())
+}
+
+#[test]
+fn test_gas_diff() -> Status {
+    // Reports are named after the contract
+    std::fs::create_dir_all("tests/gas-old")?;
+    std::fs::create_dir_all("tests/gas-new")?;
+    std::fs::write("tests/gas-old/Token.gas.json", r#"[{"name": "transfer", "worst": 1000, "typical": 800}, {"name": "burn", "worst": 500, "typical": 500}]"#)?;
+    std::fs::write("tests/gas-new/Token.gas.json", r#"[{"name": "transfer", "worst": 1100, "typical": 800}, {"name": "mint", "worst": 700, "typical": 600}]"#)?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("gas-diff")
+        .arg("tests/gas-old/Token.gas.json")
+        .arg("tests/gas-new/Token.gas.json")
+        .arg("--fail-on-regression")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Token.transfer: worst 1000 -> 1100 +100 (+10.0%), typical 800 -> 800 +0 (+0.0%)"))
+        .stdout(predicate::str::contains("Added Token.mint"))
+        .stdout(predicate::str::contains("Removed Token.burn"))
+        .stderr(predicate::str::contains("1 functions cost more gas: Token.transfer"));
+
+    std::fs::remove_dir_all("tests/gas-old")?;
+    std::fs::remove_dir_all("tests/gas-new")?;
+    Ok(())
+}
+
+#[test]
+fn test_size_diff() -> Status {
+    for (input, prefix) in [("tests/Trivial.sol", "SizeOld"), ("tests/Errors.sol", "SizeNew")] {
+        Command::cargo_bin(BIN_NAME)?
+            .arg(input)
+            .arg("--output-dir")
+            .arg("tests")
+            .arg("--output-prefix")
+            .arg(prefix)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("size-diff")
+        .arg("tests/SizeOld.tvc")
+        .arg("tests/SizeNew.tvc")
+        .arg("--debug")
+        .arg("tests/SizeOld.debug.json")
+        .arg("tests/SizeNew.debug.json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Code: "))
+        .stdout(predicate::str::contains("Errors.transfer"));
+
+    remove_all_outputs("SizeOld")?;
+    remove_all_outputs("SizeNew")?;
+    Ok(())
+}
+
+#[test]
+fn test_boc_inspect() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Boc")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("boc")
+        .arg("inspect")
+        .arg("tests/Boc.tvc")
+        .arg("--cell")
+        .arg("0.0")
+        .arg("--length")
+        .arg("6")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Roots: 1"))
+        .stdout(predicate::str::contains("level 0: hash "))
+        .stdout(predicate::str::is_match(r"Cell 0\.0: \d+ bits").unwrap())
+        .stdout(predicate::str::is_match(r"bits 0\.\.6: x\{[0-9a-f]{2}_\}").unwrap());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("boc")
+        .arg("inspect")
+        .arg("tests/Boc.tvc")
+        .arg("--cell")
+        .arg("0.9")
+        .assert()
+        .failure();
+
+    remove_all_outputs("Boc")?;
+    Ok(())
+}
+
+#[test]
+fn test_viz() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Errors.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Viz")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("viz")
+        .arg("tests/Viz.tvc")
+        .arg("-o")
+        .arg("tests/Viz.dot")
+        .arg("--debug")
+        .arg("tests/Viz.debug.json")
+        .assert()
+        .success();
+    let dot = std::fs::read_to_string("tests/Viz.dot")?;
+    assert!(dot.starts_with("digraph code {"));
+    assert!(dot.contains("c0 -> c1;"));
+    assert!(dot.contains("Errors.transfer"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("viz")
+        .arg("tests/Viz.tvc")
+        .arg("-o")
+        .arg("tests/Viz.svg")
+        .assert()
+        .success();
+    let svg = std::fs::read_to_string("tests/Viz.svg")?;
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("<rect "));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("viz")
+        .arg("tests/Viz.tvc")
+        .arg("-o")
+        .arg("tests/Viz.png")
+        .assert()
+        .failure();
+
+    std::fs::remove_file("tests/Viz.dot")?;
+    std::fs::remove_file("tests/Viz.svg")?;
+    remove_all_outputs("Viz")?;
+    Ok(())
+}
+
+#[test]
+fn test_serve_stdio() -> Status {
+    let requests = concat!(
+        r#"{"id": 1, "method": "version"}"#, "\n",
+        r#"{"id": 2, "method": "compile", "params": {"args": ["tests/Trivial.sol", "--output-dir", "tests", "--output-prefix", "TrivialServed"]}}"#, "\n",
+        r#"{"id": 3, "method": "abi", "params": {"input": "tests/Trivial.sol"}}"#, "\n",
+        r#"{"id": 4, "method": "unknown"}"#, "\n",
+    );
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("--serve-stdio")
+        .write_stdin(requests)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let responses: Vec<serde_json::Value> = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["result"]["contract"], "Trivial");
+    assert_eq!(responses[2]["result"]["contract"], "Trivial");
+    assert!(responses[2]["result"]["abi"]["functions"].is_array());
+    assert_eq!(responses[3]["error"]["message"], "Unknown method \"unknown\"");
+
+    remove_all_outputs("TrivialServed")?;
+    Ok(())
+}
+
+#[test]
+fn test_serve_stdio_source_cache() -> Status {
+    let requests = concat!(
+        r#"{"id": 1, "method": "compile", "params": {"args": ["tests/CycleA.sol", "--output-dir", "tests", "--output-prefix", "CycleServed"]}}"#, "\n",
+        r#"{"id": 2, "method": "compile", "params": {"args": ["tests/CycleA.sol", "--output-dir", "tests", "--output-prefix", "CycleServed"]}}"#, "\n",
+    );
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("--serve-stdio")
+        .arg("--log-level")
+        .arg("sold_lib::source_cache=trace")
+        .write_stdin(requests)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"], responses[1]["result"]);
+    // The second build gets the unchanged import from the cache
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.lines().any(|line| line.contains("source cache hit") && line.contains("CycleB.sol")));
+
+    // A single build doesn't keep sources
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/CycleA.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("CycleServed")
+        .arg("--log-level")
+        .arg("sold_lib::source_cache=trace")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("source cache hit").not());
+
+    remove_all_outputs("CycleServed")?;
+    Ok(())
+}
+
+#[test]
+fn test_solc_lib_fallback() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("TrivialSolcLib")
+        .arg("--solc-lib")
+        .arg("tests/missing-libsolc.so")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: failed to load frontend \"tests/missing-libsolc.so\", using the embedded one"));
+
+    remove_all_outputs("TrivialSolcLib")?;
+    Ok(())
+}
+
+#[test]
+fn test_frontend_selection() -> Status {
+    let dir = "tests/frontends";
+    std::fs::create_dir_all(dir)?;
+    std::fs::copy("tests/Trivial.sol", format!("{}/Trivial.sol", dir))?;
+    std::fs::write(format!("{}/sold.toml", dir), "[frontends]\n\"99.0.0\" = \"libsolc-99.0.0.so\"\n")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg(format!("{}/Trivial.sol", dir))
+        .arg("--output-dir")
+        .arg(dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("libsolc-99.0.0.so\", using the embedded one"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_auto_install() -> Status {
+    let dir = "tests/auto_install";
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(format!("{}/Future.sol", dir), "pragma ever-solidity >= 99.0.0;\ncontract Future {}\n")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg(format!("{}/Future.sol", dir))
+        .arg("--output-dir")
+        .arg(dir)
+        .env("SOLD_CACHE_DIR", dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass --auto-install to download one"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg(format!("{}/Future.sol", dir))
+        .arg("--output-dir")
+        .arg(dir)
+        .arg("--auto-install")
+        .env("SOLD_CACHE_DIR", dir)
+        .env_remove("SOLD_FRONTEND_INDEX")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no frontend index is set"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_ast_json() -> Status {
+    let dir = "tests/ast_json";
+    std::fs::create_dir_all(dir)?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/CycleA.sol")
+        .arg("--output-dir")
+        .arg(dir)
+        .arg("--ast-json")
+        .assert()
+        .success();
+
+    // One source unit for the input and one for its import
+    let ast: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(format!("{}/CycleA.ast.json", dir))?)?;
+    let units = ast.as_array().unwrap();
+    assert_eq!(units.len(), 2);
+    assert!(units.iter().all(|unit| unit["nodeType"] == "SourceUnit"));
+    let paths: Vec<&str> = units.iter().filter_map(|unit| unit["absolutePath"].as_str()).collect();
+    assert!(paths.iter().any(|path| path.ends_with("CycleA.sol")));
+    assert!(paths.iter().any(|path| path.ends_with("CycleB.sol")));
+    assert!(!Path::new(&format!("{}/CycleA.tvc", dir)).exists());
+
+    // A build without the option writes no AST
+    std::fs::remove_file(format!("{}/CycleA.ast.json", dir))?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/CycleA.sol")
+        .arg("--output-dir")
+        .arg(dir)
+        .assert()
+        .success();
+    assert!(Path::new(&format!("{}/CycleA.tvc", dir)).exists());
+    assert!(!Path::new(&format!("{}/CycleA.ast.json", dir)).exists());
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_ast_input() -> Status {
+    let dir = "tests/ast_input";
+    std::fs::create_dir_all(dir)?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg(dir)
+        .arg("--ast-compact-json")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg(format!("{}/Trivial.ast.json", dir))
+        .arg("--output-dir")
+        .arg(dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Contract successfully compiled"));
+    assert!(std::path::Path::new(&format!("{}/Trivial.tvc", dir)).exists());
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_via_ast() -> Status {
+    std::fs::create_dir_all("tests/via_ast")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/via_ast")
+        .arg("--via-ast")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Compilation via the AST matches direct compilation"));
+
+    std::fs::remove_dir_all("tests/via_ast")?;
+    Ok(())
+}
+
+#[test]
+fn test_stdlib_report() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Stdlib.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--stdlib-report")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Stdlib.stdlib.json")?)?;
+    assert_eq!(report["library"], "stdlib_sol.tvm");
+    let linked: Vec<&str> = report["linked"].as_array().unwrap().iter()
+        .filter_map(|function| function["name"].as_str())
+        .collect();
+    assert!(linked.contains(&"concatenateStrings"));
+    let stripped: Vec<&str> = report["stripped"].as_array().unwrap().iter()
+        .filter_map(|name| name.as_str())
+        .collect();
+    assert!(stripped.contains(&"__exp"));
+    assert!(!stripped.contains(&"concatenateStrings"));
+
+    std::fs::remove_file("tests/Stdlib.stdlib.json")?;
+    remove_all_outputs("Stdlib")?;
+    Ok(())
+}
+
+#[test]
+fn test_dispatch() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--dispatch")
+        .arg("linear")
+        .arg("--dispatch-priority")
+        .arg("twice")
+        .arg("--dispatch-priority")
+        .arg("transfer")
+        .arg("--dispatch-report")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Dispatch.dispatch.json")?)?;
+    assert_eq!(report["strategy"], "linear");
+    let functions = report["functions"].as_array().unwrap();
+    assert_eq!(functions[0]["name"], "twice");
+    assert_eq!(functions[0]["comparisons"], 1);
+    assert_eq!(functions[1]["name"], "transfer");
+    assert_eq!(functions[1]["comparisons"], 2);
+    assert!(functions.iter().any(|function| function["name"] == "counter"));
+    assert_eq!(report["maxComparisons"], functions.len());
+
+    // The selector compares the prioritized functions first, in the order given
+    let code = std::fs::read_to_string("tests/Dispatch.code")?;
+    let selector = code.split(".macro public_function_selector").nth(1).unwrap();
+    let selector = selector.split("\n.").next().unwrap();
+    let position = |name: &str| selector.find(&format!("CALL ${}$", name)).unwrap();
+    assert!(position("twice") < position("transfer"));
+    assert!(position("transfer") < position("add"));
+    assert!(position("transfer") < position("reset"));
+
+    std::fs::remove_file("tests/Dispatch.dispatch.json")?;
+    remove_all_outputs("Dispatch")?;
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--dispatch-priority")
+        .arg("missing")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Dispatch priority names \"missing\", which is not a public function of contract \"Dispatch\""));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--dispatch")
+        .arg("jump-table")
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_compile_async() -> Status {
+    use clap::Parser;
+
+    let output_dir = std::env::temp_dir().join(format!("sold-async-{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir)?;
+    let args = |input: &str| sold_lib::Args::parse_from(["sold", input, "--output-dir", output_dir.to_str().unwrap()]);
+
+    let output = sold_lib::compile_async(args("tests/Trivial.sol")).await?;
+    assert_eq!(output.contract, "Trivial");
+    assert!(output_dir.join("Trivial.tvc").exists());
+
+    // A build losing the race is dropped, a later one still runs
+    tokio::select! {
+        _ = sold_lib::compile_async(args("tests/Combined.sol")) => {}
+        _ = tokio::time::sleep(std::time::Duration::from_millis(1)) => {}
+    }
+    assert!(sold_lib::compile_async(args("tests/Trivial.sol")).await.is_ok());
+
+    std::fs::remove_dir_all(&output_dir)?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct Collect(std::sync::Mutex<Vec<(sold_lib::messages::Level, String)>>);
+
+impl sold_lib::messages::MessageHandler for Collect {
+    fn output(&self, _text: &str) {}
+
+    fn message(&self, level: sold_lib::messages::Level, text: &str) {
+        self.0.lock().unwrap().push((level, text.to_owned()));
+    }
+}
+
+#[test]
+fn test_concurrent_compilers() -> Status {
+    use clap::Parser;
+    use sold_lib::messages::Level;
+    use std::sync::Arc;
+
+    let output_dir = std::env::temp_dir().join(format!("sold-compilers-{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir)?;
+    let output = output_dir.to_str().unwrap();
+
+    let failing = Arc::new(Collect::default());
+    let defining = Arc::new(Collect::default());
+    let builds = [
+        (failing.clone(), vec!["sold", "tests/ErrorReporting.sol", "--output-dir", output]),
+        (defining.clone(), vec!["sold", "tests/Trivial.sol", "--output-dir", output, "-D", "UNUSED=1"]),
+    ];
+    let threads: Vec<_> = builds.into_iter()
+        .map(|(handler, argv)| {
+            let args = sold_lib::Args::parse_from(argv);
+            std::thread::spawn(move || sold_lib::Compiler::with_message_handler(handler).build(args).is_ok())
+        })
+        .collect();
+    let results: Vec<bool> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+    assert_eq!(results, [false, true]);
+
+    // Each compiler got the messages of its own build only
+    let failing = failing.0.lock().unwrap();
+    assert!(failing.iter().any(|(level, text)| *level == Level::Error && text.contains("ErrorReporting.sol")));
+    assert!(!failing.iter().any(|(_, text)| text.contains("UNUSED")));
+    let defining = defining.0.lock().unwrap();
+    assert!(defining.iter().any(|(level, text)| *level == Level::Warning && text.contains("define \"UNUSED\"")));
+    assert!(!defining.iter().any(|(_, text)| text.contains("ErrorReporting.sol")));
+
+    std::fs::remove_dir_all(&output_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_color_per_compiler() -> Status {
+    use clap::Parser;
+    use std::sync::Arc;
+
+    // Each compiler colors the diagnostics of its builds as they ask
+    let colored = Arc::new(Collect::default());
+    let plain = Arc::new(Collect::default());
+    let threads: Vec<_> = [(colored.clone(), "always"), (plain.clone(), "never")].into_iter()
+        .map(|(handler, color)| {
+            let args = sold_lib::Args::parse_from(["sold", "tests/ErrorReporting.sol", "--output-dir", "tests", "--color", color]);
+            std::thread::spawn(move || sold_lib::Compiler::with_message_handler(handler).build(args).is_ok())
+        })
+        .collect();
+    for thread in threads {
+        assert!(!thread.join().unwrap());
+    }
+    assert!(colored.0.lock().unwrap().iter().any(|(_, text)| text.contains("\u{1b}[")));
+    let plain = plain.0.lock().unwrap();
+    assert!(!plain.is_empty());
+    assert!(!plain.iter().any(|(_, text)| text.contains("\u{1b}[")));
+    Ok(())
+}
+
+#[test]
+fn test_library_errors() -> Status {
+    use clap::Parser;
+    use sold_lib::SoldError;
+    use std::sync::Arc;
+
+    let output_dir = std::env::temp_dir().join(format!("sold-library-{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir)?;
+    let build = |input: &str| {
+        let args = sold_lib::Args::parse_from(["sold", input, "--output-dir", output_dir.to_str().unwrap()]);
+        sold_lib::Compiler::with_message_handler(Arc::new(Collect::default())).build(args)
+    };
+
+    let output = build("tests/Trivial.sol")?;
+    assert_eq!(output.contract, "Trivial");
+    assert!(output.artifacts.contains(&output_dir.join("Trivial.tvc")));
+    assert!(output.code_hash.is_some());
+
+    let missing = build("tests/Missing.sol").unwrap_err();
+    assert_eq!(missing.exit_code(), 3);
+    assert!(matches!(missing, SoldError::SourceNotFound(_)));
+
+    let failed = build("tests/ErrorReporting.sol").unwrap_err();
+    assert_eq!(failed.exit_code(), 1);
+    match failed {
+        SoldError::CompilationFailed { diagnostics } => {
+            assert!(diagnostics.iter().any(|diagnostic| diagnostic.contains("ErrorReporting.sol")))
+        }
+        e => panic!("Unexpected error {:?}", e),
+    }
+
+    std::fs::remove_dir_all(&output_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_link_quietly() -> Status {
+    std::fs::create_dir_all("tests/linked")?;
+    // The TVC is saved by the build, the linker prints nothing
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/linked")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Contract successfully compiled"))
+        .stdout(predicate::str::contains("Saved contract").not())
+        .stdout(predicate::str::contains("testnet").not());
+    assert!(std::fs::metadata("tests/linked/Trivial.tvc")?.len() > 0);
+
+    // The linker running the constructor prints itself, what it prints is passed on as
+    // messages and stdout only has the banner of the build
+    std::fs::write("tests/linked/Constructed.sol", "\
+pragma ever-solidity >=0.50.0;
+contract Constructed {
+    uint32 limit;
+    constructor(uint32 value) {
+        tvm.accept();
+        limit = value;
+    }
+}
+")?;
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/linked/Constructed.sol")
+        .arg("--output-dir")
+        .arg("tests/linked")
+        .arg("--ctor-params")
+        .arg(r#"{"value": 7}"#)
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), ["Contract successfully compiled. Saved to file tests/linked/Constructed.tvc."]);
+
+    std::fs::remove_dir_all("tests/linked")?;
+    Ok(())
+}
+
+struct InMemory;
+
+impl sold_lib::ImportResolver for InMemory {
+    fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        match path {
+            "Virtual.sol" => Ok(b"pragma ever-solidity >= 0.66.0;\nimport \"Missing.sol\";\ncontract Virtual {}\n".to_vec()),
+            _ => Err(format!("No source {}", path)),
+        }
+    }
+}
+
+#[test]
+fn test_compile_standard_json() -> Status {
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "settings": { "outputSelection": { "Virtual.sol": { "*": [ "abi" ] } } },
+        "sources": { "Virtual.sol": { "urls": [ "Virtual.sol" ] } },
+    });
+    let output: serde_json::Value = serde_json::from_str(&sold_lib::compile_standard_json(&input.to_string(), &InMemory)?)?;
+    let errors = output["errors"].as_array().unwrap();
+    assert!(errors.iter().any(|error| error["message"].as_str().unwrap_or_default().contains("No source Missing.sol")));
+    Ok(())
+}
+
+#[test]
+fn test_typed_models() -> Status {
+    let compile = |input: &str| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let request = serde_json::json!({
+            "language": "Solidity",
+            "settings": { "outputSelection": { input: { "*": [ "abi", "assembly", "showFunctionIds" ] } } },
+            "sources": { input: { "urls": [ input ] } },
+        });
+        Ok(serde_json::from_str(&sold_lib::compile_standard_json(&request.to_string(), &sold_lib::FileResolver)?)?)
+    };
+
+    let output = compile("tests/Trivial.sol")?;
+    let artifacts: sold_lib::ContractArtifacts = serde_json::from_value(output["contracts"]["tests/Trivial.sol"]["Trivial"].clone())?;
+    assert!(artifacts.assembly.is_some());
+    let constructor = artifacts.function_ids["constructor"];
+    assert_eq!(serde_json::to_value(constructor)?, output["contracts"]["tests/Trivial.sol"]["Trivial"]["functionIds"]["constructor"]);
+
+    let output = compile("tests/ErrorReporting.sol")?;
+    let diagnostics: Vec<sold_lib::Diagnostic> = serde_json::from_value(output["errors"].clone())?;
+    let error = diagnostics.iter().find(|diagnostic| diagnostic.severity == sold_lib::diagnostics::Severity::Error).unwrap();
+    assert_eq!(error.location.as_ref().unwrap().file, "tests/ErrorReporting.sol");
+    Ok(())
+}
+
+#[test]
+fn test_pragma_set() -> Status {
+    std::fs::create_dir_all("tests/pragma/lib")?;
+    let main = "\
+pragma ever-solidity >=0.50.0;
+// pragma ever-solidity 0.1.0;
+import \"./lib/Lib.sol\";
+contract Main {
+}
+";
+    let lib = "\
+pragma ton-solidity >= 0.50.0;
+library Lib {
+}
+";
+    std::fs::write("tests/pragma/Main.sol", main)?;
+    std::fs::write("tests/pragma/lib/Lib.sol", lib)?;
+    std::fs::write("tests/pragma/Unrelated.sol", lib)?;
+
+    // Imported files are updated with their keyword kept, commented out pragmas are not
+    Command::cargo_bin(BIN_NAME)?
+        .arg("pragma")
+        .arg("set")
+        .arg(">=0.60.0")
+        .arg("tests/pragma/Main.sol")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 of 2 files updated to pragma >=0.60.0"));
+    assert_eq!(
+        std::fs::read_to_string("tests/pragma/Main.sol")?,
+        main.replacen("pragma ever-solidity >=0.50.0;", "pragma ever-solidity >=0.60.0;", 1),
+    );
+    assert_eq!(std::fs::read_to_string("tests/pragma/lib/Lib.sol")?, "pragma ton-solidity >=0.60.0;\nlibrary Lib {\n}\n");
+    assert_eq!(std::fs::read_to_string("tests/pragma/Unrelated.sol")?, lib);
+
+    // A requirement the compiler doesn't meet is reverted
+    Command::cargo_bin(BIN_NAME)?
+        .arg("pragma")
+        .arg("set")
+        .arg("<0.1.0")
+        .arg("tests/pragma/Main.sol")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("The updated sources failed to compile, all changes are reverted"));
+    assert!(std::fs::read_to_string("tests/pragma/Main.sol")?.starts_with("pragma ever-solidity >=0.60.0;"));
+    assert!(std::fs::read_to_string("tests/pragma/lib/Lib.sol")?.starts_with("pragma ton-solidity >=0.60.0;"));
+
+    std::fs::remove_dir_all("tests/pragma")?;
+    Ok(())
+}
+
+#[test]
+fn test_rename() -> Status {
+    std::fs::create_dir_all("tests/rename")?;
+    std::fs::write("tests/rename/Token.sol", "\
+pragma ever-solidity >=0.50.0;
+contract Token {
+    uint128 public supply;
+    function mint(uint128 amount) public virtual {
+        tvm.accept();
+        supply += amount;
+    }
+}
+")?;
+    std::fs::write("tests/rename/Wallet.sol", "\
+pragma ever-solidity >=0.50.0;
+import {Token} from \"./Token.sol\";
+contract Wallet is Token {
+    function mint(uint128 amount) public override {
+        Token.mint(amount);
+    }
+    function refill() public {
+        mint(1);
+    }
+    function same(uint128 mint) public pure returns (uint128) {
+        return mint;
+    }
+}
+")?;
+
+    // Across an import, overrides included, leaving the parameter that shadows it
+    Command::cargo_bin(BIN_NAME)?
+        .arg("rename")
+        .arg("--from")
+        .arg("tests/rename/Token.sol:Token.mint")
+        .arg("--to")
+        .arg("issue")
+        .arg("tests/rename")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Renamed mint to issue: 4 occurrences in 2 files"));
+    let token = std::fs::read_to_string("tests/rename/Token.sol")?;
+    assert!(token.contains("function issue(uint128 amount) public virtual"));
+    let wallet = std::fs::read_to_string("tests/rename/Wallet.sol")?;
+    assert!(wallet.contains("function issue(uint128 amount) public override"));
+    assert!(wallet.contains("Token.issue(amount);"));
+    assert!(wallet.contains("issue(1);"));
+    assert!(wallet.contains("function same(uint128 mint) public pure returns (uint128) {\n        return mint;"));
+
+    // A name taken in the same scope is refused and nothing is changed
+    Command::cargo_bin(BIN_NAME)?
+        .arg("rename")
+        .arg("--from")
+        .arg("tests/rename/Token.sol:Token.issue")
+        .arg("--to")
+        .arg("supply")
+        .arg("tests/rename")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("\"supply\" is already declared in the same scope"));
+    assert_eq!(std::fs::read_to_string("tests/rename/Token.sol")?, token);
+    assert_eq!(std::fs::read_to_string("tests/rename/Wallet.sol")?, wallet);
+
+    // A contract is renamed in the import naming it as well
+    Command::cargo_bin(BIN_NAME)?
+        .arg("rename")
+        .arg("--from")
+        .arg("tests/rename/Token.sol:Token")
+        .arg("--to")
+        .arg("Coin")
+        .arg("tests/rename")
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string("tests/rename/Token.sol")?.contains("contract Coin {"));
+    let wallet = std::fs::read_to_string("tests/rename/Wallet.sol")?;
+    assert!(wallet.contains("import {Coin} from \"./Token.sol\";"));
+    assert!(wallet.contains("contract Wallet is Coin {"));
+    assert!(wallet.contains("Coin.issue(amount);"));
+
+    std::fs::remove_dir_all("tests/rename")?;
+    Ok(())
+}
+
+#[test]
+fn test_build_info_replay() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Replayed")
+        .arg("--build-info")
+        .assert()
+        .success();
+
+    let build_info: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Replayed.build-info.json")?)?;
+    assert_eq!(build_info["options"]["input"], serde_json::json!(["tests/Trivial.sol"]));
+    assert_eq!(build_info["options"]["output_prefix"], "Replayed");
+    let tvc = std::fs::read("tests/Replayed.tvc")?;
+    remove_all_outputs("Replayed")?;
+    std::fs::rename("tests/Replayed.build-info.json", "tests/Replayed.config.json")?;
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("--from-config")
+        .arg("tests/Replayed.config.json")
+        .assert()
+        .success();
+    assert_eq!(std::fs::read("tests/Replayed.tvc")?, tvc);
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("--from-config")
+        .arg("tests/Replayed.config.json")
+        .arg("tests/Trivial.sol")
+        .assert()
+        .failure();
+
+    std::fs::remove_file("tests/Replayed.config.json")?;
+    std::fs::remove_file("tests/Replayed.build-info.json")?;
+    remove_all_outputs("Replayed")?;
+    Ok(())
+}
+
+#[test]
+fn test_build_info_given_options() -> Status {
+    // The build info holds the options given, not those the project config adds
+    std::fs::create_dir_all("tests/info/.git")?;
+    std::fs::create_dir_all("tests/info/lib")?;
+    std::fs::write("tests/info/sold.toml", "[compiler]\ninclude_path = [\"lib\"]\ncontract = \"Trivial\"\n")?;
+    std::fs::copy("tests/Trivial.sol", "tests/info/Trivial.sol")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/info/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/info")
+        .arg("--build-info")
+        .assert()
+        .success();
+
+    let build_info: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/info/Trivial.build-info.json")?)?;
+    assert_eq!(build_info["options"]["input"], serde_json::json!(["tests/info/Trivial.sol"]));
+    assert_eq!(build_info["options"]["output_dir"], "tests/info");
+    assert!(build_info["options"]["contract"].is_null());
+    assert_eq!(build_info["options"]["include_path"], serde_json::json!([]));
+
+    std::fs::remove_dir_all("tests/info")?;
+    Ok(())
+}
+
+#[test]
+fn test_split_abi() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/SplitAbi.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--contract")
+        .arg("SplitAbi")
+        .arg("--split-abi")
+        .assert()
+        .success();
+
+    let names = |path: &str| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let abi: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(abi["functions"].as_array().unwrap().iter().map(|function| function["name"].as_str().unwrap().to_owned()).collect())
+    };
+    let onchain = names("tests/SplitAbi.onchain.abi.json")?;
+    assert!(onchain.contains(&"add".to_owned()));
+    assert!(onchain.contains(&"constructor".to_owned()));
+    for getter in ["counter", "total", "owner"] {
+        assert!(!onchain.contains(&getter.to_owned()));
+        assert!(names("tests/SplitAbi.abi.json")?.contains(&getter.to_owned()));
+    }
+
+    std::fs::remove_file("tests/SplitAbi.onchain.abi.json")?;
+    remove_all_outputs("SplitAbi")?;
+    Ok(())
+}
+
+#[test]
+fn test_vanity() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Init.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("VanityInit")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("vanity")
+        .arg("VanityInit")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--prefix")
+        .arg("0:a")
+        .arg("--vary")
+        .arg("initfield=field1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Address: 0:a"))
+        .stdout(predicate::str::contains("field1: "));
+    let tvc = std::fs::read("tests/VanityInit.vanity.tvc")?;
+    assert!(!tvc.is_empty());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("vanity")
+        .arg("VanityInit")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--prefix")
+        .arg("0:b")
+        .arg("--vary")
+        .arg("pubkey")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Address: 0:b"))
+        .stdout(predicate::str::contains("Keys: tests/VanityInit.vanity.keys"));
+    assert_eq!(std::fs::read("tests/VanityInit.vanity.keys")?.len(), 64);
+    assert_eq!(std::fs::read("tests/VanityInit.vanity.keys.pub")?.len(), 32);
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("vanity")
+        .arg("VanityInit")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--prefix")
+        .arg("0:g")
+        .arg("--vary")
+        .arg("salt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a prefix of hex digits"));
+
+    for file in ["VanityInit.vanity.tvc", "VanityInit.vanity.keys", "VanityInit.vanity.keys.pub"] {
+        std::fs::remove_file(format!("tests/{}", file))?;
+    }
+    remove_all_outputs("VanityInit")?;
+    Ok(())
+}
+
+#[test]
+fn test_constants_report() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Constants.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--constants-report")
+        .arg("--pool-constants")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Constants.constants.json")?)?;
+    let message = report["duplicates"].as_array().unwrap().iter()
+        .find(|duplicate| duplicate["content"] == "x4e6f7420656e6f7567682062616c616e636520666f7220746865207472616e73666572")
+        .unwrap();
+    assert_eq!(message["bits"], 280);
+    assert_eq!(message["merged"], true);
+    assert_eq!(message["wastedBits"], 0);
+    assert_eq!(message["refSites"].as_array().unwrap().len(), 2);
+    assert!(report["wastedBits"].is_u64());
+
+    std::fs::remove_file("tests/Constants.constants.json")?;
+    remove_all_outputs("Constants")?;
+    Ok(())
+}
+
+#[test]
+fn test_pool_constants() -> Status {
+    for (prefix, pool) in [("Unpooled", false), ("Pooled", true)] {
+        let mut command = Command::cargo_bin(BIN_NAME)?;
+        command
+            .arg("tests/Pooled.sol")
+            .arg("--output-dir")
+            .arg("tests")
+            .arg("--output-prefix")
+            .arg(prefix)
+            .arg("--hash-json")
+            .arg("--constants-report");
+        if pool {
+            command.arg("--pool-constants");
+        }
+        command.assert().success();
+    }
+
+    // The address is pushed inline by both functions, and from one cell once pooled
+    let report = |prefix: &str| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(format!("tests/{}.constants.json", prefix))?)?;
+        let address = report["duplicates"].as_array().unwrap().iter().find(|duplicate| duplicate["bits"] == 267).cloned();
+        Ok(address.unwrap_or_default())
+    };
+    let (unpooled, pooled) = (report("Unpooled")?, report("Pooled")?);
+    assert_eq!(unpooled["bits"], 267);
+    assert_eq!(unpooled["inlineSites"].as_array().unwrap().len(), 2);
+    assert_eq!(pooled["content"], unpooled["content"]);
+    assert_eq!(pooled["refSites"].as_array().unwrap().len(), 2);
+    assert_eq!(pooled["merged"], true);
+
+    // The linked code differs...
+    let hash = |prefix: &str| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let hashes: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(format!("tests/{}.hash.json", prefix))?)?;
+        Ok(hashes["codeHash"].clone())
+    };
+    assert!(hash("Unpooled")?.is_string());
+    assert_ne!(hash("Unpooled")?, hash("Pooled")?);
+
+    // ...but behaves the same: the pooled constant still compares equal to the address
+    for prefix in ["Unpooled", "Pooled"] {
+        for (function, code) in [("isOwner", 101), ("isOwnerToo", 102)] {
+            Command::cargo_bin(BIN_NAME)?
+                .arg("bench")
+                .arg(prefix)
+                .arg("--output-dir")
+                .arg("tests")
+                .arg("--getter")
+                .arg(function)
+                .arg("--params")
+                .arg(r#"{"a": "0:1111111111111111111111111111111111111111111111111111111111111111"}"#)
+                .arg("--iters")
+                .arg("1")
+                .assert()
+                .success();
+            Command::cargo_bin(BIN_NAME)?
+                .arg("bench")
+                .arg(prefix)
+                .arg("--output-dir")
+                .arg("tests")
+                .arg("--getter")
+                .arg(function)
+                .arg("--params")
+                .arg(r#"{"a": "0:2222222222222222222222222222222222222222222222222222222222222222"}"#)
+                .arg("--iters")
+                .arg("1")
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(format!("{} failed with exit code {}", function, code)));
+        }
+    }
+
+    for prefix in ["Unpooled", "Pooled"] {
+        std::fs::remove_file(format!("tests/{}.constants.json", prefix))?;
+        std::fs::remove_file(format!("tests/{}.hash.json", prefix))?;
+        remove_all_outputs(prefix)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_manifest() -> Status {
+    use sha2::{Digest, Sha256};
+
+    std::fs::create_dir_all("tests/manifest")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/manifest")
+        .arg("--hash-json")
+        .arg("--manifest")
+        .assert()
+        .success();
+
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/manifest/Trivial.manifest.json")?)?;
+    assert_eq!(manifest["contract"], "Trivial");
+    let artifacts = manifest["artifacts"].as_array().unwrap();
+    for name in ["Trivial.abi.json", "Trivial.tvc", "Trivial.hash.json"] {
+        let artifact = artifacts.iter().find(|artifact| artifact["path"] == name).unwrap();
+        let content = std::fs::read(Path::new("tests/manifest").join(name))?;
+        assert_eq!(artifact["size"], content.len());
+        assert_eq!(artifact["sha256"], format!("{:x}", Sha256::digest(&content)));
+    }
+
+    // The hashes the address is computed from are those of --hash-json
+    let hashes: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/manifest/Trivial.hash.json")?)?;
+    assert_eq!(manifest["codeHash"], hashes["codeHash"]);
+    assert_eq!(manifest["address"]["code"]["hash"], hashes["codeHash"]);
+    assert_eq!(manifest["address"]["stateInitHash"], hashes["stateInitHash"]);
+    assert_eq!(hashes["address"], format!("0:{}", hashes["stateInitHash"].as_str().unwrap()));
+
+    std::fs::remove_dir_all("tests/manifest")?;
+    Ok(())
+}
+
+#[test]
+fn test_archive() -> Status {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    std::fs::create_dir_all("tests/archive")?;
+    let build = |archive: &str| Command::cargo_bin(BIN_NAME).map(|mut command| {
+        command
+            .env("SOURCE_DATE_EPOCH", "1700000000")
+            .arg("tests/Trivial.sol")
+            .arg("--output-dir")
+            .arg("tests/archive")
+            .arg("--archive")
+            .arg(archive)
+            .assert()
+            .success();
+    });
+    build("tests/archive/first.tar.gz")?;
+
+    let manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/archive/Trivial.manifest.json")?)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(std::fs::File::open("tests/archive/first.tar.gz")?));
+    let mut names = vec![];
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        assert_eq!(entry.header().mode()?, 0o644);
+        assert_eq!(entry.header().mtime()?, 1700000000);
+        let mut content = vec![];
+        entry.read_to_end(&mut content)?;
+        assert_eq!(content, std::fs::read(Path::new("tests/archive").join(&name))?);
+        // Every artifact packed but the manifest is listed in it with the digest of its content
+        if name != "Trivial.manifest.json" {
+            let artifact = manifest["artifacts"].as_array().unwrap().iter().find(|artifact| artifact["path"] == name.as_str()).unwrap();
+            assert_eq!(artifact["sha256"], format!("{:x}", Sha256::digest(&content)));
+        }
+        names.push(name);
+    }
+    assert_eq!(names, ["Trivial.abi.json", "Trivial.code", "Trivial.debug.json", "Trivial.manifest.json", "Trivial.tvc"]);
+
+    // Another build of the same sources packs the same bytes
+    build("tests/archive/second.tar.gz")?;
+    assert_eq!(std::fs::read("tests/archive/first.tar.gz")?, std::fs::read("tests/archive/second.tar.gz")?);
+
+    std::fs::remove_dir_all("tests/archive")?;
+    Ok(())
+}
+
+#[test]
+fn test_input_wildcards() -> Status {
+    std::fs::create_dir_all("tests/wildcards")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Cycle?.sol")
+        .arg("--output-dir")
+        .arg("tests/wildcards")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/wildcards/build-report.json")?)?;
+    assert_eq!(report[0]["input"], "tests/CycleA.sol");
+    assert_eq!(report[1]["input"], "tests/CycleB.sol");
+    assert!(std::path::Path::new("tests/wildcards/CycleB.tvc").exists());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Missing*.sol")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No file matches tests/Missing*.sol"));
+
+    std::fs::remove_dir_all("tests/wildcards")?;
+    Ok(())
+}
+
+#[test]
+fn test_standard_json() -> Status {
+    let input = serde_json::json!({
+        "language": "Solidity",
+        "settings": { "outputSelection": { "tests/Trivial.sol": { "*": [ "abi" ] } } },
+        "sources": { "tests/Trivial.sol": { "urls": [ "tests/Trivial.sol" ] } },
+    });
+    let assert = Command::cargo_bin(BIN_NAME)?
+        .arg("--standard-json")
+        .write_stdin(input.to_string())
+        .assert()
+        .success();
+    let output: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+    assert!(output["contracts"]["tests/Trivial.sol"]["Trivial"]["abi"].is_object());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("--standard-json")
+        .arg("tests/Missing.json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tests/Missing.json"));
+    Ok(())
+}
+
+#[test]
+fn test_compile_in_memory() -> Status {
+    let output = sold_lib::Compiler::new().compile_file("tests/Trivial.sol")?;
+    assert_eq!(output.contract, "Trivial");
+    assert!(output.abi["functions"].is_array());
+    assert!(!output.assembly.is_empty());
+    assert!(!output.tvc.is_empty());
+    assert!(output.debug_map.is_object());
+    assert!(!std::path::Path::new("contract.tvc").exists());
+    Ok(())
+}
+
+#[test]
+fn test_stdin() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("-")
+        .arg("--stdin-name")
+        .arg("tests/Piped.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Piped")
+        .write_stdin("pragma ever-solidity >=0.50.0;\nimport \"CycleB.sol\";\ncontract Piped {\n}\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Contract successfully compiled"));
+    assert!(!std::path::Path::new("tests/Piped.sol").exists());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("-")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("PipedError")
+        .write_stdin("pragma ever-solidity >=0.50.0;\ncontract PipedError {\n  function f() public { g(); }\n}\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("stdin.sol:3:"));
+
+    remove_all_outputs("Piped")?;
+    Ok(())
+}
+
+#[test]
+fn test_watch() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/CycleA.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("WatchCycleA")
+        .arg("--watch")
+        .timeout(std::time::Duration::from_secs(20))
+        .assert()
+        .interrupted()
+        .stdout(predicate::str::contains("Contract successfully compiled"))
+        .stderr(predicate::str::contains("Watching 2 files for changes"));
+
+    remove_all_outputs("WatchCycleA")?;
+    Ok(())
+}
+
+#[test]
+fn test_hash_json() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("HashTrivial")
+        .arg("--hash-json")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Code hash: "))
+        .stderr(predicate::str::contains("Address: 0:"));
+
+    let hashes: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/HashTrivial.hash.json")?)?;
+    let state_init_hash = hashes["stateInitHash"].as_str().unwrap();
+    assert_eq!(state_init_hash.len(), 64);
+    assert_eq!(hashes["address"], format!("0:{}", state_init_hash));
+    assert_eq!(hashes["masterchainAddress"], format!("-1:{}", state_init_hash));
+    assert!(hashes["codeHash"].is_string());
+
+    std::fs::remove_file("tests/HashTrivial.hash.json")?;
+    remove_all_outputs("HashTrivial")?;
+    Ok(())
+}
+
+#[test]
+fn test_optimizer_settings() -> Status {
+    std::fs::create_dir_all("tests/optimizer")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/optimizer")
+        .arg("--optimize-runs")
+        .arg("1")
+        .arg("--no-peephole")
+        .arg("--commands-json")
+        .assert()
+        .success();
+
+    let commands: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/optimizer/sold_commands.json")?)?;
+    let optimizer = &commands[0]["settings"]["optimizer"];
+    assert_eq!(optimizer["enabled"], true);
+    assert_eq!(optimizer["runs"], 1);
+    assert_eq!(optimizer["details"]["peephole"], false);
+
+    std::fs::remove_dir_all("tests/optimizer")?;
+    Ok(())
+}
+
+#[test]
+fn test_commands_json() -> Status {
+    std::fs::create_dir_all("tests/commands")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/CycleA.sol")
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/commands")
+        .arg("-D")
+        .arg("NO_SUCH_CONSTANT=1")
+        .arg("--commands-json")
+        .assert()
+        .success();
+
+    let commands: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/commands/sold_commands.json")?)?;
+    let entries = commands.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    let cycle = &entries[0];
+    assert_eq!(cycle["file"], "tests/CycleA.sol");
+    assert_eq!(cycle["directory"], std::env::current_dir()?.display().to_string());
+    assert!(cycle["arguments"].as_array().unwrap().iter().any(|arg| arg == "--commands-json"));
+    assert_eq!(cycle["defines"]["NO_SUCH_CONSTANT"], "1");
+    assert!(cycle["settings"]["optimizer"].is_object());
+    let imports = cycle["imports"].as_array().unwrap();
+    assert_eq!(imports.len(), 1);
+    assert!(imports[0].as_str().unwrap().ends_with("CycleB.sol"));
+    assert_eq!(entries[1]["file"], "tests/Trivial.sol");
+    assert_eq!(entries[1]["imports"], serde_json::json!([]));
+
+    // A later build replaces the entry of its input and keeps the other
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/commands")
+        .arg("--commands-json")
+        .assert()
+        .success();
+    let commands: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/commands/sold_commands.json")?)?;
+    let entries = commands.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["defines"]["NO_SUCH_CONSTANT"], "1");
+    assert_eq!(entries[1]["file"], "tests/Trivial.sol");
+    assert_eq!(entries[1]["defines"], serde_json::json!({}));
+
+    std::fs::remove_dir_all("tests/commands")?;
+    Ok(())
+}
+
+#[test]
+fn test_no_peephole_changes_code() -> Status {
+    for (prefix, no_peephole) in [("Peephole", false), ("NoPeephole", true)] {
+        let mut cmd = Command::cargo_bin(BIN_NAME)?;
+        cmd.arg("tests/Dispatch.sol")
+            .arg("--output-dir")
+            .arg("tests")
+            .arg("--output-prefix")
+            .arg(prefix)
+            .arg("--asm-only");
+        if no_peephole {
+            cmd.arg("--no-peephole");
+        }
+        cmd.assert().success();
+    }
+
+    let optimized = std::fs::read_to_string("tests/Peephole.code")?;
+    let unoptimized = std::fs::read_to_string("tests/NoPeephole.code")?;
+    assert_ne!(optimized, unoptimized);
+    assert!(unoptimized.lines().count() > optimized.lines().count());
+
+    std::fs::remove_file("tests/Peephole.code")?;
+    std::fs::remove_file("tests/NoPeephole.code")?;
+    Ok(())
+}
+
+#[test]
+fn test_asm_only() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("AsmOnly")
+        .arg("--asm-only")
+        .assert()
+        .success();
+    assert!(std::path::Path::new("tests/AsmOnly.code").exists());
+    assert!(!std::path::Path::new("tests/AsmOnly.abi.json").exists());
+    assert!(!std::path::Path::new("tests/AsmOnly.tvc").exists());
+    std::fs::remove_file("tests/AsmOnly.code")?;
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("NoTvc")
+        .arg("--no-tvc")
+        .assert()
+        .success();
+    assert!(std::path::Path::new("tests/NoTvc.abi.json").exists());
+    assert!(std::path::Path::new("tests/NoTvc.debug.json").exists());
+    assert!(!std::path::Path::new("tests/NoTvc.tvc").exists());
+    std::fs::remove_file("tests/NoTvc.abi.json")?;
+    std::fs::remove_file("tests/NoTvc.code")?;
+    std::fs::remove_file("tests/NoTvc.debug.json")?;
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--emit")
+        .arg("abi")
+        .arg("--no-abi")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No artifacts are selected to be written"));
+    Ok(())
+}
+
+#[test]
+fn test_remap() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/remap/Remapped.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--remap")
+        .arg("@lib/=tests/remap/vendor/lib/")
+        .assert()
+        .success();
+    remove_all_outputs("Remapped")?;
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/remap/Remapped.sol")
+        .arg("--remap")
+        .arg("@lib/=tests/remap/vendor/lib/")
+        .arg("--remap")
+        .arg("@lib/=lib/")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Conflicting remappings \"@lib/=tests/remap/vendor/lib/\" and \"@lib/=lib/\""));
+    Ok(())
+}
+
+#[test]
+fn test_project_config() -> Status {
+    std::fs::create_dir_all("tests/project/out")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/project/Project.sol")
+        .assert()
+        .success();
+    assert!(std::path::Path::new("tests/project/out/Project.tvc").exists());
+    std::fs::remove_dir_all("tests/project/out")?;
+
+    // Options of the command line take precedence
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/project/Project.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--contract")
+        .arg("Helper")
+        .arg("--output-prefix")
+        .arg("ProjectHelper")
+        .assert()
+        .success();
+    remove_all_outputs("ProjectHelper")?;
+    Ok(())
+}
+
+#[test]
+fn test_project_config_discovery() -> Status {
+    // A config above the root of a repository is not the one of its sources
+    std::fs::create_dir_all("tests/outer/repo/.git")?;
+    std::fs::write("tests/outer/sold.toml", "[compiler]\ncontract = \"Missing\"\n")?;
+    std::fs::copy("tests/Trivial.sol", "tests/outer/repo/Trivial.sol")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/outer/repo/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/outer/repo")
+        .arg("--emit")
+        .arg("abi")
+        .assert()
+        .success();
+    assert!(Path::new("tests/outer/repo/Trivial.abi.json").exists());
+    // Selecting only the ABI doesn't link, with a config found or not
+    assert!(!Path::new("tests/outer/repo/Trivial.tvc").exists());
+
+    std::fs::remove_dir("tests/outer/repo/.git")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/outer/repo/Trivial.sol")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Source file doesn't contain the desired contract \"Missing\""));
+
+    // Plugins of a config that was found and not named are not run
+    std::fs::write("tests/outer/sold.toml", "[[plugins]]\nname = \"fail\"\ncommand = \"false\"\n")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/outer/repo/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/outer/repo")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("are not run, name the file with --config and give --plugins to run them"));
+
+    std::fs::remove_dir_all("tests/outer")?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_plugins() -> Status {
+    std::fs::create_dir_all("tests/plugins")?;
+    std::fs::write("tests/plugins/sold.toml", r#"
+[[plugins]]
+name = "stub"
+command = "sh"
+args = [ "-c", "cat > request.json; echo '{\"artifacts\": [{\"name\": \"Trivial.stub.txt\", \"content\": \"stub\"}]}'" ]
+"#)?;
+
+    // Nothing is run without --plugins
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/plugins")
+        .arg("--config")
+        .arg("tests/plugins/sold.toml")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("are not run"));
+    assert!(!Path::new("tests/plugins/Trivial.stub.txt").exists());
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/plugins")
+        .arg("--config")
+        .arg("tests/plugins/sold.toml")
+        .arg("--plugins")
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string("tests/plugins/Trivial.stub.txt")?, "stub");
+    let request: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/plugins/request.json")?)?;
+    assert_eq!(request["version"], 1);
+    assert_eq!(request["output_prefix"], "Trivial");
+    assert!(request["abi"]["ABI version"].is_number());
+    assert!(!request["tvc"].as_str().unwrap().is_empty());
+
+    std::fs::write("tests/plugins/sold.toml", r#"
+[[plugins]]
+name = "broken"
+command = "sh"
+args = [ "-c", "echo 'no stubs today' >&2; exit 1" ]
+"#)?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests/plugins")
+        .arg("--config")
+        .arg("tests/plugins/sold.toml")
+        .arg("--plugins")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Plugin \"broken\" failed"))
+        .stderr(predicate::str::contains("no stubs today"));
+
+    std::fs::remove_dir_all("tests/plugins")?;
+    Ok(())
+}
+
+#[test]
+fn test_jobs() -> Status {
+    std::fs::create_dir_all("tests/jobs")?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("tests/remap/Remapped.sol")
+        .arg("--remap")
+        .arg("@lib/=tests/remap/vendor/lib/")
+        .arg("--output-dir")
+        .arg("tests/jobs")
+        .arg("--jobs")
+        .arg("2")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/jobs/build-report.json")?)?;
+    assert_eq!(report[0]["input"], "tests/Trivial.sol");
+    assert_eq!(report[0]["status"], "ok");
+    assert_eq!(report[1]["input"], "tests/remap/Remapped.sol");
+    assert_eq!(report[1]["status"], "ok");
+    assert!(std::path::Path::new("tests/jobs/Remapped.tvc").exists());
+
+    std::fs::remove_dir_all("tests/jobs")?;
+    Ok(())
+}
+
+#[test]
+fn test_mnemonic() -> Status {
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Mnemonic")
+        .arg("--mnemonic")
+        .arg(phrase)
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Mnemonic")
+        .arg("--mnemonic")
+        .arg(phrase.replace("about", "abandon"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid BIP39 seed phrase"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Mnemonic")
+        .arg("--mnemonic")
+        .arg(phrase)
+        .arg("--derivation-path")
+        .arg("44'/396'")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid derivation path"));
+
+    remove_all_outputs("Mnemonic")?;
+    Ok(())
+}
+
+#[test]
+fn test_key_password() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .env("SOLD_KEY_PASSWORD", "secret")
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("KeyPassword")
+        .arg("--gen-key")
+        .arg("tests/KeyPassword.keys")
+        .arg("--key-password")
+        .assert()
+        .success();
+
+    let keys: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/KeyPassword.keys")?)?;
+    assert_eq!(keys["cipher"], "chacha20-poly1305");
+    assert_eq!(std::fs::read("tests/KeyPassword.keys.pub")?.len(), 32);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata("tests/KeyPassword.keys")?.permissions().mode() & 0o777, 0o600);
+    }
+
+    Command::cargo_bin(BIN_NAME)?
+        .env("SOLD_KEY_PASSWORD", "secret")
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("KeyPassword")
+        .arg("--set-key")
+        .arg("tests/KeyPassword.keys")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .env("SOLD_KEY_PASSWORD", "wrong")
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("KeyPassword")
+        .arg("--set-key")
+        .arg("tests/KeyPassword.keys")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Wrong key password"));
+
+    // A file stretching the password less than files are written with is refused
+    let mut weak = keys.clone();
+    weak["iterations"] = 1000.into();
+    std::fs::write("tests/KeyPassword.weak.keys", weak.to_string())?;
+    Command::cargo_bin(BIN_NAME)?
+        .env("SOLD_KEY_PASSWORD", "secret")
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("KeyPassword")
+        .arg("--set-key")
+        .arg("tests/KeyPassword.weak.keys")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Key file uses 1000 PBKDF2 iterations"));
+
+    std::fs::remove_file("tests/KeyPassword.weak.keys")?;
+    std::fs::remove_file("tests/KeyPassword.keys")?;
+    std::fs::remove_file("tests/KeyPassword.keys.pub")?;
+    remove_all_outputs("KeyPassword")?;
+    Ok(())
+}
+
+#[test]
+fn test_version_json() -> Status {
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("--version-json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let version: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(version["sold"], env!("CARGO_PKG_VERSION"));
+    assert!(version["frontend"].is_string());
+    assert_eq!(version["stdlib"]["sha256"].as_str().unwrap().len(), 64);
+    assert!(version["linker"].as_str().unwrap().starts_with("0.15."));
+    Ok(())
+}
+
+#[test]
+fn test_exit_codes() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/ErrorReporting.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .assert()
+        .code(1);
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Missing.sol")
+        .assert()
+        .code(3);
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--no-such-option")
+        .assert()
+        .code(3);
+    Ok(())
+}
+
+#[test]
+fn test_allow_paths() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/sandbox/Escape.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--base-path")
+        .arg("tests/sandbox")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is outside of the allowed directories"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/sandbox/Escape.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--base-path")
+        .arg("tests/sandbox")
+        .arg("--allow-paths")
+        .arg("tests/remap")
+        .assert()
+        .success();
+    remove_all_outputs("Escape")?;
+    Ok(())
+}
+
+#[test]
+fn test_compile_sources() -> Status {
+    let compiler = sold_lib::Compiler::new();
+    let output = compiler.compile_sources([
+        ("memory/Owner.sol", "pragma ever-solidity >=0.50.0;\ncontract Owner {\n    uint256 owner;\n}\n"),
+        ("memory/Main.sol", "pragma ever-solidity >=0.50.0;\nimport \"./Owner.sol\";\ncontract Main is Owner {\n}\n"),
+    ], "memory/Main.sol")?;
+    assert_eq!(output.contract, "Main");
+    assert!(!output.tvc.is_empty());
+    assert!(!std::path::Path::new("memory").exists());
+
+    // Sources of one compiler are not seen by another
+    assert!(sold_lib::Compiler::new().compile_file("memory/Main.sol").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_metadata() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/remap/Remapped.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Metadata")
+        .arg("--remap")
+        .arg("@lib/=tests/remap/vendor/lib/")
+        .arg("--metadata")
+        .assert()
+        .success();
+
+    let metadata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/Metadata.meta.json")?)?;
+    assert_eq!(metadata["contract"], "Remapped");
+    assert_eq!(metadata["compiler"]["sold"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(metadata["remappings"][0], "@lib/=tests/remap/vendor/lib/");
+    assert_eq!(metadata["sources"].as_object().unwrap().len(), 2);
+    assert!(metadata["library"]["stdlib_sol.tvm"]["sha256"].is_string());
+    assert!(metadata["codeHash"].is_string());
+
+    std::fs::remove_file("tests/Metadata.meta.json")?;
+    remove_all_outputs("Metadata")?;
+    Ok(())
+}
+
+/// Serves `responses` in turn, one connection each, and returns the requests it got
+fn mock_server(responses: Vec<&'static str>) -> std::io::Result<(String, std::thread::JoinHandle<Vec<String>>)> {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let server = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request += &line;
+                if line == "\r\n" {
+                    break
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request += &String::from_utf8_lossy(&body);
+            requests.push(request);
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(), response,
+            ).unwrap();
+        }
+        requests
+    });
+    Ok((url, server))
+}
+
+#[test]
+fn test_publish() -> Status {
+    let (url, server) = mock_server(vec![r#"{"id": "42"}"#, r#"{"status": "verified"}"#])?;
+    Command::cargo_bin(BIN_NAME)?
+        .arg("publish")
+        .arg("tests/remap/Remapped.sol")
+        .arg("--verifier")
+        .arg(format!("{}/submissions", url))
+        .arg("--address")
+        .arg("0:1234")
+        .arg("--")
+        .arg("--remap")
+        .arg("@lib/=tests/remap/vendor/lib/")
+        .arg("--define")
+        .arg("OWNER=1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Submitted sources for verification, id 42"))
+        .stdout(predicate::str::contains("Contract 0:1234 successfully verified"));
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("POST /submissions "));
+    assert!(requests[1].starts_with("GET /submissions/42 "));
+    let body = &requests[0][requests[0].find("\r\n\r\n").unwrap() + 4..];
+    let submission: serde_json::Value = serde_json::from_str(body)?;
+    assert_eq!(submission["address"], "0:1234");
+    assert_eq!(submission["contract"], "Remapped");
+    assert_eq!(submission["main"], "tests/remap/Remapped.sol");
+    assert_eq!(submission["compiler"]["sold"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(submission["settings"]["remappings"][0], "@lib/=tests/remap/vendor/lib/");
+    assert!(submission["settings"]["outputSelection"]["tests/remap/Remapped.sol"].is_object());
+    assert_eq!(submission["defines"]["OWNER"], "1");
+    assert!(submission["codeHash"].is_string());
+    let sources = submission["sources"].as_object().unwrap();
+    assert_eq!(sources.keys().collect::<Vec<_>>(), ["tests/remap/Remapped.sol", "tests/remap/vendor/lib/Owned.sol"]);
+    assert_eq!(sources["tests/remap/Remapped.sol"]["content"], std::fs::read_to_string("tests/remap/Remapped.sol")?);
+    // Nothing of this machine goes out
+    assert!(!body.contains(std::env::current_dir()?.to_str().unwrap()));
+    Ok(())
+}
+
+#[test]
+fn test_publish_outside_project() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("publish")
+        .arg("tests/remap/Remapped.sol")
+        .arg("--verifier")
+        .arg("http://127.0.0.1:9")
+        .arg("--address")
+        .arg("0:1234")
+        .arg("--root")
+        .arg("tests/remap/vendor")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("is outside of the project directory"));
+    Ok(())
+}
+
+#[test]
+fn test_verify() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Trivial.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("Deployed")
+        .assert()
+        .success();
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("verify")
+        .arg("tests/Trivial.sol")
+        .arg("--tvc")
+        .arg("tests/Deployed.tvc")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Code of Trivial matches the sources"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("verify")
+        .arg("tests/remap/vendor/lib/Owned.sol")
+        .arg("--tvc")
+        .arg("tests/Deployed.tvc")
+        .assert()
+        .code(5)
+        .stdout(predicate::str::contains("Code of Owned doesn't match the sources"))
+        .stdout(predicate::str::contains("cell 0"));
+
+    Command::cargo_bin(BIN_NAME)?
+        .arg("verify")
+        .arg("tests/Trivial.sol")
+        .arg("--code-hash")
+        .arg("00")
+        .assert()
+        .code(5);
+
+    remove_all_outputs("Deployed")?;
+    Ok(())
+}
+
+#[test]
+fn test_source_map() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Constants.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("SourceMap")
+        .arg("--source-map")
+        .assert()
+        .success();
+
+    let map: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/SourceMap.map.json")?)?;
+    assert_eq!(map["version"], 3);
+    assert_eq!(map["file"], "SourceMap.code");
+    assert!(map["sources"][0].as_str().unwrap().ends_with("Constants.sol"));
+    let code = std::fs::read_to_string("tests/SourceMap.code")?;
+    let mappings = map["mappings"].as_str().unwrap();
+    assert_eq!(mappings.split(';').count(), code.lines().count());
+    assert!(mappings.split(';').any(|segments| !segments.is_empty()));
+
+    std::fs::remove_file("tests/SourceMap.map.json")?;
+    remove_all_outputs("SourceMap")?;
+    Ok(())
+}
+
+#[test]
+fn test_function_ids() -> Status {
+    let output = Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--function-ids")
+        .output()?;
+    assert!(output.status.success());
+
+    let ids: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert!(ids["transfer"].as_str().unwrap().starts_with("0x"));
+    assert_eq!(ids["transfer"].as_str().unwrap().len(), 10);
+    Ok(())
+}
+
+#[test]
+fn test_function_ids_json() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--output-dir")
+        .arg("tests")
+        .arg("--output-prefix")
+        .arg("FuncIds")
+        .arg("--function-ids-json")
+        .assert()
+        .success();
+
+    let ids: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("tests/FuncIds.funcids.json")?)?;
+    let transfer = &ids["transfer"];
+    assert!(transfer["id"].as_str().unwrap().starts_with("0x"));
+    assert_eq!(transfer["kind"], "external");
+    assert!(transfer["signature"].as_str().unwrap().starts_with("transfer(address,uint128)"));
+    assert_eq!(transfer["inputs"][0]["name"], "dest");
+    assert_eq!(ids["counter"]["kind"], "getter");
+
+    std::fs::remove_file("tests/FuncIds.funcids.json")?;
+    remove_all_outputs("FuncIds")?;
+    Ok(())
+}
+
+#[test]
+fn test_interface() -> Status {
+    Command::cargo_bin(BIN_NAME)?
+        .arg("tests/Dispatch.sol")
+        .arg("--interface")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("contract Dispatch"))
+        .stdout(predicate::str::contains("transfer(address dest, uint128 value)"))
+        .stdout(predicate::str::contains("state variables:\n  uint256 counter"));
+
+    Ok(())
+}